@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+/// Vertical metrics of a font in pixels.
+#[derive(Copy, Clone, Debug)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top (positive).
+    pub ascent: f32,
+
+    /// Distance from the baseline to the bottom (positive).
+    pub descent: f32,
+}
+
+/// Types of the backend drawing the command display lists.
+pub trait Backend: Clone + 'static {
+    type Texture: crate::Texture;
+    type Fonts: crate::Fonts + Send + Sync;
+
+    /// Returns the font metrics and the horizontal advances of the characters in the text.
+    fn get_advances(
+        fonts: &Self::Fonts,
+        family_name: &str,
+        size: f32,
+        text: &str,
+    ) -> Result<(FontMetrics, HashMap<char, f32>), &'static str>;
+}
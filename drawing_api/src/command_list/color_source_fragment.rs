@@ -0,0 +1,4 @@
+#[derive(Clone)]
+pub struct ColorSourceFragment {}
+
+impl crate::ColorSourceFragment for ColorSourceFragment {}
@@ -0,0 +1,64 @@
+use crate::{ClipOperation, ImageFilter, PixelPoint, PixelRect, TextureSampling};
+
+use super::{Backend, DisplayList, ImageFilterFragment, Paint, Paragraph, Path};
+
+pub type Transform = crate::euclid::default::Transform2D<f32>;
+
+/// Drawing command of the display list.
+///
+/// The transformations are absolute (from the local to the surface coordinates).
+#[derive(Clone)]
+pub enum Command<B: Backend> {
+    /// Stashes the current clip.
+    Save,
+
+    /// Stashes the current clip and redirects the drawing to a new layer.
+    SaveLayer {
+        bounds: PixelRect,
+        transform: Transform,
+        paint: Option<Paint<B>>,
+        backdrop_filter: Option<ImageFilter<ImageFilterFragment>>,
+    },
+
+    /// Restores the clip and composites the layer (if any).
+    Restore,
+
+    Clip {
+        path: Path,
+        transform: Transform,
+        operation: ClipOperation,
+    },
+
+    /// Fills the whole clip.
+    DrawPaint {
+        paint: Paint<B>,
+        transform: Transform,
+    },
+
+    DrawPath {
+        path: Path,
+        transform: Transform,
+        paint: Paint<B>,
+    },
+
+    DrawTexture {
+        texture: B::Texture,
+        src_rect: PixelRect,
+        dst_rect: PixelRect,
+        sampling: TextureSampling,
+        transform: Transform,
+        paint: Option<Paint<B>>,
+    },
+
+    DrawParagraph {
+        paragraph: Paragraph<B>,
+        location: PixelPoint,
+        transform: Transform,
+    },
+
+    DrawDisplayList {
+        display_list: DisplayList<B>,
+        transform: Transform,
+        opacity: f32,
+    },
+}
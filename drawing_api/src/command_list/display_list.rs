@@ -0,0 +1,10 @@
+use std::sync::Arc;
+
+use super::{Backend, Command};
+
+#[derive(Clone)]
+pub struct DisplayList<B: Backend> {
+    pub commands: Arc<Vec<Command<B>>>,
+}
+
+impl<B: Backend> crate::DisplayList for DisplayList<B> {}
@@ -0,0 +1,397 @@
+use std::sync::Arc;
+
+use crate::{
+    euclid::Angle, smart_pointers::OptRef, BlurStyle, ClipOperation, DrawStyle, FillType,
+    ImageFilter, MaskFilter, Matrix, PixelPoint, PixelRect, RoundingRadii, ShadowParameters,
+    StrokeJoin, TextureSampling,
+};
+
+use super::{
+    oval_path, rect_path, rect_rounded_radii_path, Backend, Command, DisplayList,
+    ImageFilterFragment, Paint, ParagraphBuilder, Path, PathBuilder, PathElement,
+};
+
+pub struct DisplayListBuilder<B: Backend> {
+    commands: Vec<Command<B>>,
+
+    // the full transformation of the current save level
+    transform: Matrix,
+
+    // transformations of the outer save levels
+    save_stack: Vec<Matrix>,
+}
+
+impl<B: Backend> DisplayListBuilder<B> {
+    fn push_transform(&mut self, relative_transform: &Matrix) {
+        self.transform = relative_transform.then(&self.transform);
+    }
+
+    fn push_clip(&mut self, path: Vec<PathElement>, operation: ClipOperation) {
+        self.commands.push(Command::Clip {
+            path: Path::new(path),
+            transform: self.transform.to_2d(),
+            operation,
+        });
+    }
+
+    fn push_path(&mut self, path: Path, paint: &Paint<B>) {
+        self.commands.push(Command::DrawPath {
+            path,
+            transform: self.transform.to_2d(),
+            paint: paint.clone(),
+        });
+    }
+}
+
+impl<B: Backend> crate::DisplayListBuilder for DisplayListBuilder<B> {
+    type DisplayList = DisplayList<B>;
+    type ImageFilterFragment = ImageFilterFragment;
+    type Paint = Paint<B>;
+    type ParagraphBuilder = ParagraphBuilder<B>;
+    type PathBuilder = PathBuilder;
+    type Texture = B::Texture;
+
+    fn new(_bounds: impl Into<Option<PixelRect>>) -> Self {
+        Self {
+            commands: Vec::new(),
+            transform: Matrix::identity(),
+            save_stack: Vec::new(),
+        }
+    }
+
+    fn scale(&mut self, x_scale: f32, y_scale: f32) {
+        self.push_transform(&Matrix::scale(x_scale, y_scale, 1.0f32));
+    }
+
+    fn rotate(&mut self, angle_degrees: f32) {
+        self.push_transform(&Matrix::rotation(
+            0.0f32,
+            0.0f32,
+            1.0f32,
+            Angle::degrees(angle_degrees),
+        ));
+    }
+
+    fn translate(&mut self, x_translation: f32, y_translation: f32) {
+        self.push_transform(&Matrix::translation(x_translation, y_translation, 0.0f32));
+    }
+
+    fn transform(&mut self, transform: &Matrix) {
+        self.push_transform(transform);
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = *transform;
+    }
+
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn reset_transform(&mut self) {
+        self.transform = Matrix::identity();
+    }
+
+    fn clip_rect(&mut self, rect: impl Into<PixelRect>, operation: ClipOperation) {
+        self.push_clip(rect_path(rect.into()), operation);
+    }
+
+    fn clip_oval(&mut self, oval_bounds: impl Into<PixelRect>, operation: ClipOperation) {
+        self.push_clip(oval_path(oval_bounds.into()), operation);
+    }
+
+    fn clip_rounded_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        radii: impl Into<OptRef<'a, RoundingRadii>>,
+        operation: ClipOperation,
+    ) {
+        self.push_clip(
+            rect_rounded_radii_path(rect.into(), &radii.into()),
+            operation,
+        );
+    }
+
+    fn clip_path(
+        &mut self,
+        path: &<Self::PathBuilder as crate::PathBuilder>::Path,
+        operation: ClipOperation,
+    ) {
+        self.commands.push(Command::Clip {
+            path: path.clone(),
+            transform: self.transform.to_2d(),
+            operation,
+        });
+    }
+
+    fn save(&mut self) {
+        self.save_stack.push(self.transform);
+        self.commands.push(Command::Save);
+    }
+
+    fn save_layer<'a>(
+        &mut self,
+        bounds: impl Into<PixelRect>,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+        filter: Option<ImageFilter<ImageFilterFragment>>,
+    ) {
+        self.save_stack.push(self.transform);
+        self.commands.push(Command::SaveLayer {
+            bounds: bounds.into(),
+            transform: self.transform.to_2d(),
+            paint: paint.into().map(|paint| paint.to_owned()),
+            backdrop_filter: filter,
+        });
+    }
+
+    fn get_save_count(&mut self) -> usize {
+        self.save_stack.len() + 1
+    }
+
+    fn restore(&mut self) {
+        if let Some(transform) = self.save_stack.pop() {
+            self.transform = transform;
+            self.commands.push(Command::Restore);
+        }
+    }
+
+    fn draw_paint<'a>(&mut self, paint: impl Into<OptRef<'a, Self::Paint>>) {
+        self.commands.push(Command::DrawPaint {
+            paint: paint.into().to_owned(),
+            transform: self.transform.to_2d(),
+        });
+    }
+
+    fn draw_line<'a>(
+        &mut self,
+        from: impl Into<PixelPoint>,
+        to: impl Into<PixelPoint>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        let mut paint = paint.into().to_owned();
+        paint.draw_style = DrawStyle::Stroke;
+        let path = vec![
+            PathElement::MoveTo(from.into()),
+            PathElement::LineTo(to.into()),
+        ];
+        self.push_path(Path::new(path), &paint);
+    }
+
+    fn draw_dashed_line<'a>(
+        &mut self,
+        from: impl Into<PixelPoint>,
+        to: impl Into<PixelPoint>,
+        on_length: f32,
+        off_length: f32,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        let (from, to) = (from.into(), to.into());
+        let mut paint = paint.into().to_owned();
+        paint.draw_style = DrawStyle::Stroke;
+
+        let length = (to - from).length();
+        if on_length <= 0.0f32 || length <= 0.0f32 {
+            return;
+        }
+        if off_length <= 0.0f32 {
+            self.draw_line(from, to, paint);
+            return;
+        }
+
+        let direction = (to - from) / length;
+        let mut path = Vec::new();
+        let mut position = 0.0f32;
+        while position < length {
+            let end = (position + on_length).min(length);
+            path.push(PathElement::MoveTo(from + direction * position));
+            path.push(PathElement::LineTo(from + direction * end));
+            position = end + off_length;
+        }
+        self.push_path(Path::new(path), &paint);
+    }
+
+    fn draw_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.push_path(Path::new(rect_path(rect.into())), &paint.into());
+    }
+
+    fn draw_rounded_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        radii: impl Into<OptRef<'a, RoundingRadii>>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        let path = rect_rounded_radii_path(rect.into(), &radii.into());
+        self.push_path(Path::new(path), &paint.into());
+    }
+
+    fn draw_rounded_rect_difference<'a>(
+        &mut self,
+        outer_rect: impl Into<PixelRect>,
+        outer_radii: impl Into<OptRef<'a, RoundingRadii>>,
+        inner_rect: impl Into<PixelRect>,
+        inner_radii: impl Into<OptRef<'a, RoundingRadii>>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        // both outlines have the same direction, the inner one becomes a hole
+        // when filled with the even-odd rule
+        let mut path = rect_rounded_radii_path(outer_rect.into(), &outer_radii.into());
+        path.append(&mut rect_rounded_radii_path(
+            inner_rect.into(),
+            &inner_radii.into(),
+        ));
+        self.push_path(
+            Path {
+                path,
+                fill_type: FillType::Odd,
+            },
+            &paint.into(),
+        );
+    }
+
+    fn draw_oval<'a>(
+        &mut self,
+        oval_bounds: impl Into<PixelRect>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.push_path(Path::new(oval_path(oval_bounds.into())), &paint.into());
+    }
+
+    fn draw_path<'a>(
+        &mut self,
+        path: &<Self::PathBuilder as crate::PathBuilder>::Path,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.push_path(path.clone(), &paint.into());
+    }
+
+    fn draw_shadow(
+        &mut self,
+        path: &<Self::PathBuilder as crate::PathBuilder>::Path,
+        color: impl Into<crate::Color>,
+        elevation: f32,
+        _occluder_is_transparent: bool,
+        device_pixel_ratio: f32,
+    ) {
+        let ShadowParameters {
+            ambient_color,
+            ambient_sigma,
+            ambient_stroke_width,
+            spot_color,
+            spot_sigma,
+            spot_scale,
+            spot_translation,
+        } = ShadowParameters::new(
+            crate::Path::get_bounds(path),
+            color,
+            elevation,
+            device_pixel_ratio,
+        );
+
+        let ambient_paint = Paint {
+            color: ambient_color,
+            draw_style: if ambient_stroke_width > 0.0f32 {
+                DrawStyle::StrokeAndFill
+            } else {
+                DrawStyle::Fill
+            },
+            stroke_join: StrokeJoin::Round,
+            stroke_width: ambient_stroke_width,
+            mask_filter: Some(MaskFilter::Blur {
+                style: BlurStyle::Normal,
+                sigma: ambient_sigma,
+            }),
+            ..Default::default()
+        };
+        self.push_path(path.clone(), &ambient_paint);
+
+        let spot_paint = Paint {
+            color: spot_color,
+            mask_filter: Some(MaskFilter::Blur {
+                style: BlurStyle::Normal,
+                sigma: spot_sigma,
+            }),
+            ..Default::default()
+        };
+        let spot_transform = Matrix::scale(spot_scale, spot_scale, 1.0f32)
+            .then(&Matrix::translation(
+                spot_translation.0,
+                spot_translation.1,
+                0.0f32,
+            ))
+            .then(&self.transform);
+        self.commands.push(Command::DrawPath {
+            path: path.clone(),
+            transform: spot_transform.to_2d(),
+            paint: spot_paint,
+        });
+    }
+
+    fn draw_texture_rect<'a>(
+        &mut self,
+        texture: &Self::Texture,
+        src_rect: impl Into<PixelRect>,
+        dst_rect: impl Into<PixelRect>,
+        sampling: TextureSampling,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+    ) {
+        self.commands.push(Command::DrawTexture {
+            texture: texture.clone(),
+            src_rect: src_rect.into(),
+            dst_rect: dst_rect.into(),
+            sampling,
+            transform: self.transform.to_2d(),
+            paint: paint.into().map(|paint| paint.to_owned()),
+        });
+    }
+
+    fn draw_texture<'a>(
+        &mut self,
+        texture: &Self::Texture,
+        point: impl Into<PixelPoint>,
+        sampling: TextureSampling,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+    ) {
+        let descriptor = crate::Texture::get_descriptor(texture);
+        let size = crate::PixelSize::new(descriptor.width as f32, descriptor.height as f32);
+        self.draw_texture_rect(
+            texture,
+            PixelRect::new(PixelPoint::zero(), size),
+            PixelRect::new(point.into(), size),
+            sampling,
+            paint,
+        );
+    }
+
+    fn draw_paragraph(
+        &mut self,
+        location: impl Into<PixelPoint>,
+        paragraph: &<Self::ParagraphBuilder as crate::ParagraphBuilder>::Paragraph,
+    ) {
+        self.commands.push(Command::DrawParagraph {
+            paragraph: paragraph.clone(),
+            location: location.into(),
+            transform: self.transform.to_2d(),
+        });
+    }
+
+    fn draw_display_list(&mut self, display_list: &Self::DisplayList, opacity: f32) {
+        self.commands.push(Command::DrawDisplayList {
+            display_list: display_list.clone(),
+            transform: self.transform.to_2d(),
+            opacity,
+        });
+    }
+
+    fn build(mut self) -> Result<Self::DisplayList, &'static str> {
+        while !self.save_stack.is_empty() {
+            crate::DisplayListBuilder::restore(&mut self);
+        }
+        Ok(DisplayList {
+            commands: Arc::new(self.commands),
+        })
+    }
+}
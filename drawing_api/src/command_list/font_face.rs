@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+/// Horizontal metrics read from a TrueType / OpenType font file.
+///
+/// Only the tables needed for the text layout are parsed
+/// (`head`, `hhea`, `hmtx` and `cmap`), the other tables
+/// are accessed directly when the font is embedded.
+pub struct FontFace {
+    data: Arc<Vec<u8>>,
+    font_offset: usize,
+    head_offset: usize,
+    units_per_em: f32,
+    ascender: f32,
+    descender: f32,
+    hmtx_offset: usize,
+    number_of_h_metrics: usize,
+    cmap_offset: usize,
+    cmap_format: u16,
+}
+
+impl FontFace {
+    pub fn parse(data: Arc<Vec<u8>>) -> Option<FontFace> {
+        let font_offset = if data.get(0..4)? == b"ttcf" {
+            // take the first font of the collection
+            read_u32(&data, 12)? as usize
+        } else {
+            0
+        };
+
+        let (head, _) = find_table(&data, font_offset, b"head")?;
+        let (hhea, _) = find_table(&data, font_offset, b"hhea")?;
+        let (hmtx, _) = find_table(&data, font_offset, b"hmtx")?;
+        let (cmap, _) = find_table(&data, font_offset, b"cmap")?;
+
+        let units_per_em = read_u16(&data, head + 18)?.max(1) as f32;
+        let ascender = read_i16(&data, hhea + 4)? as f32;
+        let descender = -(read_i16(&data, hhea + 6)? as f32);
+        let number_of_h_metrics = read_u16(&data, hhea + 34)? as usize;
+
+        // prefer the full unicode subtable, then the basic multilingual plane one
+        let mut cmap_subtable = None;
+        let cmap_tables = read_u16(&data, cmap + 2)? as usize;
+        for i in 0..cmap_tables {
+            let record = cmap + 4 + i * 8;
+            let platform_id = read_u16(&data, record)?;
+            let encoding_id = read_u16(&data, record + 2)?;
+            let offset = cmap + read_u32(&data, record + 4)? as usize;
+            let format = read_u16(&data, offset)?;
+            let is_unicode =
+                platform_id == 0 || platform_id == 3 && (encoding_id == 1 || encoding_id == 10);
+            if is_unicode && (format == 12 || format == 4 && cmap_subtable.is_none()) {
+                cmap_subtable = Some((offset, format));
+            }
+        }
+        let (cmap_offset, cmap_format) = cmap_subtable?;
+
+        Some(FontFace {
+            data,
+            font_offset,
+            head_offset: head,
+            units_per_em,
+            ascender,
+            descender,
+            hmtx_offset: hmtx,
+            number_of_h_metrics,
+            cmap_offset,
+            cmap_format,
+        })
+    }
+
+    /// Distance from the baseline to the top in ems (positive).
+    pub fn get_ascent(&self) -> f32 {
+        self.ascender / self.units_per_em
+    }
+
+    /// Distance from the baseline to the bottom in ems (positive).
+    pub fn get_descent(&self) -> f32 {
+        self.descender / self.units_per_em
+    }
+
+    /// Returns the horizontal advance of the character in ems.
+    pub fn get_advance(&self, ch: char) -> f32 {
+        self.get_glyph_advance(self.get_glyph_index(ch))
+    }
+
+    /// Returns the horizontal advance of the glyph in ems.
+    pub fn get_glyph_advance(&self, glyph: u16) -> f32 {
+        let glyph = glyph as usize;
+        if self.number_of_h_metrics == 0 {
+            return 0.0f32;
+        }
+        // the glyphs after the last metric use the last advance
+        let index = glyph.min(self.number_of_h_metrics - 1);
+        read_u16(&self.data, self.hmtx_offset + index * 4).unwrap_or(0) as f32 / self.units_per_em
+    }
+
+    /// Bounding box of all the glyphs in ems (`[x_min, y_min, x_max, y_max]`).
+    pub fn get_bounding_box(&self) -> [f32; 4] {
+        [36, 38, 40, 42].map(|offset| {
+            read_i16(&self.data, self.head_offset + offset).unwrap_or(0) as f32 / self.units_per_em
+        })
+    }
+
+    /// Returns the PostScript name from the `name` table.
+    pub fn get_postscript_name(&self) -> Option<String> {
+        let name = self.get_table(b"name")?;
+        let count = read_u16(name, 2)? as usize;
+        let strings = read_u16(name, 4)? as usize;
+        (0..count).find_map(|i| {
+            let record = 6 + i * 12;
+            if read_u16(name, record + 6)? != 6 {
+                return None;
+            }
+            let platform_id = read_u16(name, record)?;
+            let length = read_u16(name, record + 8)? as usize;
+            let offset = strings + read_u16(name, record + 10)? as usize;
+            let bytes = name.get(offset..offset + length)?;
+            if platform_id == 1 {
+                // Macintosh, single byte Roman encoding
+                Some(bytes.iter().map(|b| *b as char).collect())
+            } else {
+                // Unicode and Windows, UTF-16BE
+                let chars = bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect::<Vec<_>>();
+                String::from_utf16(&chars).ok()
+            }
+        })
+    }
+
+    /// Returns the raw data of the table.
+    pub fn get_table(&self, tag: &[u8; 4]) -> Option<&[u8]> {
+        let (offset, length) = find_table(&self.data, self.font_offset, tag)?;
+        self.data.get(offset..offset + length)
+    }
+
+    pub fn get_glyph_index(&self, ch: char) -> u16 {
+        let code = ch as u32;
+        let data = &self.data;
+        let offset = self.cmap_offset;
+        match self.cmap_format {
+            4 => {
+                if code > 0xFFFF {
+                    return 0;
+                }
+                let seg_count = read_u16(data, offset + 6).unwrap_or(0) as usize / 2;
+                let end_codes = offset + 14;
+                let start_codes = end_codes + seg_count * 2 + 2;
+                let id_deltas = start_codes + seg_count * 2;
+                let id_range_offsets = id_deltas + seg_count * 2;
+                for i in 0..seg_count {
+                    let end = read_u16(data, end_codes + i * 2).unwrap_or(0) as u32;
+                    if end < code {
+                        continue;
+                    }
+                    let start = read_u16(data, start_codes + i * 2).unwrap_or(0) as u32;
+                    if start > code {
+                        return 0;
+                    }
+                    let delta = read_u16(data, id_deltas + i * 2).unwrap_or(0);
+                    let range_offset_position = id_range_offsets + i * 2;
+                    let range_offset = read_u16(data, range_offset_position).unwrap_or(0) as usize;
+                    if range_offset == 0 {
+                        return (code as u16).wrapping_add(delta);
+                    }
+                    let position =
+                        range_offset_position + range_offset + (code - start) as usize * 2;
+                    return match read_u16(data, position) {
+                        Some(0) | None => 0,
+                        Some(glyph) => glyph.wrapping_add(delta),
+                    };
+                }
+                0
+            }
+            12 => {
+                let groups = read_u32(data, offset + 12).unwrap_or(0) as usize;
+                for i in 0..groups {
+                    let group = offset + 16 + i * 12;
+                    let start = read_u32(data, group).unwrap_or(0);
+                    let end = read_u32(data, group + 4).unwrap_or(0);
+                    if (start..=end).contains(&code) {
+                        let start_glyph = read_u32(data, group + 8).unwrap_or(0);
+                        return (start_glyph + code - start) as u16;
+                    }
+                }
+                0
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Returns the offset and the length of the table in the font file.
+fn find_table(data: &[u8], font_offset: usize, tag: &[u8; 4]) -> Option<(usize, usize)> {
+    let num_tables = read_u16(data, font_offset + 4)? as usize;
+    (0..num_tables).find_map(|i| {
+        let record = font_offset + 12 + i * 16;
+        if data.get(record..record + 4)? == tag {
+            Some((
+                read_u32(data, record + 8)? as usize,
+                read_u32(data, record + 12)? as usize,
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// Reads a big-endian value from the font data.
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a big-endian value from the font data.
+pub fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+    Some(i16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a big-endian value from the font data.
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::{FontFace, FontMetrics};
+
+// metrics (in ems) used when no font is registered
+const FALLBACK_ASCENT: f32 = 0.8f32;
+const FALLBACK_DESCENT: f32 = 0.2f32;
+const FALLBACK_ADVANCE: f32 = 0.5f32;
+
+/// Registered font file.
+pub struct Font {
+    pub data: Arc<Vec<u8>>,
+    pub face: FontFace,
+}
+
+/// Registered fonts of the backends without a font rasterizer.
+///
+/// The fonts are used for the text layout and can be embedded
+/// in the documents (like by the SVG and PDF writers).
+#[derive(Clone, Default)]
+pub struct Fonts {
+    fonts: Arc<Mutex<HashMap<String, Arc<Font>>>>,
+}
+
+impl Fonts {
+    /// Returns the registered font with the family name and the font itself.
+    /// Falls back to the "default" family.
+    pub fn get_font(&self, family_name: &str) -> Option<(String, Arc<Font>)> {
+        let fonts = self.fonts.lock().unwrap();
+        [family_name, "default"]
+            .into_iter()
+            .find_map(|family_name| {
+                fonts
+                    .get(family_name)
+                    .map(|font| (family_name.to_string(), font.clone()))
+            })
+    }
+
+    /// Returns the font metrics and the horizontal advances of the characters in the text.
+    pub fn get_advances(
+        &self,
+        family_name: &str,
+        size: f32,
+        text: &str,
+    ) -> (FontMetrics, HashMap<char, f32>) {
+        match self.get_font(family_name) {
+            Some((_, font)) => (
+                FontMetrics {
+                    ascent: font.face.get_ascent() * size,
+                    descent: font.face.get_descent() * size,
+                },
+                text.chars()
+                    .map(|ch| (ch, font.face.get_advance(ch) * size))
+                    .collect(),
+            ),
+            None => (
+                FontMetrics {
+                    ascent: FALLBACK_ASCENT * size,
+                    descent: FALLBACK_DESCENT * size,
+                },
+                text.chars()
+                    .map(|ch| (ch, FALLBACK_ADVANCE * size))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl crate::Fonts for Fonts {
+    fn register_font(
+        &mut self,
+        font_data: Cow<'static, [u8]>,
+        family_name_alias: Option<&str>,
+    ) -> Result<(), &'static str> {
+        let data = Arc::new(Vec::from(font_data));
+        let face = FontFace::parse(data.clone()).ok_or("face: cannot load font")?;
+
+        let family_name = family_name_alias.unwrap_or("default");
+        self.fonts
+            .lock()
+            .unwrap()
+            .insert(family_name.into(), Arc::new(Font { data, face }));
+        Ok(())
+    }
+}
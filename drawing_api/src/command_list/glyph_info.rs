@@ -0,0 +1,29 @@
+use crate::{PixelRect, TextDirection};
+
+pub struct GlyphInfo {
+    pub begin_utf16: usize,
+    pub end_utf16: usize,
+    pub bounds: PixelRect,
+}
+
+impl crate::GlyphInfo for GlyphInfo {
+    fn get_grapheme_cluster_code_unit_range_begin_utf16(&self) -> usize {
+        self.begin_utf16
+    }
+
+    fn get_grapheme_cluster_code_unit_range_end_utf16(&self) -> usize {
+        self.end_utf16
+    }
+
+    fn get_grapheme_cluster_bounds(&self) -> PixelRect {
+        self.bounds
+    }
+
+    fn is_ellipsis(&self) -> bool {
+        false
+    }
+
+    fn get_text_direction(&self) -> TextDirection {
+        TextDirection::LTR
+    }
+}
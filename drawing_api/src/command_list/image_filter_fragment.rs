@@ -0,0 +1,4 @@
+#[derive(Clone)]
+pub struct ImageFilterFragment {}
+
+impl crate::ImageFilterFragment for ImageFilterFragment {}
@@ -0,0 +1,70 @@
+/// Metrics of a single line of a paragraph.
+#[derive(Clone, Debug)]
+pub struct LineInfo {
+    pub unscaled_ascent: f64,
+    pub ascent: f64,
+    pub descent: f64,
+    pub baseline: f64,
+    pub hardbreak: bool,
+    pub width: f64,
+    pub height: f64,
+    pub left: f64,
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+    pub end_excluding_whitespace_utf16: usize,
+    pub end_including_newline_utf16: usize,
+}
+
+pub struct LineMetrics {
+    pub lines: Vec<LineInfo>,
+}
+
+impl crate::LineMetrics for LineMetrics {
+    fn get_unscaled_ascent(&self, line: usize) -> f64 {
+        self.lines[line].unscaled_ascent
+    }
+
+    fn get_ascent(&self, line: usize) -> f64 {
+        self.lines[line].ascent
+    }
+
+    fn get_descent(&self, line: usize) -> f64 {
+        self.lines[line].descent
+    }
+
+    fn get_baseline(&self, line: usize) -> f64 {
+        self.lines[line].baseline
+    }
+
+    fn is_hardbreak(&self, line: usize) -> bool {
+        self.lines[line].hardbreak
+    }
+
+    fn get_width(&self, line: usize) -> f64 {
+        self.lines[line].width
+    }
+
+    fn get_height(&self, line: usize) -> f64 {
+        self.lines[line].height
+    }
+
+    fn get_left(&self, line: usize) -> f64 {
+        self.lines[line].left
+    }
+
+    fn get_code_unit_start_index_utf16(&self, line: usize) -> usize {
+        self.lines[line].start_utf16
+    }
+
+    fn get_code_unit_end_index_utf16(&self, line: usize) -> usize {
+        self.lines[line].end_utf16
+    }
+
+    fn get_code_unit_end_index_excluding_whitespace_utf16(&self, line: usize) -> usize {
+        self.lines[line].end_excluding_whitespace_utf16
+    }
+
+    fn get_code_unit_end_index_including_newline_utf16(&self, line: usize) -> usize {
+        self.lines[line].end_including_newline_utf16
+    }
+}
@@ -0,0 +1,60 @@
+//! Display list front end shared by the backends which draw the commands themselves.
+//!
+//! `DisplayListBuilder` flattens the shapes into paths and records `Command`s
+//! with absolute transformations, `ParagraphBuilder` lays out the text
+//! with the font metrics provided by the backend.
+//! The backend (like the CPU rasterizer or the SVG and PDF writers)
+//! implements `Backend` and walks the commands of the built `DisplayList`.
+//!
+//! `TextLayout` is also used by the paragraphs of the recorded display lists.
+
+mod backend;
+pub use backend::*;
+
+mod color_source_fragment;
+pub use color_source_fragment::*;
+
+mod command;
+pub use command::*;
+
+mod display_list;
+pub use display_list::*;
+
+mod display_list_builder;
+pub use display_list_builder::*;
+
+mod font_face;
+pub use font_face::*;
+
+mod fonts;
+pub use fonts::*;
+
+mod glyph_info;
+pub use glyph_info::*;
+
+mod image_filter_fragment;
+pub use image_filter_fragment::*;
+
+mod line_metrics;
+pub use line_metrics::*;
+
+mod paint;
+pub use paint::*;
+
+mod paragraph;
+pub use paragraph::*;
+
+mod paragraph_builder;
+pub use paragraph_builder::*;
+
+mod path;
+pub use path::*;
+
+mod path_builder;
+pub use path_builder::*;
+
+mod shapes;
+pub use shapes::*;
+
+mod text_layout;
+pub use text_layout::*;
@@ -0,0 +1,91 @@
+use crate::{BlendMode, DrawStyle, StrokeCap, StrokeJoin};
+
+use super::{Backend, ColorSourceFragment, ImageFilterFragment};
+
+#[derive(Clone)]
+pub struct Paint<B: Backend> {
+    // straight (not premultiplied) RGBA color
+    pub color: [f32; 4],
+    pub blend_mode: BlendMode,
+    pub draw_style: DrawStyle,
+    pub stroke_cap: StrokeCap,
+    pub stroke_join: StrokeJoin,
+    pub stroke_width: f32,
+    pub stroke_miter: f32,
+    pub color_filter: Option<crate::ColorFilter>,
+    pub image_filter: Option<crate::ImageFilter<ImageFilterFragment>>,
+    pub color_source: Option<crate::ColorSource<B::Texture, ColorSourceFragment>>,
+    pub mask_filter: Option<crate::MaskFilter>,
+}
+
+impl<B: Backend> Default for Paint<B> {
+    fn default() -> Self {
+        Self {
+            color: [0.0f32, 0.0f32, 0.0f32, 1.0f32],
+            blend_mode: BlendMode::SourceOver,
+            draw_style: DrawStyle::Fill,
+            stroke_cap: StrokeCap::Butt,
+            stroke_join: StrokeJoin::Miter,
+            stroke_width: 0.0f32, // hairline width
+            stroke_miter: 4.0f32,
+            color_filter: None,
+            image_filter: None,
+            color_source: None,
+            mask_filter: None,
+        }
+    }
+}
+
+impl<B: Backend> crate::Paint for Paint<B> {
+    type ColorSourceFragment = ColorSourceFragment;
+    type ImageFilterFragment = ImageFilterFragment;
+    type Texture = B::Texture;
+
+    fn set_color(&mut self, color: impl Into<crate::Color>) {
+        let color = color.into();
+        self.color = [color.red, color.green, color.blue, color.alpha];
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    fn set_draw_style(&mut self, draw_style: crate::DrawStyle) {
+        self.draw_style = draw_style;
+    }
+
+    fn set_stroke_cap(&mut self, cap: StrokeCap) {
+        self.stroke_cap = cap;
+    }
+
+    fn set_stroke_join(&mut self, join: StrokeJoin) {
+        self.stroke_join = join;
+    }
+
+    fn set_stroke_width(&mut self, width: f32) {
+        self.stroke_width = width;
+    }
+
+    fn set_stroke_miter(&mut self, miter: f32) {
+        self.stroke_miter = miter;
+    }
+
+    fn set_color_source(
+        &mut self,
+        color_source: crate::ColorSource<Self::Texture, ColorSourceFragment>,
+    ) {
+        self.color_source = Some(color_source);
+    }
+
+    fn set_color_filter(&mut self, color_filter: crate::ColorFilter) {
+        self.color_filter = Some(color_filter);
+    }
+
+    fn set_image_filter(&mut self, image_filter: crate::ImageFilter<ImageFilterFragment>) {
+        self.image_filter = Some(image_filter);
+    }
+
+    fn set_mask_filter(&mut self, mask_filter: crate::MaskFilter) {
+        self.mask_filter = Some(mask_filter);
+    }
+}
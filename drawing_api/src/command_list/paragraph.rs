@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use crate::{FontStyle, FontWeight, PixelRect, Range};
+
+use super::{Backend, GlyphInfo, LineMetrics, Paint, PositionedGlyph, TextLayout};
+
+/// Part of the paragraph text drawn with the same style.
+#[derive(Clone)]
+pub struct TextRun<B: Backend> {
+    pub family: String,
+    pub size: f32,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+    pub foreground: Option<Paint<B>>,
+    pub background: Option<Paint<B>>,
+}
+
+pub struct ParagraphData<B: Backend> {
+    pub fonts: B::Fonts,
+    pub max_width: f32,
+    pub runs: Vec<TextRun<B>>,
+    pub layout: TextLayout,
+}
+
+#[derive(Clone)]
+pub struct Paragraph<B: Backend> {
+    pub data: Arc<ParagraphData<B>>,
+}
+
+impl<B: Backend> Paragraph<B> {
+    /// Returns the text runs with their glyphs.
+    pub fn get_runs(&self) -> impl Iterator<Item = (&TextRun<B>, &[PositionedGlyph])> {
+        self.data
+            .runs
+            .iter()
+            .zip(self.data.layout.runs.iter().map(Vec::as_slice))
+    }
+
+    pub fn get_glyph_bounds(&self, glyph: &PositionedGlyph) -> PixelRect {
+        self.data.layout.get_glyph_bounds(glyph)
+    }
+}
+
+impl<B: Backend> crate::Paragraph for Paragraph<B> {
+    type GlyphInfo = GlyphInfo;
+
+    type LineMetrics = LineMetrics;
+
+    fn get_max_width(&self) -> f32 {
+        self.data.max_width
+    }
+
+    fn get_height(&self) -> f32 {
+        self.data.layout.get_height()
+    }
+
+    fn get_longest_line_width(&self) -> f32 {
+        self.data.layout.get_longest_line_width()
+    }
+
+    fn get_min_intrinsic_width(&self) -> f32 {
+        self.data.layout.min_intrinsic_width
+    }
+
+    fn get_max_intrinsic_width(&self) -> f32 {
+        self.data.layout.max_intrinsic_width
+    }
+
+    fn get_ideographic_baseline(&self) -> f32 {
+        self.data.layout.get_ideographic_baseline()
+    }
+
+    fn get_alphabetic_baseline(&self) -> f32 {
+        self.data.layout.get_alphabetic_baseline()
+    }
+
+    fn get_line_count(&self) -> u32 {
+        self.data.layout.lines.len() as u32
+    }
+
+    fn get_line_metrics(&self) -> Option<Self::LineMetrics> {
+        Some(self.data.layout.get_line_metrics())
+    }
+
+    fn get_word_boundary_utf16(&self, code_unit_index: usize) -> Range {
+        self.data.layout.get_word_boundary_utf16(code_unit_index)
+    }
+
+    fn create_glyph_info_at_code_unit_index_utf16(
+        &self,
+        code_unit_index: usize,
+    ) -> Option<Self::GlyphInfo> {
+        self.data
+            .layout
+            .create_glyph_info_at_code_unit_index_utf16(code_unit_index)
+    }
+
+    fn create_glyph_info_at_paragraph_coordinates(
+        &self,
+        x: f64,
+        y: f64,
+    ) -> Option<Self::GlyphInfo> {
+        self.data
+            .layout
+            .create_glyph_info_at_paragraph_coordinates(x, y)
+    }
+}
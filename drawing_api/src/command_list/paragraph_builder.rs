@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::{smart_pointers::OptRef, ParagraphStyle};
+
+use super::{
+    Backend, Paint, Paragraph, ParagraphData, TextLayoutBuilder, TextRun, VerticalMetrics,
+};
+
+pub struct ParagraphBuilder<B: Backend> {
+    fonts: B::Fonts,
+    styles: Vec<ParagraphStyle<Paint<B>>>,
+    chunks: Vec<(ParagraphStyle<Paint<B>>, String)>,
+}
+
+impl<B: Backend> crate::ParagraphBuilder for ParagraphBuilder<B> {
+    type Paragraph = Paragraph<B>;
+    type Paint = Paint<B>;
+    type Fonts = B::Fonts;
+    type Texture = B::Texture;
+
+    fn new(fonts: &B::Fonts) -> Result<Self, &'static str> {
+        Ok(ParagraphBuilder {
+            fonts: fonts.clone(),
+            styles: Vec::new(),
+            chunks: Vec::new(),
+        })
+    }
+
+    fn push_style<'a>(&mut self, style: impl Into<OptRef<'a, ParagraphStyle<Paint<B>>>>) {
+        let style = style.into();
+        self.styles.push(style.to_owned());
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+    }
+
+    fn add_text(&mut self, text: &str) {
+        let style = self.styles.last().cloned().unwrap_or_default();
+        self.chunks.push((style, text.to_owned()));
+    }
+
+    fn build(self, width: f32) -> Result<Self::Paragraph, &'static str> {
+        let mut layout = TextLayoutBuilder::new(width);
+        let mut runs = Vec::with_capacity(self.chunks.len());
+
+        for (style, chunk) in self.chunks {
+            let (font_metrics, advances) =
+                B::get_advances(&self.fonts, &style.family, style.size, &chunk)?;
+            layout.add_run(
+                &chunk,
+                VerticalMetrics::new(font_metrics, style.size, style.height_factor),
+                |ch| advances.get(&ch).copied().unwrap_or(0.0f32),
+            );
+
+            runs.push(TextRun {
+                family: style.family,
+                size: style.size,
+                weight: style.weight,
+                style: style.style,
+                foreground: style.foreground,
+                background: style.background,
+            });
+        }
+
+        Ok(Paragraph {
+            data: Arc::new(ParagraphData {
+                fonts: self.fonts,
+                max_width: width,
+                runs,
+                layout: layout.build(),
+            }),
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{FillType, PixelPoint, PixelRect};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathElement {
+    MoveTo(PixelPoint),
+    LineTo(PixelPoint),
+    BezierTo(PixelPoint, PixelPoint, PixelPoint),
+    ClosePath,
+}
+
+#[derive(Clone)]
+pub struct Path {
+    pub path: Vec<PathElement>,
+    pub fill_type: FillType,
+}
+
+impl Path {
+    pub fn new(path: Vec<PathElement>) -> Self {
+        Self {
+            path,
+            fill_type: FillType::NonZero,
+        }
+    }
+}
+
+impl crate::Path for Path {
+    fn get_bounds(&self) -> PixelRect {
+        let points = self.path.iter().flat_map(|element| match element {
+            PathElement::MoveTo(p) | PathElement::LineTo(p) => vec![*p],
+            PathElement::BezierTo(c1, c2, p) => vec![*c1, *c2, *p],
+            PathElement::ClosePath => Vec::new(),
+        });
+        PixelRect::from_points(points.collect::<Vec<PixelPoint>>())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{FillType, PixelPoint};
+
+use super::{arc_path, oval_path, rect_rounded_radii_path, PathElement};
+
+pub struct PathBuilder {
+    path: Vec<PathElement>,
+    fill_type: FillType,
+}
+
+impl Default for PathBuilder {
+    fn default() -> Self {
+        Self {
+            path: Vec::new(),
+            fill_type: FillType::NonZero,
+        }
+    }
+}
+
+impl crate::PathBuilder for PathBuilder {
+    type Path = super::Path;
+
+    fn set_fill_type(&mut self, fill_type: FillType) {
+        self.fill_type = fill_type;
+    }
+
+    fn move_to(&mut self, location: impl Into<PixelPoint>) {
+        self.path.push(PathElement::MoveTo(location.into()));
+    }
+
+    fn line_to(&mut self, location: impl Into<PixelPoint>) {
+        self.path.push(PathElement::LineTo(location.into()));
+    }
+
+    fn cubic_curve_to(
+        &mut self,
+        control_point_1: impl Into<PixelPoint>,
+        control_point_2: impl Into<PixelPoint>,
+        end_point: impl Into<PixelPoint>,
+    ) {
+        self.path.push(PathElement::BezierTo(
+            control_point_1.into(),
+            control_point_2.into(),
+            end_point.into(),
+        ));
+    }
+
+    fn add_rounded_rect(
+        &mut self,
+        rect: impl Into<crate::PixelRect>,
+        rounding_radii: &crate::RoundingRadii,
+    ) {
+        self.path
+            .append(&mut rect_rounded_radii_path(rect.into(), rounding_radii));
+    }
+
+    fn add_oval(&mut self, oval_bounds: impl Into<crate::PixelRect>) {
+        self.path.append(&mut oval_path(oval_bounds.into()));
+    }
+
+    fn add_arc(
+        &mut self,
+        oval_bounds: impl Into<crate::PixelRect>,
+        start_angle_degrees: f32,
+        end_angle_degrees: f32,
+    ) {
+        self.path.append(&mut arc_path(
+            oval_bounds.into(),
+            start_angle_degrees,
+            end_angle_degrees,
+        ));
+    }
+
+    fn close(&mut self) {
+        self.path.push(PathElement::ClosePath);
+    }
+
+    fn build(self) -> Self::Path {
+        super::Path {
+            path: self.path,
+            fill_type: self.fill_type,
+        }
+    }
+
+    fn build_copy(&mut self) -> Self::Path {
+        super::Path {
+            path: self.path.clone(),
+            fill_type: self.fill_type,
+        }
+    }
+}
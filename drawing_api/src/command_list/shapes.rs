@@ -0,0 +1,140 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use crate::{PixelPoint, PixelRect, PixelSize, RoundingRadii};
+
+use super::PathElement;
+
+const KAPPA90: f32 = 0.552_284_8;
+
+pub fn rect_path(rect: PixelRect) -> Vec<PathElement> {
+    vec![
+        PathElement::MoveTo(PixelPoint::new(rect.min_x(), rect.min_y())),
+        PathElement::LineTo(PixelPoint::new(rect.max_x(), rect.min_y())),
+        PathElement::LineTo(PixelPoint::new(rect.max_x(), rect.max_y())),
+        PathElement::LineTo(PixelPoint::new(rect.min_x(), rect.max_y())),
+        PathElement::ClosePath,
+    ]
+}
+
+/// Creates a rounded rectangle path with separate horizontal and vertical radius for every corner.
+///
+/// When the radii of the neighbouring corners do not fit on the side of the rectangle,
+/// all the radii are scaled down proportionally.
+pub fn rect_rounded_radii_path(rect: PixelRect, radii: &RoundingRadii) -> Vec<PathElement> {
+    let (left, top, right, bottom) = (rect.min_x(), rect.min_y(), rect.max_x(), rect.max_y());
+    let (width, height) = (right - left, bottom - top);
+
+    let clamp = |size: PixelSize| PixelSize::new(size.width.max(0.0), size.height.max(0.0));
+    let lt = clamp(radii.top_left);
+    let rt = clamp(radii.top_right);
+    let rb = clamp(radii.bottom_right);
+    let lb = clamp(radii.bottom_left);
+
+    let mut scale = 1.0f32;
+    for (length, r1, r2) in [
+        (width, lt.width, rt.width),
+        (width, lb.width, rb.width),
+        (height, lt.height, lb.height),
+        (height, rt.height, rb.height),
+    ] {
+        if r1 + r2 > length {
+            scale = scale.min(length / (r1 + r2));
+        }
+    }
+    let (lt, rt, rb, lb) = (lt * scale, rt * scale, rb * scale, lb * scale);
+
+    if [lt, rt, rb, lb]
+        .iter()
+        .all(|r| r.width <= 0.0f32 || r.height <= 0.0f32)
+    {
+        return rect_path(rect);
+    }
+
+    vec![
+        PathElement::MoveTo(PixelPoint::new(left + lt.width, top)),
+        PathElement::LineTo(PixelPoint::new(right - rt.width, top)),
+        PathElement::BezierTo(
+            PixelPoint::new(right - rt.width * (1.0 - KAPPA90), top),
+            PixelPoint::new(right, top + rt.height * (1.0 - KAPPA90)),
+            PixelPoint::new(right, top + rt.height),
+        ),
+        PathElement::LineTo(PixelPoint::new(right, bottom - rb.height)),
+        PathElement::BezierTo(
+            PixelPoint::new(right, bottom - rb.height * (1.0 - KAPPA90)),
+            PixelPoint::new(right - rb.width * (1.0 - KAPPA90), bottom),
+            PixelPoint::new(right - rb.width, bottom),
+        ),
+        PathElement::LineTo(PixelPoint::new(left + lb.width, bottom)),
+        PathElement::BezierTo(
+            PixelPoint::new(left + lb.width * (1.0 - KAPPA90), bottom),
+            PixelPoint::new(left, bottom - lb.height * (1.0 - KAPPA90)),
+            PixelPoint::new(left, bottom - lb.height),
+        ),
+        PathElement::LineTo(PixelPoint::new(left, top + lt.height)),
+        PathElement::BezierTo(
+            PixelPoint::new(left, top + lt.height * (1.0 - KAPPA90)),
+            PixelPoint::new(left + lt.width * (1.0 - KAPPA90), top),
+            PixelPoint::new(left + lt.width, top),
+        ),
+        PathElement::ClosePath,
+    ]
+}
+
+/// Creates an ellipse path inscribed in the given rectangle.
+pub fn oval_path(oval_bounds: PixelRect) -> Vec<PathElement> {
+    let mut path = arc_path(oval_bounds, 0.0f32, 360.0f32);
+    path.push(PathElement::ClosePath);
+    path
+}
+
+/// Creates an open path of the elliptical arc inscribed in the given rectangle.
+///
+/// # Arguments
+///
+/// * `oval_bounds` - bounds of the ellipse
+/// * `start_angle_degrees` - angle of the starting point, measured clockwise from the positive x-axis
+/// * `end_angle_degrees` - angle of the ending point, the arc is drawn clockwise when it is greater than the start angle
+pub fn arc_path(
+    oval_bounds: PixelRect,
+    start_angle_degrees: f32,
+    end_angle_degrees: f32,
+) -> Vec<PathElement> {
+    let center = oval_bounds.center();
+    let radius_x = oval_bounds.size.width * 0.5f32;
+    let radius_y = oval_bounds.size.height * 0.5f32;
+
+    let start_angle = start_angle_degrees.to_radians();
+    let sweep_angle = (end_angle_degrees - start_angle_degrees)
+        .to_radians()
+        .clamp(-2.0f32 * PI, 2.0f32 * PI);
+
+    // every bezier segment spans at most 90 degrees
+    let segments = ((sweep_angle.abs() / FRAC_PI_2).ceil() as usize).max(1);
+    let delta = sweep_angle / segments as f32;
+    let kappa = 4.0f32 / 3.0f32 * (delta * 0.25f32).tan();
+
+    let mut res = Vec::with_capacity(segments + 1);
+    let (sin0, cos0) = start_angle.sin_cos();
+    res.push(PathElement::MoveTo(PixelPoint::new(
+        center.x + radius_x * cos0,
+        center.y + radius_y * sin0,
+    )));
+
+    for i in 0..segments {
+        let (sin0, cos0) = (start_angle + delta * i as f32).sin_cos();
+        let (sin1, cos1) = (start_angle + delta * (i + 1) as f32).sin_cos();
+        res.push(PathElement::BezierTo(
+            PixelPoint::new(
+                center.x + radius_x * (cos0 - kappa * sin0),
+                center.y + radius_y * (sin0 + kappa * cos0),
+            ),
+            PixelPoint::new(
+                center.x + radius_x * (cos1 + kappa * sin1),
+                center.y + radius_y * (sin1 - kappa * cos1),
+            ),
+            PixelPoint::new(center.x + radius_x * cos1, center.y + radius_y * sin1),
+        ));
+    }
+
+    res
+}
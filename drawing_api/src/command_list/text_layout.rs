@@ -0,0 +1,508 @@
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+
+use crate::{PixelPoint, PixelRect, PixelSize, Range};
+
+use super::{FontMetrics, GlyphInfo, LineInfo, LineMetrics};
+
+/// Character positioned by the paragraph layout.
+#[derive(Clone, Debug)]
+pub struct PositionedGlyph {
+    pub ch: char,
+
+    /// Position of the glyph origin on the baseline.
+    pub position: PixelPoint,
+
+    pub advance: f32,
+    pub line: usize,
+    pub begin_utf16: usize,
+    pub end_utf16: usize,
+}
+
+/// Vertical metrics of the text of a run.
+#[derive(Copy, Clone, Default)]
+pub struct VerticalMetrics {
+    pub unscaled_ascent: f32,
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+impl VerticalMetrics {
+    /// Scales the font metrics so the line height is `size * height_factor` (if specified).
+    pub fn new(font_metrics: FontMetrics, size: f32, height_factor: Option<f32>) -> Self {
+        let (ascent, descent) = match height_factor {
+            Some(height_factor) => {
+                let font_height = (font_metrics.ascent + font_metrics.descent).max(1.0e-6f32);
+                let scale = size * height_factor / font_height;
+                (font_metrics.ascent * scale, font_metrics.descent * scale)
+            }
+            None => (font_metrics.ascent, font_metrics.descent),
+        };
+        Self {
+            unscaled_ascent: font_metrics.ascent,
+            ascent,
+            descent,
+        }
+    }
+
+    fn max(self, other: VerticalMetrics) -> VerticalMetrics {
+        VerticalMetrics {
+            unscaled_ascent: self.unscaled_ascent.max(other.unscaled_ascent),
+            ascent: self.ascent.max(other.ascent),
+            descent: self.descent.max(other.descent),
+        }
+    }
+}
+
+/// Text laid out from left to right.
+///
+/// The lines are broken on the new line characters and wrapped
+/// at the line break opportunities (UAX #14) to fit the width.
+pub struct TextLayout {
+    pub text: String,
+    pub lines: Vec<LineInfo>,
+
+    /// Glyphs of the runs in the order they were added.
+    pub runs: Vec<Vec<PositionedGlyph>>,
+
+    pub min_intrinsic_width: f32,
+    pub max_intrinsic_width: f32,
+}
+
+/// Measured character of the text.
+struct CharInfo {
+    ch: char,
+    run: usize,
+    advance: f32,
+    metrics: VerticalMetrics,
+    begin_utf16: usize,
+}
+
+/// Characters placed on a line.
+struct LineRange {
+    start: usize,
+    end: usize,
+    hardbreak: bool,
+}
+
+pub struct TextLayoutBuilder {
+    max_width: f32,
+    text: String,
+    chars: Vec<CharInfo>,
+    run_count: usize,
+    default_metrics: VerticalMetrics,
+    position: usize,
+}
+
+impl TextLayoutBuilder {
+    /// Creates the builder wrapping the lines longer than `max_width`.
+    pub fn new(max_width: f32) -> Self {
+        Self {
+            max_width: max_width.max(0.0f32),
+            text: String::new(),
+            chars: Vec::new(),
+            run_count: 0,
+            default_metrics: VerticalMetrics::default(),
+            position: 0,
+        }
+    }
+
+    /// Measures the text of the next run.
+    pub fn add_run(
+        &mut self,
+        text: &str,
+        metrics: VerticalMetrics,
+        get_advance: impl Fn(char) -> f32,
+    ) {
+        self.default_metrics = metrics;
+        for ch in text.chars() {
+            self.chars.push(CharInfo {
+                ch,
+                run: self.run_count,
+                advance: if is_line_break(ch) {
+                    0.0f32
+                } else {
+                    get_advance(ch)
+                },
+                metrics,
+                begin_utf16: self.position,
+            });
+            self.position += ch.len_utf16();
+        }
+        self.text.push_str(text);
+        self.run_count += 1;
+    }
+
+    pub fn build(self) -> TextLayout {
+        let chars = &self.chars;
+        let max_width = self.max_width;
+
+        // x positions of the characters when placed on a single line
+        let mut offsets = Vec::with_capacity(chars.len() + 1);
+        offsets.push(0.0f32);
+        for ch in chars {
+            offsets.push(offsets.last().unwrap() + ch.advance);
+        }
+        let width_of = |start: usize, end: usize| offsets[end] - offsets[start];
+        let visible_end = |start: usize, mut end: usize| {
+            while end > start && chars[end - 1].ch.is_whitespace() {
+                end -= 1;
+            }
+            end
+        };
+
+        // the break opportunities (UAX #14) as the character indices
+        let mut segments = Vec::new();
+        let mut char_index = 0usize;
+        let mut byte_index = 0usize;
+        for (byte, opportunity) in linebreaks(&self.text) {
+            while byte_index < byte {
+                byte_index += chars[char_index].ch.len_utf8();
+                char_index += 1;
+            }
+            segments.push((char_index, opportunity == BreakOpportunity::Mandatory));
+        }
+
+        // break the lines
+        let mut lines = Vec::new();
+        let mut line_start = 0usize;
+        let mut segment_start = 0usize;
+        let mut min_intrinsic_width = 0.0f32;
+        let mut max_intrinsic_width = 0.0f32;
+        let mut hard_line_start = 0usize;
+        for (segment_end, mandatory) in segments {
+            let segment_visible_end = visible_end(segment_start, segment_end);
+            min_intrinsic_width =
+                min_intrinsic_width.max(width_of(segment_start, segment_visible_end));
+
+            if line_start < segment_start && width_of(line_start, segment_visible_end) > max_width {
+                lines.push(LineRange {
+                    start: line_start,
+                    end: segment_start,
+                    hardbreak: false,
+                });
+                line_start = segment_start;
+            }
+
+            // the word longer than the line is broken between the characters
+            while width_of(line_start, visible_end(line_start, segment_end)) > max_width {
+                let mut end = line_start + 1;
+                while width_of(line_start, end + 1) <= max_width {
+                    end += 1;
+                }
+                lines.push(LineRange {
+                    start: line_start,
+                    end,
+                    hardbreak: false,
+                });
+                line_start = end;
+            }
+
+            if mandatory && segment_end > 0 && is_line_break(chars[segment_end - 1].ch) {
+                lines.push(LineRange {
+                    start: line_start,
+                    end: segment_end,
+                    hardbreak: true,
+                });
+                max_intrinsic_width = max_intrinsic_width.max(width_of(
+                    hard_line_start,
+                    visible_end(hard_line_start, segment_end),
+                ));
+                line_start = segment_end;
+                hard_line_start = segment_end;
+            }
+
+            segment_start = segment_end;
+        }
+        lines.push(LineRange {
+            start: line_start,
+            end: chars.len(),
+            hardbreak: false,
+        });
+        max_intrinsic_width = max_intrinsic_width.max(width_of(
+            hard_line_start,
+            visible_end(hard_line_start, chars.len()),
+        ));
+
+        // place the glyphs on the baselines
+        let utf16_at = |index: usize| chars.get(index).map_or(self.position, |ch| ch.begin_utf16);
+        let mut runs = (0..self.run_count).map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut line_infos: Vec<LineInfo> = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            let metrics = chars[line.start..line.end]
+                .iter()
+                .map(|ch| ch.metrics)
+                .reduce(VerticalMetrics::max)
+                .or_else(|| chars[..line.start].last().map(|ch| ch.metrics))
+                .unwrap_or(self.default_metrics);
+            let top = line_infos
+                .last()
+                .map_or(0.0f64, |line| line.baseline + line.descent);
+            let baseline = top + metrics.ascent as f64;
+
+            for (char_index, ch) in chars.iter().enumerate().take(line.end).skip(line.start) {
+                if is_line_break(ch.ch) {
+                    continue;
+                }
+                runs[ch.run].push(PositionedGlyph {
+                    ch: ch.ch,
+                    position: PixelPoint::new(width_of(line.start, char_index), baseline as f32),
+                    advance: ch.advance,
+                    line: index,
+                    begin_utf16: ch.begin_utf16,
+                    end_utf16: ch.begin_utf16 + ch.ch.len_utf16(),
+                });
+            }
+
+            let line_visible_end = visible_end(line.start, line.end);
+            let mut end_excluding_newline = line.end;
+            while end_excluding_newline > line.start
+                && is_line_break(chars[end_excluding_newline - 1].ch)
+            {
+                end_excluding_newline -= 1;
+            }
+            line_infos.push(LineInfo {
+                unscaled_ascent: metrics.unscaled_ascent as f64,
+                ascent: metrics.ascent as f64,
+                descent: metrics.descent as f64,
+                baseline,
+                hardbreak: line.hardbreak,
+                width: width_of(line.start, line_visible_end) as f64,
+                height: (metrics.ascent + metrics.descent) as f64,
+                left: 0.0f64,
+                start_utf16: utf16_at(line.start),
+                end_utf16: utf16_at(end_excluding_newline),
+                end_excluding_whitespace_utf16: utf16_at(line_visible_end),
+                end_including_newline_utf16: utf16_at(line.end),
+            });
+        }
+
+        TextLayout {
+            text: self.text,
+            lines: line_infos,
+            runs,
+            min_intrinsic_width,
+            max_intrinsic_width,
+        }
+    }
+}
+
+impl TextLayout {
+    pub fn glyphs(&self) -> impl Iterator<Item = &PositionedGlyph> {
+        self.runs.iter().flatten()
+    }
+
+    pub fn get_glyph_bounds(&self, glyph: &PositionedGlyph) -> PixelRect {
+        let line = &self.lines[glyph.line];
+        PixelRect::new(
+            PixelPoint::new(glyph.position.x, (line.baseline - line.ascent) as f32),
+            PixelSize::new(glyph.advance, line.height as f32),
+        )
+    }
+
+    fn create_glyph_info(&self, glyph: &PositionedGlyph) -> GlyphInfo {
+        GlyphInfo {
+            begin_utf16: glyph.begin_utf16,
+            end_utf16: glyph.end_utf16,
+            bounds: self.get_glyph_bounds(glyph),
+        }
+    }
+
+    pub fn get_longest_line_width(&self) -> f32 {
+        self.lines
+            .iter()
+            .map(|line| line.width as f32)
+            .fold(0.0f32, f32::max)
+    }
+
+    pub fn get_height(&self) -> f32 {
+        self.lines.iter().map(|line| line.height as f32).sum()
+    }
+
+    pub fn get_ideographic_baseline(&self) -> f32 {
+        self.lines
+            .first()
+            .map_or(0.0f32, |line| (line.baseline + line.descent) as f32)
+    }
+
+    pub fn get_alphabetic_baseline(&self) -> f32 {
+        self.lines
+            .first()
+            .map_or(0.0f32, |line| line.baseline as f32)
+    }
+
+    pub fn get_line_metrics(&self) -> LineMetrics {
+        LineMetrics {
+            lines: self.lines.clone(),
+        }
+    }
+
+    pub fn get_word_boundary_utf16(&self, code_unit_index: usize) -> Range {
+        // characters with their utf16 ranges
+        let mut position = 0;
+        let chars = self
+            .text
+            .chars()
+            .map(|ch| {
+                let start = position;
+                position += ch.len_utf16();
+                (ch, start, position)
+            })
+            .collect::<Vec<_>>();
+
+        let Some(index) = chars
+            .iter()
+            .position(|(_, start, end)| (*start..*end).contains(&code_unit_index))
+        else {
+            return Range {
+                start: position,
+                end: position,
+            };
+        };
+
+        // words are the runs of alphanumeric characters or whitespaces
+        let class = |ch: char| {
+            if ch.is_alphanumeric() || ch == '_' {
+                1
+            } else if ch.is_whitespace() && ch != '\n' {
+                2
+            } else {
+                0
+            }
+        };
+        let word_class = class(chars[index].0);
+        let (mut first, mut last) = (index, index);
+        if word_class != 0 {
+            while first > 0 && class(chars[first - 1].0) == word_class {
+                first -= 1;
+            }
+            while last + 1 < chars.len() && class(chars[last + 1].0) == word_class {
+                last += 1;
+            }
+        }
+
+        Range {
+            start: chars[first].1,
+            end: chars[last].2,
+        }
+    }
+
+    pub fn create_glyph_info_at_code_unit_index_utf16(
+        &self,
+        code_unit_index: usize,
+    ) -> Option<GlyphInfo> {
+        self.glyphs()
+            .find(|glyph| (glyph.begin_utf16..glyph.end_utf16).contains(&code_unit_index))
+            .map(|glyph| self.create_glyph_info(glyph))
+    }
+
+    pub fn create_glyph_info_at_paragraph_coordinates(&self, x: f64, y: f64) -> Option<GlyphInfo> {
+        let lines = &self.lines;
+        let line = lines
+            .iter()
+            .position(|line| y < line.baseline + line.descent)
+            .unwrap_or(lines.len().checked_sub(1)?);
+
+        // the glyph under the point or the closest one on the line
+        self.glyphs()
+            .filter(|glyph| glyph.line == line)
+            .min_by(|a, b| {
+                let distance = |glyph: &PositionedGlyph| {
+                    let (left, right) = (
+                        glyph.position.x as f64,
+                        (glyph.position.x + glyph.advance) as f64,
+                    );
+                    (left - x).max(x - right).max(0.0f64)
+                };
+                distance(a).total_cmp(&distance(b))
+            })
+            .map(|glyph| self.create_glyph_info(glyph))
+    }
+}
+
+fn is_line_break(ch: char) -> bool {
+    matches!(
+        ch,
+        '\n' | '\u{0B}' | '\u{0C}' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FontMetrics, TextLayout, TextLayoutBuilder, VerticalMetrics};
+
+    /// Lays out the text with `10` pixels wide characters and the line height of `10`.
+    fn lay_out(text: &str, max_width: f32) -> TextLayout {
+        let mut builder = TextLayoutBuilder::new(max_width);
+        let font_metrics = FontMetrics {
+            ascent: 8.0f32,
+            descent: 2.0f32,
+        };
+        builder.add_run(
+            text,
+            VerticalMetrics::new(font_metrics, 10.0f32, None),
+            |_| 10.0f32,
+        );
+        builder.build()
+    }
+
+    fn line_ranges(layout: &TextLayout) -> Vec<(usize, usize)> {
+        layout
+            .lines
+            .iter()
+            .map(|line| (line.start_utf16, line.end_utf16))
+            .collect()
+    }
+
+    #[test]
+    fn lines_are_wrapped_on_whitespace() {
+        let layout = lay_out("aaa bbb ccc", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 8), (8, 11)]);
+        assert!(!layout.lines[0].hardbreak);
+        assert_eq!(layout.lines[0].end_excluding_whitespace_utf16, 7);
+        assert_eq!(layout.lines[0].width, 70.0f64);
+        assert_eq!(layout.lines[1].baseline, 18.0f64);
+        assert_eq!(layout.get_longest_line_width(), 70.0f32);
+        assert_eq!(layout.min_intrinsic_width, 30.0f32);
+        assert_eq!(layout.max_intrinsic_width, 110.0f32);
+
+        // the glyphs of the wrapped line start at the left edge
+        let glyph = layout
+            .glyphs()
+            .find(|glyph| glyph.begin_utf16 == 8)
+            .unwrap();
+        assert_eq!((glyph.line, glyph.position.x), (1, 0.0f32));
+        assert_eq!(glyph.position.y, 18.0f32);
+    }
+
+    #[test]
+    fn lines_are_wrapped_at_the_break_opportunities() {
+        // UAX #14 allows breaking after the hyphen
+        let layout = lay_out("aaa-bbb", 55.0f32);
+        assert_eq!(line_ranges(&layout), [(0, 4), (4, 7)]);
+
+        // but not before the closing punctuation
+        let layout = lay_out("aa bb!", 55.0f32);
+        assert_eq!(line_ranges(&layout), [(0, 3), (3, 6)]);
+    }
+
+    #[test]
+    fn new_lines_are_kept_with_unbounded_width() {
+        let layout = lay_out("aaa bbb\nccc\n", f32::INFINITY);
+
+        assert_eq!(line_ranges(&layout), [(0, 7), (8, 11), (12, 12)]);
+        assert!(layout.lines[0].hardbreak);
+        assert!(layout.lines[1].hardbreak);
+        assert_eq!(layout.lines[0].end_including_newline_utf16, 8);
+        assert_eq!(layout.max_intrinsic_width, 70.0f32);
+        assert_eq!(layout.get_height(), 30.0f32);
+    }
+
+    #[test]
+    fn word_longer_than_the_line_is_broken_between_characters() {
+        let layout = lay_out("abcdefgh", 35.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 3), (3, 6), (6, 8)]);
+        assert_eq!(layout.min_intrinsic_width, 80.0f32);
+    }
+}
@@ -1,7 +1,12 @@
+#[non_exhaustive]
 pub enum GraphicsApi {
     OpenGL { major: u32, minor: u32 },
     OpenGLES { major: u32, minor: u32 },
     Vulkan { major: u32, minor: u32 },
+    Software,
+    Svg,
+    Pdf,
+    Recording,
 }
 
 /// Drawing backend capabilities.
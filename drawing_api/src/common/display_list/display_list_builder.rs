@@ -153,6 +153,9 @@ pub trait DisplayListBuilder: 'static {
         <Self as crate::DisplayListBuilder>::Paint: 'a;
 
     /// Draws a shadow for a Path given a material elevation.
+    ///
+    /// If `occluder_is_transparent` is false, the backend may skip the part of the shadow
+    /// covered by the occluder.
     fn draw_shadow(
         &mut self,
         path: &<Self::PathBuilder as crate::PathBuilder>::Path,
@@ -16,5 +16,8 @@ pub use path::*;
 mod rounding_radii;
 pub use rounding_radii::*;
 
+mod shadow;
+pub use shadow::*;
+
 mod text;
 pub use text::*;
@@ -180,7 +180,7 @@ pub enum ColorSpace {
     DisplayP3,
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ColorFormat {
     // for color images, 24-bit color with 8-bit alpha channel
     RGBA,
@@ -189,6 +189,16 @@ pub enum ColorFormat {
     Y8,
 }
 
+impl ColorFormat {
+    /// Returns the number of bytes taken by a single pixel.
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            ColorFormat::RGBA => 4,
+            ColorFormat::Y8 => 1,
+        }
+    }
+}
+
 impl Default for ColorFormat {
     fn default() -> Self {
         ColorFormat::RGBA
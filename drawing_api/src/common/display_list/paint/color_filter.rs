@@ -1,6 +1,6 @@
 use super::{BlendMode, Color, ColorMatrix};
 
-#[derive(Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColorFilter {
     Blend(Color, BlendMode),
     Matrix(ColorMatrix),
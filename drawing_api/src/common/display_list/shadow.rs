@@ -0,0 +1,114 @@
+use crate::{Color, PixelRect};
+
+// the same shadow parameters as Flutter uses
+const SHADOW_AMBIENT_ALPHA: f32 = 0.039f32;
+const SHADOW_SPOT_ALPHA: f32 = 0.25f32;
+const SHADOW_LIGHT_HEIGHT: f32 = 600.0f32;
+const SHADOW_LIGHT_RADIUS: f32 = 800.0f32;
+
+/// Parameters of the ambient and spot shadows drawn under an occluder.
+///
+/// The shadow is always filled under the whole occluder (the umbra is not cut out),
+/// so it is correct for both opaque and transparent occluders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowParameters {
+    /// Color of the ambient shadow (straight RGBA).
+    pub ambient_color: [f32; 4],
+
+    /// Blur sigma of the ambient shadow.
+    pub ambient_sigma: f32,
+
+    /// Width of the stroke which grows the outline of the ambient shadow.
+    pub ambient_stroke_width: f32,
+
+    /// Color of the spot shadow (straight RGBA).
+    pub spot_color: [f32; 4],
+
+    /// Blur sigma of the spot shadow.
+    pub spot_sigma: f32,
+
+    /// Scale of the outline projected from the light.
+    pub spot_scale: f32,
+
+    /// Translation applied after `spot_scale` to the outline projected from the light.
+    pub spot_translation: (f32, f32),
+}
+
+impl ShadowParameters {
+    /// Computes the shadow parameters of an occluder with `bounds` at the `elevation`.
+    pub fn new(
+        bounds: PixelRect,
+        color: impl Into<Color>,
+        elevation: f32,
+        device_pixel_ratio: f32,
+    ) -> Self {
+        let color = color.into();
+        let color = [color.red, color.green, color.blue, color.alpha];
+        let occluder_z = elevation * device_pixel_ratio;
+        let light_x = bounds.center().x;
+        let light_y = bounds.min_y() - SHADOW_LIGHT_HEIGHT;
+        let light_z = SHADOW_LIGHT_HEIGHT * device_pixel_ratio;
+        let light_radius = SHADOW_LIGHT_RADIUS * device_pixel_ratio;
+
+        let mut ambient_color = color;
+        ambient_color[3] *= SHADOW_AMBIENT_ALPHA;
+        let mut spot_color = color;
+        spot_color[3] *= SHADOW_SPOT_ALPHA;
+
+        // ambient shadow: the outline grown and blurred depending on the height
+        let outset = (occluder_z / 128.0f32 * 64.0f32).min(300.0f32 / 128.0f32 * 64.0f32);
+        let one_over_alpha = 1.0f32 + (occluder_z / 128.0f32).max(0.0f32);
+        let ambient_blur_radius = 0.5f32 * outset * one_over_alpha;
+
+        // spot shadow: the outline projected from the light and blurred by its radius
+        let z_ratio = (occluder_z / (light_z - occluder_z)).clamp(0.0f32, 0.95f32);
+
+        Self {
+            ambient_color,
+            ambient_sigma: blur_radius_to_sigma(ambient_blur_radius),
+            ambient_stroke_width: 0.5f32 * (outset - ambient_blur_radius),
+            spot_color: shadow_tonal_color(spot_color),
+            spot_sigma: blur_radius_to_sigma(light_radius * z_ratio),
+            spot_scale: (light_z / (light_z - occluder_z)).clamp(1.0f32, 1.95f32),
+            spot_translation: (-z_ratio * light_x, -z_ratio * light_y),
+        }
+    }
+}
+
+/// Converts a blur radius to the gaussian sigma, the same way as Skia does.
+pub fn blur_radius_to_sigma(radius: f32) -> f32 {
+    if radius > 0.0f32 {
+        0.57735f32 * radius + 0.5f32
+    } else {
+        0.0f32
+    }
+}
+
+/// Computes the spot shadow color tinted by the occluder color,
+/// the same way as Skia's tonal shadow colors.
+fn shadow_tonal_color(color: [f32; 4]) -> [f32; 4] {
+    let [r, g, b, alpha] = color;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let luminance = 0.5f32 * (max + min);
+
+    let alpha_adjust = (2.6f32 + (-2.66667f32 + 1.06667f32 * alpha) * alpha) * alpha;
+    let color_alpha = ((3.544762f32 + (-4.891428f32 + 2.3466f32 * luminance) * luminance)
+        * luminance
+        * alpha_adjust)
+        .clamp(0.0f32, 1.0f32);
+    let greyscale_alpha = (alpha * (1.0f32 - 0.4f32 * luminance)).clamp(0.0f32, 1.0f32);
+
+    let color_scale = color_alpha * (1.0f32 - greyscale_alpha);
+    let tonal_alpha = color_scale + greyscale_alpha;
+    if tonal_alpha <= 0.0f32 {
+        return [0.0f32; 4];
+    }
+    let unpremul_scale = color_scale / tonal_alpha;
+    [
+        r * unpremul_scale,
+        g * unpremul_scale,
+        b * unpremul_scale,
+        tonal_alpha,
+    ]
+}
@@ -18,5 +18,7 @@ pub trait ParagraphBuilder: Sized + 'static {
 
     fn add_text(&mut self, text: &str);
 
-    fn build(self) -> Result<Self::Paragraph, &'static str>;
+    /// Lays out the paragraph, lines longer than `width` are wrapped.
+    /// Use `f32::INFINITY` to get the unbounded (intrinsic) size of the text.
+    fn build(self, width: f32) -> Result<Self::Paragraph, &'static str>;
 }
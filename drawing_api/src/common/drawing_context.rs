@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::{Capabilities, ColorSource, GraphicsApi, ImageFilter, TextureDescriptor};
+use crate::{Capabilities, ColorFormat, ColorSource, GraphicsApi, ImageFilter, TextureDescriptor};
 
 /// An abstraction over graphics context (like OpenGL or Vulkan context).
 ///
@@ -46,6 +46,17 @@ pub trait DrawingContext: Clone + 'static {
         descriptor: TextureDescriptor,
     ) -> Result<Self::Texture, &'static str>;
 
+    /// Creates an offscreen surface together with the texture it draws to.
+    ///
+    /// The texture can be used in display lists (after drawing to the surface)
+    /// to cache rendered content.
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str>;
+
     /// Creates a color source whose pixels are shaded by a fragment program.
     unsafe fn new_color_source_from_fragment_program(
         &self,
@@ -10,6 +10,9 @@ pub use drawing_context::*;
 mod fragment_program;
 pub use fragment_program::*;
 
+mod pixel_data;
+pub use pixel_data::*;
+
 mod surface;
 pub use surface::*;
 
@@ -0,0 +1,94 @@
+use crate::{ColorFormat, PixelIntRect};
+
+/// Pixels read back from a surface or a texture.
+///
+/// Rows are ordered from top to bottom. Every pixel takes
+/// `color_format.bytes_per_pixel()` bytes (RGBA or Y8 channel order).
+#[derive(Debug, Clone)]
+pub struct PixelData {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+
+    /// Number of bytes between the beginnings of two consecutive rows.
+    pub row_stride: u32,
+
+    pub color_format: ColorFormat,
+
+    /// Whether the color channels are multiplied by alpha.
+    pub premultiplied: bool,
+}
+
+impl PixelData {
+    /// Returns bytes of the pixel at the given position.
+    pub fn get_pixel(&self, x: u32, y: u32) -> &[u8] {
+        let bytes_per_pixel = self.color_format.bytes_per_pixel();
+        let offset = (y * self.row_stride) as usize + x as usize * bytes_per_pixel;
+        &self.data[offset..offset + bytes_per_pixel]
+    }
+
+    /// Returns a copy of the given area.
+    pub fn crop(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        check_pixel_rect(rect, self.width, self.height)?;
+
+        let bytes_per_pixel = self.color_format.bytes_per_pixel();
+        let row_stride = rect.size.width as usize * bytes_per_pixel;
+        let mut data = Vec::with_capacity(row_stride * rect.size.height as usize);
+        for y in rect.min_y()..rect.max_y() {
+            let offset = (y * self.row_stride) as usize + rect.min_x() as usize * bytes_per_pixel;
+            data.extend_from_slice(&self.data[offset..offset + row_stride]);
+        }
+
+        Ok(PixelData {
+            data,
+            width: rect.size.width,
+            height: rect.size.height,
+            row_stride: row_stride as u32,
+            color_format: self.color_format,
+            premultiplied: self.premultiplied,
+        })
+    }
+
+    /// Reverses the order of rows.
+    ///
+    /// Useful for backends which store images from bottom to top.
+    pub fn flip_vertically(&mut self) {
+        let row_stride = self.row_stride as usize;
+        let height = self.height as usize;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - 1 - y) * row_stride);
+            top[y * row_stride..(y + 1) * row_stride].swap_with_slice(&mut bottom[..row_stride]);
+        }
+    }
+
+    /// Converts premultiplied RGBA colors to straight alpha.
+    pub fn unpremultiply(&mut self) {
+        if !self.premultiplied || self.color_format != ColorFormat::RGBA {
+            return;
+        }
+
+        let width = self.width as usize;
+        for row in self.data.chunks_mut(self.row_stride as usize) {
+            for pixel in row[..width * 4].chunks_exact_mut(4) {
+                let alpha = pixel[3] as u32;
+                for channel in &mut pixel[0..3] {
+                    if let Some(value) = (*channel as u32 * 255 + alpha / 2).checked_div(alpha) {
+                        *channel = value.min(255) as u8;
+                    }
+                }
+            }
+        }
+
+        self.premultiplied = false;
+    }
+}
+
+/// Returns an error if the rectangle doesn't fit in the image of the given size.
+pub fn check_pixel_rect(rect: PixelIntRect, width: u32, height: u32) -> Result<(), &'static str> {
+    let max_x = rect.origin.x.checked_add(rect.size.width);
+    let max_y = rect.origin.y.checked_add(rect.size.height);
+    match (max_x, max_y) {
+        (Some(max_x), Some(max_y)) if max_x <= width && max_y <= height => Ok(()),
+        _ => Err("rectangle is out of bounds"),
+    }
+}
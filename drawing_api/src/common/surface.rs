@@ -1,3 +1,5 @@
+use crate::{PixelData, PixelIntRect};
+
 /// A surface represents a render target.
 /// That can be usually a window or a texture.
 pub trait Surface: 'static {
@@ -6,6 +8,10 @@ pub trait Surface: 'static {
     /// Draws a display list on the surface.
     fn draw(&mut self, display_list: &Self::DisplayList) -> Result<(), &'static str>;
 
+    /// Reads back the pixels of the given area of the surface
+    /// (in the color format of the surface).
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str>;
+
     /// Present the surface to the underlying window system (for Vulkan).
     fn present(self) -> Result<(), &'static str>;
 }
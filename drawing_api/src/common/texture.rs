@@ -1,6 +1,6 @@
 use core::marker::Sized;
 
-use crate::ColorFormat;
+use crate::{ColorFormat, PixelData, PixelIntRect};
 
 /// Represents an image whose data is resident in GPU memory.
 /// Reference counted, thread safe, immutable object.
@@ -10,6 +10,10 @@ pub trait Texture: Sized + Sync + Send + Clone + 'static {
 
     /// Gets the native OpenGL handle associated with this texture.
     fn get_gl_handle(&self) -> usize;
+
+    /// Reads back the pixels of the given area of the texture
+    /// (in the color format of the texture).
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str>;
 }
 
 #[derive(Clone)]
@@ -5,5 +5,6 @@ pub struct PixelUnit;
 pub type PixelPoint = euclid::Point2D<f32, PixelUnit>;
 pub type PixelSize = euclid::Size2D<f32, PixelUnit>;
 pub type PixelRect = euclid::Rect<f32, PixelUnit>;
+pub type PixelIntRect = euclid::Rect<u32, PixelUnit>;
 
 pub type Matrix = euclid::Transform3D<f32, euclid::UnknownUnit, euclid::UnknownUnit>;
@@ -1,16 +1,19 @@
 use std::{any::Any, borrow::Cow, rc::Rc, sync::Arc};
 
 use crate::{
-    Capabilities, ColorSource, DisplayListBuilder, DrawingContext, FragmentProgram, ImageFilter,
-    ParagraphBuilder, PixelRect, TextureDescriptor,
+    Capabilities, ColorFormat, ColorSource, DisplayListBuilder, DrawingContext, FragmentProgram,
+    ImageFilter, ParagraphBuilder, PixelRect, TextureDescriptor,
 };
 
 use super::{
     ColorSourceFragmentObject, DisplayListBuilderObject, FontsObject, FragmentProgramObject,
     ImageFilterFragmentObject, PaintObject, ParagraphBuilderObject, PathBuilderObject,
-    TextureObject,
+    SurfaceObject, TextureObject,
 };
 
+/// Offscreen surface together with the texture it draws to.
+pub type OffscreenSurfaceObjects = (Box<dyn SurfaceObject>, Box<dyn TextureObject>);
+
 pub trait DrawingContextObject {
     /// Gets implementation capabilities of the current instance.
     fn get_capabilities(&self) -> Capabilities;
@@ -49,6 +52,14 @@ pub trait DrawingContextObject {
         descriptor: TextureDescriptor,
     ) -> Result<Box<dyn TextureObject>, &'static str>;
 
+    /// Creates an offscreen surface together with the texture it draws to.
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<OffscreenSurfaceObjects, &'static str>;
+
     /// Creates a color source whose pixels are shaded by a fragment program.
     unsafe fn new_color_source_from_fragment_program(
         &self,
@@ -118,6 +129,16 @@ impl<C: DrawingContext> DrawingContextObject for C {
         }
     }
 
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<OffscreenSurfaceObjects, &'static str> {
+        let (surface, texture) = self.create_offscreen_surface(width, height, color_format)?;
+        Ok((Box::new(surface), Box::new(texture)))
+    }
+
     unsafe fn new_color_source_from_fragment_program(
         &self,
         frag_program: &dyn FragmentProgramObject,
@@ -1,6 +1,6 @@
 use std::any::Any;
 
-use crate::Surface;
+use crate::{PixelData, PixelIntRect, Surface};
 
 use super::DisplayListObject;
 
@@ -8,6 +8,10 @@ pub trait SurfaceObject {
     /// Draws a display list on the surface.
     fn draw(&mut self, display_list: &Box<dyn DisplayListObject>) -> Result<(), &'static str>;
 
+    /// Reads back the pixels of the given area of the surface
+    /// (in the color format of the surface).
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str>;
+
     /// Present the surface to the underlying window system (for Vulkan).
     fn present(self) -> Result<(), &'static str>;
 }
@@ -20,6 +24,10 @@ impl<S: Surface> SurfaceObject for S {
         self.draw(display_list)
     }
 
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        self.read_pixels(rect)
+    }
+
     fn present(self) -> Result<(), &'static str> {
         self.present()
     }
@@ -1,4 +1,4 @@
-use crate::{Texture, TextureDescriptor};
+use crate::{PixelData, PixelIntRect, Texture, TextureDescriptor};
 
 pub trait TextureObject {
     /// Returns descriptor of the texture.
@@ -6,6 +6,10 @@ pub trait TextureObject {
 
     /// Gets the native OpenGL handle associated with this texture.
     fn get_gl_handle(&self) -> usize;
+
+    /// Reads back the pixels of the given area of the texture
+    /// (in the color format of the texture).
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str>;
 }
 
 impl<T: Texture> TextureObject for T {
@@ -16,4 +20,8 @@ impl<T: Texture> TextureObject for T {
     fn get_gl_handle(&self) -> usize {
         self.get_gl_handle()
     }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        self.read_pixels(rect)
+    }
 }
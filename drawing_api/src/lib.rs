@@ -13,8 +13,12 @@ pub mod prelude {
     pub use ::euclid::rect;
 }
 
+pub mod command_list;
+
 pub mod dyn_api;
 
+pub mod recording;
+
 pub mod euclid {
     pub use ::euclid::*;
 }
@@ -0,0 +1,1426 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{
+    BlendMode, BlurStyle, ClipOperation, Color, ColorFilter, ColorFormat, ColorMatrix, ColorSource,
+    ColorSpace, DrawStyle, FillType, FontStyle, FontWeight, ImageFilter, MaskFilter, Matrix,
+    ParagraphStyle, PixelData, PixelPoint, PixelRect, PixelSize, RoundingRadii, StrokeCap,
+    StrokeJoin, TextAlignment, TextDecoration, TextDecorationStyle, TextDecorationType,
+    TextDirection, TextureSampling, TileMode,
+};
+
+use super::{
+    DisplayListOp, PathOp, RecordedDisplayList, RecordedFont, RecordedFonts, RecordedFragment,
+    RecordedPaint, RecordedParagraph, RecordedPath, RecordedTexture, TextRun,
+};
+
+// The file starts with the magic bytes and the format version
+// followed by the textures, the fonts and the root display list.
+// All the numbers are little endian.
+const MAGIC: &[u8; 4] = b"DRDL";
+const VERSION: u32 = 1;
+
+// the values of the enums are stored as the indices of these arrays
+const BLEND_MODES: [BlendMode; 29] = [
+    BlendMode::Clear,
+    BlendMode::Source,
+    BlendMode::Destination,
+    BlendMode::SourceOver,
+    BlendMode::DestinationOver,
+    BlendMode::SourceIn,
+    BlendMode::DestinationIn,
+    BlendMode::SourceOut,
+    BlendMode::DestinationOut,
+    BlendMode::SourceATop,
+    BlendMode::DestinationATop,
+    BlendMode::Xor,
+    BlendMode::Plus,
+    BlendMode::Modulate,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Darken,
+    BlendMode::Lighten,
+    BlendMode::ColorDodge,
+    BlendMode::ColorBurn,
+    BlendMode::HardLight,
+    BlendMode::SoftLight,
+    BlendMode::Difference,
+    BlendMode::Exclusion,
+    BlendMode::Multiply,
+    BlendMode::Hue,
+    BlendMode::Saturation,
+    BlendMode::Color,
+    BlendMode::Luminosity,
+];
+const BLUR_STYLES: [BlurStyle; 4] = [
+    BlurStyle::Normal,
+    BlurStyle::Solid,
+    BlurStyle::Outer,
+    BlurStyle::Inner,
+];
+const CLIP_OPERATIONS: [ClipOperation; 2] = [ClipOperation::Difference, ClipOperation::Intersect];
+const COLOR_FORMATS: [ColorFormat; 2] = [ColorFormat::RGBA, ColorFormat::Y8];
+const COLOR_SPACES: [ColorSpace; 3] = [
+    ColorSpace::SRGB,
+    ColorSpace::ExtendedSRGB,
+    ColorSpace::DisplayP3,
+];
+const DRAW_STYLES: [DrawStyle; 3] = [DrawStyle::Fill, DrawStyle::Stroke, DrawStyle::StrokeAndFill];
+const FILL_TYPES: [FillType; 2] = [FillType::NonZero, FillType::Odd];
+const FONT_STYLES: [FontStyle; 2] = [FontStyle::Normal, FontStyle::Italic];
+const FONT_WEIGHTS: [FontWeight; 9] = [
+    FontWeight::Thin,
+    FontWeight::ExtraLight,
+    FontWeight::Light,
+    FontWeight::Regular,
+    FontWeight::Medium,
+    FontWeight::SemiBold,
+    FontWeight::Bold,
+    FontWeight::ExtraBold,
+    FontWeight::Black,
+];
+const STROKE_CAPS: [StrokeCap; 3] = [StrokeCap::Butt, StrokeCap::Round, StrokeCap::Square];
+const STROKE_JOINS: [StrokeJoin; 3] = [StrokeJoin::Miter, StrokeJoin::Round, StrokeJoin::Bevel];
+const TEXT_ALIGNMENTS: [TextAlignment; 6] = [
+    TextAlignment::Left,
+    TextAlignment::Right,
+    TextAlignment::Center,
+    TextAlignment::Justify,
+    TextAlignment::Start,
+    TextAlignment::End,
+];
+const TEXT_DECORATION_STYLES: [TextDecorationStyle; 5] = [
+    TextDecorationStyle::Solid,
+    TextDecorationStyle::Double,
+    TextDecorationStyle::Dotted,
+    TextDecorationStyle::Dashed,
+    TextDecorationStyle::Wavy,
+];
+const TEXT_DIRECTIONS: [TextDirection; 2] = [TextDirection::RTL, TextDirection::LTR];
+const TEXTURE_SAMPLINGS: [TextureSampling; 2] =
+    [TextureSampling::NearestNeighbor, TextureSampling::Linear];
+const TILE_MODES: [TileMode; 4] = [
+    TileMode::Clamp,
+    TileMode::Repeat,
+    TileMode::Mirror,
+    TileMode::Decal,
+];
+
+pub(crate) fn write_display_list(display_list: &RecordedDisplayList) -> Vec<u8> {
+    let mut body = Writer::default();
+    body.write_display_list(display_list);
+
+    let mut writer = Writer::default();
+    writer.data.extend_from_slice(MAGIC);
+    writer.write_u32(VERSION);
+
+    writer.write_u32(body.textures.len() as u32);
+    for texture in &body.textures {
+        let pixels = texture.get_pixels();
+        writer.write_u32(pixels.width);
+        writer.write_u32(pixels.height);
+        writer.write_u32(pixels.row_stride);
+        writer.write_u8(pixels.color_format as u8);
+        writer.write_bool(pixels.premultiplied);
+        writer.write_bytes(&pixels.data);
+    }
+
+    writer.write_u32(body.fonts.len() as u32);
+    for fonts in &body.fonts {
+        let fonts = fonts.get_fonts();
+        writer.write_u32(fonts.len() as u32);
+        for font in fonts {
+            writer.write_option(font.family_name_alias.as_ref(), Writer::write_string);
+            writer.write_bytes(&font.data);
+        }
+    }
+
+    writer.data.extend_from_slice(&body.data);
+    writer.data
+}
+
+pub(crate) fn read_display_list(data: &[u8]) -> Result<RecordedDisplayList, &'static str> {
+    if !data.starts_with(MAGIC) {
+        return Err("not a recorded display list");
+    }
+    let mut reader = Reader {
+        data,
+        position: MAGIC.len(),
+        textures: Vec::new(),
+        fonts: Vec::new(),
+    };
+    if reader.read_u32()? != VERSION {
+        return Err("unsupported version of recorded display list");
+    }
+
+    for _ in 0..reader.read_u32()? {
+        let width = reader.read_u32()?;
+        let height = reader.read_u32()?;
+        let row_stride = reader.read_u32()?;
+        let color_format = reader.read_enum(&COLOR_FORMATS)?;
+        let premultiplied = reader.read_bool()?;
+        let data = reader.read_bytes()?.to_vec();
+        let min_row_stride = width as usize * color_format.bytes_per_pixel();
+        if (row_stride as usize) < min_row_stride
+            || data.len() < row_stride as usize * height as usize
+        {
+            return Err("invalid texture size");
+        }
+        reader.textures.push(RecordedTexture::new(PixelData {
+            data,
+            width,
+            height,
+            row_stride,
+            color_format,
+            premultiplied,
+        }));
+    }
+
+    for _ in 0..reader.read_u32()? {
+        let mut fonts = Vec::new();
+        for _ in 0..reader.read_u32()? {
+            let family_name_alias = reader.read_option(Reader::read_string)?;
+            let data = reader.read_bytes()?.to_vec();
+            fonts.push(RecordedFont {
+                data: Arc::new(Cow::Owned(data)),
+                family_name_alias,
+            });
+        }
+        reader.fonts.push(RecordedFonts {
+            fonts: Arc::new(std::sync::Mutex::new(fonts)),
+        });
+    }
+
+    let display_list = reader.read_display_list()?;
+    if reader.position != data.len() {
+        return Err("unexpected data after recorded display list");
+    }
+    Ok(display_list)
+}
+
+#[derive(Default)]
+struct Writer {
+    data: Vec<u8>,
+
+    // textures and fonts referenced by the index
+    textures: Vec<RecordedTexture>,
+    fonts: Vec<RecordedFonts>,
+}
+
+impl Writer {
+    fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn write_bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, value: &[u8]) {
+        self.write_u32(value.len() as u32);
+        self.data.extend_from_slice(value);
+    }
+
+    fn write_string(&mut self, value: &String) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    fn write_option<T>(&mut self, value: Option<&T>, write: impl FnOnce(&mut Self, &T)) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            write(self, value);
+        }
+    }
+
+    fn write_f32_option(&mut self, value: Option<f32>) {
+        self.write_option(value.as_ref(), |writer, value| writer.write_f32(*value));
+    }
+
+    fn write_point(&mut self, point: &PixelPoint) {
+        self.write_f32(point.x);
+        self.write_f32(point.y);
+    }
+
+    fn write_size(&mut self, size: &PixelSize) {
+        self.write_f32(size.width);
+        self.write_f32(size.height);
+    }
+
+    fn write_rect(&mut self, rect: &PixelRect) {
+        self.write_point(&rect.origin);
+        self.write_size(&rect.size);
+    }
+
+    fn write_radii(&mut self, radii: &RoundingRadii) {
+        self.write_size(&radii.top_left);
+        self.write_size(&radii.bottom_left);
+        self.write_size(&radii.top_right);
+        self.write_size(&radii.bottom_right);
+    }
+
+    fn write_matrix(&mut self, matrix: &Matrix) {
+        for value in matrix.to_array() {
+            self.write_f32(value);
+        }
+    }
+
+    fn write_color(&mut self, color: &Color) {
+        self.write_f32(color.red);
+        self.write_f32(color.green);
+        self.write_f32(color.blue);
+        self.write_f32(color.alpha);
+        self.write_u8(color.color_space.clone() as u8);
+    }
+
+    fn write_colors(&mut self, colors: &[Color], stops: &[f32]) {
+        self.write_u32(colors.len() as u32);
+        for color in colors {
+            self.write_color(color);
+        }
+        self.write_u32(stops.len() as u32);
+        for stop in stops {
+            self.write_f32(*stop);
+        }
+    }
+
+    fn write_texture(&mut self, texture: &RecordedTexture) {
+        let index = match self.textures.iter().position(|t| t.ptr_eq(texture)) {
+            Some(index) => index,
+            None => {
+                self.textures.push(texture.clone());
+                self.textures.len() - 1
+            }
+        };
+        self.write_u32(index as u32);
+    }
+
+    fn write_fonts(&mut self, fonts: &RecordedFonts) {
+        let index = match self.fonts.iter().position(|f| f.ptr_eq(fonts)) {
+            Some(index) => index,
+            None => {
+                self.fonts.push(fonts.clone());
+                self.fonts.len() - 1
+            }
+        };
+        self.write_u32(index as u32);
+    }
+
+    fn write_color_source(
+        &mut self,
+        color_source: &ColorSource<RecordedTexture, RecordedFragment>,
+    ) {
+        match color_source {
+            ColorSource::LinearGradient {
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                self.write_u8(0);
+                self.write_point(start);
+                self.write_point(end);
+                self.write_colors(colors, stops);
+                self.write_u8(*tile_mode as u8);
+                self.write_option(transformation.as_ref(), Self::write_matrix);
+            }
+            ColorSource::RadialGradient {
+                center,
+                radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                self.write_u8(1);
+                self.write_point(center);
+                self.write_f32(*radius);
+                self.write_colors(colors, stops);
+                self.write_u8(*tile_mode as u8);
+                self.write_option(transformation.as_ref(), Self::write_matrix);
+            }
+            ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                self.write_u8(2);
+                self.write_point(start_center);
+                self.write_f32(*start_radius);
+                self.write_point(end_center);
+                self.write_f32(*end_radius);
+                self.write_colors(colors, stops);
+                self.write_u8(*tile_mode as u8);
+                self.write_option(transformation.as_ref(), Self::write_matrix);
+            }
+            ColorSource::SweepGradient {
+                center,
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                self.write_u8(3);
+                self.write_point(center);
+                self.write_f32(*start);
+                self.write_f32(*end);
+                self.write_colors(colors, stops);
+                self.write_u8(*tile_mode as u8);
+                self.write_option(transformation.as_ref(), Self::write_matrix);
+            }
+            ColorSource::Image {
+                image,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
+                transformation,
+            } => {
+                self.write_u8(4);
+                self.write_texture(image);
+                self.write_u8(*horizontal_tile_mode as u8);
+                self.write_u8(*vertical_tile_mode as u8);
+                self.write_u8(*sampling as u8);
+                self.write_option(transformation.as_ref(), Self::write_matrix);
+            }
+            ColorSource::Fragment { .. } => self.write_u8(5),
+        }
+    }
+
+    fn write_color_filter(&mut self, color_filter: &ColorFilter) {
+        match color_filter {
+            ColorFilter::Blend(color, blend_mode) => {
+                self.write_u8(0);
+                self.write_color(color);
+                self.write_u8(*blend_mode as u8);
+            }
+            ColorFilter::Matrix(matrix) => {
+                self.write_u8(1);
+                for value in matrix.m {
+                    self.write_f32(value);
+                }
+            }
+        }
+    }
+
+    fn write_image_filter(&mut self, image_filter: &ImageFilter<RecordedFragment>) {
+        match image_filter {
+            ImageFilter::Blur {
+                x_sigma,
+                y_sigma,
+                tile_mode,
+            } => {
+                self.write_u8(0);
+                self.write_f32(*x_sigma);
+                self.write_f32(*y_sigma);
+                self.write_u8(*tile_mode as u8);
+            }
+            ImageFilter::Dilate { x_radius, y_radius } => {
+                self.write_u8(1);
+                self.write_f32(*x_radius);
+                self.write_f32(*y_radius);
+            }
+            ImageFilter::Erode { x_radius, y_radius } => {
+                self.write_u8(2);
+                self.write_f32(*x_radius);
+                self.write_f32(*y_radius);
+            }
+            ImageFilter::Matrix { matrix, sampling } => {
+                self.write_u8(3);
+                self.write_matrix(matrix);
+                self.write_u8(*sampling as u8);
+            }
+            ImageFilter::Fragment { .. } => self.write_u8(4),
+            ImageFilter::Compose { outer, inner } => {
+                self.write_u8(5);
+                self.write_image_filter(outer);
+                self.write_image_filter(inner);
+            }
+        }
+    }
+
+    fn write_mask_filter(&mut self, mask_filter: &MaskFilter) {
+        match mask_filter {
+            MaskFilter::Blur { style, sigma } => {
+                self.write_u8(0);
+                self.write_u8(*style as u8);
+                self.write_f32(*sigma);
+            }
+        }
+    }
+
+    fn write_paint(&mut self, paint: &RecordedPaint) {
+        self.write_option(paint.color.as_ref(), Self::write_color);
+        self.write_option(paint.blend_mode.as_ref(), |writer, value| {
+            writer.write_u8(*value as u8)
+        });
+        self.write_option(paint.draw_style.as_ref(), |writer, value| {
+            writer.write_u8(*value as u8)
+        });
+        self.write_option(paint.stroke_cap.as_ref(), |writer, value| {
+            writer.write_u8(*value as u8)
+        });
+        self.write_option(paint.stroke_join.as_ref(), |writer, value| {
+            writer.write_u8(*value as u8)
+        });
+        self.write_f32_option(paint.stroke_width);
+        self.write_f32_option(paint.stroke_miter);
+        self.write_option(paint.color_source.as_ref(), Self::write_color_source);
+        self.write_option(paint.color_filter.as_ref(), Self::write_color_filter);
+        self.write_option(paint.image_filter.as_ref(), Self::write_image_filter);
+        self.write_option(paint.mask_filter.as_ref(), Self::write_mask_filter);
+    }
+
+    fn write_path(&mut self, path: &RecordedPath) {
+        self.write_u8(path.fill_type as u8);
+        self.write_u32(path.ops.len() as u32);
+        for op in &path.ops {
+            match op {
+                PathOp::MoveTo(point) => {
+                    self.write_u8(0);
+                    self.write_point(point);
+                }
+                PathOp::LineTo(point) => {
+                    self.write_u8(1);
+                    self.write_point(point);
+                }
+                PathOp::QuadraticCurveTo(control_point, end_point) => {
+                    self.write_u8(2);
+                    self.write_point(control_point);
+                    self.write_point(end_point);
+                }
+                PathOp::CubicCurveTo(control_point_1, control_point_2, end_point) => {
+                    self.write_u8(3);
+                    self.write_point(control_point_1);
+                    self.write_point(control_point_2);
+                    self.write_point(end_point);
+                }
+                PathOp::AddRect(rect) => {
+                    self.write_u8(4);
+                    self.write_rect(rect);
+                }
+                PathOp::AddRoundedRect(rect, radii) => {
+                    self.write_u8(5);
+                    self.write_rect(rect);
+                    self.write_radii(radii);
+                }
+                PathOp::AddOval(oval_bounds) => {
+                    self.write_u8(6);
+                    self.write_rect(oval_bounds);
+                }
+                PathOp::AddArc(oval_bounds, start_angle_degrees, end_angle_degrees) => {
+                    self.write_u8(7);
+                    self.write_rect(oval_bounds);
+                    self.write_f32(*start_angle_degrees);
+                    self.write_f32(*end_angle_degrees);
+                }
+                PathOp::Close => self.write_u8(8),
+            }
+        }
+    }
+
+    fn write_paragraph_style(&mut self, style: &ParagraphStyle<RecordedPaint>) {
+        self.write_option(style.foreground.as_ref(), Self::write_paint);
+        self.write_option(style.background.as_ref(), Self::write_paint);
+        self.write_u8(style.weight as u8);
+        self.write_u8(style.style as u8);
+        self.write_string(&style.family);
+        self.write_f32(style.size);
+        self.write_f32_option(style.height_factor);
+        self.write_u8(style.text_alignment as u8);
+        self.write_u8(style.text_direction as u8);
+        self.write_option(style.text_decoration.as_ref(), |writer, decoration| {
+            writer.write_bool(decoration.types.underline);
+            writer.write_bool(decoration.types.overline);
+            writer.write_bool(decoration.types.line_through);
+            writer.write_color(&decoration.color);
+            writer.write_u8(decoration.style as u8);
+            writer.write_f32(decoration.thickness_multiplier);
+        });
+        self.write_option(style.max_lines.as_ref(), |writer, value| {
+            writer.write_u32(*value)
+        });
+        self.write_option(style.ellipsis.as_ref(), Self::write_string);
+        self.write_option(style.locale.as_ref(), Self::write_string);
+    }
+
+    fn write_paragraph(&mut self, paragraph: &RecordedParagraph) {
+        self.write_fonts(&paragraph.fonts);
+        self.write_u32(paragraph.runs.len() as u32);
+        for run in paragraph.runs.iter() {
+            self.write_option(run.style.as_ref(), Self::write_paragraph_style);
+            self.write_string(&run.text);
+        }
+        self.write_f32(paragraph.width);
+    }
+
+    fn write_display_list(&mut self, display_list: &RecordedDisplayList) {
+        self.write_option(display_list.bounds.as_ref(), Self::write_rect);
+        self.write_u32(display_list.ops.len() as u32);
+        for op in display_list.ops.iter() {
+            self.write_op(op);
+        }
+    }
+
+    fn write_op(&mut self, op: &DisplayListOp) {
+        match op {
+            DisplayListOp::Scale { x_scale, y_scale } => {
+                self.write_u8(0);
+                self.write_f32(*x_scale);
+                self.write_f32(*y_scale);
+            }
+            DisplayListOp::Rotate { angle_degrees } => {
+                self.write_u8(1);
+                self.write_f32(*angle_degrees);
+            }
+            DisplayListOp::Translate {
+                x_translation,
+                y_translation,
+            } => {
+                self.write_u8(2);
+                self.write_f32(*x_translation);
+                self.write_f32(*y_translation);
+            }
+            DisplayListOp::Transform { transform } => {
+                self.write_u8(3);
+                self.write_matrix(transform);
+            }
+            DisplayListOp::SetTransform { transform } => {
+                self.write_u8(4);
+                self.write_matrix(transform);
+            }
+            DisplayListOp::ResetTransform => self.write_u8(5),
+            DisplayListOp::ClipRect { rect, operation } => {
+                self.write_u8(6);
+                self.write_rect(rect);
+                self.write_u8(*operation as u8);
+            }
+            DisplayListOp::ClipOval {
+                oval_bounds,
+                operation,
+            } => {
+                self.write_u8(7);
+                self.write_rect(oval_bounds);
+                self.write_u8(*operation as u8);
+            }
+            DisplayListOp::ClipRoundedRect {
+                rect,
+                radii,
+                operation,
+            } => {
+                self.write_u8(8);
+                self.write_rect(rect);
+                self.write_radii(radii);
+                self.write_u8(*operation as u8);
+            }
+            DisplayListOp::ClipPath { path, operation } => {
+                self.write_u8(9);
+                self.write_path(path);
+                self.write_u8(*operation as u8);
+            }
+            DisplayListOp::Save => self.write_u8(10),
+            DisplayListOp::SaveLayer {
+                bounds,
+                paint,
+                filter,
+            } => {
+                self.write_u8(11);
+                self.write_rect(bounds);
+                self.write_option(paint.as_ref(), Self::write_paint);
+                self.write_option(filter.as_ref(), Self::write_image_filter);
+            }
+            DisplayListOp::Restore => self.write_u8(12),
+            DisplayListOp::DrawPaint { paint } => {
+                self.write_u8(13);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawLine { from, to, paint } => {
+                self.write_u8(14);
+                self.write_point(from);
+                self.write_point(to);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawDashedLine {
+                from,
+                to,
+                on_length,
+                off_length,
+                paint,
+            } => {
+                self.write_u8(15);
+                self.write_point(from);
+                self.write_point(to);
+                self.write_f32(*on_length);
+                self.write_f32(*off_length);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawRect { rect, paint } => {
+                self.write_u8(16);
+                self.write_rect(rect);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawRoundedRect { rect, radii, paint } => {
+                self.write_u8(17);
+                self.write_rect(rect);
+                self.write_radii(radii);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawRoundedRectDifference {
+                outer_rect,
+                outer_radii,
+                inner_rect,
+                inner_radii,
+                paint,
+            } => {
+                self.write_u8(18);
+                self.write_rect(outer_rect);
+                self.write_radii(outer_radii);
+                self.write_rect(inner_rect);
+                self.write_radii(inner_radii);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawOval { oval_bounds, paint } => {
+                self.write_u8(19);
+                self.write_rect(oval_bounds);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawPath { path, paint } => {
+                self.write_u8(20);
+                self.write_path(path);
+                self.write_paint(paint);
+            }
+            DisplayListOp::DrawShadow {
+                path,
+                color,
+                elevation,
+                occluder_is_transparent,
+                device_pixel_ratio,
+            } => {
+                self.write_u8(21);
+                self.write_path(path);
+                self.write_color(color);
+                self.write_f32(*elevation);
+                self.write_bool(*occluder_is_transparent);
+                self.write_f32(*device_pixel_ratio);
+            }
+            DisplayListOp::DrawTextureRect {
+                texture,
+                src_rect,
+                dst_rect,
+                sampling,
+                paint,
+            } => {
+                self.write_u8(22);
+                self.write_texture(texture);
+                self.write_rect(src_rect);
+                self.write_rect(dst_rect);
+                self.write_u8(*sampling as u8);
+                self.write_option(paint.as_ref(), Self::write_paint);
+            }
+            DisplayListOp::DrawTexture {
+                texture,
+                point,
+                sampling,
+                paint,
+            } => {
+                self.write_u8(23);
+                self.write_texture(texture);
+                self.write_point(point);
+                self.write_u8(*sampling as u8);
+                self.write_option(paint.as_ref(), Self::write_paint);
+            }
+            DisplayListOp::DrawParagraph {
+                location,
+                paragraph,
+            } => {
+                self.write_u8(24);
+                self.write_point(location);
+                self.write_paragraph(paragraph);
+            }
+            DisplayListOp::DrawDisplayList {
+                display_list,
+                opacity,
+            } => {
+                self.write_u8(25);
+                self.write_display_list(display_list);
+                self.write_f32(*opacity);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    textures: Vec<RecordedTexture>,
+    fonts: Vec<RecordedFonts>,
+}
+
+impl<'a> Reader<'a> {
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or("unexpected end of recorded display list")?;
+        let slice = &self.data[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, &'static str> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err("invalid boolean value"),
+        }
+    }
+
+    fn read_u32(&mut self) -> Result<u32, &'static str> {
+        let bytes = self.read_slice(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f32(&mut self) -> Result<f32, &'static str> {
+        Ok(f32::from_bits(self.read_u32()?))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.read_u32()? as usize;
+        self.read_slice(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, &'static str> {
+        let bytes = self.read_bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| "invalid UTF-8 string")
+    }
+
+    fn read_option<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, &'static str>,
+    ) -> Result<Option<T>, &'static str> {
+        if self.read_bool()? {
+            Ok(Some(read(self)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_f32_option(&mut self) -> Result<Option<f32>, &'static str> {
+        self.read_option(Self::read_f32)
+    }
+
+    fn read_enum<T: Clone>(&mut self, values: &[T]) -> Result<T, &'static str> {
+        values
+            .get(self.read_u8()? as usize)
+            .cloned()
+            .ok_or("invalid enum value")
+    }
+
+    fn read_point(&mut self) -> Result<PixelPoint, &'static str> {
+        Ok(PixelPoint::new(self.read_f32()?, self.read_f32()?))
+    }
+
+    fn read_size(&mut self) -> Result<PixelSize, &'static str> {
+        Ok(PixelSize::new(self.read_f32()?, self.read_f32()?))
+    }
+
+    fn read_rect(&mut self) -> Result<PixelRect, &'static str> {
+        Ok(PixelRect::new(self.read_point()?, self.read_size()?))
+    }
+
+    fn read_radii(&mut self) -> Result<RoundingRadii, &'static str> {
+        Ok(RoundingRadii {
+            top_left: self.read_size()?,
+            bottom_left: self.read_size()?,
+            top_right: self.read_size()?,
+            bottom_right: self.read_size()?,
+        })
+    }
+
+    fn read_matrix(&mut self) -> Result<Matrix, &'static str> {
+        let mut values = [0.0f32; 16];
+        for value in &mut values {
+            *value = self.read_f32()?;
+        }
+        Ok(Matrix::from_array(values))
+    }
+
+    fn read_color(&mut self) -> Result<Color, &'static str> {
+        Ok(Color {
+            red: self.read_f32()?,
+            green: self.read_f32()?,
+            blue: self.read_f32()?,
+            alpha: self.read_f32()?,
+            color_space: self.read_enum(&COLOR_SPACES)?,
+        })
+    }
+
+    fn read_colors(&mut self) -> Result<(Vec<Color>, Vec<f32>), &'static str> {
+        let mut colors = Vec::new();
+        for _ in 0..self.read_u32()? {
+            colors.push(self.read_color()?);
+        }
+        let mut stops = Vec::new();
+        for _ in 0..self.read_u32()? {
+            stops.push(self.read_f32()?);
+        }
+        Ok((colors, stops))
+    }
+
+    fn read_texture(&mut self) -> Result<RecordedTexture, &'static str> {
+        let index = self.read_u32()? as usize;
+        self.textures
+            .get(index)
+            .cloned()
+            .ok_or("invalid texture index")
+    }
+
+    fn read_fonts(&mut self) -> Result<RecordedFonts, &'static str> {
+        let index = self.read_u32()? as usize;
+        self.fonts.get(index).cloned().ok_or("invalid fonts index")
+    }
+
+    fn read_color_source(
+        &mut self,
+    ) -> Result<ColorSource<RecordedTexture, RecordedFragment>, &'static str> {
+        Ok(match self.read_u8()? {
+            0 => {
+                let start = self.read_point()?;
+                let end = self.read_point()?;
+                let (colors, stops) = self.read_colors()?;
+                ColorSource::LinearGradient {
+                    start,
+                    end,
+                    colors,
+                    stops,
+                    tile_mode: self.read_enum(&TILE_MODES)?,
+                    transformation: self.read_option(Self::read_matrix)?,
+                }
+            }
+            1 => {
+                let center = self.read_point()?;
+                let radius = self.read_f32()?;
+                let (colors, stops) = self.read_colors()?;
+                ColorSource::RadialGradient {
+                    center,
+                    radius,
+                    colors,
+                    stops,
+                    tile_mode: self.read_enum(&TILE_MODES)?,
+                    transformation: self.read_option(Self::read_matrix)?,
+                }
+            }
+            2 => {
+                let start_center = self.read_point()?;
+                let start_radius = self.read_f32()?;
+                let end_center = self.read_point()?;
+                let end_radius = self.read_f32()?;
+                let (colors, stops) = self.read_colors()?;
+                ColorSource::ConicalGradient {
+                    start_center,
+                    start_radius,
+                    end_center,
+                    end_radius,
+                    colors,
+                    stops,
+                    tile_mode: self.read_enum(&TILE_MODES)?,
+                    transformation: self.read_option(Self::read_matrix)?,
+                }
+            }
+            3 => {
+                let center = self.read_point()?;
+                let start = self.read_f32()?;
+                let end = self.read_f32()?;
+                let (colors, stops) = self.read_colors()?;
+                ColorSource::SweepGradient {
+                    center,
+                    start,
+                    end,
+                    colors,
+                    stops,
+                    tile_mode: self.read_enum(&TILE_MODES)?,
+                    transformation: self.read_option(Self::read_matrix)?,
+                }
+            }
+            4 => ColorSource::Image {
+                image: self.read_texture()?,
+                horizontal_tile_mode: self.read_enum(&TILE_MODES)?,
+                vertical_tile_mode: self.read_enum(&TILE_MODES)?,
+                sampling: self.read_enum(&TEXTURE_SAMPLINGS)?,
+                transformation: self.read_option(Self::read_matrix)?,
+            },
+            5 => ColorSource::Fragment {
+                color_source: RecordedFragment,
+            },
+            _ => return Err("invalid color source"),
+        })
+    }
+
+    fn read_color_filter(&mut self) -> Result<ColorFilter, &'static str> {
+        Ok(match self.read_u8()? {
+            0 => ColorFilter::Blend(self.read_color()?, self.read_enum(&BLEND_MODES)?),
+            1 => {
+                let mut m = [0.0f32; 20];
+                for value in &mut m {
+                    *value = self.read_f32()?;
+                }
+                ColorFilter::Matrix(ColorMatrix { m })
+            }
+            _ => return Err("invalid color filter"),
+        })
+    }
+
+    fn read_image_filter(&mut self) -> Result<ImageFilter<RecordedFragment>, &'static str> {
+        Ok(match self.read_u8()? {
+            0 => ImageFilter::Blur {
+                x_sigma: self.read_f32()?,
+                y_sigma: self.read_f32()?,
+                tile_mode: self.read_enum(&TILE_MODES)?,
+            },
+            1 => ImageFilter::Dilate {
+                x_radius: self.read_f32()?,
+                y_radius: self.read_f32()?,
+            },
+            2 => ImageFilter::Erode {
+                x_radius: self.read_f32()?,
+                y_radius: self.read_f32()?,
+            },
+            3 => ImageFilter::Matrix {
+                matrix: self.read_matrix()?,
+                sampling: self.read_enum(&TEXTURE_SAMPLINGS)?,
+            },
+            4 => ImageFilter::Fragment {
+                image_filter: RecordedFragment,
+            },
+            5 => ImageFilter::Compose {
+                outer: Box::new(self.read_image_filter()?),
+                inner: Box::new(self.read_image_filter()?),
+            },
+            _ => return Err("invalid image filter"),
+        })
+    }
+
+    fn read_mask_filter(&mut self) -> Result<MaskFilter, &'static str> {
+        Ok(match self.read_u8()? {
+            0 => MaskFilter::Blur {
+                style: self.read_enum(&BLUR_STYLES)?,
+                sigma: self.read_f32()?,
+            },
+            _ => return Err("invalid mask filter"),
+        })
+    }
+
+    fn read_paint(&mut self) -> Result<RecordedPaint, &'static str> {
+        Ok(RecordedPaint {
+            color: self.read_option(Self::read_color)?,
+            blend_mode: self.read_option(|reader| reader.read_enum(&BLEND_MODES))?,
+            draw_style: self.read_option(|reader| reader.read_enum(&DRAW_STYLES))?,
+            stroke_cap: self.read_option(|reader| reader.read_enum(&STROKE_CAPS))?,
+            stroke_join: self.read_option(|reader| reader.read_enum(&STROKE_JOINS))?,
+            stroke_width: self.read_f32_option()?,
+            stroke_miter: self.read_f32_option()?,
+            color_source: self.read_option(Self::read_color_source)?,
+            color_filter: self.read_option(Self::read_color_filter)?,
+            image_filter: self.read_option(Self::read_image_filter)?,
+            mask_filter: self.read_option(Self::read_mask_filter)?,
+        })
+    }
+
+    fn read_path(&mut self) -> Result<RecordedPath, &'static str> {
+        let fill_type = self.read_enum(&FILL_TYPES)?;
+        let mut ops = Vec::new();
+        for _ in 0..self.read_u32()? {
+            ops.push(match self.read_u8()? {
+                0 => PathOp::MoveTo(self.read_point()?),
+                1 => PathOp::LineTo(self.read_point()?),
+                2 => PathOp::QuadraticCurveTo(self.read_point()?, self.read_point()?),
+                3 => {
+                    PathOp::CubicCurveTo(self.read_point()?, self.read_point()?, self.read_point()?)
+                }
+                4 => PathOp::AddRect(self.read_rect()?),
+                5 => PathOp::AddRoundedRect(self.read_rect()?, self.read_radii()?),
+                6 => PathOp::AddOval(self.read_rect()?),
+                7 => PathOp::AddArc(self.read_rect()?, self.read_f32()?, self.read_f32()?),
+                8 => PathOp::Close,
+                _ => return Err("invalid path operation"),
+            });
+        }
+        Ok(RecordedPath { fill_type, ops })
+    }
+
+    fn read_paragraph_style(&mut self) -> Result<ParagraphStyle<RecordedPaint>, &'static str> {
+        Ok(ParagraphStyle {
+            foreground: self.read_option(Self::read_paint)?,
+            background: self.read_option(Self::read_paint)?,
+            weight: self.read_enum(&FONT_WEIGHTS)?,
+            style: self.read_enum(&FONT_STYLES)?,
+            family: self.read_string()?,
+            size: self.read_f32()?,
+            height_factor: self.read_f32_option()?,
+            text_alignment: self.read_enum(&TEXT_ALIGNMENTS)?,
+            text_direction: self.read_enum(&TEXT_DIRECTIONS)?,
+            text_decoration: self.read_option(|reader| {
+                Ok(TextDecoration {
+                    types: TextDecorationType {
+                        underline: reader.read_bool()?,
+                        overline: reader.read_bool()?,
+                        line_through: reader.read_bool()?,
+                    },
+                    color: reader.read_color()?,
+                    style: reader.read_enum(&TEXT_DECORATION_STYLES)?,
+                    thickness_multiplier: reader.read_f32()?,
+                })
+            })?,
+            max_lines: self.read_option(Self::read_u32)?,
+            ellipsis: self.read_option(Self::read_string)?,
+            locale: self.read_option(Self::read_string)?,
+        })
+    }
+
+    fn read_paragraph(&mut self) -> Result<RecordedParagraph, &'static str> {
+        let fonts = self.read_fonts()?;
+        let mut runs = Vec::new();
+        for _ in 0..self.read_u32()? {
+            runs.push(TextRun {
+                style: self.read_option(Self::read_paragraph_style)?,
+                text: self.read_string()?,
+            });
+        }
+        let width = self.read_f32()?;
+        Ok(RecordedParagraph::new(fonts, runs, width))
+    }
+
+    fn read_display_list(&mut self) -> Result<RecordedDisplayList, &'static str> {
+        let bounds = self.read_option(Self::read_rect)?;
+        let mut ops = Vec::new();
+        for _ in 0..self.read_u32()? {
+            ops.push(self.read_op()?);
+        }
+        Ok(RecordedDisplayList::new(bounds, ops))
+    }
+
+    fn read_op(&mut self) -> Result<DisplayListOp, &'static str> {
+        Ok(match self.read_u8()? {
+            0 => DisplayListOp::Scale {
+                x_scale: self.read_f32()?,
+                y_scale: self.read_f32()?,
+            },
+            1 => DisplayListOp::Rotate {
+                angle_degrees: self.read_f32()?,
+            },
+            2 => DisplayListOp::Translate {
+                x_translation: self.read_f32()?,
+                y_translation: self.read_f32()?,
+            },
+            3 => DisplayListOp::Transform {
+                transform: self.read_matrix()?,
+            },
+            4 => DisplayListOp::SetTransform {
+                transform: self.read_matrix()?,
+            },
+            5 => DisplayListOp::ResetTransform,
+            6 => DisplayListOp::ClipRect {
+                rect: self.read_rect()?,
+                operation: self.read_enum(&CLIP_OPERATIONS)?,
+            },
+            7 => DisplayListOp::ClipOval {
+                oval_bounds: self.read_rect()?,
+                operation: self.read_enum(&CLIP_OPERATIONS)?,
+            },
+            8 => DisplayListOp::ClipRoundedRect {
+                rect: self.read_rect()?,
+                radii: self.read_radii()?,
+                operation: self.read_enum(&CLIP_OPERATIONS)?,
+            },
+            9 => DisplayListOp::ClipPath {
+                path: self.read_path()?,
+                operation: self.read_enum(&CLIP_OPERATIONS)?,
+            },
+            10 => DisplayListOp::Save,
+            11 => DisplayListOp::SaveLayer {
+                bounds: self.read_rect()?,
+                paint: self.read_option(Self::read_paint)?,
+                filter: self.read_option(Self::read_image_filter)?,
+            },
+            12 => DisplayListOp::Restore,
+            13 => DisplayListOp::DrawPaint {
+                paint: self.read_paint()?,
+            },
+            14 => DisplayListOp::DrawLine {
+                from: self.read_point()?,
+                to: self.read_point()?,
+                paint: self.read_paint()?,
+            },
+            15 => DisplayListOp::DrawDashedLine {
+                from: self.read_point()?,
+                to: self.read_point()?,
+                on_length: self.read_f32()?,
+                off_length: self.read_f32()?,
+                paint: self.read_paint()?,
+            },
+            16 => DisplayListOp::DrawRect {
+                rect: self.read_rect()?,
+                paint: self.read_paint()?,
+            },
+            17 => DisplayListOp::DrawRoundedRect {
+                rect: self.read_rect()?,
+                radii: self.read_radii()?,
+                paint: self.read_paint()?,
+            },
+            18 => DisplayListOp::DrawRoundedRectDifference {
+                outer_rect: self.read_rect()?,
+                outer_radii: self.read_radii()?,
+                inner_rect: self.read_rect()?,
+                inner_radii: self.read_radii()?,
+                paint: self.read_paint()?,
+            },
+            19 => DisplayListOp::DrawOval {
+                oval_bounds: self.read_rect()?,
+                paint: self.read_paint()?,
+            },
+            20 => DisplayListOp::DrawPath {
+                path: self.read_path()?,
+                paint: self.read_paint()?,
+            },
+            21 => DisplayListOp::DrawShadow {
+                path: self.read_path()?,
+                color: self.read_color()?,
+                elevation: self.read_f32()?,
+                occluder_is_transparent: self.read_bool()?,
+                device_pixel_ratio: self.read_f32()?,
+            },
+            22 => DisplayListOp::DrawTextureRect {
+                texture: self.read_texture()?,
+                src_rect: self.read_rect()?,
+                dst_rect: self.read_rect()?,
+                sampling: self.read_enum(&TEXTURE_SAMPLINGS)?,
+                paint: self.read_option(Self::read_paint)?,
+            },
+            23 => DisplayListOp::DrawTexture {
+                texture: self.read_texture()?,
+                point: self.read_point()?,
+                sampling: self.read_enum(&TEXTURE_SAMPLINGS)?,
+                paint: self.read_option(Self::read_paint)?,
+            },
+            24 => DisplayListOp::DrawParagraph {
+                location: self.read_point()?,
+                paragraph: self.read_paragraph()?,
+            },
+            25 => DisplayListOp::DrawDisplayList {
+                display_list: self.read_display_list()?,
+                opacity: self.read_f32()?,
+            },
+            _ => return Err("invalid display list operation"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::smart_pointers::OptRef;
+    use crate::{
+        ClipOperation, Color, ColorFormat, ColorSource, DisplayListBuilder, Fonts, ImageFilter,
+        MaskFilter, Paint, ParagraphBuilder, ParagraphStyle, PathBuilder, PixelData, PixelPoint,
+        PixelRect, PixelSize, RoundingRadii, TextureSampling, TileMode,
+    };
+
+    use super::super::{
+        RecordedDisplayList, RecordedFonts, RecordedPaint, RecordedParagraphBuilder,
+        RecordedPathBuilder, RecordedTexture, RecordingDisplayListBuilder,
+    };
+    use super::{MAGIC, VERSION};
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> PixelRect {
+        PixelRect::new(PixelPoint::new(x, y), PixelSize::new(width, height))
+    }
+
+    fn create_display_list() -> RecordedDisplayList {
+        let texture = RecordedTexture::new(PixelData {
+            data: (0..32).collect(),
+            width: 2,
+            height: 4,
+            row_stride: 8,
+            color_format: ColorFormat::RGBA,
+            premultiplied: false,
+        });
+
+        let mut fonts = RecordedFonts::default();
+        fonts
+            .register_font(Cow::Borrowed(b"font data"), Some("serif"))
+            .unwrap();
+        let mut paragraph_builder = RecordedParagraphBuilder::new(&fonts).unwrap();
+        paragraph_builder.push_style(ParagraphStyle {
+            foreground: Some(RecordedPaint::color(Color::rgb(0.0f32, 0.0f32, 1.0f32))),
+            family: "serif".to_string(),
+            size: 12.0f32,
+            height_factor: Some(1.5f32),
+            ..Default::default()
+        });
+        paragraph_builder.add_text("Hello\nworld");
+        let paragraph = paragraph_builder.build(100.0f32).unwrap();
+
+        let mut path_builder = RecordedPathBuilder::default();
+        path_builder.move_to((1.0f32, 2.0f32));
+        path_builder.line_to((10.0f32, 2.0f32));
+        path_builder.quadratic_curve_to((15.0f32, 5.0f32), (10.0f32, 10.0f32));
+        path_builder.add_oval(rect(0.0f32, 0.0f32, 5.0f32, 5.0f32));
+        path_builder.close();
+        let path = path_builder.build();
+
+        let mut nested = RecordingDisplayListBuilder::new(None);
+        nested.draw_rect(
+            rect(0.0f32, 0.0f32, 4.0f32, 4.0f32),
+            RecordedPaint::color(Color::rgba(1.0f32, 0.0f32, 0.0f32, 0.5f32)),
+        );
+        let nested = nested.build().unwrap();
+
+        let mut paint = RecordedPaint::color(Color::rgb(0.0f32, 1.0f32, 0.0f32));
+        paint.set_color_source(ColorSource::LinearGradient {
+            start: PixelPoint::new(0.0f32, 0.0f32),
+            end: PixelPoint::new(10.0f32, 0.0f32),
+            colors: vec![
+                Color::rgb(1.0f32, 0.0f32, 0.0f32),
+                Color::rgb(0.0f32, 0.0f32, 1.0f32),
+            ],
+            stops: vec![0.0f32, 1.0f32],
+            tile_mode: TileMode::Mirror,
+            transformation: None,
+        });
+        paint.set_mask_filter(MaskFilter::Blur {
+            style: crate::BlurStyle::Normal,
+            sigma: 2.0f32,
+        });
+
+        let mut builder = RecordingDisplayListBuilder::new(rect(0.0f32, 0.0f32, 64.0f32, 48.0f32));
+        builder.translate(5.0f32, 6.0f32);
+        builder.scale(2.0f32, 3.0f32);
+        builder.clip_rounded_rect(
+            rect(1.0f32, 1.0f32, 30.0f32, 20.0f32),
+            RoundingRadii::single_radii(4.0f32),
+            ClipOperation::Intersect,
+        );
+        builder.clip_path(&path, ClipOperation::Difference);
+        builder.save_layer(
+            rect(0.0f32, 0.0f32, 32.0f32, 24.0f32),
+            Some(OptRef::Borrowed(&paint)),
+            Some(ImageFilter::Blur {
+                x_sigma: 1.0f32,
+                y_sigma: 2.0f32,
+                tile_mode: TileMode::Decal,
+            }),
+        );
+        builder.draw_path(&path, &paint);
+        builder.draw_texture(
+            &texture,
+            PixelPoint::new(3.0f32, 4.0f32),
+            TextureSampling::Linear,
+            None,
+        );
+        builder.draw_paragraph(PixelPoint::new(7.0f32, 8.0f32), &paragraph);
+        builder.restore();
+        builder.rotate(45.0f32);
+        builder.draw_display_list(&nested, 0.75f32);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let display_list = create_display_list();
+        let bytes = display_list.to_bytes();
+        let loaded = RecordedDisplayList::from_bytes(&bytes).unwrap();
+
+        assert_eq!(loaded.to_json(), display_list.to_json());
+        assert_eq!(loaded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn round_trip_empty_display_list() {
+        let display_list = RecordingDisplayListBuilder::new(None).build().unwrap();
+        let bytes = display_list.to_bytes();
+        let loaded = RecordedDisplayList::from_bytes(&bytes).unwrap();
+
+        assert_eq!(loaded.get_bounds(), None);
+        assert!(loaded.get_ops().is_empty());
+        assert_eq!(loaded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        let bytes = create_display_list().to_bytes();
+        for len in 0..bytes.len() {
+            assert!(
+                RecordedDisplayList::from_bytes(&bytes[..len]).is_err(),
+                "{len} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_input_is_rejected() {
+        let bytes = create_display_list().to_bytes();
+
+        let mut data = bytes.clone();
+        data[0] = b'X';
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+
+        let mut data = bytes.clone();
+        data.push(0);
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+
+        // the header of the first texture: width, height, row stride, color format and premultiplied
+        let texture = MAGIC.len() + 8;
+        let mut data = bytes.clone();
+        data[texture + 12] = 0xff;
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+
+        let mut data = bytes.clone();
+        data[texture + 13] = 2;
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+
+        let mut data = bytes.clone();
+        data[texture + 8..texture + 12].copy_from_slice(&1u32.to_le_bytes());
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+
+        // no textures and fonts, the display list without bounds and with one operation
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.push(0xff);
+        assert!(RecordedDisplayList::from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut bytes = create_display_list().to_bytes();
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert!(RecordedDisplayList::from_bytes(&bytes).is_err());
+
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&0u32.to_le_bytes());
+        assert!(RecordedDisplayList::from_bytes(&bytes).is_err());
+    }
+}
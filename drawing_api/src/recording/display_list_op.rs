@@ -0,0 +1,148 @@
+use crate::{
+    ClipOperation, Color, ImageFilter, Matrix, PixelPoint, PixelRect, RoundingRadii,
+    TextureSampling,
+};
+
+use super::{
+    RecordedDisplayList, RecordedFragment, RecordedPaint, RecordedParagraph, RecordedPath,
+    RecordedTexture,
+};
+
+/// Single call of the display list builder.
+#[derive(Clone)]
+pub enum DisplayListOp {
+    Scale {
+        x_scale: f32,
+        y_scale: f32,
+    },
+
+    Rotate {
+        angle_degrees: f32,
+    },
+
+    Translate {
+        x_translation: f32,
+        y_translation: f32,
+    },
+
+    Transform {
+        transform: Matrix,
+    },
+
+    SetTransform {
+        transform: Matrix,
+    },
+
+    ResetTransform,
+
+    ClipRect {
+        rect: PixelRect,
+        operation: ClipOperation,
+    },
+
+    ClipOval {
+        oval_bounds: PixelRect,
+        operation: ClipOperation,
+    },
+
+    ClipRoundedRect {
+        rect: PixelRect,
+        radii: RoundingRadii,
+        operation: ClipOperation,
+    },
+
+    ClipPath {
+        path: RecordedPath,
+        operation: ClipOperation,
+    },
+
+    Save,
+
+    SaveLayer {
+        bounds: PixelRect,
+        paint: Option<RecordedPaint>,
+        filter: Option<ImageFilter<RecordedFragment>>,
+    },
+
+    Restore,
+
+    DrawPaint {
+        paint: RecordedPaint,
+    },
+
+    DrawLine {
+        from: PixelPoint,
+        to: PixelPoint,
+        paint: RecordedPaint,
+    },
+
+    DrawDashedLine {
+        from: PixelPoint,
+        to: PixelPoint,
+        on_length: f32,
+        off_length: f32,
+        paint: RecordedPaint,
+    },
+
+    DrawRect {
+        rect: PixelRect,
+        paint: RecordedPaint,
+    },
+
+    DrawRoundedRect {
+        rect: PixelRect,
+        radii: RoundingRadii,
+        paint: RecordedPaint,
+    },
+
+    DrawRoundedRectDifference {
+        outer_rect: PixelRect,
+        outer_radii: RoundingRadii,
+        inner_rect: PixelRect,
+        inner_radii: RoundingRadii,
+        paint: RecordedPaint,
+    },
+
+    DrawOval {
+        oval_bounds: PixelRect,
+        paint: RecordedPaint,
+    },
+
+    DrawPath {
+        path: RecordedPath,
+        paint: RecordedPaint,
+    },
+
+    DrawShadow {
+        path: RecordedPath,
+        color: Color,
+        elevation: f32,
+        occluder_is_transparent: bool,
+        device_pixel_ratio: f32,
+    },
+
+    DrawTextureRect {
+        texture: RecordedTexture,
+        src_rect: PixelRect,
+        dst_rect: PixelRect,
+        sampling: TextureSampling,
+        paint: Option<RecordedPaint>,
+    },
+
+    DrawTexture {
+        texture: RecordedTexture,
+        point: PixelPoint,
+        sampling: TextureSampling,
+        paint: Option<RecordedPaint>,
+    },
+
+    DrawParagraph {
+        location: PixelPoint,
+        paragraph: RecordedParagraph,
+    },
+
+    DrawDisplayList {
+        display_list: RecordedDisplayList,
+        opacity: f32,
+    },
+}
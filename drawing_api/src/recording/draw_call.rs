@@ -0,0 +1,377 @@
+use crate::{euclid::Angle, ClipOperation, Matrix, PixelRect, RoundingRadii};
+
+use super::{DisplayListOp, RecordedDisplayList, RecordedPaint, RecordedPath};
+
+/// Shape of a clip.
+#[derive(Clone)]
+pub enum ClipShape {
+    Rect(PixelRect),
+    Oval(PixelRect),
+    RoundedRect(PixelRect, RoundingRadii),
+    Path(RecordedPath),
+}
+
+/// Clip in effect during a drawing call.
+#[derive(Clone)]
+pub struct Clip {
+    pub shape: ClipShape,
+    pub operation: ClipOperation,
+
+    /// The full transformation at the time the clip was applied.
+    pub transform: Matrix,
+}
+
+/// Drawing call together with the state of the display list builder.
+#[derive(Clone)]
+pub struct DrawCall {
+    /// One of the `Draw*` operations or `SaveLayer`.
+    pub op: DisplayListOp,
+
+    /// The full transformation (including the transformations of the outer display lists).
+    pub transform: Matrix,
+
+    /// The clips in effect, starting from the outermost one.
+    pub clips: Vec<Clip>,
+
+    /// The opacity of the outer display lists.
+    pub opacity: f32,
+}
+
+impl DrawCall {
+    /// Returns the paint of the operation (if it has one).
+    pub fn get_paint(&self) -> Option<&RecordedPaint> {
+        match &self.op {
+            DisplayListOp::DrawPaint { paint }
+            | DisplayListOp::DrawLine { paint, .. }
+            | DisplayListOp::DrawDashedLine { paint, .. }
+            | DisplayListOp::DrawRect { paint, .. }
+            | DisplayListOp::DrawRoundedRect { paint, .. }
+            | DisplayListOp::DrawRoundedRectDifference { paint, .. }
+            | DisplayListOp::DrawOval { paint, .. }
+            | DisplayListOp::DrawPath { paint, .. } => Some(paint),
+            DisplayListOp::SaveLayer { paint, .. }
+            | DisplayListOp::DrawTextureRect { paint, .. }
+            | DisplayListOp::DrawTexture { paint, .. } => paint.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves the state of the builder for every drawing call.
+/// The nested display lists are flattened.
+pub(crate) fn collect_draw_calls(
+    display_list: &RecordedDisplayList,
+    base_transform: &Matrix,
+    base_clips: &[Clip],
+    opacity: f32,
+    draw_calls: &mut Vec<DrawCall>,
+) {
+    // the transformation relative to the display list
+    let mut transform = Matrix::identity();
+    let mut clips = base_clips.to_vec();
+
+    // transformations and clip counts of the outer save levels
+    let mut save_stack = Vec::new();
+
+    for op in display_list.ops.iter() {
+        let full_transform = transform.then(base_transform);
+        let mut push_clip = |shape: ClipShape, operation: ClipOperation| {
+            clips.push(Clip {
+                shape,
+                operation,
+                transform: full_transform,
+            });
+        };
+        match op {
+            DisplayListOp::Scale { x_scale, y_scale } => {
+                transform = Matrix::scale(*x_scale, *y_scale, 1.0f32).then(&transform);
+            }
+            DisplayListOp::Rotate { angle_degrees } => {
+                transform =
+                    Matrix::rotation(0.0f32, 0.0f32, 1.0f32, Angle::degrees(*angle_degrees))
+                        .then(&transform);
+            }
+            DisplayListOp::Translate {
+                x_translation,
+                y_translation,
+            } => {
+                transform =
+                    Matrix::translation(*x_translation, *y_translation, 0.0f32).then(&transform);
+            }
+            DisplayListOp::Transform {
+                transform: relative_transform,
+            } => {
+                transform = relative_transform.then(&transform);
+            }
+            DisplayListOp::SetTransform {
+                transform: new_transform,
+            } => {
+                transform = *new_transform;
+            }
+            DisplayListOp::ResetTransform => {
+                transform = Matrix::identity();
+            }
+            DisplayListOp::ClipRect { rect, operation } => {
+                push_clip(ClipShape::Rect(*rect), *operation);
+            }
+            DisplayListOp::ClipOval {
+                oval_bounds,
+                operation,
+            } => {
+                push_clip(ClipShape::Oval(*oval_bounds), *operation);
+            }
+            DisplayListOp::ClipRoundedRect {
+                rect,
+                radii,
+                operation,
+            } => {
+                push_clip(ClipShape::RoundedRect(*rect, radii.clone()), *operation);
+            }
+            DisplayListOp::ClipPath { path, operation } => {
+                push_clip(ClipShape::Path(path.clone()), *operation);
+            }
+            DisplayListOp::Save => {
+                save_stack.push((transform, clips.len()));
+            }
+            DisplayListOp::SaveLayer { .. } => {
+                draw_calls.push(DrawCall {
+                    op: op.clone(),
+                    transform: full_transform,
+                    clips: clips.clone(),
+                    opacity,
+                });
+                save_stack.push((transform, clips.len()));
+            }
+            DisplayListOp::Restore => {
+                if let Some((saved_transform, clip_count)) = save_stack.pop() {
+                    transform = saved_transform;
+                    clips.truncate(clip_count);
+                }
+            }
+            DisplayListOp::DrawDisplayList {
+                display_list,
+                opacity: nested_opacity,
+            } => {
+                collect_draw_calls(
+                    display_list,
+                    &full_transform,
+                    &clips,
+                    opacity * nested_opacity,
+                    draw_calls,
+                );
+            }
+            _ => {
+                draw_calls.push(DrawCall {
+                    op: op.clone(),
+                    transform: full_transform,
+                    clips: clips.clone(),
+                    opacity,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smart_pointers::OptRef;
+    use crate::{
+        ClipOperation, Color, DisplayListBuilder, Matrix, Paint, PixelPoint, PixelRect, PixelSize,
+    };
+
+    use super::super::{
+        DisplayListOp, RecordedDisplayList, RecordedPaint, RecordingDisplayListBuilder,
+    };
+    use super::{ClipShape, DrawCall};
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> PixelRect {
+        PixelRect::new(PixelPoint::new(x, y), PixelSize::new(width, height))
+    }
+
+    fn draw_rect(builder: &mut RecordingDisplayListBuilder, rect: PixelRect) {
+        builder.draw_rect(
+            rect,
+            RecordedPaint::color(Color::rgb(1.0f32, 0.0f32, 0.0f32)),
+        );
+    }
+
+    /// Returns the rectangle drawn by the call.
+    fn get_rect(draw_call: &DrawCall) -> PixelRect {
+        match &draw_call.op {
+            DisplayListOp::DrawRect { rect, .. } => *rect,
+            _ => panic!("not a rectangle"),
+        }
+    }
+
+    fn create_nested_display_list(opacity: f32) -> RecordedDisplayList {
+        let mut inner = RecordingDisplayListBuilder::new(None);
+        draw_rect(&mut inner, rect(0.0f32, 0.0f32, 1.0f32, 1.0f32));
+        let inner = inner.build().unwrap();
+
+        let mut nested = RecordingDisplayListBuilder::new(None);
+        nested.translate(1.0f32, 2.0f32);
+        nested.clip_oval(
+            rect(0.0f32, 0.0f32, 4.0f32, 4.0f32),
+            ClipOperation::Intersect,
+        );
+        draw_rect(&mut nested, rect(0.0f32, 0.0f32, 2.0f32, 2.0f32));
+        nested.draw_display_list(&inner, opacity);
+        nested.build().unwrap()
+    }
+
+    #[test]
+    fn nested_display_lists_are_flattened() {
+        let nested = create_nested_display_list(0.5f32);
+
+        let mut builder = RecordingDisplayListBuilder::new(None);
+        builder.translate(10.0f32, 20.0f32);
+        builder.save();
+        builder.scale(2.0f32, 2.0f32);
+        builder.clip_rect(
+            rect(0.0f32, 0.0f32, 8.0f32, 8.0f32),
+            ClipOperation::Difference,
+        );
+        builder.draw_display_list(&nested, 0.5f32);
+        builder.restore();
+        draw_rect(&mut builder, rect(0.0f32, 0.0f32, 3.0f32, 3.0f32));
+        let draw_calls = builder.build().unwrap().get_draw_calls();
+
+        assert_eq!(draw_calls.len(), 3);
+        let outer_transform = Matrix::scale(2.0f32, 2.0f32, 1.0f32)
+            .then(&Matrix::translation(10.0f32, 20.0f32, 0.0f32));
+        let nested_transform = Matrix::translation(1.0f32, 2.0f32, 0.0f32).then(&outer_transform);
+
+        // the outer clip stays in the outer coordinates, the nested clip is added after it
+        for draw_call in &draw_calls[0..2] {
+            assert_eq!(draw_call.transform, nested_transform);
+            assert_eq!(draw_call.clips.len(), 2);
+            assert!(matches!(draw_call.clips[0].shape, ClipShape::Rect(_)));
+            assert!(matches!(
+                draw_call.clips[0].operation,
+                ClipOperation::Difference
+            ));
+            assert_eq!(draw_call.clips[0].transform, outer_transform);
+            assert!(matches!(draw_call.clips[1].shape, ClipShape::Oval(_)));
+            assert_eq!(draw_call.clips[1].transform, nested_transform);
+        }
+        assert_eq!(
+            get_rect(&draw_calls[0]).size,
+            PixelSize::new(2.0f32, 2.0f32)
+        );
+        assert_eq!(draw_calls[0].opacity, 0.5f32);
+
+        // the opacities of the nested levels are multiplied
+        assert_eq!(
+            get_rect(&draw_calls[1]).size,
+            PixelSize::new(1.0f32, 1.0f32)
+        );
+        assert_eq!(draw_calls[1].opacity, 0.25f32);
+
+        // the state of the outer save level is restored
+        assert_eq!(
+            get_rect(&draw_calls[2]).size,
+            PixelSize::new(3.0f32, 3.0f32)
+        );
+        assert_eq!(
+            draw_calls[2].transform,
+            Matrix::translation(10.0f32, 20.0f32, 0.0f32)
+        );
+        assert!(draw_calls[2].clips.is_empty());
+        assert_eq!(draw_calls[2].opacity, 1.0f32);
+    }
+
+    #[test]
+    fn nested_display_list_does_not_change_the_outer_state() {
+        let mut nested = RecordingDisplayListBuilder::new(None);
+        nested.set_transform(&Matrix::scale(3.0f32, 3.0f32, 1.0f32));
+        nested.clip_rect(
+            rect(0.0f32, 0.0f32, 4.0f32, 4.0f32),
+            ClipOperation::Intersect,
+        );
+        draw_rect(&mut nested, rect(0.0f32, 0.0f32, 2.0f32, 2.0f32));
+
+        // unbalanced restore is ignored
+        nested.restore();
+        let nested = nested.build().unwrap();
+
+        let mut builder = RecordingDisplayListBuilder::new(None);
+        builder.translate(10.0f32, 20.0f32);
+        builder.draw_display_list(&nested, 1.0f32);
+        draw_rect(&mut builder, rect(0.0f32, 0.0f32, 3.0f32, 3.0f32));
+        let draw_calls = builder.build().unwrap().get_draw_calls();
+
+        // the transformation set in the nested display list is relative to the outer one
+        assert_eq!(
+            draw_calls[0].transform,
+            Matrix::scale(3.0f32, 3.0f32, 1.0f32)
+                .then(&Matrix::translation(10.0f32, 20.0f32, 0.0f32))
+        );
+        assert_eq!(draw_calls[0].clips.len(), 1);
+
+        assert_eq!(
+            draw_calls[1].transform,
+            Matrix::translation(10.0f32, 20.0f32, 0.0f32)
+        );
+        assert!(draw_calls[1].clips.is_empty());
+    }
+
+    #[test]
+    fn save_layer_is_a_draw_call_and_a_save_level() {
+        let mut builder = RecordingDisplayListBuilder::new(None);
+        builder.translate(10.0f32, 20.0f32);
+        builder.save_layer(
+            rect(0.0f32, 0.0f32, 8.0f32, 8.0f32),
+            Some(OptRef::Owned(RecordedPaint::color(Color::rgba(
+                0.0f32, 0.0f32, 0.0f32, 0.5f32,
+            )))),
+            None,
+        );
+        builder.rotate(90.0f32);
+        builder.clip_rect(
+            rect(0.0f32, 0.0f32, 4.0f32, 4.0f32),
+            ClipOperation::Intersect,
+        );
+        builder.save();
+        builder.scale(2.0f32, 2.0f32);
+        draw_rect(&mut builder, rect(0.0f32, 0.0f32, 2.0f32, 2.0f32));
+        builder.restore();
+        draw_rect(&mut builder, rect(0.0f32, 0.0f32, 1.0f32, 1.0f32));
+        builder.restore();
+        draw_rect(&mut builder, rect(0.0f32, 0.0f32, 3.0f32, 3.0f32));
+        let draw_calls = builder.build().unwrap().get_draw_calls();
+
+        assert_eq!(draw_calls.len(), 4);
+        let translation = Matrix::translation(10.0f32, 20.0f32, 0.0f32);
+        let layer_transform = Matrix::rotation(
+            0.0f32,
+            0.0f32,
+            1.0f32,
+            crate::euclid::Angle::degrees(90.0f32),
+        )
+        .then(&translation);
+
+        assert!(matches!(draw_calls[0].op, DisplayListOp::SaveLayer { .. }));
+        assert_eq!(draw_calls[0].transform, translation);
+        assert!(draw_calls[0].clips.is_empty());
+        assert_eq!(
+            draw_calls[0]
+                .get_paint()
+                .and_then(|paint| paint.color.as_ref())
+                .map(|color| color.alpha),
+            Some(0.5f32)
+        );
+
+        assert_eq!(
+            draw_calls[1].transform,
+            Matrix::scale(2.0f32, 2.0f32, 1.0f32).then(&layer_transform)
+        );
+        assert_eq!(draw_calls[1].clips.len(), 1);
+        assert_eq!(draw_calls[1].clips[0].transform, layer_transform);
+
+        assert_eq!(draw_calls[2].transform, layer_transform);
+        assert_eq!(draw_calls[2].clips.len(), 1);
+
+        assert_eq!(draw_calls[3].transform, translation);
+        assert!(draw_calls[3].clips.is_empty());
+    }
+}
@@ -0,0 +1,687 @@
+use std::fmt::Write;
+
+use crate::{
+    Color, ColorFilter, ColorSource, ImageFilter, MaskFilter, Matrix, ParagraphStyle, PixelPoint,
+    PixelRect, PixelSize, RoundingRadii,
+};
+
+use super::{
+    DisplayListOp, PathOp, RecordedDisplayList, RecordedFonts, RecordedFragment, RecordedPaint,
+    RecordedParagraph, RecordedPath, RecordedTexture,
+};
+
+/// JSON value with the keys of the objects in the insertion order.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn object(op: &'static str) -> Self {
+        Json::Object(vec![("op", Json::String(op.to_string()))])
+    }
+
+    fn with(mut self, key: &'static str, value: impl Into<Json>) -> Self {
+        if let Json::Object(entries) = &mut self {
+            entries.push((key, value.into()));
+        }
+        self
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Number(value) if value.is_finite() => write!(out, "{}", value).unwrap(),
+            Json::Number(_) => out.push_str("null"),
+            Json::String(value) => write_string(out, value),
+            Json::Array(values) if values.iter().all(Json::is_number) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    value.write(out, indent);
+                }
+                out.push(']');
+            }
+            Json::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    out.push_str(if i > 0 { ",\n" } else { "\n" });
+                    push_indent(out, indent + 1);
+                    value.write(out, indent + 1);
+                }
+                if !values.is_empty() {
+                    out.push('\n');
+                    push_indent(out, indent);
+                }
+                out.push(']');
+            }
+            Json::Object(entries) => {
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    out.push_str(if i > 0 { ",\n" } else { "\n" });
+                    push_indent(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                if !entries.is_empty() {
+                    out.push('\n');
+                    push_indent(out, indent);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Json::Number(_))
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<f32> for Json {
+    fn from(value: f32) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(value: Vec<T>) -> Self {
+        Json::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Enums are written as the names of their variants.
+fn name(value: impl std::fmt::Debug) -> Json {
+    Json::String(format!("{:?}", value))
+}
+
+pub(crate) fn write_display_list(display_list: &RecordedDisplayList) -> String {
+    let mut writer = Writer::default();
+    let root = writer.display_list(display_list);
+
+    let textures = writer
+        .textures
+        .iter()
+        .map(|texture| {
+            let pixels = texture.get_pixels();
+            Json::Object(Vec::new())
+                .with("width", pixels.width)
+                .with("height", pixels.height)
+                .with("color_format", name(pixels.color_format))
+                .with("premultiplied", pixels.premultiplied)
+        })
+        .collect::<Vec<_>>();
+    let fonts = writer
+        .fonts
+        .iter()
+        .map(|fonts| {
+            fonts
+                .get_fonts()
+                .iter()
+                .map(|font| {
+                    Json::Object(Vec::new())
+                        .with("family_name_alias", font.family_name_alias.as_deref())
+                        .with("size", font.data.len())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let document = Json::Object(Vec::new())
+        .with("textures", textures)
+        .with("fonts", fonts)
+        .with("display_list", root);
+
+    let mut out = String::new();
+    document.write(&mut out, 0);
+    out.push('\n');
+    out
+}
+
+#[derive(Default)]
+struct Writer {
+    // textures and fonts referenced by the index
+    textures: Vec<RecordedTexture>,
+    fonts: Vec<RecordedFonts>,
+}
+
+impl Writer {
+    fn texture(&mut self, texture: &RecordedTexture) -> Json {
+        let index = match self.textures.iter().position(|t| t.ptr_eq(texture)) {
+            Some(index) => index,
+            None => {
+                self.textures.push(texture.clone());
+                self.textures.len() - 1
+            }
+        };
+        index.into()
+    }
+
+    fn fonts(&mut self, fonts: &RecordedFonts) -> Json {
+        let index = match self.fonts.iter().position(|f| f.ptr_eq(fonts)) {
+            Some(index) => index,
+            None => {
+                self.fonts.push(fonts.clone());
+                self.fonts.len() - 1
+            }
+        };
+        index.into()
+    }
+
+    fn color_source(
+        &mut self,
+        color_source: &ColorSource<RecordedTexture, RecordedFragment>,
+    ) -> Json {
+        match color_source {
+            ColorSource::LinearGradient {
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => Json::Object(Vec::new())
+                .with("type", "LinearGradient")
+                .with("start", point(start))
+                .with("end", point(end))
+                .with("colors", colors.iter().map(color).collect::<Vec<_>>())
+                .with("stops", stops.clone())
+                .with("tile_mode", name(tile_mode))
+                .with("transformation", transformation.as_ref().map(matrix)),
+            ColorSource::RadialGradient {
+                center,
+                radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => Json::Object(Vec::new())
+                .with("type", "RadialGradient")
+                .with("center", point(center))
+                .with("radius", *radius)
+                .with("colors", colors.iter().map(color).collect::<Vec<_>>())
+                .with("stops", stops.clone())
+                .with("tile_mode", name(tile_mode))
+                .with("transformation", transformation.as_ref().map(matrix)),
+            ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => Json::Object(Vec::new())
+                .with("type", "ConicalGradient")
+                .with("start_center", point(start_center))
+                .with("start_radius", *start_radius)
+                .with("end_center", point(end_center))
+                .with("end_radius", *end_radius)
+                .with("colors", colors.iter().map(color).collect::<Vec<_>>())
+                .with("stops", stops.clone())
+                .with("tile_mode", name(tile_mode))
+                .with("transformation", transformation.as_ref().map(matrix)),
+            ColorSource::SweepGradient {
+                center,
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => Json::Object(Vec::new())
+                .with("type", "SweepGradient")
+                .with("center", point(center))
+                .with("start", *start)
+                .with("end", *end)
+                .with("colors", colors.iter().map(color).collect::<Vec<_>>())
+                .with("stops", stops.clone())
+                .with("tile_mode", name(tile_mode))
+                .with("transformation", transformation.as_ref().map(matrix)),
+            ColorSource::Image {
+                image,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
+                transformation,
+            } => Json::Object(Vec::new())
+                .with("type", "Image")
+                .with("texture", self.texture(image))
+                .with("horizontal_tile_mode", name(horizontal_tile_mode))
+                .with("vertical_tile_mode", name(vertical_tile_mode))
+                .with("sampling", name(sampling))
+                .with("transformation", transformation.as_ref().map(matrix)),
+            ColorSource::Fragment { .. } => Json::Object(Vec::new()).with("type", "Fragment"),
+        }
+    }
+
+    fn paint(&mut self, paint: &RecordedPaint) -> Json {
+        let color_source = paint
+            .color_source
+            .as_ref()
+            .map(|color_source| self.color_source(color_source));
+        Json::Object(Vec::new())
+            .with("color", paint.color.as_ref().map(color))
+            .with("blend_mode", paint.blend_mode.map(name))
+            .with("draw_style", paint.draw_style.map(name))
+            .with("stroke_cap", paint.stroke_cap.map(name))
+            .with("stroke_join", paint.stroke_join.map(name))
+            .with("stroke_width", paint.stroke_width)
+            .with("stroke_miter", paint.stroke_miter)
+            .with("color_source", color_source)
+            .with(
+                "color_filter",
+                paint.color_filter.as_ref().map(color_filter),
+            )
+            .with(
+                "image_filter",
+                paint.image_filter.as_ref().map(image_filter),
+            )
+            .with("mask_filter", paint.mask_filter.as_ref().map(mask_filter))
+    }
+
+    fn paragraph_style(&mut self, style: &ParagraphStyle<RecordedPaint>) -> Json {
+        let foreground = style.foreground.as_ref().map(|paint| self.paint(paint));
+        let background = style.background.as_ref().map(|paint| self.paint(paint));
+        let text_decoration = style.text_decoration.as_ref().map(|decoration| {
+            Json::Object(Vec::new())
+                .with("underline", decoration.types.underline)
+                .with("overline", decoration.types.overline)
+                .with("line_through", decoration.types.line_through)
+                .with("color", color(&decoration.color))
+                .with("style", name(decoration.style))
+                .with("thickness_multiplier", decoration.thickness_multiplier)
+        });
+        Json::Object(Vec::new())
+            .with("foreground", foreground)
+            .with("background", background)
+            .with("weight", name(style.weight))
+            .with("style", name(style.style))
+            .with("family", style.family.as_str())
+            .with("size", style.size)
+            .with("height_factor", style.height_factor)
+            .with("text_alignment", name(style.text_alignment))
+            .with("text_direction", name(style.text_direction))
+            .with("text_decoration", text_decoration)
+            .with("max_lines", style.max_lines)
+            .with("ellipsis", style.ellipsis.as_deref())
+            .with("locale", style.locale.as_deref())
+    }
+
+    fn paragraph(&mut self, paragraph: &RecordedParagraph) -> Json {
+        let runs = paragraph
+            .runs
+            .iter()
+            .map(|run| {
+                let style = run.style.as_ref().map(|style| self.paragraph_style(style));
+                Json::Object(Vec::new())
+                    .with("style", style)
+                    .with("text", run.text.as_str())
+            })
+            .collect::<Vec<_>>();
+        Json::Object(Vec::new())
+            .with("fonts", self.fonts(&paragraph.fonts))
+            .with("runs", runs)
+            .with("width", paragraph.width)
+    }
+
+    fn display_list(&mut self, display_list: &RecordedDisplayList) -> Json {
+        let ops = display_list
+            .ops
+            .iter()
+            .map(|op| self.op(op))
+            .collect::<Vec<_>>();
+        Json::Object(Vec::new())
+            .with("bounds", display_list.bounds.as_ref().map(rect))
+            .with("ops", ops)
+    }
+
+    fn op(&mut self, op: &DisplayListOp) -> Json {
+        match op {
+            DisplayListOp::Scale { x_scale, y_scale } => Json::object("Scale")
+                .with("x_scale", *x_scale)
+                .with("y_scale", *y_scale),
+            DisplayListOp::Rotate { angle_degrees } => {
+                Json::object("Rotate").with("angle_degrees", *angle_degrees)
+            }
+            DisplayListOp::Translate {
+                x_translation,
+                y_translation,
+            } => Json::object("Translate")
+                .with("x_translation", *x_translation)
+                .with("y_translation", *y_translation),
+            DisplayListOp::Transform { transform } => {
+                Json::object("Transform").with("transform", matrix(transform))
+            }
+            DisplayListOp::SetTransform { transform } => {
+                Json::object("SetTransform").with("transform", matrix(transform))
+            }
+            DisplayListOp::ResetTransform => Json::object("ResetTransform"),
+            DisplayListOp::ClipRect { rect: r, operation } => Json::object("ClipRect")
+                .with("rect", rect(r))
+                .with("operation", name(operation)),
+            DisplayListOp::ClipOval {
+                oval_bounds,
+                operation,
+            } => Json::object("ClipOval")
+                .with("oval_bounds", rect(oval_bounds))
+                .with("operation", name(operation)),
+            DisplayListOp::ClipRoundedRect {
+                rect: r,
+                radii: rr,
+                operation,
+            } => Json::object("ClipRoundedRect")
+                .with("rect", rect(r))
+                .with("radii", radii(rr))
+                .with("operation", name(operation)),
+            DisplayListOp::ClipPath { path: p, operation } => Json::object("ClipPath")
+                .with("path", path(p))
+                .with("operation", name(operation)),
+            DisplayListOp::Save => Json::object("Save"),
+            DisplayListOp::SaveLayer {
+                bounds,
+                paint,
+                filter,
+            } => {
+                let paint = paint.as_ref().map(|paint| self.paint(paint));
+                Json::object("SaveLayer")
+                    .with("bounds", rect(bounds))
+                    .with("paint", paint)
+                    .with("filter", filter.as_ref().map(image_filter))
+            }
+            DisplayListOp::Restore => Json::object("Restore"),
+            DisplayListOp::DrawPaint { paint } => {
+                Json::object("DrawPaint").with("paint", self.paint(paint))
+            }
+            DisplayListOp::DrawLine { from, to, paint } => Json::object("DrawLine")
+                .with("from", point(from))
+                .with("to", point(to))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawDashedLine {
+                from,
+                to,
+                on_length,
+                off_length,
+                paint,
+            } => Json::object("DrawDashedLine")
+                .with("from", point(from))
+                .with("to", point(to))
+                .with("on_length", *on_length)
+                .with("off_length", *off_length)
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawRect { rect: r, paint } => Json::object("DrawRect")
+                .with("rect", rect(r))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawRoundedRect {
+                rect: r,
+                radii: rr,
+                paint,
+            } => Json::object("DrawRoundedRect")
+                .with("rect", rect(r))
+                .with("radii", radii(rr))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawRoundedRectDifference {
+                outer_rect,
+                outer_radii,
+                inner_rect,
+                inner_radii,
+                paint,
+            } => Json::object("DrawRoundedRectDifference")
+                .with("outer_rect", rect(outer_rect))
+                .with("outer_radii", radii(outer_radii))
+                .with("inner_rect", rect(inner_rect))
+                .with("inner_radii", radii(inner_radii))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawOval { oval_bounds, paint } => Json::object("DrawOval")
+                .with("oval_bounds", rect(oval_bounds))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawPath { path: p, paint } => Json::object("DrawPath")
+                .with("path", path(p))
+                .with("paint", self.paint(paint)),
+            DisplayListOp::DrawShadow {
+                path: p,
+                color: c,
+                elevation,
+                occluder_is_transparent,
+                device_pixel_ratio,
+            } => Json::object("DrawShadow")
+                .with("path", path(p))
+                .with("color", color(c))
+                .with("elevation", *elevation)
+                .with("occluder_is_transparent", *occluder_is_transparent)
+                .with("device_pixel_ratio", *device_pixel_ratio),
+            DisplayListOp::DrawTextureRect {
+                texture,
+                src_rect,
+                dst_rect,
+                sampling,
+                paint,
+            } => {
+                let paint = paint.as_ref().map(|paint| self.paint(paint));
+                Json::object("DrawTextureRect")
+                    .with("texture", self.texture(texture))
+                    .with("src_rect", rect(src_rect))
+                    .with("dst_rect", rect(dst_rect))
+                    .with("sampling", name(sampling))
+                    .with("paint", paint)
+            }
+            DisplayListOp::DrawTexture {
+                texture,
+                point: p,
+                sampling,
+                paint,
+            } => {
+                let paint = paint.as_ref().map(|paint| self.paint(paint));
+                Json::object("DrawTexture")
+                    .with("texture", self.texture(texture))
+                    .with("point", point(p))
+                    .with("sampling", name(sampling))
+                    .with("paint", paint)
+            }
+            DisplayListOp::DrawParagraph {
+                location,
+                paragraph,
+            } => Json::object("DrawParagraph")
+                .with("location", point(location))
+                .with("paragraph", self.paragraph(paragraph)),
+            DisplayListOp::DrawDisplayList {
+                display_list,
+                opacity,
+            } => Json::object("DrawDisplayList")
+                .with("display_list", self.display_list(display_list))
+                .with("opacity", *opacity),
+        }
+    }
+}
+
+fn point(point: &PixelPoint) -> Json {
+    vec![point.x, point.y].into()
+}
+
+fn size(size: &PixelSize) -> Json {
+    vec![size.width, size.height].into()
+}
+
+/// Rectangles are written as `[x, y, width, height]`.
+fn rect(rect: &PixelRect) -> Json {
+    vec![
+        rect.origin.x,
+        rect.origin.y,
+        rect.size.width,
+        rect.size.height,
+    ]
+    .into()
+}
+
+fn radii(radii: &RoundingRadii) -> Json {
+    Json::Object(Vec::new())
+        .with("top_left", size(&radii.top_left))
+        .with("bottom_left", size(&radii.bottom_left))
+        .with("top_right", size(&radii.top_right))
+        .with("bottom_right", size(&radii.bottom_right))
+}
+
+fn matrix(matrix: &Matrix) -> Json {
+    matrix.to_array().to_vec().into()
+}
+
+fn color(color: &Color) -> Json {
+    Json::Object(Vec::new())
+        .with("red", color.red)
+        .with("green", color.green)
+        .with("blue", color.blue)
+        .with("alpha", color.alpha)
+        .with("color_space", name(&color.color_space))
+}
+
+fn color_filter(color_filter: &ColorFilter) -> Json {
+    match color_filter {
+        ColorFilter::Blend(c, blend_mode) => Json::Object(Vec::new())
+            .with("type", "Blend")
+            .with("color", color(c))
+            .with("blend_mode", name(blend_mode)),
+        ColorFilter::Matrix(color_matrix) => Json::Object(Vec::new())
+            .with("type", "Matrix")
+            .with("matrix", color_matrix.m.to_vec()),
+    }
+}
+
+fn image_filter(filter: &ImageFilter<RecordedFragment>) -> Json {
+    match filter {
+        ImageFilter::Blur {
+            x_sigma,
+            y_sigma,
+            tile_mode,
+        } => Json::Object(Vec::new())
+            .with("type", "Blur")
+            .with("x_sigma", *x_sigma)
+            .with("y_sigma", *y_sigma)
+            .with("tile_mode", name(tile_mode)),
+        ImageFilter::Dilate { x_radius, y_radius } => Json::Object(Vec::new())
+            .with("type", "Dilate")
+            .with("x_radius", *x_radius)
+            .with("y_radius", *y_radius),
+        ImageFilter::Erode { x_radius, y_radius } => Json::Object(Vec::new())
+            .with("type", "Erode")
+            .with("x_radius", *x_radius)
+            .with("y_radius", *y_radius),
+        ImageFilter::Matrix {
+            matrix: m,
+            sampling,
+        } => Json::Object(Vec::new())
+            .with("type", "Matrix")
+            .with("matrix", matrix(m))
+            .with("sampling", name(sampling)),
+        ImageFilter::Fragment { .. } => Json::Object(Vec::new()).with("type", "Fragment"),
+        ImageFilter::Compose { outer, inner } => Json::Object(Vec::new())
+            .with("type", "Compose")
+            .with("outer", image_filter(outer))
+            .with("inner", image_filter(inner)),
+    }
+}
+
+fn mask_filter(mask_filter: &MaskFilter) -> Json {
+    match mask_filter {
+        MaskFilter::Blur { style, sigma } => Json::Object(Vec::new())
+            .with("type", "Blur")
+            .with("style", name(style))
+            .with("sigma", *sigma),
+    }
+}
+
+fn path(path: &RecordedPath) -> Json {
+    let ops = path
+        .ops
+        .iter()
+        .map(|op| match op {
+            PathOp::MoveTo(p) => Json::object("MoveTo").with("point", point(p)),
+            PathOp::LineTo(p) => Json::object("LineTo").with("point", point(p)),
+            PathOp::QuadraticCurveTo(control_point, end_point) => Json::object("QuadraticCurveTo")
+                .with("control_point", point(control_point))
+                .with("end_point", point(end_point)),
+            PathOp::CubicCurveTo(control_point_1, control_point_2, end_point) => {
+                Json::object("CubicCurveTo")
+                    .with("control_point_1", point(control_point_1))
+                    .with("control_point_2", point(control_point_2))
+                    .with("end_point", point(end_point))
+            }
+            PathOp::AddRect(r) => Json::object("AddRect").with("rect", rect(r)),
+            PathOp::AddRoundedRect(r, rr) => Json::object("AddRoundedRect")
+                .with("rect", rect(r))
+                .with("radii", radii(rr)),
+            PathOp::AddOval(oval_bounds) => {
+                Json::object("AddOval").with("oval_bounds", rect(oval_bounds))
+            }
+            PathOp::AddArc(oval_bounds, start_angle_degrees, end_angle_degrees) => {
+                Json::object("AddArc")
+                    .with("oval_bounds", rect(oval_bounds))
+                    .with("start_angle_degrees", *start_angle_degrees)
+                    .with("end_angle_degrees", *end_angle_degrees)
+            }
+            PathOp::Close => Json::object("Close"),
+        })
+        .collect::<Vec<_>>();
+    Json::Object(Vec::new())
+        .with("fill_type", name(path.fill_type))
+        .with("ops", ops)
+}
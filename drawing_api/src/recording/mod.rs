@@ -0,0 +1,53 @@
+//! Backend independent display lists.
+//!
+//! `RecordingDisplayListBuilder` records every call into a `RecordedDisplayList`
+//! which can be saved in a binary format, exported to JSON for inspection
+//! and replayed into the display list builder of any backend.
+//!
+//! `RecordingContext` is a drawing context using the recorded types.
+//! It doesn't need any graphics API, so it can be used to test the drawing code.
+
+mod binary_format;
+
+mod display_list_op;
+pub use display_list_op::*;
+
+mod draw_call;
+pub use draw_call::*;
+
+mod json_format;
+
+mod recorded_display_list;
+pub use recorded_display_list::*;
+
+mod recorded_fonts;
+pub use recorded_fonts::*;
+
+mod recorded_fragment;
+pub use recorded_fragment::*;
+
+mod recorded_paint;
+pub use recorded_paint::*;
+
+mod recorded_paragraph;
+pub use recorded_paragraph::*;
+
+mod recorded_path;
+pub use recorded_path::*;
+
+mod recorded_texture;
+pub use recorded_texture::*;
+
+mod recording_context;
+pub use recording_context::*;
+
+mod recording_display_list_builder;
+pub use recording_display_list_builder::*;
+
+mod recording_fragment_program;
+pub use recording_fragment_program::*;
+
+mod recording_surface;
+pub use recording_surface::*;
+
+mod replay;
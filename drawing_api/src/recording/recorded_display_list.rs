@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::{DisplayListBuilder, Matrix, Paint, ParagraphBuilder, PixelRect};
+
+use super::{
+    binary_format, collect_draw_calls, json_format, replay::Replay, DisplayListOp, DrawCall,
+    RecordedTexture,
+};
+
+/// Backend independent display list.
+///
+/// Created with `RecordingDisplayListBuilder`.
+#[derive(Clone)]
+pub struct RecordedDisplayList {
+    pub(crate) bounds: Option<PixelRect>,
+    pub(crate) ops: Arc<Vec<DisplayListOp>>,
+}
+
+impl RecordedDisplayList {
+    pub fn new(bounds: Option<PixelRect>, ops: Vec<DisplayListOp>) -> Self {
+        Self {
+            bounds,
+            ops: Arc::new(ops),
+        }
+    }
+
+    /// Returns the cull rectangle the display list was created with.
+    pub fn get_bounds(&self) -> Option<PixelRect> {
+        self.bounds
+    }
+
+    pub fn get_ops(&self) -> &[DisplayListOp] {
+        &self.ops
+    }
+
+    /// Returns the drawing calls with the transformations, clips and opacity they were made with.
+    ///
+    /// The nested display lists are flattened. Useful to test the drawing code.
+    pub fn get_draw_calls(&self) -> Vec<DrawCall> {
+        let mut draw_calls = Vec::new();
+        collect_draw_calls(self, &Matrix::identity(), &[], 1.0f32, &mut draw_calls);
+        draw_calls
+    }
+
+    /// Saves the display list (with the textures and fonts) in the binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary_format::write_display_list(self)
+    }
+
+    /// Loads the display list saved with `to_bytes()`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, &'static str> {
+        binary_format::read_display_list(data)
+    }
+
+    /// Exports the display list to JSON for inspection.
+    ///
+    /// The pixels of the textures and the font data are not included.
+    pub fn to_json(&self) -> String {
+        json_format::write_display_list(self)
+    }
+
+    /// Replays the recorded calls into the display list builder of a backend.
+    ///
+    /// The textures are skipped, use `replay_into_with_textures()`
+    /// to create them with the drawing context of the backend.
+    pub fn replay_into<B>(&self, builder: &mut B) -> Result<(), &'static str>
+    where
+        B: DisplayListBuilder,
+        B::Paint: Paint<Texture = B::Texture>,
+        B::ParagraphBuilder: ParagraphBuilder<Paint = B::Paint>,
+    {
+        self.replay_into_with_textures(builder, |_| None)
+    }
+
+    /// Replays the recorded calls into the display list builder of a backend.
+    ///
+    /// The `resolve_texture` function is called once for every texture,
+    /// the drawing commands using textures it returns `None` for are skipped.
+    pub fn replay_into_with_textures<B>(
+        &self,
+        builder: &mut B,
+        resolve_texture: impl FnMut(&RecordedTexture) -> Option<B::Texture>,
+    ) -> Result<(), &'static str>
+    where
+        B: DisplayListBuilder,
+        B::Paint: Paint<Texture = B::Texture>,
+        B::ParagraphBuilder: ParagraphBuilder<Paint = B::Paint>,
+    {
+        Replay::new(resolve_texture).replay(self, builder)
+    }
+}
+
+impl crate::DisplayList for RecordedDisplayList {}
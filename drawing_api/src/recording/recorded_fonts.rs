@@ -0,0 +1,43 @@
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+/// Font registered in `RecordedFonts`.
+#[derive(Clone)]
+pub struct RecordedFont {
+    pub data: Arc<Cow<'static, [u8]>>,
+    pub family_name_alias: Option<String>,
+}
+
+/// Fonts used by recorded paragraphs.
+///
+/// The font data is kept, so the display list can be saved
+/// together with the fonts and replayed on another machine.
+#[derive(Clone, Default)]
+pub struct RecordedFonts {
+    pub(crate) fonts: Arc<Mutex<Vec<RecordedFont>>>,
+}
+
+impl RecordedFonts {
+    pub fn get_fonts(&self) -> Vec<RecordedFont> {
+        self.fonts.lock().unwrap().clone()
+    }
+
+    /// Returns whether both objects refer to the same set of fonts.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.fonts, &other.fonts)
+    }
+}
+
+impl crate::Fonts for RecordedFonts {
+    fn register_font(
+        &mut self,
+        font_data: Cow<'static, [u8]>,
+        family_name_alias: Option<&str>,
+    ) -> Result<(), &'static str> {
+        self.fonts.lock().unwrap().push(RecordedFont {
+            data: Arc::new(font_data),
+            family_name_alias: family_name_alias.map(|alias| alias.to_string()),
+        });
+        Ok(())
+    }
+}
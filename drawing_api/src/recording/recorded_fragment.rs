@@ -0,0 +1,10 @@
+/// Placeholder for fragment program color sources and image filters.
+///
+/// Shader programs are backend specific, so only the fact
+/// that a fragment was used is recorded. Fragments are skipped on replay.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RecordedFragment;
+
+impl crate::ColorSourceFragment for RecordedFragment {}
+
+impl crate::ImageFilterFragment for RecordedFragment {}
@@ -0,0 +1,75 @@
+use crate::{
+    BlendMode, Color, ColorFilter, ColorSource, DrawStyle, ImageFilter, MaskFilter, StrokeCap,
+    StrokeJoin,
+};
+
+use super::{RecordedFragment, RecordedTexture};
+
+/// Paint of a recorded display list.
+///
+/// Only the properties which were set are stored (`None` means
+/// the default value of the backend the display list is replayed into).
+#[derive(Clone, Default)]
+pub struct RecordedPaint {
+    pub color: Option<Color>,
+    pub blend_mode: Option<BlendMode>,
+    pub draw_style: Option<DrawStyle>,
+    pub stroke_cap: Option<StrokeCap>,
+    pub stroke_join: Option<StrokeJoin>,
+    pub stroke_width: Option<f32>,
+    pub stroke_miter: Option<f32>,
+    pub color_source: Option<ColorSource<RecordedTexture, RecordedFragment>>,
+    pub color_filter: Option<ColorFilter>,
+    pub image_filter: Option<ImageFilter<RecordedFragment>>,
+    pub mask_filter: Option<MaskFilter>,
+}
+
+impl crate::Paint for RecordedPaint {
+    type ColorSourceFragment = RecordedFragment;
+    type ImageFilterFragment = RecordedFragment;
+    type Texture = RecordedTexture;
+
+    fn set_color(&mut self, color: impl Into<Color>) {
+        self.color = Some(color.into());
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = Some(blend_mode);
+    }
+
+    fn set_draw_style(&mut self, draw_style: DrawStyle) {
+        self.draw_style = Some(draw_style);
+    }
+
+    fn set_stroke_cap(&mut self, cap: StrokeCap) {
+        self.stroke_cap = Some(cap);
+    }
+
+    fn set_stroke_join(&mut self, join: StrokeJoin) {
+        self.stroke_join = Some(join);
+    }
+
+    fn set_stroke_width(&mut self, width: f32) {
+        self.stroke_width = Some(width);
+    }
+
+    fn set_stroke_miter(&mut self, miter: f32) {
+        self.stroke_miter = Some(miter);
+    }
+
+    fn set_color_source(&mut self, color_source: ColorSource<RecordedTexture, RecordedFragment>) {
+        self.color_source = Some(color_source);
+    }
+
+    fn set_color_filter(&mut self, color_filter: ColorFilter) {
+        self.color_filter = Some(color_filter);
+    }
+
+    fn set_image_filter(&mut self, image_filter: ImageFilter<RecordedFragment>) {
+        self.image_filter = Some(image_filter);
+    }
+
+    fn set_mask_filter(&mut self, mask_filter: MaskFilter) {
+        self.mask_filter = Some(mask_filter);
+    }
+}
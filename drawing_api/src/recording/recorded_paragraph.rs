@@ -0,0 +1,312 @@
+use std::sync::Arc;
+
+use crate::command_list::{
+    FontMetrics, GlyphInfo, LineMetrics, TextLayout, TextLayoutBuilder, VerticalMetrics,
+};
+use crate::{smart_pointers::OptRef, ParagraphStyle, Range};
+
+use super::{RecordedFonts, RecordedPaint, RecordedTexture};
+
+// deterministic font metrics (relative to the font size)
+const ADVANCE: f32 = 0.5f32;
+const ASCENT: f32 = 0.8f32;
+const DESCENT: f32 = 0.2f32;
+
+/// Text added with the same paragraph style.
+#[derive(Clone)]
+pub struct TextRun {
+    /// The style on top of the style stack (`None` if no style was pushed).
+    pub style: Option<ParagraphStyle<RecordedPaint>>,
+    pub text: String,
+}
+
+/// Paragraph of a recorded display list.
+///
+/// The metrics are deterministic and do not depend on the fonts
+/// (so the drawing code can be tested on any machine): every character
+/// is `0.5 * size` wide, the ascent is `0.8 * size` and the descent is `0.2 * size`
+/// (both multiplied by `height_factor`).
+/// The real layout is done by the backend the display list is replayed into.
+#[derive(Clone)]
+pub struct RecordedParagraph {
+    pub(crate) fonts: RecordedFonts,
+    pub(crate) runs: Arc<Vec<TextRun>>,
+
+    /// The layout width passed to `build()`.
+    pub(crate) width: f32,
+
+    layout: Arc<TextLayout>,
+}
+
+impl RecordedParagraph {
+    pub fn new(fonts: RecordedFonts, runs: Vec<TextRun>, width: f32) -> Self {
+        let layout = Arc::new(Self::layout(&runs, width));
+        Self {
+            fonts,
+            runs: Arc::new(runs),
+            width,
+            layout,
+        }
+    }
+
+    pub fn get_fonts(&self) -> &RecordedFonts {
+        &self.fonts
+    }
+
+    pub fn get_runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /// Returns the whole text of the paragraph.
+    pub fn get_text(&self) -> &str {
+        &self.layout.text
+    }
+
+    fn layout(runs: &[TextRun], width: f32) -> TextLayout {
+        let default_style = ParagraphStyle::<RecordedPaint>::default();
+        let mut layout = TextLayoutBuilder::new(width);
+        for run in runs {
+            let style = run.style.as_ref().unwrap_or(&default_style);
+            let font_metrics = FontMetrics {
+                ascent: style.size * ASCENT,
+                descent: style.size * DESCENT,
+            };
+            layout.add_run(
+                &run.text,
+                VerticalMetrics::new(font_metrics, style.size, style.height_factor),
+                |_| style.size * ADVANCE,
+            );
+        }
+        layout.build()
+    }
+}
+
+impl crate::Paragraph for RecordedParagraph {
+    type GlyphInfo = GlyphInfo;
+    type LineMetrics = LineMetrics;
+
+    fn get_max_width(&self) -> f32 {
+        self.width
+    }
+
+    fn get_height(&self) -> f32 {
+        self.layout.get_height()
+    }
+
+    fn get_longest_line_width(&self) -> f32 {
+        self.layout.get_longest_line_width()
+    }
+
+    fn get_min_intrinsic_width(&self) -> f32 {
+        self.layout.min_intrinsic_width
+    }
+
+    fn get_max_intrinsic_width(&self) -> f32 {
+        self.layout.max_intrinsic_width
+    }
+
+    fn get_ideographic_baseline(&self) -> f32 {
+        self.layout.get_ideographic_baseline()
+    }
+
+    fn get_alphabetic_baseline(&self) -> f32 {
+        self.layout.get_alphabetic_baseline()
+    }
+
+    fn get_line_count(&self) -> u32 {
+        self.layout.lines.len() as u32
+    }
+
+    fn get_line_metrics(&self) -> Option<Self::LineMetrics> {
+        Some(self.layout.get_line_metrics())
+    }
+
+    fn get_word_boundary_utf16(&self, code_unit_index: usize) -> Range {
+        self.layout.get_word_boundary_utf16(code_unit_index)
+    }
+
+    fn create_glyph_info_at_code_unit_index_utf16(
+        &self,
+        code_unit_index: usize,
+    ) -> Option<Self::GlyphInfo> {
+        self.layout
+            .create_glyph_info_at_code_unit_index_utf16(code_unit_index)
+    }
+
+    fn create_glyph_info_at_paragraph_coordinates(
+        &self,
+        x: f64,
+        y: f64,
+    ) -> Option<Self::GlyphInfo> {
+        self.layout.create_glyph_info_at_paragraph_coordinates(x, y)
+    }
+}
+
+pub struct RecordedParagraphBuilder {
+    fonts: RecordedFonts,
+    styles: Vec<ParagraphStyle<RecordedPaint>>,
+    runs: Vec<TextRun>,
+}
+
+impl crate::ParagraphBuilder for RecordedParagraphBuilder {
+    type Paragraph = RecordedParagraph;
+    type Paint = RecordedPaint;
+    type Fonts = RecordedFonts;
+    type Texture = RecordedTexture;
+
+    fn new(fonts: &Self::Fonts) -> Result<Self, &'static str> {
+        Ok(Self {
+            fonts: fonts.clone(),
+            styles: Vec::new(),
+            runs: Vec::new(),
+        })
+    }
+
+    fn push_style<'a>(&mut self, style: impl Into<OptRef<'a, ParagraphStyle<Self::Paint>>>) {
+        self.styles.push(style.into().to_owned());
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+    }
+
+    fn add_text(&mut self, text: &str) {
+        self.runs.push(TextRun {
+            style: self.styles.last().cloned(),
+            text: text.to_string(),
+        });
+    }
+
+    fn build(self, width: f32) -> Result<Self::Paragraph, &'static str> {
+        Ok(RecordedParagraph::new(self.fonts, self.runs, width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        GlyphInfo as _, Paragraph as _, ParagraphBuilder as _, ParagraphStyle, PixelPoint,
+        PixelRect, PixelSize,
+    };
+
+    use super::super::{RecordedFonts, RecordedPaint};
+    use super::{RecordedParagraph, RecordedParagraphBuilder};
+
+    fn style(size: f32, height_factor: Option<f32>) -> ParagraphStyle<RecordedPaint> {
+        ParagraphStyle {
+            size,
+            height_factor,
+            ..Default::default()
+        }
+    }
+
+    fn build(runs: &[(ParagraphStyle<RecordedPaint>, &str)]) -> RecordedParagraph {
+        let mut builder = RecordedParagraphBuilder::new(&RecordedFonts::default()).unwrap();
+        for (style, text) in runs {
+            builder.push_style(style);
+            builder.add_text(text);
+            builder.pop_style();
+        }
+        builder.build(f32::INFINITY).unwrap()
+    }
+
+    #[test]
+    fn metrics_depend_only_on_the_size() {
+        let paragraph = build(&[(style(10.0f32, None), "ab cd\nefg")]);
+
+        assert_eq!(paragraph.get_line_count(), 2);
+        assert_eq!(paragraph.get_height(), 20.0f32);
+        assert_eq!(paragraph.get_alphabetic_baseline(), 8.0f32);
+        assert_eq!(paragraph.get_ideographic_baseline(), 10.0f32);
+        assert_eq!(paragraph.get_longest_line_width(), 25.0f32);
+        assert_eq!(paragraph.get_max_intrinsic_width(), 25.0f32);
+        assert_eq!(paragraph.get_min_intrinsic_width(), 15.0f32);
+
+        let lines = paragraph.get_line_metrics().unwrap().lines;
+        assert_eq!(lines[0].baseline, 8.0f64);
+        assert_eq!(lines[0].width, 25.0f64);
+        assert!(lines[0].hardbreak);
+        assert_eq!((lines[0].start_utf16, lines[0].end_utf16), (0, 5));
+        assert_eq!(lines[1].baseline, 18.0f64);
+        assert_eq!(lines[1].width, 15.0f64);
+        assert!(!lines[1].hardbreak);
+        assert_eq!((lines[1].start_utf16, lines[1].end_utf16), (6, 9));
+    }
+
+    #[test]
+    fn height_factor_scales_the_line_height() {
+        let paragraph = build(&[(style(10.0f32, Some(1.5f32)), "ab")]);
+
+        assert_eq!(paragraph.get_height(), 15.0f32);
+        assert_eq!(paragraph.get_alphabetic_baseline(), 12.0f32);
+
+        let lines = paragraph.get_line_metrics().unwrap().lines;
+        assert_eq!(lines[0].ascent, 12.0f64);
+        assert_eq!(lines[0].descent, 3.0f64);
+        assert_eq!(lines[0].unscaled_ascent, 8.0f64);
+    }
+
+    #[test]
+    fn line_metrics_include_all_the_runs() {
+        let paragraph = build(&[(style(10.0f32, None), "ab"), (style(20.0f32, None), "cd")]);
+
+        assert_eq!(paragraph.get_line_count(), 1);
+        assert_eq!(paragraph.get_height(), 20.0f32);
+        assert_eq!(paragraph.get_alphabetic_baseline(), 16.0f32);
+        assert_eq!(paragraph.get_longest_line_width(), 30.0f32);
+
+        // the glyphs of both runs are on the same baseline
+        let glyph = paragraph
+            .create_glyph_info_at_code_unit_index_utf16(1)
+            .unwrap();
+        assert_eq!(
+            glyph.get_grapheme_cluster_bounds(),
+            PixelRect::new(
+                PixelPoint::new(5.0f32, 0.0f32),
+                PixelSize::new(5.0f32, 20.0f32)
+            )
+        );
+        let glyph = paragraph
+            .create_glyph_info_at_code_unit_index_utf16(2)
+            .unwrap();
+        assert_eq!(
+            glyph.get_grapheme_cluster_bounds(),
+            PixelRect::new(
+                PixelPoint::new(10.0f32, 0.0f32),
+                PixelSize::new(10.0f32, 20.0f32)
+            )
+        );
+    }
+
+    #[test]
+    fn glyphs_are_found_by_the_coordinates() {
+        let paragraph = build(&[(style(10.0f32, None), "ab cd\nefg")]);
+
+        let glyph = paragraph
+            .create_glyph_info_at_paragraph_coordinates(12.0f64, 14.0f64)
+            .unwrap();
+        assert_eq!(
+            (
+                glyph.get_grapheme_cluster_code_unit_range_begin_utf16(),
+                glyph.get_grapheme_cluster_code_unit_range_end_utf16()
+            ),
+            (8, 9)
+        );
+        assert_eq!(
+            glyph.get_grapheme_cluster_bounds(),
+            PixelRect::new(
+                PixelPoint::new(10.0f32, 10.0f32),
+                PixelSize::new(5.0f32, 10.0f32)
+            )
+        );
+
+        // the closest glyph of the line
+        let glyph = paragraph
+            .create_glyph_info_at_paragraph_coordinates(100.0f64, 2.0f64)
+            .unwrap();
+        assert_eq!(glyph.get_grapheme_cluster_code_unit_range_begin_utf16(), 4);
+
+        let word = paragraph.get_word_boundary_utf16(4);
+        assert_eq!((word.start, word.end), (3, 5));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{FillType, PixelPoint, PixelRect, RoundingRadii};
+
+/// Single call of the path builder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathOp {
+    MoveTo(PixelPoint),
+    LineTo(PixelPoint),
+    QuadraticCurveTo(PixelPoint, PixelPoint),
+    CubicCurveTo(PixelPoint, PixelPoint, PixelPoint),
+    AddRect(PixelRect),
+    AddRoundedRect(PixelRect, RoundingRadii),
+    AddOval(PixelRect),
+    AddArc(PixelRect, f32, f32),
+    Close,
+}
+
+/// Path of a recorded display list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedPath {
+    pub fill_type: FillType,
+    pub ops: Vec<PathOp>,
+}
+
+impl crate::Path for RecordedPath {
+    /// Returns the bounds of the control points (can be larger than the path).
+    fn get_bounds(&self) -> PixelRect {
+        let mut points = Vec::new();
+        for op in &self.ops {
+            match op {
+                PathOp::MoveTo(p) | PathOp::LineTo(p) => points.push(*p),
+                PathOp::QuadraticCurveTo(c, p) => points.extend([*c, *p]),
+                PathOp::CubicCurveTo(c1, c2, p) => points.extend([*c1, *c2, *p]),
+                PathOp::AddRect(rect)
+                | PathOp::AddRoundedRect(rect, _)
+                | PathOp::AddOval(rect)
+                | PathOp::AddArc(rect, _, _) => points.extend([rect.min(), rect.max()]),
+                PathOp::Close => (),
+            }
+        }
+        PixelRect::from_points(points)
+    }
+}
+
+pub struct RecordedPathBuilder {
+    path: RecordedPath,
+}
+
+impl Default for RecordedPathBuilder {
+    fn default() -> Self {
+        Self {
+            path: RecordedPath {
+                fill_type: FillType::NonZero,
+                ops: Vec::new(),
+            },
+        }
+    }
+}
+
+impl crate::PathBuilder for RecordedPathBuilder {
+    type Path = RecordedPath;
+
+    fn set_fill_type(&mut self, fill_type: FillType) {
+        self.path.fill_type = fill_type;
+    }
+
+    fn move_to(&mut self, location: impl Into<PixelPoint>) {
+        self.path.ops.push(PathOp::MoveTo(location.into()));
+    }
+
+    fn line_to(&mut self, location: impl Into<PixelPoint>) {
+        self.path.ops.push(PathOp::LineTo(location.into()));
+    }
+
+    fn quadratic_curve_to(
+        &mut self,
+        control_point: impl Into<PixelPoint>,
+        end_point: impl Into<PixelPoint>,
+    ) {
+        self.path.ops.push(PathOp::QuadraticCurveTo(
+            control_point.into(),
+            end_point.into(),
+        ));
+    }
+
+    fn cubic_curve_to(
+        &mut self,
+        control_point_1: impl Into<PixelPoint>,
+        control_point_2: impl Into<PixelPoint>,
+        end_point: impl Into<PixelPoint>,
+    ) {
+        self.path.ops.push(PathOp::CubicCurveTo(
+            control_point_1.into(),
+            control_point_2.into(),
+            end_point.into(),
+        ));
+    }
+
+    fn add_rect(&mut self, rect: impl Into<PixelRect>) {
+        self.path.ops.push(PathOp::AddRect(rect.into()));
+    }
+
+    fn add_rounded_rect(&mut self, rect: impl Into<PixelRect>, rounding_radii: &RoundingRadii) {
+        self.path
+            .ops
+            .push(PathOp::AddRoundedRect(rect.into(), rounding_radii.clone()));
+    }
+
+    fn add_oval(&mut self, oval_bounds: impl Into<PixelRect>) {
+        self.path.ops.push(PathOp::AddOval(oval_bounds.into()));
+    }
+
+    fn add_arc(
+        &mut self,
+        oval_bounds: impl Into<PixelRect>,
+        start_angle_degrees: f32,
+        end_angle_degrees: f32,
+    ) {
+        self.path.ops.push(PathOp::AddArc(
+            oval_bounds.into(),
+            start_angle_degrees,
+            end_angle_degrees,
+        ));
+    }
+
+    fn close(&mut self) {
+        self.path.ops.push(PathOp::Close);
+    }
+
+    fn build(self) -> Self::Path {
+        self.path
+    }
+
+    fn build_copy(&mut self) -> Self::Path {
+        self.path.clone()
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::{PixelData, PixelIntRect, TextureDescriptor};
+
+/// Texture of a recorded display list.
+///
+/// Keeps the pixels in memory, so they can be saved with the display list.
+#[derive(Clone)]
+pub struct RecordedTexture {
+    pub(crate) pixels: Arc<PixelData>,
+}
+
+impl RecordedTexture {
+    pub fn new(pixels: PixelData) -> Self {
+        Self {
+            pixels: Arc::new(pixels),
+        }
+    }
+
+    /// Copies the pixels of a texture of any backend
+    /// (converted to straight alpha, as expected by `create_texture()`).
+    pub fn from_texture(texture: &impl crate::Texture) -> Result<Self, &'static str> {
+        let descriptor = texture.get_descriptor();
+        let mut pixels = texture.read_pixels(PixelIntRect::new(
+            euclid::point2(0, 0),
+            euclid::size2(descriptor.width, descriptor.height),
+        ))?;
+        pixels.unpremultiply();
+        Ok(Self::new(pixels))
+    }
+
+    pub fn get_pixels(&self) -> &PixelData {
+        &self.pixels
+    }
+
+    /// Returns whether both objects refer to the same texture.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.pixels, &other.pixels)
+    }
+}
+
+impl crate::Texture for RecordedTexture {
+    fn get_descriptor(&self) -> TextureDescriptor {
+        TextureDescriptor {
+            width: self.pixels.width,
+            height: self.pixels.height,
+            color_format: self.pixels.color_format,
+            mip_count: 1,
+        }
+    }
+
+    fn get_gl_handle(&self) -> usize {
+        0
+    }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        self.pixels.crop(rect)
+    }
+}
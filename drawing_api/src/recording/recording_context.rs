@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+
+use crate::{
+    Capabilities, ColorFormat, ColorSource, DrawingContext, GraphicsApi, ImageFilter, PixelData,
+    TextureDescriptor,
+};
+
+use super::{
+    RecordedDisplayList, RecordedFonts, RecordedFragment, RecordedPaint, RecordedParagraphBuilder,
+    RecordedPathBuilder, RecordedTexture, RecordingDisplayListBuilder, RecordingFragmentProgram,
+    RecordingSurface,
+};
+
+/// Drawing context which doesn't render anything, it only records the display lists.
+///
+/// Useful to test the drawing code without any graphics API.
+#[derive(Clone, Default)]
+pub struct RecordingContext {}
+
+impl RecordingContext {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Creates a surface keeping the display lists drawn on it.
+    pub fn create_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<RecordingSurface, &'static str> {
+        Ok(RecordingSurface::new(width, height, color_format))
+    }
+}
+
+impl DrawingContext for RecordingContext {
+    type ColorSourceFragment = RecordedFragment;
+    type DisplayList = RecordedDisplayList;
+    type DisplayListBuilder = RecordingDisplayListBuilder;
+    type Fonts = RecordedFonts;
+    type FragmentProgram = RecordingFragmentProgram;
+    type ImageFilterFragment = RecordedFragment;
+    type Paint = RecordedPaint;
+    type ParagraphBuilder = RecordedParagraphBuilder;
+    type PathBuilder = RecordedPathBuilder;
+    type Surface = RecordingSurface;
+    type Texture = RecordedTexture;
+
+    fn get_api_capabilities(api: GraphicsApi) -> Option<Capabilities> {
+        match api {
+            GraphicsApi::Recording => Some(Capabilities {
+                api: GraphicsApi::Recording,
+                transformations: true,
+                layers: true,
+                rect_clipping: true,
+                path_clipping: true,
+                color_filters: true,
+                image_filters: true,
+                mask_filters: true,
+                textures: true,
+                text_metrics: true,
+                text_decorations: true,
+                shadows: true,
+                fragment_color_sources: true,
+                fragment_image_filters: true,
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_capabilities(&self) -> Capabilities {
+        RecordingContext::get_api_capabilities(GraphicsApi::Recording).unwrap()
+    }
+
+    unsafe fn create_texture(
+        &self,
+        contents: Cow<'static, [u8]>,
+        descriptor: TextureDescriptor,
+    ) -> Result<Self::Texture, &'static str> {
+        let row_stride = descriptor.width * descriptor.color_format.bytes_per_pixel() as u32;
+        let len = row_stride as usize * descriptor.height as usize;
+        if contents.len() < len {
+            return Err("not enough texture data");
+        }
+
+        Ok(RecordedTexture::new(PixelData {
+            data: contents[..len].to_vec(),
+            width: descriptor.width,
+            height: descriptor.height,
+            row_stride,
+            color_format: descriptor.color_format,
+            premultiplied: false,
+        }))
+    }
+
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        // nothing is rendered, so the texture stays transparent
+        let surface = RecordingSurface::new(width, height, color_format);
+        let texture = RecordedTexture::new(PixelData {
+            data: vec![0u8; width as usize * height as usize * color_format.bytes_per_pixel()],
+            width,
+            height,
+            row_stride: width * color_format.bytes_per_pixel() as u32,
+            color_format,
+            premultiplied: false,
+        });
+        Ok((surface, texture))
+    }
+
+    unsafe fn new_color_source_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ColorSource<Self::Texture, Self::ColorSourceFragment> {
+        ColorSource::Fragment {
+            color_source: RecordedFragment,
+        }
+    }
+
+    unsafe fn new_image_filter_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ImageFilter<Self::ImageFilterFragment> {
+        ImageFilter::Fragment {
+            image_filter: RecordedFragment,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::sync::Arc;
+
+use crate::{
+    euclid::Angle, smart_pointers::OptRef, ClipOperation, Color, ImageFilter, Matrix, PixelPoint,
+    PixelRect, RoundingRadii, TextureSampling,
+};
+
+use super::{
+    DisplayListOp, RecordedDisplayList, RecordedFragment, RecordedPaint, RecordedParagraph,
+    RecordedPath, RecordedTexture,
+};
+
+/// Display list builder which records all the calls.
+pub struct RecordingDisplayListBuilder {
+    bounds: Option<PixelRect>,
+    ops: Vec<DisplayListOp>,
+
+    // the full transformation of the current save level
+    transform: Matrix,
+
+    // transformations of the outer save levels
+    save_stack: Vec<Matrix>,
+}
+
+impl RecordingDisplayListBuilder {
+    fn push_transform(&mut self, relative_transform: &Matrix) {
+        self.transform = relative_transform.then(&self.transform);
+    }
+}
+
+impl crate::DisplayListBuilder for RecordingDisplayListBuilder {
+    type DisplayList = RecordedDisplayList;
+    type ImageFilterFragment = RecordedFragment;
+    type Paint = RecordedPaint;
+    type ParagraphBuilder = super::RecordedParagraphBuilder;
+    type PathBuilder = super::RecordedPathBuilder;
+    type Texture = RecordedTexture;
+
+    fn new(bounds: impl Into<Option<PixelRect>>) -> Self {
+        Self {
+            bounds: bounds.into(),
+            ops: Vec::new(),
+            transform: Matrix::identity(),
+            save_stack: Vec::new(),
+        }
+    }
+
+    fn scale(&mut self, x_scale: f32, y_scale: f32) {
+        self.push_transform(&Matrix::scale(x_scale, y_scale, 1.0f32));
+        self.ops.push(DisplayListOp::Scale { x_scale, y_scale });
+    }
+
+    fn rotate(&mut self, angle_degrees: f32) {
+        self.push_transform(&Matrix::rotation(
+            0.0f32,
+            0.0f32,
+            1.0f32,
+            Angle::degrees(angle_degrees),
+        ));
+        self.ops.push(DisplayListOp::Rotate { angle_degrees });
+    }
+
+    fn translate(&mut self, x_translation: f32, y_translation: f32) {
+        self.push_transform(&Matrix::translation(x_translation, y_translation, 0.0f32));
+        self.ops.push(DisplayListOp::Translate {
+            x_translation,
+            y_translation,
+        });
+    }
+
+    fn transform(&mut self, transform: &Matrix) {
+        self.push_transform(transform);
+        self.ops.push(DisplayListOp::Transform {
+            transform: *transform,
+        });
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = *transform;
+        self.ops.push(DisplayListOp::SetTransform {
+            transform: *transform,
+        });
+    }
+
+    fn get_transform(&self) -> Matrix {
+        self.transform
+    }
+
+    fn reset_transform(&mut self) {
+        self.transform = Matrix::identity();
+        self.ops.push(DisplayListOp::ResetTransform);
+    }
+
+    fn clip_rect(&mut self, rect: impl Into<PixelRect>, operation: ClipOperation) {
+        self.ops.push(DisplayListOp::ClipRect {
+            rect: rect.into(),
+            operation,
+        });
+    }
+
+    fn clip_oval(&mut self, oval_bounds: impl Into<PixelRect>, operation: ClipOperation) {
+        self.ops.push(DisplayListOp::ClipOval {
+            oval_bounds: oval_bounds.into(),
+            operation,
+        });
+    }
+
+    fn clip_rounded_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        radii: impl Into<OptRef<'a, RoundingRadii>>,
+        operation: ClipOperation,
+    ) {
+        self.ops.push(DisplayListOp::ClipRoundedRect {
+            rect: rect.into(),
+            radii: radii.into().to_owned(),
+            operation,
+        });
+    }
+
+    fn clip_path(&mut self, path: &RecordedPath, operation: ClipOperation) {
+        self.ops.push(DisplayListOp::ClipPath {
+            path: path.clone(),
+            operation,
+        });
+    }
+
+    fn save(&mut self) {
+        self.save_stack.push(self.transform);
+        self.ops.push(DisplayListOp::Save);
+    }
+
+    fn save_layer<'a>(
+        &mut self,
+        bounds: impl Into<PixelRect>,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+        filter: Option<ImageFilter<RecordedFragment>>,
+    ) {
+        self.save_stack.push(self.transform);
+        self.ops.push(DisplayListOp::SaveLayer {
+            bounds: bounds.into(),
+            paint: paint.into().map(|paint| paint.to_owned()),
+            filter,
+        });
+    }
+
+    fn get_save_count(&mut self) -> usize {
+        self.save_stack.len() + 1
+    }
+
+    fn restore(&mut self) {
+        if let Some(transform) = self.save_stack.pop() {
+            self.transform = transform;
+            self.ops.push(DisplayListOp::Restore);
+        }
+    }
+
+    fn draw_paint<'a>(&mut self, paint: impl Into<OptRef<'a, Self::Paint>>) {
+        self.ops.push(DisplayListOp::DrawPaint {
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_line<'a>(
+        &mut self,
+        from: impl Into<PixelPoint>,
+        to: impl Into<PixelPoint>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawLine {
+            from: from.into(),
+            to: to.into(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_dashed_line<'a>(
+        &mut self,
+        from: impl Into<PixelPoint>,
+        to: impl Into<PixelPoint>,
+        on_length: f32,
+        off_length: f32,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawDashedLine {
+            from: from.into(),
+            to: to.into(),
+            on_length,
+            off_length,
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawRect {
+            rect: rect.into(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_rounded_rect<'a>(
+        &mut self,
+        rect: impl Into<PixelRect>,
+        radii: impl Into<OptRef<'a, RoundingRadii>>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawRoundedRect {
+            rect: rect.into(),
+            radii: radii.into().to_owned(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_rounded_rect_difference<'a>(
+        &mut self,
+        outer_rect: impl Into<PixelRect>,
+        outer_radii: impl Into<OptRef<'a, RoundingRadii>>,
+        inner_rect: impl Into<PixelRect>,
+        inner_radii: impl Into<OptRef<'a, RoundingRadii>>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawRoundedRectDifference {
+            outer_rect: outer_rect.into(),
+            outer_radii: outer_radii.into().to_owned(),
+            inner_rect: inner_rect.into(),
+            inner_radii: inner_radii.into().to_owned(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_oval<'a>(
+        &mut self,
+        oval_bounds: impl Into<PixelRect>,
+        paint: impl Into<OptRef<'a, Self::Paint>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawOval {
+            oval_bounds: oval_bounds.into(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_path<'a>(&mut self, path: &RecordedPath, paint: impl Into<OptRef<'a, Self::Paint>>) {
+        self.ops.push(DisplayListOp::DrawPath {
+            path: path.clone(),
+            paint: paint.into().to_owned(),
+        });
+    }
+
+    fn draw_shadow(
+        &mut self,
+        path: &RecordedPath,
+        color: impl Into<Color>,
+        elevation: f32,
+        occluder_is_transparent: bool,
+        device_pixel_ratio: f32,
+    ) {
+        self.ops.push(DisplayListOp::DrawShadow {
+            path: path.clone(),
+            color: color.into(),
+            elevation,
+            occluder_is_transparent,
+            device_pixel_ratio,
+        });
+    }
+
+    fn draw_texture_rect<'a>(
+        &mut self,
+        texture: &RecordedTexture,
+        src_rect: impl Into<PixelRect>,
+        dst_rect: impl Into<PixelRect>,
+        sampling: TextureSampling,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawTextureRect {
+            texture: texture.clone(),
+            src_rect: src_rect.into(),
+            dst_rect: dst_rect.into(),
+            sampling,
+            paint: paint.into().map(|paint| paint.to_owned()),
+        });
+    }
+
+    fn draw_texture<'a>(
+        &mut self,
+        texture: &RecordedTexture,
+        point: impl Into<PixelPoint>,
+        sampling: TextureSampling,
+        paint: impl Into<Option<OptRef<'a, Self::Paint>>>,
+    ) {
+        self.ops.push(DisplayListOp::DrawTexture {
+            texture: texture.clone(),
+            point: point.into(),
+            sampling,
+            paint: paint.into().map(|paint| paint.to_owned()),
+        });
+    }
+
+    fn draw_paragraph(&mut self, location: impl Into<PixelPoint>, paragraph: &RecordedParagraph) {
+        self.ops.push(DisplayListOp::DrawParagraph {
+            location: location.into(),
+            paragraph: paragraph.clone(),
+        });
+    }
+
+    fn draw_display_list(&mut self, display_list: &RecordedDisplayList, opacity: f32) {
+        self.ops.push(DisplayListOp::DrawDisplayList {
+            display_list: display_list.clone(),
+            opacity,
+        });
+    }
+
+    fn build(self) -> Result<RecordedDisplayList, &'static str> {
+        Ok(RecordedDisplayList {
+            bounds: self.bounds,
+            ops: Arc::new(self.ops),
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Fragment program of the recording context (only the program data is kept).
+#[derive(Clone)]
+pub struct RecordingFragmentProgram {
+    program: Arc<Cow<'static, [u8]>>,
+}
+
+impl RecordingFragmentProgram {
+    pub fn get_program(&self) -> &[u8] {
+        &self.program
+    }
+}
+
+impl crate::FragmentProgram for RecordingFragmentProgram {
+    unsafe fn new(program: Cow<'static, [u8]>) -> Result<Self, &'static str> {
+        Ok(Self {
+            program: Arc::new(program),
+        })
+    }
+}
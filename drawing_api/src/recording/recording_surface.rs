@@ -0,0 +1,70 @@
+use crate::{check_pixel_rect, ColorFormat, PixelData, PixelIntRect};
+
+use super::RecordedDisplayList;
+
+/// Surface of the recording context.
+///
+/// Keeps the display lists drawn on it, so the tests can check them.
+pub struct RecordingSurface {
+    width: u32,
+    height: u32,
+    color_format: ColorFormat,
+    display_lists: Vec<RecordedDisplayList>,
+}
+
+impl RecordingSurface {
+    pub fn new(width: u32, height: u32, color_format: ColorFormat) -> Self {
+        Self {
+            width,
+            height,
+            color_format,
+            display_lists: Vec::new(),
+        }
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the display lists in the order they were drawn.
+    pub fn get_display_lists(&self) -> &[RecordedDisplayList] {
+        &self.display_lists
+    }
+
+    pub fn get_last_display_list(&self) -> Option<&RecordedDisplayList> {
+        self.display_lists.last()
+    }
+
+    /// Removes the display lists drawn so far.
+    pub fn clear(&mut self) {
+        self.display_lists.clear();
+    }
+}
+
+impl crate::Surface for RecordingSurface {
+    type DisplayList = RecordedDisplayList;
+
+    fn draw(&mut self, display_list: &Self::DisplayList) -> Result<(), &'static str> {
+        self.display_lists.push(display_list.clone());
+        Ok(())
+    }
+
+    /// Nothing is rendered, so the pixels are always transparent.
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        check_pixel_rect(rect, self.width, self.height)?;
+
+        let row_stride = rect.size.width * self.color_format.bytes_per_pixel() as u32;
+        Ok(PixelData {
+            data: vec![0u8; (row_stride * rect.size.height) as usize],
+            width: rect.size.width,
+            height: rect.size.height,
+            row_stride,
+            color_format: self.color_format,
+            premultiplied: false,
+        })
+    }
+
+    fn present(self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
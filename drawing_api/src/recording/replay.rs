@@ -0,0 +1,438 @@
+use crate::{
+    smart_pointers::OptRef, ColorSource, DisplayListBuilder, Fonts, ImageFilter, Paint,
+    ParagraphBuilder, ParagraphStyle, PathBuilder,
+};
+
+use super::{
+    DisplayListOp, PathOp, RecordedDisplayList, RecordedFonts, RecordedFragment, RecordedPaint,
+    RecordedParagraph, RecordedPath, RecordedTexture,
+};
+
+type FontsOf<B> = <<B as DisplayListBuilder>::ParagraphBuilder as ParagraphBuilder>::Fonts;
+
+/// State shared by the replayed display list and the nested display lists.
+pub(crate) struct Replay<B: DisplayListBuilder, T> {
+    resolve_texture: T,
+    textures: Vec<(RecordedTexture, Option<B::Texture>)>,
+    fonts: Vec<(RecordedFonts, FontsOf<B>)>,
+}
+
+impl<B, T> Replay<B, T>
+where
+    B: DisplayListBuilder,
+    B::Paint: Paint<Texture = B::Texture>,
+    B::ParagraphBuilder: ParagraphBuilder<Paint = B::Paint>,
+    T: FnMut(&RecordedTexture) -> Option<B::Texture>,
+{
+    pub fn new(resolve_texture: T) -> Self {
+        Self {
+            resolve_texture,
+            textures: Vec::new(),
+            fonts: Vec::new(),
+        }
+    }
+
+    pub fn replay(
+        &mut self,
+        display_list: &RecordedDisplayList,
+        builder: &mut B,
+    ) -> Result<(), &'static str> {
+        for op in display_list.ops.iter() {
+            match op {
+                DisplayListOp::Scale { x_scale, y_scale } => builder.scale(*x_scale, *y_scale),
+                DisplayListOp::Rotate { angle_degrees } => builder.rotate(*angle_degrees),
+                DisplayListOp::Translate {
+                    x_translation,
+                    y_translation,
+                } => builder.translate(*x_translation, *y_translation),
+                DisplayListOp::Transform { transform } => builder.transform(transform),
+                DisplayListOp::SetTransform { transform } => builder.set_transform(transform),
+                DisplayListOp::ResetTransform => builder.reset_transform(),
+                DisplayListOp::ClipRect { rect, operation } => builder.clip_rect(*rect, *operation),
+                DisplayListOp::ClipOval {
+                    oval_bounds,
+                    operation,
+                } => builder.clip_oval(*oval_bounds, *operation),
+                DisplayListOp::ClipRoundedRect {
+                    rect,
+                    radii,
+                    operation,
+                } => builder.clip_rounded_rect(*rect, radii, *operation),
+                DisplayListOp::ClipPath { path, operation } => {
+                    builder.clip_path(&build_path::<B::PathBuilder>(path), *operation)
+                }
+                DisplayListOp::Save => builder.save(),
+                DisplayListOp::SaveLayer {
+                    bounds,
+                    paint,
+                    filter,
+                } => {
+                    let paint = paint.as_ref().map(|paint| self.convert_paint(paint));
+                    let filter = filter.as_ref().and_then(convert_image_filter);
+                    builder.save_layer(*bounds, paint.map(OptRef::Owned), filter);
+                }
+                DisplayListOp::Restore => builder.restore(),
+                DisplayListOp::DrawPaint { paint } => builder.draw_paint(self.convert_paint(paint)),
+                DisplayListOp::DrawLine { from, to, paint } => {
+                    builder.draw_line(*from, *to, self.convert_paint(paint))
+                }
+                DisplayListOp::DrawDashedLine {
+                    from,
+                    to,
+                    on_length,
+                    off_length,
+                    paint,
+                } => builder.draw_dashed_line(
+                    *from,
+                    *to,
+                    *on_length,
+                    *off_length,
+                    self.convert_paint(paint),
+                ),
+                DisplayListOp::DrawRect { rect, paint } => {
+                    builder.draw_rect(*rect, self.convert_paint(paint))
+                }
+                DisplayListOp::DrawRoundedRect { rect, radii, paint } => {
+                    builder.draw_rounded_rect(*rect, radii, self.convert_paint(paint))
+                }
+                DisplayListOp::DrawRoundedRectDifference {
+                    outer_rect,
+                    outer_radii,
+                    inner_rect,
+                    inner_radii,
+                    paint,
+                } => builder.draw_rounded_rect_difference(
+                    *outer_rect,
+                    outer_radii,
+                    *inner_rect,
+                    inner_radii,
+                    self.convert_paint(paint),
+                ),
+                DisplayListOp::DrawOval { oval_bounds, paint } => {
+                    builder.draw_oval(*oval_bounds, self.convert_paint(paint))
+                }
+                DisplayListOp::DrawPath { path, paint } => builder.draw_path(
+                    &build_path::<B::PathBuilder>(path),
+                    self.convert_paint(paint),
+                ),
+                DisplayListOp::DrawShadow {
+                    path,
+                    color,
+                    elevation,
+                    occluder_is_transparent,
+                    device_pixel_ratio,
+                } => builder.draw_shadow(
+                    &build_path::<B::PathBuilder>(path),
+                    color.clone(),
+                    *elevation,
+                    *occluder_is_transparent,
+                    *device_pixel_ratio,
+                ),
+                DisplayListOp::DrawTextureRect {
+                    texture,
+                    src_rect,
+                    dst_rect,
+                    sampling,
+                    paint,
+                } => {
+                    // textures which cannot be resolved are skipped
+                    if let Some(texture) = self.resolve_texture(texture) {
+                        let paint = paint.as_ref().map(|paint| self.convert_paint(paint));
+                        builder.draw_texture_rect(
+                            &texture,
+                            *src_rect,
+                            *dst_rect,
+                            *sampling,
+                            paint.map(OptRef::Owned),
+                        );
+                    }
+                }
+                DisplayListOp::DrawTexture {
+                    texture,
+                    point,
+                    sampling,
+                    paint,
+                } => {
+                    if let Some(texture) = self.resolve_texture(texture) {
+                        let paint = paint.as_ref().map(|paint| self.convert_paint(paint));
+                        builder.draw_texture(&texture, *point, *sampling, paint.map(OptRef::Owned));
+                    }
+                }
+                DisplayListOp::DrawParagraph {
+                    location,
+                    paragraph,
+                } => {
+                    let paragraph = self.build_paragraph(paragraph)?;
+                    builder.draw_paragraph(*location, &paragraph);
+                }
+                DisplayListOp::DrawDisplayList {
+                    display_list,
+                    opacity,
+                } => {
+                    let mut nested_builder = B::new(display_list.bounds);
+                    self.replay(display_list, &mut nested_builder)?;
+                    builder.draw_display_list(&nested_builder.build()?, *opacity);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_texture(&mut self, texture: &RecordedTexture) -> Option<B::Texture> {
+        if let Some((_, resolved)) = self.textures.iter().find(|(t, _)| t.ptr_eq(texture)) {
+            return resolved.clone();
+        }
+        let resolved = (self.resolve_texture)(texture);
+        self.textures.push((texture.clone(), resolved.clone()));
+        resolved
+    }
+
+    fn get_fonts(&mut self, fonts: &RecordedFonts) -> Result<FontsOf<B>, &'static str> {
+        if let Some((_, replayed)) = self.fonts.iter().find(|(f, _)| f.ptr_eq(fonts)) {
+            return Ok(replayed.clone());
+        }
+        let mut replayed = FontsOf::<B>::default();
+        for font in fonts.get_fonts() {
+            replayed.register_font((*font.data).clone(), font.family_name_alias.as_deref())?;
+        }
+        self.fonts.push((fonts.clone(), replayed.clone()));
+        Ok(replayed)
+    }
+
+    fn build_paragraph(
+        &mut self,
+        paragraph: &RecordedParagraph,
+    ) -> Result<<B::ParagraphBuilder as ParagraphBuilder>::Paragraph, &'static str> {
+        let fonts = self.get_fonts(&paragraph.fonts)?;
+        let mut paragraph_builder = B::ParagraphBuilder::new(&fonts)?;
+        for run in paragraph.runs.iter() {
+            if let Some(style) = &run.style {
+                let style = ParagraphStyle {
+                    foreground: style
+                        .foreground
+                        .as_ref()
+                        .map(|paint| self.convert_paint(paint)),
+                    background: style
+                        .background
+                        .as_ref()
+                        .map(|paint| self.convert_paint(paint)),
+                    weight: style.weight,
+                    style: style.style,
+                    family: style.family.clone(),
+                    size: style.size,
+                    height_factor: style.height_factor,
+                    text_alignment: style.text_alignment,
+                    text_direction: style.text_direction,
+                    text_decoration: style.text_decoration.clone(),
+                    max_lines: style.max_lines,
+                    ellipsis: style.ellipsis.clone(),
+                    locale: style.locale.clone(),
+                };
+                paragraph_builder.push_style(style);
+                paragraph_builder.add_text(&run.text);
+                paragraph_builder.pop_style();
+            } else {
+                paragraph_builder.add_text(&run.text);
+            }
+        }
+        paragraph_builder.build(paragraph.width)
+    }
+
+    fn convert_paint(&mut self, recorded: &RecordedPaint) -> B::Paint {
+        let mut paint = B::Paint::default();
+        if let Some(color) = &recorded.color {
+            paint.set_color(color.clone());
+        }
+        if let Some(blend_mode) = recorded.blend_mode {
+            paint.set_blend_mode(blend_mode);
+        }
+        if let Some(draw_style) = recorded.draw_style {
+            paint.set_draw_style(draw_style);
+        }
+        if let Some(stroke_cap) = recorded.stroke_cap {
+            paint.set_stroke_cap(stroke_cap);
+        }
+        if let Some(stroke_join) = recorded.stroke_join {
+            paint.set_stroke_join(stroke_join);
+        }
+        if let Some(stroke_width) = recorded.stroke_width {
+            paint.set_stroke_width(stroke_width);
+        }
+        if let Some(stroke_miter) = recorded.stroke_miter {
+            paint.set_stroke_miter(stroke_miter);
+        }
+        if let Some(color_source) = recorded
+            .color_source
+            .as_ref()
+            .and_then(|color_source| self.convert_color_source(color_source))
+        {
+            paint.set_color_source(color_source);
+        }
+        if let Some(color_filter) = &recorded.color_filter {
+            paint.set_color_filter(color_filter.clone());
+        }
+        if let Some(image_filter) = recorded
+            .image_filter
+            .as_ref()
+            .and_then(convert_image_filter)
+        {
+            paint.set_image_filter(image_filter);
+        }
+        if let Some(mask_filter) = &recorded.mask_filter {
+            paint.set_mask_filter(mask_filter.clone());
+        }
+        paint
+    }
+
+    fn convert_color_source<F: crate::ColorSourceFragment>(
+        &mut self,
+        color_source: &ColorSource<RecordedTexture, RecordedFragment>,
+    ) -> Option<ColorSource<B::Texture, F>> {
+        Some(match color_source.clone() {
+            ColorSource::LinearGradient {
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => ColorSource::LinearGradient {
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            },
+            ColorSource::RadialGradient {
+                center,
+                radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => ColorSource::RadialGradient {
+                center,
+                radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            },
+            ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            },
+            ColorSource::SweepGradient {
+                center,
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => ColorSource::SweepGradient {
+                center,
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            },
+            ColorSource::Image {
+                image,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
+                transformation,
+            } => ColorSource::Image {
+                image: self.resolve_texture(&image)?,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
+                transformation,
+            },
+            ColorSource::Fragment { .. } => return None,
+        })
+    }
+}
+
+fn build_path<P: PathBuilder>(recorded: &RecordedPath) -> P::Path {
+    let mut path_builder = P::default();
+    path_builder.set_fill_type(recorded.fill_type);
+    for op in &recorded.ops {
+        match op {
+            PathOp::MoveTo(point) => path_builder.move_to(*point),
+            PathOp::LineTo(point) => path_builder.line_to(*point),
+            PathOp::QuadraticCurveTo(control_point, end_point) => {
+                path_builder.quadratic_curve_to(*control_point, *end_point)
+            }
+            PathOp::CubicCurveTo(control_point_1, control_point_2, end_point) => {
+                path_builder.cubic_curve_to(*control_point_1, *control_point_2, *end_point)
+            }
+            PathOp::AddRect(rect) => path_builder.add_rect(*rect),
+            PathOp::AddRoundedRect(rect, radii) => path_builder.add_rounded_rect(*rect, radii),
+            PathOp::AddOval(oval_bounds) => path_builder.add_oval(*oval_bounds),
+            PathOp::AddArc(oval_bounds, start_angle_degrees, end_angle_degrees) => {
+                path_builder.add_arc(*oval_bounds, *start_angle_degrees, *end_angle_degrees)
+            }
+            PathOp::Close => path_builder.close(),
+        }
+    }
+    path_builder.build()
+}
+
+/// Converts the image filter, the fragments are skipped.
+fn convert_image_filter<F: crate::ImageFilterFragment>(
+    image_filter: &ImageFilter<RecordedFragment>,
+) -> Option<ImageFilter<F>> {
+    Some(match image_filter {
+        ImageFilter::Blur {
+            x_sigma,
+            y_sigma,
+            tile_mode,
+        } => ImageFilter::Blur {
+            x_sigma: *x_sigma,
+            y_sigma: *y_sigma,
+            tile_mode: *tile_mode,
+        },
+        ImageFilter::Dilate { x_radius, y_radius } => ImageFilter::Dilate {
+            x_radius: *x_radius,
+            y_radius: *y_radius,
+        },
+        ImageFilter::Erode { x_radius, y_radius } => ImageFilter::Erode {
+            x_radius: *x_radius,
+            y_radius: *y_radius,
+        },
+        ImageFilter::Matrix { matrix, sampling } => ImageFilter::Matrix {
+            matrix: *matrix,
+            sampling: *sampling,
+        },
+        ImageFilter::Fragment { .. } => return None,
+        ImageFilter::Compose { outer, inner } => {
+            match (convert_image_filter(outer), convert_image_filter(inner)) {
+                (Some(outer), Some(inner)) => ImageFilter::Compose {
+                    outer: Box::new(outer),
+                    inner: Box::new(inner),
+                },
+                (outer, inner) => return outer.or(inner),
+            }
+        }
+    })
+}
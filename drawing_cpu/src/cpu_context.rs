@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+
+use drawing_api::{
+    Capabilities, ColorFormat, ColorSource, DrawingContext, GraphicsApi, ImageFilter,
+    TextureDescriptor,
+};
+
+use crate::raster::Pixmap;
+use crate::{CpuSurface, CpuTexture};
+
+/// Software renderer drawing into images in the main memory.
+#[derive(Clone, Default)]
+pub struct CpuContext {}
+
+impl CpuContext {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Creates a transparent surface.
+    pub fn create_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<CpuSurface, &'static str> {
+        Ok(CpuSurface {
+            pixmap: Arc::new(RwLock::new(Arc::new(Pixmap::new(width, height)))),
+            color_format,
+        })
+    }
+}
+
+impl DrawingContext for CpuContext {
+    type ColorSourceFragment = crate::display_list::ColorSourceFragment;
+    type DisplayList = crate::display_list::DisplayList;
+    type DisplayListBuilder = crate::display_list::DisplayListBuilder;
+    type Fonts = crate::display_list::Fonts;
+    type FragmentProgram = crate::CpuFragmentProgram;
+    type ImageFilterFragment = crate::display_list::ImageFilterFragment;
+    type Paint = crate::display_list::Paint;
+    type ParagraphBuilder = crate::display_list::ParagraphBuilder;
+    type PathBuilder = crate::display_list::PathBuilder;
+    type Surface = CpuSurface;
+    type Texture = CpuTexture;
+
+    fn get_api_capabilities(api: GraphicsApi) -> Option<Capabilities> {
+        match api {
+            GraphicsApi::Software => Some(Capabilities {
+                api: GraphicsApi::Software,
+                transformations: true,
+                layers: true,
+                rect_clipping: true,
+                path_clipping: true,
+                color_filters: true,
+                image_filters: true,
+                mask_filters: true,
+                textures: true,
+                text_metrics: true,
+                text_decorations: false,
+                shadows: true,
+                fragment_color_sources: false,
+                fragment_image_filters: false,
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_capabilities(&self) -> Capabilities {
+        CpuContext::get_api_capabilities(GraphicsApi::Software).unwrap()
+    }
+
+    unsafe fn create_texture(
+        &self,
+        contents: Cow<'static, [u8]>,
+        descriptor: TextureDescriptor,
+    ) -> Result<Self::Texture, &'static str> {
+        let pixmap = Pixmap::from_bytes(
+            &contents,
+            descriptor.width,
+            descriptor.height,
+            descriptor.color_format,
+        )?;
+        Ok(CpuTexture {
+            pixmap: Arc::new(RwLock::new(Arc::new(pixmap))),
+            color_format: descriptor.color_format,
+        })
+    }
+
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        let surface = self.create_surface(width, height, color_format)?;
+        let texture = CpuTexture {
+            pixmap: surface.pixmap.clone(),
+            color_format,
+        };
+        Ok((surface, texture))
+    }
+
+    unsafe fn new_color_source_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ColorSource<Self::Texture, Self::ColorSourceFragment> {
+        // fragment programs are not supported, the color source is ignored when drawing
+        ColorSource::Fragment {
+            color_source: crate::display_list::ColorSourceFragment {},
+        }
+    }
+
+    unsafe fn new_image_filter_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ImageFilter<Self::ImageFilterFragment> {
+        // fragment programs are not supported, the filter is ignored when drawing
+        ImageFilter::Fragment {
+            image_filter: crate::display_list::ImageFilterFragment {},
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::{Arc, RwLock};
+
+use drawing_api::{ColorFormat, PixelData, PixelIntRect};
+
+use crate::raster::{Pixmap, Renderer, Transform};
+
+/// Render target in the main memory.
+pub struct CpuSurface {
+    pub(crate) pixmap: Arc<RwLock<Arc<Pixmap>>>,
+    pub(crate) color_format: ColorFormat,
+}
+
+impl CpuSurface {
+    pub(crate) fn get_pixmap(&self) -> Arc<Pixmap> {
+        self.pixmap.read().unwrap().clone()
+    }
+}
+
+impl drawing_api::Surface for CpuSurface {
+    type DisplayList = crate::display_list::DisplayList;
+
+    fn draw(&mut self, display_list: &Self::DisplayList) -> Result<(), &'static str> {
+        // the textures sharing the pixels keep the old content while drawing
+        let mut renderer = Renderer::new(self.get_pixmap().as_ref().clone());
+        renderer.draw_display_list(display_list, &Transform::identity());
+        *self.pixmap.write().unwrap() = Arc::new(renderer.finish());
+        Ok(())
+    }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        self.get_pixmap().to_pixel_data(rect, self.color_format)
+    }
+
+    fn present(self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::{Arc, RwLock};
+
+use drawing_api::{ColorFormat, PixelData, PixelIntRect, TextureDescriptor};
+
+use crate::raster::Pixmap;
+
+/// Image in the main memory.
+///
+/// The texture of an offscreen surface shares the pixels with the surface.
+#[derive(Clone)]
+pub struct CpuTexture {
+    pub(crate) pixmap: Arc<RwLock<Arc<Pixmap>>>,
+    pub(crate) color_format: ColorFormat,
+}
+
+impl CpuTexture {
+    pub(crate) fn get_pixmap(&self) -> Arc<Pixmap> {
+        self.pixmap.read().unwrap().clone()
+    }
+}
+
+impl drawing_api::Texture for CpuTexture {
+    fn get_descriptor(&self) -> TextureDescriptor {
+        let pixmap = self.get_pixmap();
+        TextureDescriptor {
+            width: pixmap.width,
+            height: pixmap.height,
+            color_format: self.color_format,
+            mip_count: 1,
+        }
+    }
+
+    fn get_gl_handle(&self) -> usize {
+        0
+    }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        self.get_pixmap().to_pixel_data(rect, self.color_format)
+    }
+}
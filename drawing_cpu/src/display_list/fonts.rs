@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use drawing_api::command_list::FontMetrics;
+use freetype as ft;
+
+/// Glyph rendered with FreeType.
+pub(crate) struct Glyph {
+    /// Horizontal offset of the bitmap from the pen position.
+    pub left: i32,
+
+    /// Vertical offset of the top of the bitmap above the baseline.
+    pub top: i32,
+
+    pub width: u32,
+    pub rows: u32,
+
+    /// Coverage of the pixels (`width` x `rows`).
+    pub coverage: Vec<u8>,
+
+    /// Horizontal advance in pixels.
+    pub advance: f32,
+}
+
+struct FontSize {
+    metrics: FontMetrics,
+
+    // `None` for the characters which cannot be rendered
+    glyphs: HashMap<char, Option<Arc<Glyph>>>,
+}
+
+struct Font {
+    data: Arc<Vec<u8>>,
+    sizes: HashMap<u32, FontSize>,
+}
+
+pub(crate) struct FontsData {
+    fonts: HashMap<String, Font>,
+}
+
+/// Registered fonts with the cache of rendered glyphs.
+///
+/// FreeType faces cannot be shared between threads, so only the font data
+/// and the rendered glyphs are stored and the faces are created when needed.
+#[derive(Clone)]
+pub struct Fonts {
+    pub(crate) data: Arc<Mutex<FontsData>>,
+}
+
+impl Fonts {
+    /// Returns the font metrics and the glyphs of the characters in the text
+    /// (rendering the missing ones). Falls back to the "default" family.
+    pub(crate) fn get_glyphs(
+        &self,
+        family_name: &str,
+        size: u32,
+        text: &str,
+    ) -> Result<(FontMetrics, HashMap<char, Arc<Glyph>>), &'static str> {
+        let mut data = self.data.lock().unwrap();
+        let family_name = if data.fonts.contains_key(family_name) {
+            family_name
+        } else {
+            "default"
+        };
+        let font = data
+            .fonts
+            .get_mut(family_name)
+            .ok_or("font is not registered")?;
+
+        let missing = text
+            .chars()
+            .filter(|ch| {
+                font.sizes
+                    .get(&size)
+                    .is_none_or(|font_size| !font_size.glyphs.contains_key(ch))
+            })
+            .collect::<Vec<_>>();
+        if missing.is_empty() && font.sizes.contains_key(&size) {
+            let font_size = &font.sizes[&size];
+            return Ok((font_size.metrics, Self::collect_glyphs(font_size, text)));
+        }
+
+        let library = ft::Library::init().map_err(|_| "cannot init freetype library")?;
+        let face = library
+            .new_memory_face(Rc::new(font.data.as_ref().clone()), 0)
+            .map_err(|_| "face: cannot allocate memory")?;
+        face.set_pixel_sizes(0, size.max(1))
+            .map_err(|_| "face: unable set pixel size")?;
+
+        let font_size = font.sizes.entry(size).or_insert_with(|| {
+            let metrics = face.size_metrics();
+            FontSize {
+                metrics: FontMetrics {
+                    ascent: metrics.map_or(size as f32, |m| m.ascender as f32 / 64.0f32),
+                    descent: metrics.map_or(0.0f32, |m| -m.descender as f32 / 64.0f32),
+                },
+                glyphs: HashMap::new(),
+            }
+        });
+
+        for ch in missing {
+            font_size
+                .glyphs
+                .insert(ch, Self::render_glyph(&face, ch).map(Arc::new));
+        }
+
+        Ok((font_size.metrics, Self::collect_glyphs(font_size, text)))
+    }
+
+    fn render_glyph(face: &ft::Face, ch: char) -> Option<Glyph> {
+        face.load_char(ch as usize, ft::face::LoadFlag::RENDER)
+            .ok()?;
+        let glyph = face.glyph();
+        let bitmap = glyph.bitmap();
+        let (width, rows) = (bitmap.width().max(0) as u32, bitmap.rows().max(0) as u32);
+        let pitch = bitmap.pitch().unsigned_abs() as usize;
+
+        // the buffer of an empty bitmap (like space) is a null pointer
+        let mut coverage = Vec::with_capacity((width * rows) as usize);
+        if width > 0 && rows > 0 {
+            let buffer = bitmap.buffer();
+            for row in 0..rows as usize {
+                coverage.extend_from_slice(&buffer[row * pitch..row * pitch + width as usize]);
+            }
+        }
+
+        Some(Glyph {
+            left: glyph.bitmap_left(),
+            top: glyph.bitmap_top(),
+            width,
+            rows,
+            coverage,
+            advance: glyph.advance().x as f32 / 64.0f32,
+        })
+    }
+
+    fn collect_glyphs(font_size: &FontSize, text: &str) -> HashMap<char, Arc<Glyph>> {
+        text.chars()
+            .filter_map(|ch| {
+                font_size
+                    .glyphs
+                    .get(&ch)
+                    .cloned()
+                    .flatten()
+                    .map(|glyph| (ch, glyph))
+            })
+            .collect()
+    }
+}
+
+impl Debug for Fonts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fonts")
+            .field("data", &self.data.lock().unwrap().fonts.keys())
+            .finish()
+    }
+}
+
+impl Default for Fonts {
+    fn default() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(FontsData {
+                fonts: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl drawing_api::Fonts for Fonts {
+    fn register_font(
+        &mut self,
+        font_data: Cow<'static, [u8]>,
+        family_name_alias: Option<&str>,
+    ) -> Result<(), &'static str> {
+        // check that the font can be loaded
+        let library = ft::Library::init().map_err(|_| "cannot init freetype library")?;
+        library
+            .new_memory_face(Rc::new(Vec::from(font_data.as_ref())), 0)
+            .map_err(|_| "face: cannot load font")?;
+
+        let family_name = family_name_alias.unwrap_or("default");
+        self.data.lock().unwrap().fonts.insert(
+            family_name.into(),
+            Font {
+                data: Arc::new(Vec::from(font_data)),
+                sizes: HashMap::new(),
+            },
+        );
+        Ok(())
+    }
+}
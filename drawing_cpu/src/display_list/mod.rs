@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use drawing_api::command_list::{self, FontMetrics};
+
+use crate::{CpuContext, CpuTexture};
+
+mod fonts;
+pub use fonts::*;
+
+pub(crate) use command_list::rect_path;
+pub use command_list::{
+    ColorSourceFragment, GlyphInfo, ImageFilterFragment, LineMetrics, Path, PathBuilder,
+    PathElement,
+};
+
+pub type DisplayList = command_list::DisplayList<CpuContext>;
+pub type DisplayListBuilder = command_list::DisplayListBuilder<CpuContext>;
+pub type Paint = command_list::Paint<CpuContext>;
+pub type Paragraph = command_list::Paragraph<CpuContext>;
+pub type ParagraphBuilder = command_list::ParagraphBuilder<CpuContext>;
+pub(crate) type Command = command_list::Command<CpuContext>;
+
+impl command_list::Backend for CpuContext {
+    type Texture = CpuTexture;
+    type Fonts = Fonts;
+
+    fn get_advances(
+        fonts: &Fonts,
+        family_name: &str,
+        size: f32,
+        text: &str,
+    ) -> Result<(FontMetrics, HashMap<char, f32>), &'static str> {
+        let pixel_size = size.round().max(1.0f32) as u32;
+        let (metrics, glyphs) = fonts.get_glyphs(family_name, pixel_size, text)?;
+        let advances = glyphs
+            .iter()
+            .map(|(ch, glyph)| (*ch, glyph.advance))
+            .collect();
+        Ok((metrics, advances))
+    }
+}
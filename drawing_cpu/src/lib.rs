@@ -0,0 +1,20 @@
+mod display_list;
+
+mod cpu_context;
+pub use cpu_context::*;
+
+mod cpu_fragment_program;
+pub use cpu_fragment_program::*;
+
+mod cpu_surface;
+pub use cpu_surface::*;
+
+mod cpu_texture;
+pub use cpu_texture::*;
+
+mod raster;
+
+pub use display_list::{
+    ColorSourceFragment, DisplayList, DisplayListBuilder, Fonts, GlyphInfo, ImageFilterFragment,
+    LineMetrics, Paint, Paragraph, ParagraphBuilder, Path, PathBuilder, PathElement,
+};
@@ -0,0 +1,194 @@
+use drawing_api::{BlendMode, ColorFilter};
+
+use super::Rgba;
+
+/// Blends the premultiplied colors.
+///
+/// The advanced modes follow the W3C compositing specification.
+pub(crate) fn blend(src: Rgba, dst: Rgba, mode: BlendMode) -> Rgba {
+    let porter_duff = |fs: f32, fd: f32| -> Rgba {
+        [
+            src[0] * fs + dst[0] * fd,
+            src[1] * fs + dst[1] * fd,
+            src[2] * fs + dst[2] * fd,
+            src[3] * fs + dst[3] * fd,
+        ]
+    };
+    let (sa, da) = (src[3], dst[3]);
+
+    match mode {
+        BlendMode::Clear => return [0.0f32; 4],
+        BlendMode::Source => return src,
+        BlendMode::Destination => return dst,
+        BlendMode::SourceOver => return porter_duff(1.0f32, 1.0f32 - sa),
+        BlendMode::DestinationOver => return porter_duff(1.0f32 - da, 1.0f32),
+        BlendMode::SourceIn => return porter_duff(da, 0.0f32),
+        BlendMode::DestinationIn => return porter_duff(0.0f32, sa),
+        BlendMode::SourceOut => return porter_duff(1.0f32 - da, 0.0f32),
+        BlendMode::DestinationOut => return porter_duff(0.0f32, 1.0f32 - sa),
+        BlendMode::SourceATop => return porter_duff(da, 1.0f32 - sa),
+        BlendMode::DestinationATop => return porter_duff(1.0f32 - da, sa),
+        BlendMode::Xor => return porter_duff(1.0f32 - da, 1.0f32 - sa),
+        BlendMode::Plus => {
+            return std::array::from_fn(|i| (src[i] + dst[i]).min(1.0f32));
+        }
+        BlendMode::Modulate => return std::array::from_fn(|i| src[i] * dst[i]),
+        BlendMode::Screen => return std::array::from_fn(|i| src[i] + dst[i] - src[i] * dst[i]),
+        _ => (),
+    }
+
+    // advanced modes, the blend function works on unpremultiplied colors
+    let cs = unpremultiply_rgb(src);
+    let cb = unpremultiply_rgb(dst);
+    let per_channel =
+        |f: fn(f32, f32) -> f32| -> [f32; 3] { std::array::from_fn(|i| f(cb[i], cs[i])) };
+    let b = match mode {
+        BlendMode::Overlay => hard_light(cs, cb),
+        BlendMode::Darken => per_channel(|cb, cs| cb.min(cs)),
+        BlendMode::Lighten => per_channel(|cb, cs| cb.max(cs)),
+        BlendMode::ColorDodge => per_channel(color_dodge),
+        BlendMode::ColorBurn => per_channel(color_burn),
+        BlendMode::HardLight => hard_light(cb, cs),
+        BlendMode::SoftLight => per_channel(soft_light),
+        BlendMode::Difference => per_channel(|cb, cs| (cb - cs).abs()),
+        BlendMode::Exclusion => per_channel(|cb, cs| cb + cs - 2.0f32 * cb * cs),
+        BlendMode::Multiply => per_channel(|cb, cs| cb * cs),
+        BlendMode::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+        BlendMode::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+        BlendMode::Color => set_lum(cs, lum(cb)),
+        _ => set_lum(cb, lum(cs)),
+    };
+
+    let mut result = [0.0f32, 0.0f32, 0.0f32, sa + da - sa * da];
+    for i in 0..3 {
+        result[i] =
+            (1.0f32 - da) * src[i] + (1.0f32 - sa) * dst[i] + sa * da * b[i].clamp(0.0f32, 1.0f32);
+    }
+    result
+}
+
+/// Applies the color filter to the premultiplied color.
+pub(crate) fn apply_color_filter(color: Rgba, filter: &ColorFilter) -> Rgba {
+    match filter {
+        ColorFilter::Matrix(matrix) => {
+            // the matrix works on unpremultiplied colors
+            let [r, g, b] = unpremultiply_rgb(color);
+            let a = color[3];
+            let m = &matrix.m;
+            let mut result: Rgba = std::array::from_fn(|i| {
+                (m[i * 5] * r
+                    + m[i * 5 + 1] * g
+                    + m[i * 5 + 2] * b
+                    + m[i * 5 + 3] * a
+                    + m[i * 5 + 4])
+                    .clamp(0.0f32, 1.0f32)
+            });
+            for i in 0..3 {
+                result[i] *= result[3];
+            }
+            result
+        }
+
+        ColorFilter::Blend(filter_color, mode) => {
+            let alpha = filter_color.alpha;
+            let src = [
+                filter_color.red * alpha,
+                filter_color.green * alpha,
+                filter_color.blue * alpha,
+                alpha,
+            ];
+            blend(src, color, *mode)
+        }
+    }
+}
+
+fn unpremultiply_rgb(color: Rgba) -> [f32; 3] {
+    if color[3] > 0.0f32 {
+        [
+            color[0] / color[3],
+            color[1] / color[3],
+            color[2] / color[3],
+        ]
+    } else {
+        [0.0f32; 3]
+    }
+}
+
+fn color_dodge(cb: f32, cs: f32) -> f32 {
+    if cb <= 0.0f32 {
+        0.0f32
+    } else if cs >= 1.0f32 {
+        1.0f32
+    } else {
+        (cb / (1.0f32 - cs)).min(1.0f32)
+    }
+}
+
+fn color_burn(cb: f32, cs: f32) -> f32 {
+    if cb >= 1.0f32 {
+        1.0f32
+    } else if cs <= 0.0f32 {
+        0.0f32
+    } else {
+        1.0f32 - ((1.0f32 - cb) / cs).min(1.0f32)
+    }
+}
+
+fn soft_light(cb: f32, cs: f32) -> f32 {
+    if cs <= 0.5f32 {
+        return cb - (1.0f32 - 2.0f32 * cs) * cb * (1.0f32 - cb);
+    }
+    let d = if cb <= 0.25f32 {
+        ((16.0f32 * cb - 12.0f32) * cb + 4.0f32) * cb
+    } else {
+        cb.sqrt()
+    };
+    cb + (2.0f32 * cs - 1.0f32) * (d - cb)
+}
+
+fn hard_light(cb: [f32; 3], cs: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|i| {
+        if cs[i] < 0.5f32 {
+            cb[i] * 2.0f32 * cs[i]
+        } else {
+            1.0f32 - (1.0f32 - cb[i]) * (2.0f32 - 2.0f32 * cs[i])
+        }
+    })
+}
+
+fn lum(c: [f32; 3]) -> f32 {
+    0.3f32 * c[0] + 0.59f32 * c[1] + 0.11f32 * c[2]
+}
+
+fn clip_color(c: [f32; 3]) -> [f32; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut c = c;
+    if n < 0.0f32 {
+        c = c.map(|v| l + (v - l) * l / (l - n).max(1.0e-6f32));
+    }
+    if x > 1.0f32 {
+        c = c.map(|v| l + (v - l) * (1.0f32 - l) / (x - l).max(1.0e-6f32));
+    }
+    c
+}
+
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    clip_color(c.map(|v| v + d))
+}
+
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    if x > n {
+        c.map(|v| (v - n) * s / (x - n))
+    } else {
+        [0.0f32; 3]
+    }
+}
@@ -0,0 +1,258 @@
+use drawing_api::{BlurStyle, ImageFilter, TileMode};
+
+use crate::ImageFilterFragment;
+
+use super::{sample, tile_index, IntRect, Mask, Pixmap, Point, Transform};
+
+/// Applies the image filter to the whole image.
+///
+/// The filter parameters are in the local coordinates of the given transformation.
+pub(crate) fn apply_image_filter(
+    pixmap: &Pixmap,
+    filter: &ImageFilter<ImageFilterFragment>,
+    transform: &Transform,
+) -> Pixmap {
+    let (scale_x, scale_y) = get_scale(transform);
+    match filter {
+        ImageFilter::Blur {
+            x_sigma,
+            y_sigma,
+            tile_mode,
+        } => {
+            let mut result = pixmap.clone();
+            let region = IntRect::new(0, 0, pixmap.width as i32, pixmap.height as i32);
+            gaussian_blur(
+                &mut result.data,
+                pixmap.width,
+                region,
+                x_sigma * scale_x,
+                y_sigma * scale_y,
+                *tile_mode,
+            );
+            result
+        }
+
+        ImageFilter::Dilate { x_radius, y_radius } => morphology(
+            pixmap,
+            (x_radius * scale_x).round() as usize,
+            (y_radius * scale_y).round() as usize,
+            f32::max,
+        ),
+
+        ImageFilter::Erode { x_radius, y_radius } => morphology(
+            pixmap,
+            (x_radius * scale_x).round() as usize,
+            (y_radius * scale_y).round() as usize,
+            f32::min,
+        ),
+
+        ImageFilter::Matrix { matrix, sampling } => {
+            // the matrix is applied in the local coordinates
+            let device_matrix = transform
+                .inverse()
+                .unwrap_or_else(Transform::identity)
+                .then(&matrix.to_2d())
+                .then(transform);
+            let Some(inverse) = device_matrix.inverse() else {
+                return Pixmap::new(pixmap.width, pixmap.height);
+            };
+
+            let mut result = Pixmap::new(pixmap.width, pixmap.height);
+            for y in 0..pixmap.height {
+                for x in 0..pixmap.width {
+                    let point =
+                        inverse.transform_point(Point::new(x as f32 + 0.5f32, y as f32 + 0.5f32));
+                    result.data[(y * pixmap.width + x) as usize] =
+                        sample(pixmap, point, TileMode::Decal, TileMode::Decal, *sampling);
+                }
+            }
+            result
+        }
+
+        // fragment programs are not supported
+        ImageFilter::Fragment { .. } => pixmap.clone(),
+
+        ImageFilter::Compose { outer, inner } => apply_image_filter(
+            &apply_image_filter(pixmap, inner, transform),
+            outer,
+            transform,
+        ),
+    }
+}
+
+/// Applies the mask blur filter, sigma is in pixels.
+pub(crate) fn blur_mask(mask: &Mask, sigma: f32, style: BlurStyle) -> Mask {
+    if sigma <= 0.0f32 || mask.bounds.is_empty() {
+        return mask.clone();
+    }
+
+    let spread = (3.0f32 * sigma).ceil() as i32;
+    let region = mask
+        .bounds
+        .inflate(spread, spread)
+        .intersection(&mask.get_full_rect());
+    let mut data = mask.data.iter().map(|value| [*value]).collect::<Vec<_>>();
+    gaussian_blur(&mut data, mask.width, region, sigma, sigma, TileMode::Decal);
+
+    let mut result = mask.clone();
+    result.bounds = region;
+    for y in region.y0..region.y1 {
+        for x in region.x0..region.x1 {
+            let index = y as usize * mask.width as usize + x as usize;
+            let (original, blurred) = (mask.data[index], data[index][0].clamp(0.0f32, 1.0f32));
+            result.data[index] = match style {
+                BlurStyle::Normal => blurred,
+                BlurStyle::Solid => original.max(blurred),
+                BlurStyle::Outer => blurred * (1.0f32 - original),
+                BlurStyle::Inner => blurred * original,
+            };
+        }
+    }
+    if style == BlurStyle::Inner {
+        result.bounds = mask.bounds;
+    }
+    result
+}
+
+/// Returns the scale of the transformation along the axes.
+pub(crate) fn get_scale(transform: &Transform) -> (f32, f32) {
+    (
+        (transform.m11 * transform.m11 + transform.m12 * transform.m12).sqrt(),
+        (transform.m21 * transform.m21 + transform.m22 * transform.m22).sqrt(),
+    )
+}
+
+/// Approximates the gaussian blur with three box blurs.
+fn gaussian_blur<const N: usize>(
+    data: &mut [[f32; N]],
+    width: u32,
+    region: IntRect,
+    sigma_x: f32,
+    sigma_y: f32,
+    tile_mode: TileMode,
+) {
+    let width = width as usize;
+    let (x0, x1) = (region.x0 as usize, region.x1 as usize);
+    let (y0, y1) = (region.y0 as usize, region.y1 as usize);
+
+    let mut line = Vec::new();
+    let mut blurred = Vec::new();
+    for radius in box_blur_radii(sigma_x) {
+        for y in y0..y1 {
+            let row = &mut data[y * width + x0..y * width + x1];
+            line.clear();
+            line.extend_from_slice(row);
+            blurred.resize(line.len(), [0.0f32; N]);
+            box_blur_line(&line, &mut blurred, radius, tile_mode);
+            row.copy_from_slice(&blurred);
+        }
+    }
+    for radius in box_blur_radii(sigma_y) {
+        for x in x0..x1 {
+            line.clear();
+            line.extend((y0..y1).map(|y| data[y * width + x]));
+            blurred.resize(line.len(), [0.0f32; N]);
+            box_blur_line(&line, &mut blurred, radius, tile_mode);
+            for (y, value) in (y0..y1).zip(&blurred) {
+                data[y * width + x] = *value;
+            }
+        }
+    }
+}
+
+/// Returns radii of the three box blurs giving the gaussian blur with the sigma.
+fn box_blur_radii(sigma: f32) -> Vec<usize> {
+    if sigma < 0.3f32 {
+        return Vec::new();
+    }
+
+    let n = 3.0f32;
+    let ideal_width = (12.0f32 * sigma * sigma / n + 1.0f32).sqrt();
+    let mut lower_width = ideal_width.floor();
+    if lower_width as i32 % 2 == 0 {
+        lower_width -= 1.0f32;
+    }
+    let ideal_count = (12.0f32 * sigma * sigma
+        - n * lower_width * lower_width
+        - 4.0f32 * n * lower_width
+        - 3.0f32 * n)
+        / (-4.0f32 * lower_width - 4.0f32);
+    let count = ideal_count.round() as usize;
+
+    (0..3)
+        .map(|i| {
+            let width = if i < count {
+                lower_width
+            } else {
+                lower_width + 2.0f32
+            };
+            (width as usize - 1) / 2
+        })
+        .collect()
+}
+
+fn box_blur_line<const N: usize>(
+    src: &[[f32; N]],
+    dst: &mut [[f32; N]],
+    radius: usize,
+    tile_mode: TileMode,
+) {
+    let len = src.len() as i32;
+    let radius = radius as i32;
+    let get = |i: i32| {
+        tile_index(i, len as u32, tile_mode).map_or([0.0f32; N], |index| src[index as usize])
+    };
+
+    let scale = 1.0f32 / (2 * radius + 1) as f32;
+    let mut sum = [0.0f32; N];
+    for i in -radius..=radius {
+        let value = get(i);
+        for c in 0..N {
+            sum[c] += value[c];
+        }
+    }
+    for i in 0..len {
+        dst[i as usize] = sum.map(|value| value * scale);
+        let (added, removed) = (get(i + radius + 1), get(i - radius));
+        for c in 0..N {
+            sum[c] += added[c] - removed[c];
+        }
+    }
+}
+
+/// Replaces every channel with the maximum (dilate) or minimum (erode) in the rectangle.
+fn morphology(
+    pixmap: &Pixmap,
+    radius_x: usize,
+    radius_y: usize,
+    op: fn(f32, f32) -> f32,
+) -> Pixmap {
+    let (width, height) = (pixmap.width as usize, pixmap.height as usize);
+    let pass = |src: &Pixmap, dx: usize, dy: usize, radius: usize| {
+        let mut result = src.clone();
+        if radius == 0 {
+            return result;
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let mut value = src.data[y * width + x];
+                for k in 1..=radius {
+                    for (sx, sy) in [
+                        (x.wrapping_sub(k * dx), y.wrapping_sub(k * dy)),
+                        (x + k * dx, y + k * dy),
+                    ] {
+                        if sx < width && sy < height {
+                            let other = src.data[sy * width + sx];
+                            value = std::array::from_fn(|c| op(value[c], other[c]));
+                        }
+                    }
+                }
+                result.data[y * width + x] = value;
+            }
+        }
+        result
+    };
+
+    let horizontal = pass(pixmap, 1, 0, radius_x);
+    pass(&horizontal, 0, 1, radius_y)
+}
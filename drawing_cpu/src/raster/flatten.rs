@@ -0,0 +1,105 @@
+use crate::PathElement;
+
+use super::{Point, Transform};
+
+// maximum number of line segments a bezier curve is divided into
+const MAX_CURVE_SEGMENTS: usize = 256;
+
+/// Sub-path approximated with line segments.
+pub(crate) struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+/// Approximates the path with line segments.
+///
+/// The points are transformed before flattening, the `tolerance`
+/// is the maximum distance from the curve in the transformed coordinates.
+pub(crate) fn flatten_path(
+    path: &[PathElement],
+    transform: &Transform,
+    tolerance: f32,
+) -> Vec<Polyline> {
+    let mut polylines = Vec::new();
+    let mut current = Polyline {
+        points: Vec::new(),
+        closed: false,
+    };
+    let mut start_point = Point::zero();
+
+    let mut finish = |polyline: &mut Polyline, closed: bool| {
+        if polyline.points.len() > 1 {
+            polylines.push(Polyline {
+                points: std::mem::take(&mut polyline.points),
+                closed,
+            });
+        }
+        polyline.points.clear();
+    };
+
+    for element in path {
+        match element {
+            PathElement::MoveTo(point) => {
+                finish(&mut current, false);
+                start_point = transform.transform_point(point.to_untyped());
+                current.points.push(start_point);
+            }
+
+            PathElement::LineTo(point) => {
+                if current.points.is_empty() {
+                    current.points.push(start_point);
+                }
+                current
+                    .points
+                    .push(transform.transform_point(point.to_untyped()));
+            }
+
+            PathElement::BezierTo(c1, c2, point) => {
+                if current.points.is_empty() {
+                    current.points.push(start_point);
+                }
+                let p0 = *current.points.last().unwrap();
+                let p1 = transform.transform_point(c1.to_untyped());
+                let p2 = transform.transform_point(c2.to_untyped());
+                let p3 = transform.transform_point(point.to_untyped());
+                flatten_cubic(p0, p1, p2, p3, tolerance, &mut current.points);
+            }
+
+            PathElement::ClosePath => {
+                finish(&mut current, true);
+            }
+        }
+    }
+    finish(&mut current, false);
+
+    polylines
+}
+
+/// Appends the points of the cubic bezier curve (excluding the first one).
+fn flatten_cubic(
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    tolerance: f32,
+    points: &mut Vec<Point>,
+) {
+    // Wang's formula for the number of segments
+    let dd = (p0.to_vector() - p1.to_vector() * 2.0f32 + p2.to_vector())
+        .length()
+        .max((p1.to_vector() - p2.to_vector() * 2.0f32 + p3.to_vector()).length());
+    let segments = ((0.75f32 * dd / tolerance).sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS);
+
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0f32 - t;
+        let a = mt * mt * mt;
+        let b = 3.0f32 * mt * mt * t;
+        let c = 3.0f32 * mt * t * t;
+        let d = t * t * t;
+        points.push(Point::new(
+            a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+            a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+        ));
+    }
+}
@@ -0,0 +1,318 @@
+use drawing_api::FillType;
+
+use super::Point;
+
+// number of sample lines per pixel row used to compute the coverage
+const SUBSAMPLES: usize = 16;
+
+/// Area of pixels, the maximum coordinates are exclusive.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct IntRect {
+    pub x0: i32,
+    pub y0: i32,
+    pub x1: i32,
+    pub y1: i32,
+}
+
+impl IntRect {
+    pub fn new(x0: i32, y0: i32, x1: i32, y1: i32) -> Self {
+        Self { x0, y0, x1, y1 }
+    }
+
+    pub fn empty() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x0 >= self.x1 || self.y0 >= self.y1
+    }
+
+    pub fn intersection(&self, other: &IntRect) -> IntRect {
+        let rect = IntRect::new(
+            self.x0.max(other.x0),
+            self.y0.max(other.y0),
+            self.x1.min(other.x1),
+            self.y1.min(other.y1),
+        );
+        if rect.is_empty() {
+            IntRect::empty()
+        } else {
+            rect
+        }
+    }
+
+    pub fn union(&self, other: &IntRect) -> IntRect {
+        if self.is_empty() {
+            *other
+        } else if other.is_empty() {
+            *self
+        } else {
+            IntRect::new(
+                self.x0.min(other.x0),
+                self.y0.min(other.y0),
+                self.x1.max(other.x1),
+                self.y1.max(other.y1),
+            )
+        }
+    }
+
+    pub fn inflate(&self, dx: i32, dy: i32) -> IntRect {
+        if self.is_empty() {
+            *self
+        } else {
+            IntRect::new(self.x0 - dx, self.y0 - dy, self.x1 + dx, self.y1 + dy)
+        }
+    }
+
+    /// Returns the pixels touched by the given points.
+    pub fn covering<'a>(points: impl IntoIterator<Item = &'a Point>) -> IntRect {
+        let (mut x0, mut y0) = (f32::MAX, f32::MAX);
+        let (mut x1, mut y1) = (f32::MIN, f32::MIN);
+        for point in points {
+            x0 = x0.min(point.x);
+            y0 = y0.min(point.y);
+            x1 = x1.max(point.x);
+            y1 = y1.max(point.y);
+        }
+        if x0 > x1 || y0 > y1 {
+            return IntRect::empty();
+        }
+
+        // limit the values, so they do not overflow
+        let limit = |value: f32| value.clamp(-1.0e7f32, 1.0e7f32);
+        IntRect::new(
+            limit(x0.floor()) as i32,
+            limit(y0.floor()) as i32,
+            limit(x1.ceil()) as i32,
+            limit(y1.ceil()) as i32,
+        )
+    }
+}
+
+/// Coverage (in 0..1 range) of every pixel of a render target.
+///
+/// The coverage outside of the bounds is zero.
+#[derive(Clone)]
+pub(crate) struct Mask {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<f32>,
+    pub bounds: IntRect,
+}
+
+impl Mask {
+    /// Creates an empty mask.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0.0f32; width as usize * height as usize],
+            bounds: IntRect::empty(),
+        }
+    }
+
+    /// Creates a mask covering the given area.
+    pub fn filled(width: u32, height: u32, rect: IntRect) -> Self {
+        let mut mask = Self::new(width, height);
+        mask.bounds = rect.intersection(&mask.get_full_rect());
+        let bounds = mask.bounds;
+        for y in bounds.y0..bounds.y1 {
+            mask.get_row_mut(y)[bounds.x0 as usize..bounds.x1 as usize].fill(1.0f32);
+        }
+        mask
+    }
+
+    pub fn get_full_rect(&self) -> IntRect {
+        IntRect::new(0, 0, self.width as i32, self.height as i32)
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> f32 {
+        self.data[y as usize * self.width as usize + x as usize]
+    }
+
+    pub fn get_row_mut(&mut self, y: i32) -> &mut [f32] {
+        let width = self.width as usize;
+        &mut self.data[y as usize * width..(y as usize + 1) * width]
+    }
+
+    /// Leaves only the area covered by both masks.
+    pub fn intersect(&mut self, other: &Mask) {
+        for y in self.bounds.y0..self.bounds.y1 {
+            for x in self.bounds.x0..self.bounds.x1 {
+                let index = y as usize * self.width as usize + x as usize;
+                self.data[index] *= other.data[index];
+            }
+        }
+        self.bounds = self.bounds.intersection(&other.bounds);
+    }
+
+    /// Removes the area covered by the other mask.
+    pub fn subtract(&mut self, other: &Mask) {
+        let bounds = self.bounds.intersection(&other.bounds);
+        for y in bounds.y0..bounds.y1 {
+            for x in bounds.x0..bounds.x1 {
+                let index = y as usize * self.width as usize + x as usize;
+                self.data[index] *= 1.0f32 - other.data[index];
+            }
+        }
+    }
+
+    /// Adds the area covered by the other mask.
+    pub fn union(&mut self, other: &Mask) {
+        for y in other.bounds.y0..other.bounds.y1 {
+            for x in other.bounds.x0..other.bounds.x1 {
+                let index = y as usize * self.width as usize + x as usize;
+                self.data[index] = self.data[index].max(other.data[index]);
+            }
+        }
+        self.bounds = self.bounds.union(&other.bounds);
+    }
+
+    /// Fills the polygons with the anti-aliased scanline algorithm.
+    ///
+    /// Polygons are closed implicitly, points are in the pixel coordinates.
+    pub fn fill_polygons(
+        width: u32,
+        height: u32,
+        polygons: &[Vec<Point>],
+        fill_type: FillType,
+    ) -> Mask {
+        let mut mask = Mask::new(width, height);
+        let bounds =
+            IntRect::covering(polygons.iter().flatten()).intersection(&mask.get_full_rect());
+        if bounds.is_empty() {
+            return mask;
+        }
+        mask.bounds = bounds;
+
+        let mut edges = Vec::new();
+        for polygon in polygons {
+            for i in 0..polygon.len() {
+                let (p0, p1) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                if p0.y != p1.y && p0.x.is_finite() && p1.x.is_finite() {
+                    edges.push(Edge::new(p0, p1));
+                }
+            }
+        }
+        edges.sort_unstable_by(|a, b| a.y0.total_cmp(&b.y0));
+
+        let span_width = (bounds.x1 - bounds.x0) as usize;
+        let mut coverage = vec![0.0f32; span_width + 2];
+        let mut active = Vec::<usize>::new();
+        let mut crossings = Vec::<(f32, i32)>::new();
+        let mut next_edge = 0;
+        let weight = 1.0f32 / SUBSAMPLES as f32;
+
+        for y in bounds.y0..bounds.y1 {
+            let (row_top, row_bottom) = (y as f32, (y + 1) as f32);
+            while next_edge < edges.len() && edges[next_edge].y0 < row_bottom {
+                active.push(next_edge);
+                next_edge += 1;
+            }
+            active.retain(|index| edges[*index].y1 > row_top);
+            if active.is_empty() {
+                continue;
+            }
+
+            coverage.fill(0.0f32);
+            for sample in 0..SUBSAMPLES {
+                let sample_y = row_top + (sample as f32 + 0.5f32) * weight;
+                crossings.clear();
+                for index in &active {
+                    let edge = &edges[*index];
+                    if edge.y0 <= sample_y && sample_y < edge.y1 {
+                        crossings.push((edge.x_at(sample_y), edge.direction));
+                    }
+                }
+                crossings.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                for i in 0..crossings.len().saturating_sub(1) {
+                    winding += crossings[i].1;
+                    let inside = match fill_type {
+                        FillType::NonZero => winding != 0,
+                        FillType::Odd => winding % 2 != 0,
+                    };
+                    if inside {
+                        add_span(
+                            &mut coverage,
+                            crossings[i].0 - bounds.x0 as f32,
+                            crossings[i + 1].0 - bounds.x0 as f32,
+                            weight,
+                        );
+                    }
+                }
+            }
+
+            // the spans are stored as differences between the neighbouring cells
+            let row = &mut mask.get_row_mut(y)[bounds.x0 as usize..bounds.x1 as usize];
+            let mut value = 0.0f32;
+            for (cell, delta) in row.iter_mut().zip(&coverage) {
+                value += delta;
+                *cell = value.clamp(0.0f32, 1.0f32);
+            }
+        }
+
+        mask
+    }
+}
+
+/// Polygon edge going from the top to the bottom.
+struct Edge {
+    x0: f32,
+    y0: f32,
+    y1: f32,
+    dx_dy: f32,
+    direction: i32,
+}
+
+impl Edge {
+    fn new(p0: Point, p1: Point) -> Self {
+        let (top, bottom, direction) = if p0.y < p1.y {
+            (p0, p1, 1)
+        } else {
+            (p1, p0, -1)
+        };
+        Self {
+            x0: top.x,
+            y0: top.y,
+            y1: bottom.y,
+            dx_dy: (bottom.x - top.x) / (bottom.y - top.y),
+            direction,
+        }
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (y - self.y0) * self.dx_dy
+    }
+}
+
+/// Adds a horizontal span between `x0` and `x1` with the given weight.
+///
+/// The values are stored as differences between the neighbouring cells,
+/// so the cost does not depend on the length of the span.
+fn add_span(coverage: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let max = (coverage.len() - 2) as f32;
+    let x0 = x0.clamp(0.0f32, max);
+    let x1 = x1.clamp(0.0f32, max);
+    if x1 <= x0 {
+        return;
+    }
+
+    let (cell0, cell1) = (x0.floor() as usize, x1.floor() as usize);
+    if cell0 == cell1 {
+        let value = (x1 - x0) * weight;
+        coverage[cell0] += value;
+        coverage[cell0 + 1] -= value;
+    } else {
+        // partially covered first cell
+        let first = (cell0 as f32 + 1.0f32 - x0) * weight;
+        coverage[cell0] += first;
+        coverage[cell0 + 1] += weight - first;
+
+        // fully covered cells in between and partially covered last cell
+        let last = (x1 - cell1 as f32) * weight;
+        coverage[cell1] -= weight - last;
+        coverage[cell1 + 1] -= last;
+    }
+}
@@ -0,0 +1,32 @@
+mod blend;
+pub(crate) use blend::*;
+
+mod filter;
+pub(crate) use filter::*;
+
+mod flatten;
+pub(crate) use flatten::*;
+
+mod mask;
+pub(crate) use mask::*;
+
+mod pixmap;
+pub(crate) use pixmap::*;
+
+mod renderer;
+pub(crate) use renderer::*;
+
+mod shader;
+pub(crate) use shader::*;
+
+mod stroker;
+pub(crate) use stroker::*;
+
+/// 2D affine transformation from the local to the device (pixel) coordinates.
+pub(crate) type Transform = drawing_api::euclid::default::Transform2D<f32>;
+
+/// Point in the device (pixel) coordinates.
+pub(crate) type Point = drawing_api::euclid::default::Point2D<f32>;
+
+/// Vector in the device (pixel) coordinates.
+pub(crate) type Vector = drawing_api::euclid::default::Vector2D<f32>;
@@ -0,0 +1,104 @@
+use drawing_api::{check_pixel_rect, ColorFormat, PixelData, PixelIntRect};
+
+/// RGBA color with the color channels multiplied by alpha (all in 0..1 range).
+pub(crate) type Rgba = [f32; 4];
+
+/// Image in memory, stored from top to bottom with premultiplied colors.
+#[derive(Clone)]
+pub(crate) struct Pixmap {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<Rgba>,
+}
+
+impl Pixmap {
+    /// Creates a transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![[0.0f32; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Creates an image from 8-bit channels with straight (not premultiplied) alpha.
+    ///
+    /// Y8 images are treated as the coverage (all channels equal to the value).
+    pub fn from_bytes(
+        contents: &[u8],
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<Self, &'static str> {
+        let len = width as usize * height as usize;
+        if contents.len() < len * color_format.bytes_per_pixel() {
+            return Err("not enough texture data");
+        }
+
+        let data = match color_format {
+            ColorFormat::RGBA => contents
+                .chunks_exact(4)
+                .take(len)
+                .map(|pixel| {
+                    let alpha = pixel[3] as f32 / 255.0f32;
+                    [
+                        pixel[0] as f32 / 255.0f32 * alpha,
+                        pixel[1] as f32 / 255.0f32 * alpha,
+                        pixel[2] as f32 / 255.0f32 * alpha,
+                        alpha,
+                    ]
+                })
+                .collect(),
+            ColorFormat::Y8 => contents[..len]
+                .iter()
+                .map(|value| [*value as f32 / 255.0f32; 4])
+                .collect(),
+        };
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Returns the pixel at the given position, which has to be inside of the image.
+    pub fn get(&self, x: u32, y: u32) -> Rgba {
+        self.data[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Converts the given area to 8-bit channels (premultiplied).
+    pub fn to_pixel_data(
+        &self,
+        rect: PixelIntRect,
+        color_format: ColorFormat,
+    ) -> Result<PixelData, &'static str> {
+        check_pixel_rect(rect, self.width, self.height)?;
+
+        let bytes_per_pixel = color_format.bytes_per_pixel();
+        let row_stride = rect.size.width as usize * bytes_per_pixel;
+        let mut data = Vec::with_capacity(row_stride * rect.size.height as usize);
+        for y in rect.min_y()..rect.max_y() {
+            for x in rect.min_x()..rect.max_x() {
+                let pixel = self.get(x, y);
+                match color_format {
+                    ColorFormat::RGBA => data.extend(pixel.iter().map(|c| to_u8(*c))),
+                    ColorFormat::Y8 => data.push(to_u8(pixel[0])),
+                }
+            }
+        }
+
+        Ok(PixelData {
+            data,
+            width: rect.size.width,
+            height: rect.size.height,
+            row_stride: row_stride as u32,
+            color_format,
+            premultiplied: true,
+        })
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0f32, 1.0f32) * 255.0f32).round() as u8
+}
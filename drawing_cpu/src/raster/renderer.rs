@@ -0,0 +1,562 @@
+use std::rc::Rc;
+
+use drawing_api::{BlendMode, ClipOperation, ColorSource, DrawStyle, FillType, MaskFilter};
+
+use crate::{display_list::rect_path, display_list::Command, DisplayList, Paint, Paragraph, Path};
+
+use super::{
+    apply_color_filter, apply_image_filter, blend, blur_mask, flatten_path, stroke_polylines,
+    Gradient, IntRect, Mask, Pixmap, Point, Rgba, Shader, StrokeStyle, Transform,
+};
+
+// maximum distance (in pixels) of the flattened curves from the original ones
+const TOLERANCE: f32 = 0.1f32;
+
+enum StackEntry {
+    Save {
+        clip: Option<Rc<Mask>>,
+    },
+
+    Layer {
+        parent: Pixmap,
+        clip: Option<Rc<Mask>>,
+        bounds: Mask,
+        paint: Option<Paint>,
+        transform: Transform,
+    },
+}
+
+/// Executes the display list commands on the image.
+pub(crate) struct Renderer {
+    target: Pixmap,
+
+    // `None` when nothing is clipped
+    clip: Option<Rc<Mask>>,
+
+    stack: Vec<StackEntry>,
+}
+
+impl Renderer {
+    pub fn new(target: Pixmap) -> Self {
+        Self {
+            target,
+            clip: None,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Finishes the drawing (composites the unclosed layers) and returns the image.
+    pub fn finish(mut self) -> Pixmap {
+        while !self.stack.is_empty() {
+            self.restore();
+        }
+        self.target
+    }
+
+    /// Draws the display list, the command transformations are followed by the `base` one.
+    pub fn draw_display_list(&mut self, display_list: &DisplayList, base: &Transform) {
+        for command in display_list.commands.iter() {
+            match command {
+                Command::Save => self.stack.push(StackEntry::Save {
+                    clip: self.clip.clone(),
+                }),
+
+                Command::SaveLayer {
+                    bounds,
+                    transform,
+                    paint,
+                    backdrop_filter,
+                } => {
+                    let transform = transform.then(base);
+                    let layer = match backdrop_filter {
+                        Some(filter) => apply_image_filter(&self.target, filter, &transform),
+                        None => Pixmap::new(self.target.width, self.target.height),
+                    };
+                    let bounds = self.fill_path(&rect_path(*bounds), FillType::NonZero, &transform);
+                    self.push_layer(layer, bounds, paint.clone(), transform);
+                }
+
+                Command::Restore => self.restore(),
+
+                Command::Clip {
+                    path,
+                    transform,
+                    operation,
+                } => self.clip(path, &transform.then(base), *operation),
+
+                Command::DrawPaint { paint, transform } => {
+                    let mask = match &self.clip {
+                        Some(clip) => clip.as_ref().clone(),
+                        None => Mask::filled(
+                            self.target.width,
+                            self.target.height,
+                            self.get_full_rect(),
+                        ),
+                    };
+                    self.draw_mask(mask, paint, &transform.then(base));
+                }
+
+                Command::DrawPath {
+                    path,
+                    transform,
+                    paint,
+                } => {
+                    let transform = transform.then(base);
+                    let mask = self.rasterize_path(path, paint, &transform);
+                    self.draw_mask(mask, paint, &transform);
+                }
+
+                Command::DrawTexture {
+                    texture,
+                    src_rect,
+                    dst_rect,
+                    sampling,
+                    transform,
+                    paint,
+                } => {
+                    let transform = transform.then(base);
+                    let paint = paint.clone().unwrap_or_default();
+                    let image_transform =
+                        Transform::translation(-src_rect.origin.x, -src_rect.origin.y)
+                            .then_scale(
+                                dst_rect.size.width / src_rect.size.width,
+                                dst_rect.size.height / src_rect.size.height,
+                            )
+                            .then_translate(dst_rect.origin.to_vector().to_untyped())
+                            .then(&transform);
+                    let Some(inverse) = image_transform.inverse() else {
+                        continue;
+                    };
+                    let shader = Shader::Image {
+                        inverse,
+                        pixmap: texture.get_pixmap(),
+                        horizontal_tile_mode: drawing_api::TileMode::Decal,
+                        vertical_tile_mode: drawing_api::TileMode::Decal,
+                        sampling: *sampling,
+                        alpha: paint.color[3],
+                    };
+                    let mask = self.fill_path(&rect_path(*dst_rect), FillType::NonZero, &transform);
+                    self.draw_shaded_mask(mask, &paint, shader, &transform);
+                }
+
+                Command::DrawParagraph {
+                    paragraph,
+                    location,
+                    transform,
+                } => {
+                    let transform = Transform::translation(location.x, location.y)
+                        .then(transform)
+                        .then(base);
+                    self.draw_paragraph(paragraph, &transform);
+                }
+
+                Command::DrawDisplayList {
+                    display_list,
+                    transform,
+                    opacity,
+                } => {
+                    let transform = transform.then(base);
+                    if *opacity < 1.0f32 {
+                        let layer = Pixmap::new(self.target.width, self.target.height);
+                        let bounds = Mask::filled(
+                            self.target.width,
+                            self.target.height,
+                            self.get_full_rect(),
+                        );
+                        let paint = Paint {
+                            color: [0.0f32, 0.0f32, 0.0f32, opacity.max(0.0f32)],
+                            ..Default::default()
+                        };
+                        self.push_layer(layer, bounds, Some(paint), transform);
+                    } else {
+                        self.stack.push(StackEntry::Save {
+                            clip: self.clip.clone(),
+                        });
+                    }
+                    self.draw_display_list(display_list, &transform);
+                    self.restore();
+                }
+            }
+        }
+    }
+
+    fn get_full_rect(&self) -> IntRect {
+        IntRect::new(0, 0, self.target.width as i32, self.target.height as i32)
+    }
+
+    fn push_layer(
+        &mut self,
+        layer: Pixmap,
+        bounds: Mask,
+        paint: Option<Paint>,
+        transform: Transform,
+    ) {
+        let parent = std::mem::replace(&mut self.target, layer);
+        self.stack.push(StackEntry::Layer {
+            parent,
+            clip: self.clip.clone(),
+            bounds,
+            paint,
+            transform,
+        });
+    }
+
+    fn restore(&mut self) {
+        match self.stack.pop() {
+            Some(StackEntry::Save { clip }) => self.clip = clip,
+
+            Some(StackEntry::Layer {
+                parent,
+                clip,
+                mut bounds,
+                paint,
+                transform,
+            }) => {
+                let layer = std::mem::replace(&mut self.target, parent);
+                self.clip = clip;
+
+                let paint = paint.unwrap_or_default();
+                let layer = match &paint.image_filter {
+                    Some(filter) => apply_image_filter(&layer, filter, &transform),
+                    None => layer,
+                };
+                if let Some(clip) = &self.clip {
+                    bounds.intersect(clip);
+                }
+
+                let alpha = paint.color[3];
+                composite(&mut self.target, &bounds, paint.blend_mode, |x, y| {
+                    let color = layer.get(x as u32, y as u32).map(|c| c * alpha);
+                    match &paint.color_filter {
+                        Some(filter) => apply_color_filter(color, filter),
+                        None => color,
+                    }
+                });
+            }
+
+            None => (),
+        }
+    }
+
+    fn clip(&mut self, path: &Path, transform: &Transform, operation: ClipOperation) {
+        let mut mask = self.fill_path(&path.path, path.fill_type, transform);
+        let clip = match operation {
+            ClipOperation::Intersect => {
+                if let Some(clip) = &self.clip {
+                    mask.intersect(clip);
+                }
+                mask
+            }
+            ClipOperation::Difference => {
+                let mut clip = match &self.clip {
+                    Some(clip) => clip.as_ref().clone(),
+                    None => {
+                        Mask::filled(self.target.width, self.target.height, self.get_full_rect())
+                    }
+                };
+                clip.subtract(&mask);
+                clip
+            }
+        };
+        self.clip = Some(Rc::new(clip));
+    }
+
+    fn fill_path(
+        &self,
+        path: &[crate::PathElement],
+        fill_type: FillType,
+        transform: &Transform,
+    ) -> Mask {
+        let polygons = flatten_path(path, transform, TOLERANCE)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .collect::<Vec<_>>();
+        Mask::fill_polygons(self.target.width, self.target.height, &polygons, fill_type)
+    }
+
+    fn stroke_path(&self, path: &Path, paint: &Paint, transform: &Transform) -> Mask {
+        // the stroke is built in the local coordinates, so it is scaled with the shape
+        let scale = transform.determinant().abs().sqrt();
+        if scale <= 0.0f32 || !scale.is_finite() {
+            return Mask::new(self.target.width, self.target.height);
+        }
+        let tolerance = TOLERANCE / scale;
+
+        let polylines = flatten_path(&path.path, &Transform::identity(), tolerance);
+        let style = StrokeStyle {
+            width: if paint.stroke_width > 0.0f32 {
+                paint.stroke_width
+            } else {
+                // hairline
+                1.0f32 / scale
+            },
+            cap: paint.stroke_cap,
+            join: paint.stroke_join,
+            miter_limit: paint.stroke_miter,
+        };
+        let polygons = stroke_polylines(&polylines, &style, tolerance)
+            .into_iter()
+            .map(|polygon| {
+                polygon
+                    .into_iter()
+                    .map(|point| transform.transform_point(point))
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        Mask::fill_polygons(
+            self.target.width,
+            self.target.height,
+            &polygons,
+            FillType::NonZero,
+        )
+    }
+
+    fn rasterize_path(&self, path: &Path, paint: &Paint, transform: &Transform) -> Mask {
+        match paint.draw_style {
+            DrawStyle::Fill => self.fill_path(&path.path, path.fill_type, transform),
+            DrawStyle::Stroke => self.stroke_path(path, paint, transform),
+            DrawStyle::StrokeAndFill => {
+                let mut mask = self.fill_path(&path.path, path.fill_type, transform);
+                mask.union(&self.stroke_path(path, paint, transform));
+                mask
+            }
+        }
+    }
+
+    fn draw_paragraph(&mut self, paragraph: &Paragraph, transform: &Transform) {
+        let data = &paragraph.data;
+
+        // backgrounds go below the whole text
+        for (run, glyphs) in paragraph.get_runs() {
+            if let Some(background) = &run.background {
+                let path = glyphs
+                    .iter()
+                    .flat_map(|glyph| rect_path(paragraph.get_glyph_bounds(glyph)))
+                    .collect::<Vec<_>>();
+                let mask = self.fill_path(&path, FillType::NonZero, transform);
+                self.draw_mask(mask, background, transform);
+            }
+        }
+
+        // glyphs are rendered in the device pixel size, the rotation is not applied to them
+        let scale = transform.determinant().abs().sqrt();
+        for (run, run_glyphs) in paragraph.get_runs() {
+            let Some(foreground) = &run.foreground else {
+                continue;
+            };
+            let pixel_size = (run.size * scale).round().max(1.0f32) as u32;
+            let text = run_glyphs.iter().map(|glyph| glyph.ch).collect::<String>();
+            let Ok((_, glyphs)) = data.fonts.get_glyphs(&run.family, pixel_size, &text) else {
+                continue;
+            };
+
+            let mut mask = Mask::new(self.target.width, self.target.height);
+            let full_rect = mask.get_full_rect();
+            for glyph in run_glyphs {
+                let Some(bitmap) = glyphs.get(&glyph.ch) else {
+                    continue;
+                };
+                let origin = transform.transform_point(glyph.position.to_untyped());
+                let x0 = origin.x.round() as i32 + bitmap.left;
+                let y0 = origin.y.round() as i32 - bitmap.top;
+                let rect = IntRect::new(x0, y0, x0 + bitmap.width as i32, y0 + bitmap.rows as i32)
+                    .intersection(&full_rect);
+
+                for y in rect.y0..rect.y1 {
+                    let src_row = (y - y0) as usize * bitmap.width as usize;
+                    let row = mask.get_row_mut(y);
+                    for x in rect.x0..rect.x1 {
+                        let coverage =
+                            bitmap.coverage[src_row + (x - x0) as usize] as f32 / 255.0f32;
+                        row[x as usize] = row[x as usize].max(coverage);
+                    }
+                }
+                mask.bounds = mask.bounds.union(&rect);
+            }
+
+            self.draw_mask(mask, foreground, transform);
+        }
+    }
+
+    /// Draws the coverage mask with the paint.
+    fn draw_mask(&mut self, mask: Mask, paint: &Paint, transform: &Transform) {
+        let shader = create_shader(paint, transform);
+        self.draw_shaded_mask(mask, paint, shader, transform);
+    }
+
+    /// Applies the mask filter, image filter, color filter and clip
+    /// and blends the shaded mask with the target.
+    fn draw_shaded_mask(
+        &mut self,
+        mask: Mask,
+        paint: &Paint,
+        shader: Shader,
+        transform: &Transform,
+    ) {
+        let mut mask = match &paint.mask_filter {
+            Some(MaskFilter::Blur { style, sigma }) => {
+                let scale = transform.determinant().abs().sqrt();
+                blur_mask(&mask, sigma * scale, *style)
+            }
+            None => mask,
+        };
+
+        let shade = |x: i32, y: i32| {
+            let color = shader.shade(x, y);
+            match &paint.color_filter {
+                Some(filter) => apply_color_filter(color, filter),
+                None => color,
+            }
+        };
+
+        match &paint.image_filter {
+            Some(filter) => {
+                // the filter works on the whole drawn shape before clipping
+                let mut layer = Pixmap::new(self.target.width, self.target.height);
+                composite(&mut layer, &mask, BlendMode::SourceOver, shade);
+                let layer = apply_image_filter(&layer, filter, transform);
+
+                let mask = match &self.clip {
+                    Some(clip) => clip.as_ref().clone(),
+                    None => {
+                        Mask::filled(self.target.width, self.target.height, self.get_full_rect())
+                    }
+                };
+                composite(&mut self.target, &mask, paint.blend_mode, |x, y| {
+                    layer.get(x as u32, y as u32)
+                });
+            }
+
+            None => {
+                if let Some(clip) = &self.clip {
+                    mask.intersect(clip);
+                }
+                composite(&mut self.target, &mask, paint.blend_mode, shade);
+            }
+        }
+    }
+}
+
+/// Blends the colors with the target in the area covered by the mask.
+fn composite(
+    target: &mut Pixmap,
+    mask: &Mask,
+    blend_mode: BlendMode,
+    shade: impl Fn(i32, i32) -> Rgba,
+) {
+    let width = target.width as usize;
+    for y in mask.bounds.y0..mask.bounds.y1 {
+        for x in mask.bounds.x0..mask.bounds.x1 {
+            let coverage = mask.get(x, y);
+            if coverage <= 0.0f32 {
+                continue;
+            }
+            let index = y as usize * width + x as usize;
+            let dst = target.data[index];
+            let result = blend(shade(x, y), dst, blend_mode);
+            target.data[index] = std::array::from_fn(|i| dst[i] + (result[i] - dst[i]) * coverage);
+        }
+    }
+}
+
+/// Creates the shader of the paint, the color sources are in the local coordinates.
+fn create_shader(paint: &Paint, transform: &Transform) -> Shader {
+    let alpha = paint.color[3];
+    let inverse = |transformation: &Option<drawing_api::Matrix>| {
+        transformation
+            .map_or_else(Transform::identity, |matrix| matrix.to_2d())
+            .then(transform)
+            .inverse()
+            .unwrap_or_else(Transform::identity)
+    };
+    let point = |point: &drawing_api::PixelPoint| Point::new(point.x, point.y);
+
+    match &paint.color_source {
+        Some(ColorSource::LinearGradient {
+            start,
+            end,
+            colors,
+            stops,
+            tile_mode,
+            transformation,
+        }) => Shader::LinearGradient {
+            inverse: inverse(transformation),
+            start: point(start),
+            end: point(end),
+            gradient: Gradient::new(colors, stops, *tile_mode),
+            alpha,
+        },
+
+        Some(ColorSource::RadialGradient {
+            center,
+            radius,
+            colors,
+            stops,
+            tile_mode,
+            transformation,
+        }) => Shader::RadialGradient {
+            inverse: inverse(transformation),
+            center: point(center),
+            radius: *radius,
+            gradient: Gradient::new(colors, stops, *tile_mode),
+            alpha,
+        },
+
+        Some(ColorSource::ConicalGradient {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+            colors,
+            stops,
+            tile_mode,
+            transformation,
+        }) => Shader::ConicalGradient {
+            inverse: inverse(transformation),
+            start_center: point(start_center),
+            start_radius: *start_radius,
+            end_center: point(end_center),
+            end_radius: *end_radius,
+            gradient: Gradient::new(colors, stops, *tile_mode),
+            alpha,
+        },
+
+        Some(ColorSource::SweepGradient {
+            center,
+            start,
+            end,
+            colors,
+            stops,
+            tile_mode,
+            transformation,
+        }) => Shader::SweepGradient {
+            inverse: inverse(transformation),
+            center: point(center),
+            start_angle: *start,
+            end_angle: *end,
+            gradient: Gradient::new(colors, stops, *tile_mode),
+            alpha,
+        },
+
+        Some(ColorSource::Image {
+            image,
+            horizontal_tile_mode,
+            vertical_tile_mode,
+            sampling,
+            transformation,
+        }) => Shader::Image {
+            inverse: inverse(transformation),
+            pixmap: image.get_pixmap(),
+            horizontal_tile_mode: *horizontal_tile_mode,
+            vertical_tile_mode: *vertical_tile_mode,
+            sampling: *sampling,
+            alpha,
+        },
+
+        // fragment programs are not supported, fall back to the paint color
+        Some(ColorSource::Fragment { .. }) | None => {
+            let [r, g, b, a] = paint.color;
+            Shader::Solid([r * a, g * a, b * a, a])
+        }
+    }
+}
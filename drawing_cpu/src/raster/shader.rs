@@ -0,0 +1,335 @@
+use std::sync::Arc;
+
+use drawing_api::{TextureSampling, TileMode};
+
+use super::{Pixmap, Point, Rgba, Transform};
+
+/// Color ramp of a gradient.
+pub(crate) struct Gradient {
+    // colors with straight alpha
+    colors: Vec<Rgba>,
+    stops: Vec<f32>,
+    tile_mode: TileMode,
+}
+
+impl Gradient {
+    /// Creates the gradient, when the number of stops doesn't match
+    /// the number of colors, the colors are distributed evenly.
+    pub fn new(colors: &[drawing_api::Color], stops: &[f32], tile_mode: TileMode) -> Self {
+        let stops = if stops.len() == colors.len() {
+            stops.to_vec()
+        } else if colors.len() > 1 {
+            (0..colors.len())
+                .map(|i| i as f32 / (colors.len() - 1) as f32)
+                .collect()
+        } else {
+            vec![0.0f32; colors.len()]
+        };
+        Self {
+            colors: colors
+                .iter()
+                .map(|c| [c.red, c.green, c.blue, c.alpha])
+                .collect(),
+            stops,
+            tile_mode,
+        }
+    }
+
+    /// Returns the premultiplied color at the position, `None` when outside in the decal mode.
+    fn get_color(&self, t: f32) -> Option<Rgba> {
+        if self.colors.is_empty() || !t.is_finite() {
+            return None;
+        }
+        let t = apply_tile_mode(t, self.tile_mode)?;
+
+        let index = self.stops.partition_point(|stop| *stop < t);
+        let color = if index == 0 {
+            self.colors[0]
+        } else if index >= self.colors.len() {
+            self.colors[self.colors.len() - 1]
+        } else {
+            let (t0, t1) = (self.stops[index - 1], self.stops[index]);
+            let f = if t1 > t0 {
+                ((t - t0) / (t1 - t0)).clamp(0.0f32, 1.0f32)
+            } else {
+                1.0f32
+            };
+            let (c0, c1) = (self.colors[index - 1], self.colors[index]);
+            std::array::from_fn(|i| c0[i] + (c1[i] - c0[i]) * f)
+        };
+
+        Some([
+            color[0] * color[3],
+            color[1] * color[3],
+            color[2] * color[3],
+            color[3],
+        ])
+    }
+}
+
+/// Computes the color of every pixel of a drawn shape.
+///
+/// The transformations map the device coordinates to the color source ones.
+pub(crate) enum Shader {
+    Solid(Rgba),
+
+    LinearGradient {
+        inverse: Transform,
+        start: Point,
+        end: Point,
+        gradient: Gradient,
+        alpha: f32,
+    },
+
+    RadialGradient {
+        inverse: Transform,
+        center: Point,
+        radius: f32,
+        gradient: Gradient,
+        alpha: f32,
+    },
+
+    ConicalGradient {
+        inverse: Transform,
+        start_center: Point,
+        start_radius: f32,
+        end_center: Point,
+        end_radius: f32,
+        gradient: Gradient,
+        alpha: f32,
+    },
+
+    SweepGradient {
+        inverse: Transform,
+        center: Point,
+        start_angle: f32,
+        end_angle: f32,
+        gradient: Gradient,
+        alpha: f32,
+    },
+
+    Image {
+        inverse: Transform,
+        pixmap: Arc<Pixmap>,
+        horizontal_tile_mode: TileMode,
+        vertical_tile_mode: TileMode,
+        sampling: TextureSampling,
+        alpha: f32,
+    },
+}
+
+impl Shader {
+    /// Returns the premultiplied color of the pixel.
+    pub fn shade(&self, x: i32, y: i32) -> Rgba {
+        let device_point = Point::new(x as f32 + 0.5f32, y as f32 + 0.5f32);
+        match self {
+            Shader::Solid(color) => *color,
+
+            Shader::LinearGradient {
+                inverse,
+                start,
+                end,
+                gradient,
+                alpha,
+            } => {
+                let point = inverse.transform_point(device_point);
+                let direction = *end - *start;
+                let t = (point - *start).dot(direction) / direction.square_length().max(1.0e-6f32);
+                scale(gradient.get_color(t), *alpha)
+            }
+
+            Shader::RadialGradient {
+                inverse,
+                center,
+                radius,
+                gradient,
+                alpha,
+            } => {
+                let point = inverse.transform_point(device_point);
+                let t = (point - *center).length() / radius.max(1.0e-6f32);
+                scale(gradient.get_color(t), *alpha)
+            }
+
+            Shader::ConicalGradient {
+                inverse,
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                gradient,
+                alpha,
+            } => {
+                let point = inverse.transform_point(device_point);
+                let t = conical_gradient_position(
+                    point,
+                    *start_center,
+                    *start_radius,
+                    *end_center,
+                    *end_radius,
+                );
+                scale(t.and_then(|t| gradient.get_color(t)), *alpha)
+            }
+
+            Shader::SweepGradient {
+                inverse,
+                center,
+                start_angle,
+                end_angle,
+                gradient,
+                alpha,
+            } => {
+                // angles in degrees clockwise from the x axis
+                let d = inverse.transform_point(device_point) - *center;
+                let mut angle = d.y.atan2(d.x).to_degrees();
+                if angle < 0.0f32 {
+                    angle += 360.0f32;
+                }
+                let mut range = end_angle - start_angle;
+                if range.abs() < 1.0e-6f32 {
+                    range = 1.0e-6f32;
+                }
+                scale(gradient.get_color((angle - start_angle) / range), *alpha)
+            }
+
+            Shader::Image {
+                inverse,
+                pixmap,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
+                alpha,
+            } => {
+                let point = inverse.transform_point(device_point);
+                let color = sample(
+                    pixmap,
+                    point,
+                    *horizontal_tile_mode,
+                    *vertical_tile_mode,
+                    *sampling,
+                );
+                scale(Some(color), *alpha)
+            }
+        }
+    }
+}
+
+/// Returns the pixel of the image at the given position (in pixels).
+pub(crate) fn sample(
+    pixmap: &Pixmap,
+    point: Point,
+    horizontal_tile_mode: TileMode,
+    vertical_tile_mode: TileMode,
+    sampling: TextureSampling,
+) -> Rgba {
+    let texel = |x: i32, y: i32| -> Rgba {
+        match (
+            tile_index(x, pixmap.width, horizontal_tile_mode),
+            tile_index(y, pixmap.height, vertical_tile_mode),
+        ) {
+            (Some(x), Some(y)) => pixmap.get(x, y),
+            _ => [0.0f32; 4],
+        }
+    };
+
+    if !point.x.is_finite() || !point.y.is_finite() {
+        return [0.0f32; 4];
+    }
+
+    match sampling {
+        TextureSampling::NearestNeighbor => {
+            texel(limit(point.x.floor()) as i32, limit(point.y.floor()) as i32)
+        }
+
+        TextureSampling::Linear => {
+            let (x, y) = (point.x - 0.5f32, point.y - 0.5f32);
+            let (x0, y0) = (limit(x.floor()), limit(y.floor()));
+            let (fx, fy) = (x - x0, y - y0);
+            let (x0, y0) = (x0 as i32, y0 as i32);
+            let (c00, c10) = (texel(x0, y0), texel(x0 + 1, y0));
+            let (c01, c11) = (texel(x0, y0 + 1), texel(x0 + 1, y0 + 1));
+            std::array::from_fn(|i| {
+                let top = c00[i] + (c10[i] - c00[i]) * fx;
+                let bottom = c01[i] + (c11[i] - c01[i]) * fx;
+                top + (bottom - top) * fy
+            })
+        }
+    }
+}
+
+/// Maps the position to the 0..1 range, `None` when outside in the decal mode.
+pub(crate) fn apply_tile_mode(t: f32, tile_mode: TileMode) -> Option<f32> {
+    match tile_mode {
+        TileMode::Clamp => Some(t.clamp(0.0f32, 1.0f32)),
+        TileMode::Repeat => Some(t - t.floor()),
+        TileMode::Mirror => Some(1.0f32 - ((t.rem_euclid(2.0f32)) - 1.0f32).abs()),
+        TileMode::Decal => (0.0f32..=1.0f32).contains(&t).then_some(t),
+    }
+}
+
+/// Maps the pixel index to the image, `None` when outside in the decal mode.
+pub(crate) fn tile_index(index: i32, size: u32, tile_mode: TileMode) -> Option<u32> {
+    let size = size as i32;
+    if size == 0 {
+        return None;
+    }
+    match tile_mode {
+        TileMode::Clamp => Some(index.clamp(0, size - 1) as u32),
+        TileMode::Repeat => Some(index.rem_euclid(size) as u32),
+        TileMode::Mirror => {
+            let index = index.rem_euclid(2 * size);
+            Some(if index >= size {
+                2 * size - 1 - index
+            } else {
+                index
+            } as u32)
+        }
+        TileMode::Decal => (0..size).contains(&index).then_some(index as u32),
+    }
+}
+
+/// Finds the largest t for which the point lies on the circle
+/// with the interpolated center and radius >= 0.
+fn conical_gradient_position(
+    point: Point,
+    start_center: Point,
+    start_radius: f32,
+    end_center: Point,
+    end_radius: f32,
+) -> Option<f32> {
+    let cd = end_center - start_center;
+    let pd = point - start_center;
+    let dr = end_radius - start_radius;
+    let a = cd.dot(cd) - dr * dr;
+    let b = pd.dot(cd) + start_radius * dr;
+    let c = pd.dot(pd) - start_radius * start_radius;
+
+    let t = if a.abs() < 1.0e-6f32 {
+        if b.abs() < 1.0e-6f32 {
+            return None;
+        }
+        c / (2.0f32 * b)
+    } else {
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0f32 {
+            return None;
+        }
+        let t1 = (b + discriminant.sqrt()) / a;
+        let t2 = (b - discriminant.sqrt()) / a;
+        if start_radius + t1.max(t2) * dr >= 0.0f32 {
+            t1.max(t2)
+        } else {
+            t1.min(t2)
+        }
+    };
+
+    (start_radius + t * dr >= 0.0f32).then_some(t)
+}
+
+fn scale(color: Option<Rgba>, alpha: f32) -> Rgba {
+    color.map_or([0.0f32; 4], |color| color.map(|c| c * alpha))
+}
+
+// keeps the coordinates in the range of i32
+fn limit(value: f32) -> f32 {
+    value.clamp(-1.0e7f32, 1.0e7f32)
+}
@@ -0,0 +1,192 @@
+use std::f32::consts::PI;
+
+use drawing_api::{StrokeCap, StrokeJoin};
+
+use super::{Point, Polyline, Vector};
+
+pub(crate) struct StrokeStyle {
+    pub width: f32,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    pub miter_limit: f32,
+}
+
+/// Converts the stroke of the polylines to polygons.
+///
+/// Every segment, join and cap becomes a separate polygon. All the polygons
+/// have the same orientation, so they should be filled with the non-zero rule.
+pub(crate) fn stroke_polylines(
+    polylines: &[Polyline],
+    style: &StrokeStyle,
+    tolerance: f32,
+) -> Vec<Vec<Point>> {
+    let half_width = style.width * 0.5f32;
+    let mut polygons = Vec::new();
+    if half_width <= 0.0f32 {
+        return polygons;
+    }
+
+    for polyline in polylines {
+        let mut points = polyline.points.clone();
+        points.dedup_by(|a, b| (*a - *b).square_length() < 1.0e-12f32);
+        if polyline.closed && points.len() > 2 && points.first() == points.last() {
+            points.pop();
+        }
+
+        if points.len() == 1 {
+            // zero length line has only the caps
+            let point = points[0];
+            match style.cap {
+                StrokeCap::Butt => (),
+                StrokeCap::Round => polygons.push(circle(point, half_width, tolerance)),
+                StrokeCap::Square => polygons.push(vec![
+                    point + Vector::new(-half_width, -half_width),
+                    point + Vector::new(half_width, -half_width),
+                    point + Vector::new(half_width, half_width),
+                    point + Vector::new(-half_width, half_width),
+                ]),
+            }
+            continue;
+        }
+
+        let count = points.len();
+        let closed = polyline.closed && count > 2;
+        let segment_count = if closed { count } else { count - 1 };
+        let direction = |i: usize| (points[(i + 1) % count] - points[i]).normalize();
+        let normal = |d: Vector| Vector::new(d.y, -d.x) * half_width;
+
+        for i in 0..segment_count {
+            let (a, b) = (points[i], points[(i + 1) % count]);
+            let n = normal(direction(i));
+            polygons.push(vec![a + n, b + n, b - n, a - n]);
+        }
+
+        let joins = if closed { 0..count } else { 1..count - 1 };
+        for i in joins {
+            let d0 = direction((i + count - 1) % count);
+            let d1 = direction(i);
+            add_join(
+                &mut polygons,
+                points[i],
+                d0,
+                d1,
+                half_width,
+                style,
+                tolerance,
+            );
+        }
+
+        if !closed {
+            add_cap(
+                &mut polygons,
+                points[0],
+                -direction(0),
+                half_width,
+                style,
+                tolerance,
+            );
+            add_cap(
+                &mut polygons,
+                points[count - 1],
+                direction(count - 2),
+                half_width,
+                style,
+                tolerance,
+            );
+        }
+    }
+
+    for polygon in &mut polygons {
+        if signed_area(polygon) < 0.0f32 {
+            polygon.reverse();
+        }
+    }
+
+    polygons
+}
+
+/// Adds the join between the segments with `d0` and `d1` directions meeting at the point.
+fn add_join(
+    polygons: &mut Vec<Vec<Point>>,
+    point: Point,
+    d0: Vector,
+    d1: Vector,
+    half_width: f32,
+    style: &StrokeStyle,
+    tolerance: f32,
+) {
+    let cross = d0.cross(d1);
+    if cross.abs() < 1.0e-6f32 && d0.dot(d1) > 0.0f32 {
+        // no turn
+        return;
+    }
+
+    // normals on the outer side of the turn
+    let side = if cross > 0.0f32 { 1.0f32 } else { -1.0f32 };
+    let o0 = Vector::new(d0.y, -d0.x) * half_width * side;
+    let o1 = Vector::new(d1.y, -d1.x) * half_width * side;
+
+    match style.join {
+        StrokeJoin::Round => polygons.push(circle(point, half_width, tolerance)),
+
+        StrokeJoin::Bevel => polygons.push(vec![point, point + o0, point + o1]),
+
+        StrokeJoin::Miter => {
+            let bisector = o0 + o1;
+            let cos_half_angle = bisector.length() / (2.0f32 * half_width);
+            if cos_half_angle > 1.0e-6f32 && 1.0f32 / cos_half_angle <= style.miter_limit {
+                let miter = point + bisector.normalize() * (half_width / cos_half_angle);
+                polygons.push(vec![point, point + o0, miter, point + o1]);
+            } else {
+                polygons.push(vec![point, point + o0, point + o1]);
+            }
+        }
+    }
+}
+
+/// Adds the cap at the end of the line going in the `direction`.
+fn add_cap(
+    polygons: &mut Vec<Vec<Point>>,
+    point: Point,
+    direction: Vector,
+    half_width: f32,
+    style: &StrokeStyle,
+    tolerance: f32,
+) {
+    match style.cap {
+        StrokeCap::Butt => (),
+
+        StrokeCap::Round => polygons.push(circle(point, half_width, tolerance)),
+
+        StrokeCap::Square => {
+            let n = Vector::new(direction.y, -direction.x) * half_width;
+            let d = direction * half_width;
+            polygons.push(vec![point + n, point + n + d, point - n + d, point - n]);
+        }
+    }
+}
+
+fn circle(center: Point, radius: f32, tolerance: f32) -> Vec<Point> {
+    let segments = if radius > tolerance {
+        (PI / (1.0f32 - tolerance / radius).acos()).ceil() as usize
+    } else {
+        0
+    }
+    .clamp(8, 512);
+
+    (0..segments)
+        .map(|i| {
+            let (sin, cos) = (2.0f32 * PI * i as f32 / segments as f32).sin_cos();
+            center + Vector::new(cos, sin) * radius
+        })
+        .collect()
+}
+
+fn signed_area(polygon: &[Point]) -> f32 {
+    let mut area = 0.0f32;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        area += a.x * b.y - b.x * a.y;
+    }
+    area * 0.5f32
+}
@@ -0,0 +1,152 @@
+use drawing_api::euclid;
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, Paint as _};
+use drawing_cpu::{CpuContext, DisplayListBuilder, Paint};
+
+const SIZE: u32 = 32;
+
+/// Draws the display list and returns the RGBA pixels of the whole surface.
+fn render(display_list: &<CpuContext as DrawingContext>::DisplayList) -> PixelData {
+    let mut surface = CpuContext::new()
+        .create_surface(SIZE, SIZE, ColorFormat::RGBA)
+        .unwrap();
+    surface.draw(display_list).unwrap();
+    surface
+        .read_pixels(PixelIntRect::new(
+            euclid::point2(0, 0),
+            euclid::size2(SIZE, SIZE),
+        ))
+        .unwrap()
+}
+
+/// Returns whether the channels differ by at most 2 (rounding).
+fn is_close(pixel: &[u8], expected: [u8; 4]) -> bool {
+    pixel
+        .iter()
+        .zip(expected)
+        .all(|(a, b)| (*a as i32 - b as i32).abs() <= 2)
+}
+
+#[test]
+fn fill_covers_the_rectangle() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(8.0, 8.0, 16.0, 8.0), "#F00");
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(pixels.premultiplied);
+    assert!(is_close(pixels.get_pixel(8, 8), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(23, 15), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(7, 8), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(24, 8), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(16, 16), [0, 0, 0, 0]));
+}
+
+#[test]
+fn fill_edges_are_antialiased() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(8.5, 8.0, 16.0, 8.0), "#F00");
+    let pixels = render(&dlb.build().unwrap());
+
+    // half of the pixel is covered
+    assert!(is_close(pixels.get_pixel(8, 10), [128, 0, 0, 128]));
+    assert!(is_close(pixels.get_pixel(9, 10), [255, 0, 0, 255]));
+}
+
+#[test]
+fn oval_fill_leaves_the_corners_empty() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_oval(rect(0.0, 0.0, 32.0, 32.0), "#00F");
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(16, 16), [0, 0, 255, 255]));
+    assert!(is_close(pixels.get_pixel(1, 1), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(30, 30), [0, 0, 0, 0]));
+}
+
+#[test]
+fn clip_rect_intersect_limits_drawing() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_rect(rect(8.0, 8.0, 8.0, 8.0), ClipOperation::Intersect);
+    dlb.draw_paint(Paint::color("#F00"));
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(8, 8), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(15, 15), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(16, 15), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(4, 4), [0, 0, 0, 0]));
+}
+
+#[test]
+fn clip_rect_difference_draws_outside_the_hole() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_rect(rect(8.0, 8.0, 8.0, 8.0), ClipOperation::Difference);
+    dlb.draw_paint(Paint::color("#F00"));
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(12, 12), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(4, 4), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(20, 20), [255, 0, 0, 255]));
+}
+
+#[test]
+fn clip_is_restored() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.save();
+    dlb.clip_oval(rect(0.0, 0.0, 16.0, 16.0), ClipOperation::Intersect);
+    dlb.draw_paint(Paint::color("#F00"));
+    dlb.restore();
+    dlb.draw_rect(rect(24.0, 24.0, 8.0, 8.0), "#00F");
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(8, 8), [255, 0, 0, 255]));
+    assert!(is_close(pixels.get_pixel(0, 0), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(20, 8), [0, 0, 0, 0]));
+    assert!(is_close(pixels.get_pixel(28, 28), [0, 0, 255, 255]));
+}
+
+#[test]
+fn source_over_blends_translucent_colors() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_paint(Paint::color("#F00"));
+    dlb.draw_paint(Paint::color((0.0, 0.0, 1.0, 0.5)));
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(16, 16), [128, 0, 128, 255]));
+}
+
+#[test]
+fn blend_modes_combine_with_the_destination() {
+    let draw = |blend_mode: BlendMode| {
+        let mut dlb = DisplayListBuilder::new(None);
+        dlb.draw_paint(Paint::color((1.0, 0.5, 0.0, 1.0)));
+        let paint = Paint::color((0.5, 1.0, 1.0, 1.0)).with_blend_mode(blend_mode);
+        dlb.draw_rect(rect(0.0, 0.0, 16.0, 32.0), &paint);
+        let pixels = render(&dlb.build().unwrap());
+        let (inside, outside) = (pixels.get_pixel(8, 8), pixels.get_pixel(24, 8));
+        assert!(is_close(outside, [255, 128, 0, 255]));
+        inside.to_vec()
+    };
+
+    assert!(is_close(&draw(BlendMode::Multiply), [128, 128, 0, 255]));
+    assert!(is_close(&draw(BlendMode::Screen), [255, 255, 255, 255]));
+    assert!(is_close(&draw(BlendMode::Darken), [128, 128, 0, 255]));
+    assert!(is_close(&draw(BlendMode::Clear), [0, 0, 0, 0]));
+    assert!(is_close(&draw(BlendMode::Destination), [255, 128, 0, 255]));
+}
+
+#[test]
+fn layer_opacity_is_applied_once() {
+    let mut paint = Paint::default();
+    paint.set_color((0.0, 0.0, 0.0, 0.5));
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.save_layer(rect(0.0, 0.0, 32.0, 32.0), Some((&paint).into()), None);
+    // the overlapping rectangles don't add up in the layer
+    dlb.draw_rect(rect(0.0, 0.0, 16.0, 16.0), "#F00");
+    dlb.draw_rect(rect(8.0, 8.0, 16.0, 16.0), "#F00");
+    dlb.restore();
+    let pixels = render(&dlb.build().unwrap());
+
+    assert!(is_close(pixels.get_pixel(4, 4), [128, 0, 0, 128]));
+    assert!(is_close(pixels.get_pixel(12, 12), [128, 0, 0, 128]));
+    assert!(is_close(pixels.get_pixel(28, 28), [0, 0, 0, 0]));
+}
@@ -1,24 +1,38 @@
 use drawing_api::{
-    euclid::{Angle, Vector2D},
-    smart_pointers::OptRef,
-    PixelPoint, PixelRect, PixelSize, PixelUnit, RoundingRadii, TextureSampling,
+    euclid::Angle, smart_pointers::OptRef, Matrix, PixelPoint, PixelRect, PixelSize, RoundingRadii,
+    ShadowParameters, TextureSampling,
 };
 
-use crate::{generic::device::convert_color, units::PixelTransform, GlTexture};
+use crate::{
+    generic::{
+        device::convert_color,
+        renderer::{oval_path, rect_path, rect_rounded_radii_path, reverse_path},
+    },
+    units::PixelTransform,
+    GlTexture, LineCap, LineJoin,
+};
 
-use super::{ImageFilterFragment, PathElement, Primitive};
+use super::{
+    Glyph, GradientShape, ImageFilter, ImageFilterFragment, PathElement, Primitive, StrokeStyle,
+};
 
 enum StackElement {
     Start,
-    RestorePoint,
+    RestorePoint {
+        transform: Matrix,
+    },
     Transform {
         transform: PixelTransform,
+
+        // the full transformation below this element
+        parent_transform: Matrix,
     },
     ClipRect {
         rect: PixelRect,
     },
     ClipPath {
         path: Vec<PathElement>,
+        operation: drawing_api::ClipOperation,
     },
     Layer {
         bounds: PixelRect,
@@ -27,11 +41,337 @@ enum StackElement {
     },
 }
 
+/// Display list builder of the GL backend.
+///
+/// The renderer works with 2D affine transformations only. The matrices passed to
+/// `transform()` and `set_transform()` are flattened to 2D, the perspective
+/// and the z axis components (see `Matrix::is_2d()`) are dropped.
 pub struct DisplayListBuilder {
     display_list_stack: Vec<(StackElement, crate::display_list::DisplayList)>,
+
+    // the full transformation built up on the current save level
+    transform: Matrix,
 }
 
 impl DisplayListBuilder {
+    /// Appends a transformation (relative to the current one) to the stack.
+    ///
+    /// The renderer works with 2D affine transformations only,
+    /// so the perspective and z components of the matrix are dropped.
+    fn push_transform(&mut self, relative_transform: &Matrix) {
+        let relative_transform = relative_transform.to_2d();
+        let parent_transform = self.transform;
+        self.transform = relative_transform.to_3d().then(&self.transform);
+        self.display_list_stack.push((
+            StackElement::Transform {
+                transform: PixelTransform::from_untyped(&relative_transform),
+                parent_transform,
+            },
+            crate::display_list::DisplayList::new(),
+        ));
+    }
+
+    /// Pops the element from the top of the stack and appends its content
+    /// to the display list of the element below.
+    fn pop_stack_element(&mut self) -> StackElement {
+        let (stack_element, mut display_list) = self.display_list_stack.pop().unwrap();
+        let parent_display_list = &mut self.display_list_stack.last_mut().unwrap().1.display_list;
+
+        match &stack_element {
+            StackElement::Start => unreachable!(),
+
+            StackElement::RestorePoint { .. } => {
+                parent_display_list.append(&mut display_list.display_list);
+            }
+
+            StackElement::Transform { transform, .. } => {
+                parent_display_list.push(Primitive::Transform {
+                    transform: *transform,
+                    primitives: display_list.display_list,
+                });
+            }
+
+            StackElement::ClipRect { rect } => {
+                parent_display_list.push(Primitive::ClipRect {
+                    rect: *rect,
+                    primitives: display_list.display_list,
+                });
+            }
+
+            StackElement::ClipPath { path, operation } => {
+                parent_display_list.push(Primitive::ClipPath {
+                    path: path.clone(),
+                    operation: *operation,
+                    primitives: display_list.display_list,
+                });
+            }
+
+            StackElement::Layer {
+                bounds,
+                paint,
+                filter,
+            } => {
+                let mut primitives = display_list.display_list;
+                if let Some(filter) = paint
+                    .as_ref()
+                    .and_then(|p| p.image_filter.as_ref())
+                    .and_then(DisplayListBuilder::convert_image_filter)
+                {
+                    primitives = vec![Primitive::Filter { filter, primitives }];
+                }
+                let mut primitives = vec![Primitive::Composite {
+                    bounds: *bounds,
+                    alpha: paint.as_ref().map(|p| p.color[3]).unwrap_or(1.0f32),
+                    backdrop_filter: filter
+                        .as_ref()
+                        .and_then(DisplayListBuilder::convert_image_filter),
+                    primitives,
+                }];
+                if let Some(paint) = paint {
+                    if let Some(filter) = &paint.color_filter {
+                        primitives = vec![Primitive::ColorFilter {
+                            filter: filter.clone(),
+                            primitives,
+                        }];
+                    }
+                    if paint.blend_mode != drawing_api::BlendMode::SourceOver {
+                        primitives = vec![Primitive::Blend {
+                            blend_mode: paint.blend_mode,
+                            primitives,
+                        }];
+                    }
+                }
+                parent_display_list.append(&mut primitives);
+            }
+        }
+
+        stack_element
+    }
+
+    fn push_clip_path(&mut self, path: Vec<PathElement>, operation: drawing_api::ClipOperation) {
+        self.display_list_stack.push((
+            StackElement::ClipPath { path, operation },
+            crate::display_list::DisplayList::new(),
+        ));
+    }
+
+    /// Appends the primitives drawn with the paint to the current display list
+    /// applying the paint's effects.
+    fn push_primitives(
+        &mut self,
+        mut primitives: Vec<Primitive<GlTexture, crate::Fonts<crate::GlContext>>>,
+        paint: &crate::Paint,
+    ) {
+        if let Some(drawing_api::MaskFilter::Blur { style, sigma }) = paint.mask_filter {
+            primitives = vec![Primitive::Filter {
+                filter: ImageFilter::MaskBlur { sigma, style },
+                primitives,
+            }];
+        }
+        self.push_primitives_without_mask_filter(primitives, paint);
+    }
+
+    fn push_primitives_without_mask_filter(
+        &mut self,
+        mut primitives: Vec<Primitive<GlTexture, crate::Fonts<crate::GlContext>>>,
+        paint: &crate::Paint,
+    ) {
+        if let Some(filter) = &paint.color_filter {
+            primitives = vec![Primitive::ColorFilter {
+                filter: filter.clone(),
+                primitives,
+            }];
+        }
+        if let Some(filter) = paint
+            .image_filter
+            .as_ref()
+            .and_then(DisplayListBuilder::convert_image_filter)
+        {
+            primitives = vec![Primitive::Filter { filter, primitives }];
+        }
+        if paint.blend_mode != drawing_api::BlendMode::SourceOver {
+            primitives = vec![Primitive::Blend {
+                blend_mode: paint.blend_mode,
+                primitives,
+            }];
+        }
+        self.display_list_stack
+            .last_mut()
+            .unwrap()
+            .1
+            .display_list
+            .append(&mut primitives);
+    }
+
+    /// Draws the rounded rectangle blurred with the normal style
+    /// using the shadow gradient, without an offscreen pass.
+    ///
+    /// Returns `false` when the paint is not suitable for it.
+    fn try_draw_blurred_rect(
+        &mut self,
+        rect: PixelRect,
+        radius: f32,
+        paint: &crate::Paint,
+    ) -> bool {
+        let sigma = match paint.mask_filter {
+            Some(drawing_api::MaskFilter::Blur {
+                style: drawing_api::BlurStyle::Normal,
+                sigma,
+            }) => sigma,
+            _ => return false,
+        };
+        if paint.color_source.is_some() || paint.draw_style != drawing_api::DrawStyle::Fill {
+            return false;
+        }
+
+        self.push_primitives_without_mask_filter(
+            vec![DisplayListBuilder::blurred_rect_primitive(
+                rect,
+                radius,
+                sigma,
+                paint.color,
+            )],
+            paint,
+        );
+        true
+    }
+
+    /// Approximates a gaussian blurred rounded rectangle with a shadow gradient fill.
+    fn blurred_rect_primitive(
+        rect: PixelRect,
+        radius: f32,
+        sigma: f32,
+        color: crate::generic::device::Color,
+    ) -> Primitive<GlTexture, crate::Fonts<crate::GlContext>> {
+        // the linear falloff has the same slope as the gaussian at the edge
+        // (at least one pixel wide for the antialiasing)
+        let feather = (sigma * (2.0f32 * std::f32::consts::PI).sqrt()).max(1.0f32);
+        let spread = 3.0f32 * sigma;
+        let mut outer_color = color;
+        outer_color[3] = 0.0f32;
+
+        Primitive::Fill {
+            path: rect_path(rect.inflate(spread, spread)),
+            brush: super::Brush::ShadowGradient {
+                rect,
+                radius,
+                feather,
+                inner_color: color,
+                outer_color,
+            },
+        }
+    }
+
+    /// Adds fill and / or stroke primitives of the path depending on the paint's draw style.
+    fn add_path_primitives(&mut self, path: Vec<PathElement>, paint: &crate::Paint) {
+        let primitives = match paint.draw_style {
+            drawing_api::DrawStyle::Fill => vec![Primitive::Fill {
+                path,
+                brush: DisplayListBuilder::paint_to_brush(paint),
+            }],
+            drawing_api::DrawStyle::Stroke => {
+                vec![DisplayListBuilder::stroke_primitive(path, paint)]
+            }
+            drawing_api::DrawStyle::StrokeAndFill => vec![
+                Primitive::Fill {
+                    path: path.clone(),
+                    brush: DisplayListBuilder::paint_to_brush(paint),
+                },
+                DisplayListBuilder::stroke_primitive(path, paint),
+            ],
+        };
+        self.push_primitives(primitives, paint);
+    }
+
+    /// Converts the image filter, fragment programs are not supported.
+    fn convert_image_filter(
+        filter: &drawing_api::ImageFilter<ImageFilterFragment>,
+    ) -> Option<ImageFilter> {
+        match filter {
+            drawing_api::ImageFilter::Blur {
+                x_sigma,
+                y_sigma,
+                tile_mode,
+            } => Some(ImageFilter::Blur {
+                x_sigma: *x_sigma,
+                y_sigma: *y_sigma,
+                tile_mode: *tile_mode,
+            }),
+
+            drawing_api::ImageFilter::Dilate { x_radius, y_radius } => Some(ImageFilter::Dilate {
+                x_radius: *x_radius,
+                y_radius: *y_radius,
+            }),
+
+            drawing_api::ImageFilter::Erode { x_radius, y_radius } => Some(ImageFilter::Erode {
+                x_radius: *x_radius,
+                y_radius: *y_radius,
+            }),
+
+            drawing_api::ImageFilter::Matrix { matrix, sampling } => Some(ImageFilter::Matrix {
+                transform: PixelTransform::from_untyped(&matrix.to_2d()),
+                filtering: *sampling == TextureSampling::Linear,
+            }),
+
+            drawing_api::ImageFilter::Fragment { .. } => None,
+
+            drawing_api::ImageFilter::Compose { outer, inner } => {
+                match (
+                    DisplayListBuilder::convert_image_filter(outer),
+                    DisplayListBuilder::convert_image_filter(inner),
+                ) {
+                    (Some(outer), Some(inner)) => Some(ImageFilter::Compose {
+                        outer: Box::new(outer),
+                        inner: Box::new(inner),
+                    }),
+                    (outer, inner) => outer.or(inner),
+                }
+            }
+        }
+    }
+
+    fn stroke_primitive(
+        path: Vec<PathElement>,
+        paint: &crate::Paint,
+    ) -> Primitive<GlTexture, crate::Fonts<crate::GlContext>> {
+        Primitive::StrokeStyled {
+            path,
+            thickness: paint.stroke_width.max(1.0f32),
+            brush: DisplayListBuilder::paint_to_brush(paint),
+            style: StrokeStyle {
+                line_cap: match paint.stroke_cap {
+                    drawing_api::StrokeCap::Butt => LineCap::Butt,
+                    drawing_api::StrokeCap::Round => LineCap::Round,
+                    drawing_api::StrokeCap::Square => LineCap::Square,
+                },
+                line_join: match paint.stroke_join {
+                    drawing_api::StrokeJoin::Miter => LineJoin::Miter,
+                    drawing_api::StrokeJoin::Round => LineJoin::Round,
+                    drawing_api::StrokeJoin::Bevel => LineJoin::Bevel,
+                },
+                miter_limit: paint.stroke_miter,
+            },
+        }
+    }
+
+    fn gradient_brush(
+        shape: GradientShape,
+        colors: &[drawing_api::Color],
+        stops: &[f32],
+        tile_mode: drawing_api::TileMode,
+        transformation: &Option<Matrix>,
+    ) -> super::Brush<GlTexture> {
+        super::Brush::Gradient {
+            shape,
+            colors: colors.iter().map(convert_color).collect(),
+            stops: stops.to_vec(),
+            tile_mode,
+            transform: transformation
+                .map(|t| PixelTransform::from_untyped(&t.to_2d()))
+                .unwrap_or_else(PixelTransform::identity),
+        }
+    }
+
     fn paint_to_brush(paint: &crate::Paint) -> super::Brush<GlTexture> {
         if let Some(color_source) = &paint.color_source {
             match color_source {
@@ -42,12 +382,76 @@ impl DisplayListBuilder {
                     stops,
                     tile_mode,
                     transformation,
-                } => super::Brush::LinearGradient {
-                    start_point: PixelPoint::new(start.x, start.y),
-                    end_point: PixelPoint::new(end.x, end.y),
-                    inner_color: convert_color(&colors[0]),
-                    outer_color: convert_color(&colors[1]),
-                },
+                } => DisplayListBuilder::gradient_brush(
+                    GradientShape::Linear {
+                        start_point: *start,
+                        end_point: *end,
+                    },
+                    colors,
+                    stops,
+                    *tile_mode,
+                    transformation,
+                ),
+
+                drawing_api::ColorSource::RadialGradient {
+                    center,
+                    radius,
+                    colors,
+                    stops,
+                    tile_mode,
+                    transformation,
+                } => DisplayListBuilder::gradient_brush(
+                    GradientShape::Radial {
+                        center_point: *center,
+                        radius: *radius,
+                    },
+                    colors,
+                    stops,
+                    *tile_mode,
+                    transformation,
+                ),
+
+                drawing_api::ColorSource::ConicalGradient {
+                    start_center,
+                    start_radius,
+                    end_center,
+                    end_radius,
+                    colors,
+                    stops,
+                    tile_mode,
+                    transformation,
+                } => DisplayListBuilder::gradient_brush(
+                    GradientShape::Conical {
+                        start_center: *start_center,
+                        start_radius: *start_radius,
+                        end_center: *end_center,
+                        end_radius: *end_radius,
+                    },
+                    colors,
+                    stops,
+                    *tile_mode,
+                    transformation,
+                ),
+
+                drawing_api::ColorSource::SweepGradient {
+                    center,
+                    start,
+                    end,
+                    colors,
+                    stops,
+                    tile_mode,
+                    transformation,
+                } => DisplayListBuilder::gradient_brush(
+                    GradientShape::Sweep {
+                        center_point: *center,
+                        start_angle: *start,
+                        end_angle: *end,
+                    },
+                    colors,
+                    stops,
+                    *tile_mode,
+                    transformation,
+                ),
 
                 drawing_api::ColorSource::Image {
                     image,
@@ -60,7 +464,10 @@ impl DisplayListBuilder {
                     transform: transformation
                         .map(|t| PixelTransform::from_untyped(&t.to_2d()))
                         .unwrap_or_else(|| PixelTransform::identity()),
-                    alpha: 1.0f32,
+                    alpha: paint.color[3],
+                    horizontal_tile_mode: *horizontal_tile_mode,
+                    vertical_tile_mode: *vertical_tile_mode,
+                    sampling: *sampling,
                 },
 
                 _ => super::Brush::Color { color: paint.color },
@@ -85,56 +492,86 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
                 StackElement::Start,
                 crate::display_list::DisplayList::new(),
             )],
+            transform: Matrix::identity(),
         }
     }
 
     fn scale(&mut self, x_scale: f32, y_scale: f32) {
-        self.display_list_stack.push((
-            StackElement::Transform {
-                transform: PixelTransform::identity().then_scale(x_scale, y_scale),
-            },
-            crate::display_list::DisplayList::new(),
-        ));
+        self.push_transform(&Matrix::scale(x_scale, y_scale, 1.0f32));
     }
 
     fn rotate(&mut self, angle_degrees: f32) {
-        self.display_list_stack.push((
-            StackElement::Transform {
-                transform: PixelTransform::identity().then_rotate(Angle::degrees(angle_degrees)),
-            },
-            crate::display_list::DisplayList::new(),
+        self.push_transform(&Matrix::rotation(
+            0.0f32,
+            0.0f32,
+            1.0f32,
+            Angle::degrees(angle_degrees),
         ));
     }
 
     fn translate(&mut self, x_translation: f32, y_translation: f32) {
-        self.display_list_stack.push((
-            StackElement::Transform {
-                transform: PixelTransform::identity().then_translate(
-                    Vector2D::<f32, PixelUnit>::new(x_translation, y_translation),
-                ),
-            },
-            crate::display_list::DisplayList::new(),
-        ));
+        self.push_transform(&Matrix::translation(x_translation, y_translation, 0.0f32));
     }
 
     fn transform(&mut self, transform: &drawing_api::Matrix) {
-        todo!()
+        self.push_transform(transform);
     }
 
     fn set_transform(&mut self, transform: &drawing_api::Matrix) {
-        todo!()
+        let transform = transform.to_2d().to_3d();
+
+        // The primitives are nested, so the new transformation is expressed
+        // relative to the current one. A singular transformation (e.g. zero
+        // scale) cannot be inverted, so the transformation elements are
+        // unwound down to an invertible one (the restore points on the way
+        // are put back, `restore()` re-applies their transformations).
+        // Below a clip or a layer pushed with a singular transformation
+        // nothing is visible anyway, there the new transformation is
+        // applied as relative one.
+        let mut restore_points = Vec::new();
+        while self.transform.inverse().is_none() {
+            match self.display_list_stack.last() {
+                Some((StackElement::Transform { .. }, _)) => {
+                    if let StackElement::Transform {
+                        parent_transform, ..
+                    } = self.pop_stack_element()
+                    {
+                        self.transform = parent_transform;
+                    }
+                }
+                Some((StackElement::RestorePoint { .. }, _)) => {
+                    restore_points.push(self.pop_stack_element());
+                }
+                _ => break,
+            }
+        }
+        let relative_transform = match self.transform.inverse() {
+            Some(inverse) => transform.then(&inverse),
+            None => transform,
+        };
+        for restore_point in restore_points.into_iter().rev() {
+            self.display_list_stack
+                .push((restore_point, crate::display_list::DisplayList::new()));
+        }
+        self.push_transform(&relative_transform);
+        self.transform = transform;
     }
 
     fn get_transform(&self) -> drawing_api::Matrix {
-        todo!()
+        self.transform
     }
 
     fn reset_transform(&mut self) {
-        todo!()
+        self.set_transform(&Matrix::identity());
     }
 
     fn clip_rect(&mut self, rect: impl Into<PixelRect>, operation: drawing_api::ClipOperation) {
         let rect = rect.into();
+        if operation == drawing_api::ClipOperation::Difference {
+            // a hole cannot be expressed with the scissor, use the stencil clip instead
+            self.push_clip_path(rect_path(rect), operation);
+            return;
+        }
         self.display_list_stack.push((
             StackElement::ClipRect {
                 rect: PixelRect::new(
@@ -151,7 +588,7 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         oval_bounds: impl Into<PixelRect>,
         operation: drawing_api::ClipOperation,
     ) {
-        //todo!()
+        self.push_clip_path(oval_path(oval_bounds), operation);
     }
 
     fn clip_rounded_rect<'a>(
@@ -160,7 +597,7 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         radii: impl Into<OptRef<'a, RoundingRadii>>,
         operation: drawing_api::ClipOperation,
     ) {
-        //todo!()
+        self.push_clip_path(rect_rounded_radii_path(rect, &radii.into()), operation);
     }
 
     fn clip_path(
@@ -168,17 +605,14 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         path: &<Self::PathBuilder as drawing_api::PathBuilder>::Path,
         operation: drawing_api::ClipOperation,
     ) {
-        self.display_list_stack.push((
-            StackElement::ClipPath {
-                path: path.path.to_vec(),
-            },
-            crate::display_list::DisplayList::new(),
-        ));
+        self.push_clip_path(path.path.to_vec(), operation);
     }
 
     fn save(&mut self) {
         self.display_list_stack.push((
-            StackElement::RestorePoint,
+            StackElement::RestorePoint {
+                transform: self.transform,
+            },
             crate::display_list::DisplayList::new(),
         ));
     }
@@ -190,7 +624,9 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         filter: Option<drawing_api::ImageFilter<ImageFilterFragment>>,
     ) {
         self.display_list_stack.push((
-            StackElement::RestorePoint,
+            StackElement::RestorePoint {
+                transform: self.transform,
+            },
             crate::display_list::DisplayList::new(),
         ));
         let paint = paint.into();
@@ -209,92 +645,28 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
     }
 
     fn restore(&mut self) {
-        loop {
-            if self.display_list_stack.len() <= 1 {
-                return;
-            }
-
-            let mut stack_el = self.display_list_stack.pop().unwrap();
-
-            match stack_el.0 {
-                StackElement::Start => unreachable!(),
-
-                StackElement::RestorePoint => {
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .append(&mut stack_el.1.display_list);
+        while self.display_list_stack.len() > 1 {
+            match self.pop_stack_element() {
+                StackElement::Transform {
+                    parent_transform, ..
+                } => self.transform = parent_transform,
+                StackElement::RestorePoint { transform } => {
+                    // the transformation elements may have been unwound by `set_transform()`
+                    if transform != self.transform {
+                        self.set_transform(&transform);
+                    }
                     return;
                 }
-
-                StackElement::Transform { transform } => {
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .push(Primitive::Transform {
-                            transform,
-                            primitives: stack_el.1.display_list,
-                        });
-                }
-
-                StackElement::ClipRect { rect } => {
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .push(Primitive::ClipRect {
-                            rect,
-                            primitives: stack_el.1.display_list,
-                        });
-                }
-
-                StackElement::ClipPath { path } => {
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .push(Primitive::ClipPath {
-                            path,
-                            primitives: stack_el.1.display_list,
-                        });
-                }
-
-                StackElement::Layer {
-                    bounds,
-                    paint,
-                    filter,
-                } => {
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .push(Primitive::Composite {
-                            color: paint
-                                .map(|p| p.color)
-                                .unwrap_or([0.0f32, 0.0f32, 0.0f32, 1.0f32]),
-                            primitives: stack_el.1.display_list,
-                        });
-                }
+                _ => (),
             }
         }
+        self.transform = Matrix::identity();
     }
 
     fn draw_paint<'a>(&mut self, paint: impl Into<OptRef<'a, Self::Paint>>) {
         let paint = paint.into();
         // TODO: handle other cases
-        self.display_list_stack
-            .last_mut()
-            .unwrap()
-            .1
-            .display_list
-            .push(Primitive::Clear { color: paint.color });
+        self.push_primitives(vec![Primitive::Clear { color: paint.color }], &paint);
     }
 
     fn draw_line<'a>(
@@ -306,18 +678,25 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         let from = from.into();
         let to = to.into();
         let paint = paint.into();
-        self.display_list_stack
-            .last_mut()
-            .unwrap()
-            .1
-            .display_list
-            .push(Primitive::Line {
+        if paint.blend_mode != drawing_api::BlendMode::SourceOver {
+            // blend modes are applied to paths
+            let path = vec![PathElement::MoveTo(from), PathElement::LineTo(to)];
+            self.push_primitives(
+                vec![DisplayListBuilder::stroke_primitive(path, &paint)],
+                &paint,
+            );
+            return;
+        }
+        self.push_primitives(
+            vec![Primitive::Line {
                 color: paint.color,
                 thickness: 1.0f32,
                 // TODO: convert
                 start_point: PixelPoint::new(from.x, from.y),
                 end_point: PixelPoint::new(to.x, to.y),
-            });
+            }],
+            &paint,
+        );
     }
 
     fn draw_dashed_line<'a>(
@@ -338,18 +717,27 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
     ) {
         let rect = rect.into();
         let paint = paint.into();
-        self.display_list_stack
-            .last_mut()
-            .unwrap()
-            .1
-            .display_list
-            .push(Primitive::Rectangle {
+        if self.try_draw_blurred_rect(rect, 0.0f32, &paint) {
+            return;
+        }
+        if paint.blend_mode != drawing_api::BlendMode::SourceOver
+            || paint.color_source.is_some()
+            || paint.draw_style != drawing_api::DrawStyle::Fill
+        {
+            // blend modes, color sources and strokes are applied to paths
+            self.add_path_primitives(rect_path(rect), &paint);
+            return;
+        }
+        self.push_primitives(
+            vec![Primitive::Rectangle {
                 color: paint.color,
                 rect: PixelRect::new(
                     PixelPoint::new(rect.origin.x, rect.origin.y),
                     PixelSize::new(rect.size.width, rect.size.height),
                 ),
-            });
+            }],
+            &paint,
+        );
     }
 
     fn draw_rounded_rect<'a>(
@@ -358,7 +746,26 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         radii: impl Into<OptRef<'a, RoundingRadii>>,
         paint: impl Into<OptRef<'a, Self::Paint>>,
     ) {
-        todo!()
+        let rect = rect.into();
+        let radii = radii.into();
+        let paint = paint.into();
+
+        // fast path for blurred rounded rectangles with equal circular corners
+        let radius = radii.top_left.width;
+        let is_uniform = [
+            radii.top_left,
+            radii.top_right,
+            radii.bottom_left,
+            radii.bottom_right,
+        ]
+        .iter()
+        .all(|r| r.width == radius && r.height == radius);
+        if is_uniform && self.try_draw_blurred_rect(rect, radius, &paint) {
+            return;
+        }
+
+        let path = rect_rounded_radii_path(rect, &radii);
+        self.add_path_primitives(path, &paint);
     }
 
     fn draw_rounded_rect_difference<'a>(
@@ -369,7 +776,14 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         inner_radii: impl Into<OptRef<'a, RoundingRadii>>,
         paint: impl Into<OptRef<'a, Self::Paint>>,
     ) {
-        todo!()
+        // the inner path has the opposite direction, so it becomes a hole
+        // when filled with the non-zero winding rule
+        let mut path = rect_rounded_radii_path(outer_rect, &outer_radii.into());
+        path.append(&mut reverse_path(&rect_rounded_radii_path(
+            inner_rect,
+            &inner_radii.into(),
+        )));
+        self.add_path_primitives(path, &paint.into());
     }
 
     fn draw_oval<'a>(
@@ -377,7 +791,7 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         oval_bounds: impl Into<PixelRect>,
         paint: impl Into<OptRef<'a, Self::Paint>>,
     ) {
-        todo!()
+        self.add_path_primitives(oval_path(oval_bounds), &paint.into());
     }
 
     fn draw_path<'a>(
@@ -385,51 +799,7 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         path: &<Self::PathBuilder as drawing_api::PathBuilder>::Path,
         paint: impl Into<OptRef<'a, Self::Paint>>,
     ) {
-        let paint = paint.into();
-        match paint.draw_style {
-            drawing_api::DrawStyle::Fill => self
-                .display_list_stack
-                .last_mut()
-                .unwrap()
-                .1
-                .display_list
-                .push(Primitive::Fill {
-                    path: path.path.to_vec(),
-                    brush: DisplayListBuilder::paint_to_brush(&paint),
-                }),
-            drawing_api::DrawStyle::Stroke => self
-                .display_list_stack
-                .last_mut()
-                .unwrap()
-                .1
-                .display_list
-                .push(Primitive::Stroke {
-                    path: path.path.to_vec(),
-                    thickness: paint.stroke_width.max(1.0f32),
-                    brush: DisplayListBuilder::paint_to_brush(&paint),
-                }),
-            drawing_api::DrawStyle::StrokeAndFill => {
-                self.display_list_stack
-                    .last_mut()
-                    .unwrap()
-                    .1
-                    .display_list
-                    .push(Primitive::Fill {
-                        path: path.path.to_vec(),
-                        brush: DisplayListBuilder::paint_to_brush(&paint),
-                    });
-                self.display_list_stack
-                    .last_mut()
-                    .unwrap()
-                    .1
-                    .display_list
-                    .push(Primitive::Stroke {
-                        path: path.path.to_vec(),
-                        thickness: paint.stroke_width.max(1.0f32),
-                        brush: DisplayListBuilder::paint_to_brush(&paint),
-                    })
-            }
-        }
+        self.add_path_primitives(path.path.to_vec(), &paint.into());
     }
 
     fn draw_shadow(
@@ -437,10 +807,99 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         path: &<Self::PathBuilder as drawing_api::PathBuilder>::Path,
         color: impl Into<drawing_api::Color>,
         elevation: f32,
-        occluder_is_transparent: bool,
+        _occluder_is_transparent: bool,
         device_pixel_ratio: f32,
     ) {
-        todo!()
+        let ShadowParameters {
+            ambient_color,
+            ambient_sigma,
+            ambient_stroke_width,
+            spot_color,
+            spot_sigma,
+            spot_scale,
+            spot_translation,
+        } = ShadowParameters::new(
+            drawing_api::Path::get_bounds(path),
+            color,
+            elevation,
+            device_pixel_ratio,
+        );
+
+        let mut primitives = if let Some((rect, radius)) = path.rounded_rect {
+            let ambient_outset = ambient_stroke_width.max(0.0f32) * 0.5f32;
+            let spot_rect = PixelRect::new(
+                PixelPoint::new(
+                    rect.origin.x * spot_scale + spot_translation.0,
+                    rect.origin.y * spot_scale + spot_translation.1,
+                ),
+                rect.size * spot_scale,
+            );
+            vec![
+                DisplayListBuilder::blurred_rect_primitive(
+                    rect.inflate(ambient_outset, ambient_outset),
+                    radius + ambient_outset,
+                    ambient_sigma,
+                    ambient_color,
+                ),
+                DisplayListBuilder::blurred_rect_primitive(
+                    spot_rect,
+                    radius * spot_scale,
+                    spot_sigma,
+                    spot_color,
+                ),
+            ]
+        } else {
+            let mut ambient = vec![Primitive::Fill {
+                path: path.path.clone(),
+                brush: super::Brush::Color {
+                    color: ambient_color,
+                },
+            }];
+            if ambient_stroke_width > 0.0f32 {
+                ambient.push(Primitive::StrokeStyled {
+                    path: path.path.clone(),
+                    thickness: ambient_stroke_width,
+                    brush: super::Brush::Color {
+                        color: ambient_color,
+                    },
+                    style: StrokeStyle {
+                        line_cap: LineCap::Butt,
+                        line_join: LineJoin::Round,
+                        miter_limit: 4.0f32,
+                    },
+                });
+            }
+            vec![
+                Primitive::Filter {
+                    filter: ImageFilter::MaskBlur {
+                        sigma: ambient_sigma,
+                        style: drawing_api::BlurStyle::Normal,
+                    },
+                    primitives: ambient,
+                },
+                Primitive::Filter {
+                    filter: ImageFilter::MaskBlur {
+                        sigma: spot_sigma,
+                        style: drawing_api::BlurStyle::Normal,
+                    },
+                    primitives: vec![Primitive::Transform {
+                        transform: PixelTransform::scale(spot_scale, spot_scale)
+                            .then_translate(spot_translation.into()),
+                        primitives: vec![Primitive::Fill {
+                            path: path.path.clone(),
+                            brush: super::Brush::Color { color: spot_color },
+                        }],
+                    }],
+                },
+            ]
+        };
+
+        self.display_list_stack
+            .last_mut()
+            .unwrap()
+            .1
+            .display_list
+            .append(&mut primitives);
     }
 
     fn draw_texture<'a>(
@@ -463,16 +922,42 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
     ) {
         let src_rect = src_rect.into();
         let dst_rect = dst_rect.into();
-        self.display_list_stack
-            .last_mut()
-            .unwrap()
-            .1
-            .display_list
-            .push(Primitive::Image {
-                texture: texture.clone(),
-                rect: dst_rect,
-                src: src_rect,
-            });
+        let primitive = Primitive::Image {
+            texture: texture.clone(),
+            rect: dst_rect,
+            src: src_rect,
+        };
+        match paint.into() {
+            Some(paint) if paint.blend_mode != drawing_api::BlendMode::SourceOver => {
+                // blend modes are applied to paths, fill the rectangle with the image
+                let transform = PixelTransform::translation(-src_rect.origin.x, -src_rect.origin.y)
+                    .then_scale(
+                        dst_rect.size.width / src_rect.size.width,
+                        dst_rect.size.height / src_rect.size.height,
+                    )
+                    .then_translate(dst_rect.origin.to_vector());
+                let primitive = Primitive::Fill {
+                    path: rect_path(dst_rect),
+                    brush: super::Brush::ImagePattern {
+                        texture: texture.clone(),
+                        transform,
+                        alpha: paint.color[3],
+                        horizontal_tile_mode: drawing_api::TileMode::Decal,
+                        vertical_tile_mode: drawing_api::TileMode::Decal,
+                        sampling,
+                    },
+                };
+                self.push_primitives(vec![primitive], &paint)
+            }
+            Some(paint) => self.push_primitives(vec![primitive], &paint),
+            None => self
+                .display_list_stack
+                .last_mut()
+                .unwrap()
+                .1
+                .display_list
+                .push(primitive),
+        }
     }
 
     fn draw_paragraph(
@@ -481,38 +966,32 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
         paragraph: &<Self::ParagraphBuilder as drawing_api::ParagraphBuilder>::Paragraph,
     ) {
         let location = location.into();
-        let location = PixelPoint::new(location.x, location.y);
-        for el in &paragraph.primitives {
-            match el {
-                Primitive::Text {
-                    fonts,
-                    family_name,
-                    size,
-                    color,
-                    position,
-                    clipping_rect,
-                    text,
-                } => {
-                    let position =
-                        PixelPoint::new(location.x + position.x, location.y + position.y);
-                    self.display_list_stack
-                        .last_mut()
-                        .unwrap()
-                        .1
-                        .display_list
-                        .push(Primitive::Text {
-                            fonts: fonts.clone(),
-                            family_name: family_name.clone(),
-                            size: size.clone(),
-                            color: color.clone(),
-                            position,
-                            clipping_rect: clipping_rect.clone(),
-                            text: text.clone(),
-                        });
-                }
-
-                _ => (),
+        for run in &paragraph.layout.runs {
+            let glyphs = run
+                .glyphs
+                .iter()
+                .map(|glyph| Glyph {
+                    glyph_id: glyph.glyph_id,
+                    position: glyph.position,
+                })
+                .collect::<Vec<_>>();
+            if glyphs.is_empty() {
+                continue;
             }
+            self.display_list_stack
+                .last_mut()
+                .unwrap()
+                .1
+                .display_list
+                .push(Primitive::Glyphs {
+                    fonts: paragraph.fonts.clone(),
+                    family_name: run.family.clone(),
+                    size: run.size,
+                    color: run.color,
+                    position: location,
+                    clipping_rect: None,
+                    glyphs,
+                });
         }
     }
 
@@ -521,6 +1000,90 @@ impl drawing_api::DisplayListBuilder for DisplayListBuilder {
     }
 
     fn build(mut self) -> Result<Self::DisplayList, &'static str> {
+        while self.display_list_stack.len() > 1 {
+            self.pop_stack_element();
+        }
         Ok(self.display_list_stack.pop().unwrap().1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use drawing_api::{DisplayListBuilder as _, Matrix, PixelRect};
+
+    use super::{DisplayListBuilder, PixelTransform, Primitive};
+
+    /// Returns the full transformations of the rectangles in the display list.
+    fn rectangle_transforms(builder: DisplayListBuilder) -> Vec<PixelTransform> {
+        fn collect(
+            primitives: &[Primitive<crate::GlTexture, crate::Fonts<crate::GlContext>>],
+            transform: PixelTransform,
+            res: &mut Vec<PixelTransform>,
+        ) {
+            for primitive in primitives {
+                match primitive {
+                    Primitive::Rectangle { .. } => res.push(transform),
+                    Primitive::Transform {
+                        transform: relative_transform,
+                        primitives,
+                    } => collect(primitives, relative_transform.then(&transform), res),
+                    _ => (),
+                }
+            }
+        }
+
+        let mut res = Vec::new();
+        let display_list = builder.build().unwrap();
+        collect(
+            &display_list.display_list,
+            PixelTransform::identity(),
+            &mut res,
+        );
+        res
+    }
+
+    #[test]
+    fn set_transform_after_zero_scale() {
+        let mut builder = DisplayListBuilder::new(None);
+        let rect = PixelRect::from_size((10.0f32, 10.0f32).into());
+        builder.scale(0.0f32, 0.0f32);
+        builder.save();
+        builder.set_transform(&Matrix::translation(5.0f32, 0.0f32, 0.0f32));
+        builder.draw_rect(rect, crate::Paint::default());
+        builder.restore();
+        builder.draw_rect(rect, crate::Paint::default());
+        assert_eq!(
+            builder.get_transform(),
+            Matrix::scale(0.0f32, 0.0f32, 1.0f32)
+        );
+        builder.reset_transform();
+        builder.draw_rect(rect, crate::Paint::default());
+
+        assert_eq!(
+            rectangle_transforms(builder),
+            vec![
+                PixelTransform::translation(5.0f32, 0.0f32),
+                PixelTransform::scale(0.0f32, 0.0f32),
+                PixelTransform::identity(),
+            ]
+        );
+    }
+
+    #[test]
+    fn perspective_is_dropped() {
+        let mut builder = DisplayListBuilder::new(None);
+        let mut transform = Matrix::translation(5.0f32, 0.0f32, 3.0f32);
+        transform.m34 = 0.5f32;
+        builder.transform(&transform);
+        assert!(builder.get_transform().is_2d());
+        builder.draw_rect(
+            PixelRect::from_size((10.0f32, 10.0f32).into()),
+            crate::Paint::default(),
+        );
+
+        assert_eq!(
+            rectangle_transforms(builder),
+            vec![PixelTransform::translation(5.0f32, 0.0f32)]
+        );
+    }
+}
@@ -1,23 +1,31 @@
-pub struct GlyphInfo {}
+use drawing_api::{PixelRect, TextDirection};
+
+pub struct GlyphInfo {
+    pub(crate) begin_utf16: usize,
+    pub(crate) end_utf16: usize,
+    pub(crate) bounds: PixelRect,
+    pub(crate) is_ellipsis: bool,
+    pub(crate) direction: TextDirection,
+}
 
 impl drawing_api::GlyphInfo for GlyphInfo {
     fn get_grapheme_cluster_code_unit_range_begin_utf16(&self) -> usize {
-        todo!()
+        self.begin_utf16
     }
 
     fn get_grapheme_cluster_code_unit_range_end_utf16(&self) -> usize {
-        todo!()
+        self.end_utf16
     }
 
-    fn get_grapheme_cluster_bounds(&self) -> drawing_api::PixelRect {
-        todo!()
+    fn get_grapheme_cluster_bounds(&self) -> PixelRect {
+        self.bounds
     }
 
     fn is_ellipsis(&self) -> bool {
-        todo!()
+        self.is_ellipsis
     }
 
-    fn get_text_direction(&self) -> drawing_api::TextDirection {
-        todo!()
+    fn get_text_direction(&self) -> TextDirection {
+        self.direction
     }
 }
@@ -1,51 +1,70 @@
-pub struct LineMetrics {}
+/// Metrics of a single line of a paragraph.
+#[derive(Clone, Debug)]
+pub(crate) struct LineInfo {
+    pub unscaled_ascent: f64,
+    pub ascent: f64,
+    pub descent: f64,
+    pub baseline: f64,
+    pub hardbreak: bool,
+    pub width: f64,
+    pub height: f64,
+    pub left: f64,
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+    pub end_excluding_whitespace_utf16: usize,
+    pub end_including_newline_utf16: usize,
+}
+
+pub struct LineMetrics {
+    pub(crate) lines: Vec<LineInfo>,
+}
 
 impl drawing_api::LineMetrics for LineMetrics {
     fn get_unscaled_ascent(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].unscaled_ascent
     }
 
     fn get_ascent(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].ascent
     }
 
     fn get_descent(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].descent
     }
 
     fn get_baseline(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].baseline
     }
 
     fn is_hardbreak(&self, line: usize) -> bool {
-        todo!()
+        self.lines[line].hardbreak
     }
 
     fn get_width(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].width
     }
 
     fn get_height(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].height
     }
 
     fn get_left(&self, line: usize) -> f64 {
-        todo!()
+        self.lines[line].left
     }
 
     fn get_code_unit_start_index_utf16(&self, line: usize) -> usize {
-        todo!()
+        self.lines[line].start_utf16
     }
 
     fn get_code_unit_end_index_utf16(&self, line: usize) -> usize {
-        todo!()
+        self.lines[line].end_utf16
     }
 
     fn get_code_unit_end_index_excluding_whitespace_utf16(&self, line: usize) -> usize {
-        todo!()
+        self.lines[line].end_excluding_whitespace_utf16
     }
 
     fn get_code_unit_end_index_including_newline_utf16(&self, line: usize) -> usize {
-        todo!()
+        self.lines[line].end_including_newline_utf16
     }
 }
@@ -28,6 +28,9 @@ pub use paragraph::*;
 mod paragraph_builder;
 pub use paragraph_builder::*;
 
+mod paragraph_layout;
+pub(crate) use paragraph_layout::*;
+
 mod path;
 pub use path::*;
 
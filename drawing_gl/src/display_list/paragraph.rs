@@ -1,10 +1,39 @@
-use crate::{GlContext, GlTexture};
+use std::sync::Arc;
 
-use super::Primitive;
+use drawing_api::{PixelPoint, PixelRect, PixelSize, Range};
+use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(Default)]
+use crate::GlContext;
+
+use super::{GlyphCluster, GlyphInfo, LineMetrics, ParagraphContent, ParagraphLayout};
+
+#[derive(Clone)]
 pub struct Paragraph {
-    pub(crate) primitives: Vec<Primitive<GlTexture, crate::Fonts<GlContext>>>,
+    pub(crate) fonts: crate::Fonts<GlContext>,
+    pub(crate) content: Arc<ParagraphContent>,
+    pub(crate) layout: Arc<ParagraphLayout>,
+}
+
+impl Paragraph {
+    /// Lays out the paragraph again, wrapping the lines longer than `max_width`.
+    pub fn layout(&mut self, max_width: f32) -> Result<(), &'static str> {
+        self.layout = Arc::new(ParagraphLayout::new(&self.fonts, &self.content, max_width)?);
+        Ok(())
+    }
+
+    fn create_glyph_info(&self, cluster: &GlyphCluster) -> GlyphInfo {
+        let line = &self.layout.lines[cluster.line];
+        GlyphInfo {
+            begin_utf16: cluster.begin_utf16,
+            end_utf16: cluster.end_utf16,
+            bounds: PixelRect::new(
+                PixelPoint::new(cluster.left, (line.baseline - line.ascent) as f32),
+                PixelSize::new(cluster.right - cluster.left, line.height as f32),
+            ),
+            is_ellipsis: cluster.is_ellipsis,
+            direction: cluster.direction,
+        }
+    }
 }
 
 impl drawing_api::Paragraph for Paragraph {
@@ -13,50 +42,76 @@ impl drawing_api::Paragraph for Paragraph {
     type LineMetrics = crate::display_list::LineMetrics;
 
     fn get_max_width(&self) -> f32 {
-        todo!()
+        self.layout.max_width
     }
 
     fn get_height(&self) -> f32 {
-        todo!()
+        self.layout
+            .lines
+            .last()
+            .map_or(0.0f32, |line| (line.baseline + line.descent) as f32)
     }
 
     fn get_longest_line_width(&self) -> f32 {
-        todo!()
+        self.layout
+            .lines
+            .iter()
+            .map(|line| line.width as f32)
+            .fold(0.0f32, f32::max)
     }
 
     fn get_min_intrinsic_width(&self) -> f32 {
-        todo!()
+        self.layout.min_intrinsic_width
     }
 
     fn get_max_intrinsic_width(&self) -> f32 {
-        todo!()
+        self.layout.max_intrinsic_width
     }
 
     fn get_ideographic_baseline(&self) -> f32 {
-        todo!()
+        self.layout
+            .lines
+            .first()
+            .map_or(0.0f32, |line| (line.baseline + line.descent) as f32)
     }
 
     fn get_alphabetic_baseline(&self) -> f32 {
-        todo!()
+        self.layout
+            .lines
+            .first()
+            .map_or(0.0f32, |line| line.baseline as f32)
     }
 
     fn get_line_count(&self) -> u32 {
-        todo!()
+        self.layout.lines.len() as u32
     }
 
     fn get_line_metrics(&self) -> Option<Self::LineMetrics> {
-        todo!()
+        Some(LineMetrics {
+            lines: self.layout.lines.clone(),
+        })
     }
 
-    fn get_word_boundary_utf16(&self, code_unit_index: usize) -> drawing_api::Range {
-        todo!()
+    fn get_word_boundary_utf16(&self, code_unit_index: usize) -> Range {
+        // word boundaries (UAX #29)
+        let mut start = 0usize;
+        for word in self.layout.text.split_word_bounds() {
+            let end = start + word.encode_utf16().count();
+            if code_unit_index < end {
+                return Range { start, end };
+            }
+            start = end;
+        }
+        Range { start, end: start }
     }
 
     fn create_glyph_info_at_code_unit_index_utf16(
         &self,
         code_unit_index: usize,
     ) -> Option<Self::GlyphInfo> {
-        todo!()
+        self.layout
+            .get_cluster_at_code_unit_index_utf16(code_unit_index)
+            .map(|cluster| self.create_glyph_info(cluster))
     }
 
     fn create_glyph_info_at_paragraph_coordinates(
@@ -64,6 +119,8 @@ impl drawing_api::Paragraph for Paragraph {
         x: f64,
         y: f64,
     ) -> Option<Self::GlyphInfo> {
-        todo!()
+        self.layout
+            .get_cluster_at_coordinates(x, y)
+            .map(|cluster| self.create_glyph_info(cluster))
     }
 }
@@ -1,13 +1,16 @@
-use drawing_api::{smart_pointers::OptRef, ParagraphStyle, PixelPoint};
+use std::sync::Arc;
+
+use drawing_api::{smart_pointers::OptRef, ParagraphStyle};
 
 use crate::{GlContext, GlTexture};
 
-use super::Primitive;
+use super::{ParagraphContent, ParagraphLayout, TextChunk};
 
 pub struct ParagraphBuilder {
     fonts: crate::Fonts<GlContext>,
-    paragraph: crate::display_list::Paragraph,
+    paragraph_style: Option<ParagraphStyle<crate::display_list::Paint>>,
     styles: Vec<ParagraphStyle<crate::display_list::Paint>>,
+    chunks: Vec<TextChunk>,
 }
 
 impl drawing_api::ParagraphBuilder for ParagraphBuilder {
@@ -19,8 +22,9 @@ impl drawing_api::ParagraphBuilder for ParagraphBuilder {
     fn new(fonts: &crate::Fonts<GlContext>) -> Result<Self, &'static str> {
         Ok(ParagraphBuilder {
             fonts: fonts.clone(),
-            paragraph: crate::display_list::Paragraph::default(),
+            paragraph_style: None,
             styles: Vec::new(),
+            chunks: Vec::new(),
         })
     }
 
@@ -28,8 +32,11 @@ impl drawing_api::ParagraphBuilder for ParagraphBuilder {
         &mut self,
         style: impl Into<OptRef<'a, drawing_api::ParagraphStyle<crate::display_list::Paint>>>,
     ) {
-        let style = style.into();
-        self.styles.push(style.to_owned());
+        let style = style.into().to_owned();
+        if self.paragraph_style.is_none() {
+            self.paragraph_style = Some(style.clone());
+        }
+        self.styles.push(style);
     }
 
     fn pop_style(&mut self) {
@@ -37,24 +44,22 @@ impl drawing_api::ParagraphBuilder for ParagraphBuilder {
     }
 
     fn add_text(&mut self, text: &str) {
-        let style = &self.styles.last();
-        self.paragraph.primitives.push(Primitive::Text {
-            fonts: self.fonts.clone(),
-            family_name: style
-                .map(|s| s.family.clone())
-                .unwrap_or("default".to_string()),
-            size: style.map(|s| s.size).unwrap_or(24.0f32),
-            color: style
-                .map(|s| s.foreground.clone().map(|p| p.color))
-                .flatten()
-                .unwrap_or([0.0f32, 0.0f32, 0.0f32, 1.0f32]),
-            position: PixelPoint::new(0.0f32, 0.0f32),
-            clipping_rect: None,
+        self.chunks.push(TextChunk {
+            style: self.styles.last().cloned().unwrap_or_default(),
             text: text.to_owned(),
         });
     }
 
-    fn build(self) -> Result<Self::Paragraph, &'static str> {
-        Ok(self.paragraph)
+    fn build(self, width: f32) -> Result<Self::Paragraph, &'static str> {
+        let content = ParagraphContent {
+            style: self.paragraph_style.unwrap_or_default(),
+            chunks: self.chunks,
+        };
+        let layout = ParagraphLayout::new(&self.fonts, &content, width)?;
+        Ok(crate::display_list::Paragraph {
+            fonts: self.fonts,
+            content: Arc::new(content),
+            layout: Arc::new(layout),
+        })
     }
 }
@@ -0,0 +1,1167 @@
+use drawing_api::{ParagraphStyle, PixelPoint, TextAlignment, TextDirection};
+use unicode_bidi::{BidiClass, BidiInfo, Level};
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+use unicode_segmentation::UnicodeSegmentation;
+
+use std::ops::Range;
+
+use crate::generic::{
+    device::{Color, Device},
+    texture_font::{Font, FontMetrics, FontParams, ShapedGlyph},
+};
+
+use super::{Fonts, LineInfo, Paint};
+
+/// Text added to the paragraph with the same style.
+#[derive(Clone)]
+pub(crate) struct TextChunk {
+    pub style: ParagraphStyle<Paint>,
+    pub text: String,
+}
+
+/// Content of the paragraph to lay out.
+pub(crate) struct ParagraphContent {
+    /// The first pushed style, it decides about the alignment, the line limit and the ellipsis.
+    pub style: ParagraphStyle<Paint>,
+
+    pub chunks: Vec<TextChunk>,
+}
+
+/// Glyph positioned by the paragraph layout.
+#[derive(Clone, Debug)]
+pub(crate) struct PositionedGlyph {
+    pub glyph_id: u32,
+
+    /// Position of the glyph origin (including the offset from the shaper).
+    pub position: PixelPoint,
+
+    /// Position of the pen on the baseline before the glyph is drawn.
+    pub pen_x: f32,
+
+    pub advance: f32,
+    pub line: usize,
+
+    /// The text range of the cluster the glyph belongs to.
+    pub begin_utf16: usize,
+    pub end_utf16: usize,
+
+    pub direction: TextDirection,
+}
+
+impl PositionedGlyph {
+    fn new(
+        glyph: &ShapedGlyph,
+        pen_x: f32,
+        baseline: f32,
+        line: usize,
+        range: Range<usize>,
+        direction: TextDirection,
+    ) -> Self {
+        PositionedGlyph {
+            glyph_id: glyph.glyph_id,
+            position: PixelPoint::new(pen_x + glyph.x_offset, baseline - glyph.y_offset),
+            pen_x,
+            advance: glyph.x_advance,
+            line,
+            begin_utf16: range.start,
+            end_utf16: range.end,
+            direction,
+        }
+    }
+}
+
+/// Part of the paragraph text drawn with the same style.
+#[derive(Clone)]
+pub(crate) struct TextRun {
+    pub family: String,
+    pub size: f32,
+    pub color: Color,
+    pub is_ellipsis: bool,
+    pub glyphs: Vec<PositionedGlyph>,
+}
+
+/// Grapheme cluster placed by the layout.
+#[derive(Clone, Debug)]
+pub(crate) struct GlyphCluster {
+    pub begin_utf16: usize,
+    pub end_utf16: usize,
+    pub line: usize,
+    pub left: f32,
+    pub right: f32,
+    pub is_ellipsis: bool,
+    pub direction: TextDirection,
+}
+
+/// Paragraph text broken into lines with the positioned glyphs.
+pub(crate) struct ParagraphLayout {
+    pub max_width: f32,
+    pub text: String,
+    pub runs: Vec<TextRun>,
+    pub lines: Vec<LineInfo>,
+
+    /// The grapheme clusters in the logical order (with their visual positions).
+    pub clusters: Vec<GlyphCluster>,
+
+    pub min_intrinsic_width: f32,
+    pub max_intrinsic_width: f32,
+}
+
+/// Vertical metrics of the text.
+#[derive(Copy, Clone, Default)]
+struct VerticalMetrics {
+    unscaled_ascent: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+impl VerticalMetrics {
+    fn max(self, other: VerticalMetrics) -> VerticalMetrics {
+        VerticalMetrics {
+            unscaled_ascent: self.unscaled_ascent.max(other.unscaled_ascent),
+            ascent: self.ascent.max(other.ascent),
+            descent: self.descent.max(other.descent),
+        }
+    }
+}
+
+/// Measured character of the paragraph text.
+///
+/// The glyphs of the cluster (and its advance) belong to the first character of the cluster.
+struct CharInfo {
+    ch: char,
+    chunk: usize,
+
+    /// The byte offset in the paragraph text.
+    byte: usize,
+
+    /// The direction the character was shaped with.
+    direction: TextDirection,
+
+    advance: f32,
+    glyphs: Range<usize>,
+    begin_utf16: usize,
+
+    /// The end of the cluster the character belongs to.
+    end_utf16: usize,
+}
+
+/// Characters placed on a line.
+struct LineRange {
+    start: usize,
+    end: usize,
+    hardbreak: bool,
+}
+
+impl ParagraphLayout {
+    pub fn new(
+        fonts: &impl LayoutFonts,
+        content: &ParagraphContent,
+        max_width: f32,
+    ) -> Result<Self, &'static str> {
+        let paragraph_style = &content.style;
+        let max_width = max_width.max(0.0f32);
+
+        // the embedding levels of the text (UAX #9)
+        let text = content
+            .chunks
+            .iter()
+            .map(|chunk| chunk.text.as_str())
+            .collect::<String>();
+        let base_direction = paragraph_style.text_direction;
+        let bidi = BidiInfo::new(
+            &text,
+            Some(match base_direction {
+                TextDirection::LTR => Level::ltr(),
+                TextDirection::RTL => Level::rtl(),
+            }),
+        );
+        let direction_at = |byte: usize| {
+            if bidi.levels[byte].is_rtl() {
+                TextDirection::RTL
+            } else {
+                TextDirection::LTR
+            }
+        };
+
+        // shape the text and measure the characters
+        let mut chars: Vec<CharInfo> = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut chunk_metrics = Vec::with_capacity(content.chunks.len());
+        let mut position = 0usize;
+        let mut chunk_byte = 0usize;
+        for (index, chunk) in content.chunks.iter().enumerate() {
+            let metrics = measure(fonts, &chunk.style)?;
+
+            // the text of the same direction is shaped separately
+            let mut shaped = Vec::new();
+            let mut run_start = 0usize;
+            while run_start < chunk.text.len() {
+                let direction = direction_at(chunk_byte + run_start);
+                let run_end = chunk.text[run_start..]
+                    .char_indices()
+                    .map(|(byte, _)| run_start + byte)
+                    .find(|byte| direction_at(chunk_byte + byte) != direction)
+                    .unwrap_or(chunk.text.len());
+                let run_text = &chunk.text[run_start..run_end];
+                shaped.extend(
+                    fonts
+                        .shape(&chunk.style, run_text, direction)?
+                        .into_iter()
+                        .map(|glyph| ShapedGlyph {
+                            cluster: run_start + glyph.cluster,
+                            ..glyph
+                        }),
+                );
+                run_start = run_end;
+            }
+
+            let first_char = chars.len();
+            let mut char_bytes = Vec::new();
+            for (byte, ch) in chunk.text.char_indices() {
+                chars.push(CharInfo {
+                    ch,
+                    chunk: index,
+                    byte: chunk_byte + byte,
+                    direction: direction_at(chunk_byte + byte),
+                    advance: 0.0f32,
+                    glyphs: 0..0,
+                    begin_utf16: position,
+                    end_utf16: position + ch.len_utf16(),
+                });
+                char_bytes.push(byte);
+                position += ch.len_utf16();
+            }
+            let char_at = |byte: usize| first_char + char_bytes.partition_point(|b| *b < byte);
+
+            let mut cluster_start = 0usize;
+            while cluster_start < shaped.len() {
+                let cluster = shaped[cluster_start].cluster;
+                let cluster_end = cluster_start
+                    + shaped[cluster_start..]
+                        .iter()
+                        .take_while(|glyph| glyph.cluster == cluster)
+                        .count();
+                let next_cluster = shaped
+                    .get(cluster_end)
+                    .map_or(chunk.text.len(), |glyph| glyph.cluster);
+                let end_utf16 = chars
+                    .get(char_at(next_cluster))
+                    .map_or(position, |ch| ch.begin_utf16);
+
+                let ch = &mut chars[char_at(cluster)];
+                ch.end_utf16 = end_utf16;
+                if !is_line_break(ch.ch) {
+                    let glyphs_start = glyphs.len();
+                    glyphs.extend_from_slice(&shaped[cluster_start..cluster_end]);
+                    ch.glyphs = glyphs_start..glyphs.len();
+                    ch.advance = shaped[cluster_start..cluster_end]
+                        .iter()
+                        .map(|glyph| glyph.x_advance)
+                        .sum();
+                }
+                cluster_start = cluster_end;
+            }
+
+            chunk_metrics.push(metrics);
+            chunk_byte += chunk.text.len();
+        }
+        let text_len_utf16 = position;
+        let default_metrics = measure(fonts, paragraph_style)?;
+
+        // x positions of the characters when placed on a single line
+        let mut offsets = Vec::with_capacity(chars.len() + 1);
+        offsets.push(0.0f32);
+        for ch in &chars {
+            offsets.push(offsets.last().unwrap() + ch.advance);
+        }
+        let width_of = |start: usize, end: usize| offsets[end] - offsets[start];
+        let visible_end = |start: usize, mut end: usize| {
+            while end > start && chars[end - 1].ch.is_whitespace() {
+                end -= 1;
+            }
+            end
+        };
+
+        // the break opportunities (UAX #14) as the character indices
+        let mut segments = Vec::new();
+        let mut char_index = 0usize;
+        let mut byte_index = 0usize;
+        for (byte, opportunity) in linebreaks(&text) {
+            while byte_index < byte {
+                byte_index += chars[char_index].ch.len_utf8();
+                char_index += 1;
+            }
+            segments.push((char_index, opportunity == BreakOpportunity::Mandatory));
+        }
+
+        // break the lines
+        let mut lines = Vec::new();
+        let mut line_start = 0usize;
+        let mut segment_start = 0usize;
+        let mut min_intrinsic_width = 0.0f32;
+        let mut max_intrinsic_width = 0.0f32;
+        let mut hard_line_start = 0usize;
+        for (segment_end, mandatory) in segments {
+            let segment_visible_end = visible_end(segment_start, segment_end);
+            min_intrinsic_width =
+                min_intrinsic_width.max(width_of(segment_start, segment_visible_end));
+
+            if line_start < segment_start && width_of(line_start, segment_visible_end) > max_width {
+                lines.push(LineRange {
+                    start: line_start,
+                    end: segment_start,
+                    hardbreak: false,
+                });
+                line_start = segment_start;
+            }
+
+            // the word longer than the line is broken between the characters
+            while width_of(line_start, visible_end(line_start, segment_end)) > max_width {
+                let mut end = line_start + 1;
+                while width_of(line_start, end + 1) <= max_width {
+                    end += 1;
+                }
+                lines.push(LineRange {
+                    start: line_start,
+                    end,
+                    hardbreak: false,
+                });
+                line_start = end;
+            }
+
+            if mandatory && segment_end > 0 && is_line_break(chars[segment_end - 1].ch) {
+                lines.push(LineRange {
+                    start: line_start,
+                    end: segment_end,
+                    hardbreak: true,
+                });
+                max_intrinsic_width = max_intrinsic_width.max(width_of(
+                    hard_line_start,
+                    visible_end(hard_line_start, segment_end),
+                ));
+                line_start = segment_end;
+                hard_line_start = segment_end;
+            }
+
+            segment_start = segment_end;
+        }
+        lines.push(LineRange {
+            start: line_start,
+            end: chars.len(),
+            hardbreak: false,
+        });
+        max_intrinsic_width = max_intrinsic_width.max(width_of(
+            hard_line_start,
+            visible_end(hard_line_start, chars.len()),
+        ));
+
+        // limit the number of lines
+        let ellipsis = paragraph_style
+            .ellipsis
+            .as_deref()
+            .filter(|ellipsis| !ellipsis.is_empty());
+        let max_lines = match (paragraph_style.max_lines, ellipsis) {
+            (Some(max_lines), _) => Some(max_lines.max(1) as usize),
+            // without the line limit the ellipsis is applied to the first wrapped line
+            (None, Some(_)) => lines
+                .iter()
+                .position(|line| !line.hardbreak && line.end < chars.len())
+                .map(|index| index + 1),
+            (None, None) => None,
+        };
+        let mut ellipsis_run = None;
+        if let Some(max_lines) = max_lines.filter(|max_lines| lines.len() > *max_lines) {
+            lines.truncate(max_lines);
+            if let Some(ellipsis) = ellipsis {
+                let line = lines.last_mut().unwrap();
+                let chunk = chars[..line.end].last().map_or(0, |ch| ch.chunk);
+                let style = &content.chunks[chunk].style;
+                let shaped = fonts.shape(style, ellipsis, base_direction)?;
+                let ellipsis_width = shaped.iter().map(|glyph| glyph.x_advance).sum::<f32>();
+
+                let mut end = visible_end(line.start, line.end);
+                while end > line.start && width_of(line.start, end) + ellipsis_width > max_width {
+                    end -= 1;
+                }
+                line.end = visible_end(line.start, end);
+                line.hardbreak = false;
+
+                ellipsis_run = Some((chunk, ellipsis_width, shaped));
+            }
+        }
+
+        // vertical metrics and the width of the lines
+        let line_count = lines.len();
+        let measured_lines = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut metrics = chars[line.start..line.end]
+                    .iter()
+                    .map(|ch| chunk_metrics[ch.chunk])
+                    .reduce(VerticalMetrics::max)
+                    .or_else(|| chars[..line.start].last().map(|ch| chunk_metrics[ch.chunk]))
+                    .unwrap_or(default_metrics);
+                let mut width = width_of(line.start, visible_end(line.start, line.end));
+                if let Some((chunk, ellipsis_width, _)) =
+                    ellipsis_run.as_ref().filter(|_| index + 1 == line_count)
+                {
+                    metrics = metrics.max(chunk_metrics[*chunk]);
+                    width += ellipsis_width;
+                }
+                (metrics, width)
+            })
+            .collect::<Vec<_>>();
+        let longest_line_width = measured_lines
+            .iter()
+            .map(|(_, width)| *width)
+            .fold(0.0f32, f32::max);
+        let layout_width = if max_width.is_finite() {
+            max_width
+        } else {
+            longest_line_width
+        };
+
+        let alignment = match (
+            paragraph_style.text_alignment,
+            paragraph_style.text_direction,
+        ) {
+            (TextAlignment::Start, TextDirection::LTR)
+            | (TextAlignment::End, TextDirection::RTL) => TextAlignment::Left,
+            (TextAlignment::Start, TextDirection::RTL)
+            | (TextAlignment::End, TextDirection::LTR) => TextAlignment::Right,
+            (alignment, _) => alignment,
+        };
+
+        // place the glyphs
+        let utf16_at = |index: usize| chars.get(index).map_or(text_len_utf16, |ch| ch.begin_utf16);
+        let mut runs: Vec<TextRun> = Vec::new();
+        let mut run_chunk = usize::MAX;
+        let mut line_infos = Vec::with_capacity(line_count);
+        for (index, (line, (metrics, width))) in lines.iter().zip(measured_lines).enumerate() {
+            let free_space = (layout_width - width).max(0.0f32);
+            let line_visible_end = visible_end(line.start, line.end);
+            let spaces = chars[line.start..line_visible_end]
+                .iter()
+                .filter(|ch| ch.ch == ' ')
+                .count();
+            let (left, space_extra) = match alignment {
+                TextAlignment::Right => (free_space, 0.0f32),
+                TextAlignment::Center => (free_space / 2.0f32, 0.0f32),
+                TextAlignment::Justify
+                    if spaces > 0 && !line.hardbreak && index + 1 < line_count =>
+                {
+                    (0.0f32, free_space / spaces as f32)
+                }
+                _ => (0.0f32, 0.0f32),
+            };
+
+            let top = line_infos
+                .last()
+                .map_or(0.0f64, |line: &LineInfo| line.baseline + line.descent);
+            let baseline = top + metrics.ascent as f64;
+
+            // the ellipsis is placed at the end of the line in the paragraph direction
+            // and the trailing whitespace goes beyond the end of the line
+            let ellipsis = ellipsis_run.as_ref().filter(|_| index + 1 == line_count);
+            let (mut x, ellipsis_x) = match base_direction {
+                TextDirection::LTR => (left, left + width_of(line.start, line_visible_end)),
+                TextDirection::RTL => (
+                    left + ellipsis.map_or(0.0f32, |(_, ellipsis_width, _)| *ellipsis_width)
+                        - width_of(line_visible_end, line.end),
+                    left,
+                ),
+            };
+
+            // place the characters in the visual order (UAX #9)
+            let line_levels = get_line_levels(&bidi, &chars[line.start..line.end]);
+            for visual_index in BidiInfo::reorder_visual(&line_levels) {
+                let char_index = line.start + visual_index;
+                let ch = &chars[char_index];
+                if run_chunk != ch.chunk && !ch.glyphs.is_empty() {
+                    let style = &content.chunks[ch.chunk].style;
+                    runs.push(TextRun {
+                        family: style.family.clone(),
+                        size: style.size,
+                        color: get_color(style),
+                        is_ellipsis: false,
+                        glyphs: Vec::new(),
+                    });
+                    run_chunk = ch.chunk;
+                }
+                for glyph in in_visual_order(&glyphs[ch.glyphs.clone()], ch.direction) {
+                    runs.last_mut().unwrap().glyphs.push(PositionedGlyph::new(
+                        glyph,
+                        x,
+                        baseline as f32,
+                        index,
+                        ch.begin_utf16..ch.end_utf16,
+                        ch.direction,
+                    ));
+                    x += glyph.x_advance;
+                }
+                if ch.ch == ' ' && char_index < line_visible_end {
+                    x += space_extra;
+                }
+            }
+
+            if let Some((chunk, _, shaped)) = ellipsis {
+                let style = &content.chunks[*chunk].style;
+                let position = utf16_at(line.end);
+                let mut x = ellipsis_x;
+                let glyphs = in_visual_order(shaped, base_direction)
+                    .map(|glyph| {
+                        let positioned = PositionedGlyph::new(
+                            glyph,
+                            x,
+                            baseline as f32,
+                            index,
+                            position..position,
+                            base_direction,
+                        );
+                        x += glyph.x_advance;
+                        positioned
+                    })
+                    .collect();
+                runs.push(TextRun {
+                    family: style.family.clone(),
+                    size: style.size,
+                    color: get_color(style),
+                    is_ellipsis: true,
+                    glyphs,
+                });
+            }
+
+            let mut end_excluding_newline = line.end;
+            while end_excluding_newline > line.start
+                && is_line_break(chars[end_excluding_newline - 1].ch)
+            {
+                end_excluding_newline -= 1;
+            }
+            line_infos.push(LineInfo {
+                unscaled_ascent: metrics.unscaled_ascent as f64,
+                ascent: metrics.ascent as f64,
+                descent: metrics.descent as f64,
+                baseline,
+                hardbreak: line.hardbreak,
+                width: width as f64,
+                height: (metrics.ascent + metrics.descent) as f64,
+                left: left as f64,
+                start_utf16: utf16_at(line.start),
+                end_utf16: utf16_at(end_excluding_newline),
+                end_excluding_whitespace_utf16: utf16_at(line_visible_end),
+                end_including_newline_utf16: utf16_at(line.end),
+            });
+        }
+
+        let clusters = collect_clusters(&text, &runs);
+
+        Ok(ParagraphLayout {
+            max_width,
+            text,
+            runs,
+            lines: line_infos,
+            clusters,
+            min_intrinsic_width,
+            max_intrinsic_width,
+        })
+    }
+}
+
+impl ParagraphLayout {
+    /// Returns the cluster of the text containing the UTF-16 code unit.
+    pub fn get_cluster_at_code_unit_index_utf16(
+        &self,
+        code_unit_index: usize,
+    ) -> Option<&GlyphCluster> {
+        self.clusters.iter().find(|cluster| {
+            !cluster.is_ellipsis
+                && (cluster.begin_utf16..cluster.end_utf16).contains(&code_unit_index)
+        })
+    }
+
+    /// Returns the cluster under the point or the closest one on the line.
+    pub fn get_cluster_at_coordinates(&self, x: f64, y: f64) -> Option<&GlyphCluster> {
+        let line = self
+            .lines
+            .iter()
+            .position(|line| y < line.baseline + line.descent)
+            .unwrap_or(self.lines.len().checked_sub(1)?);
+
+        self.clusters
+            .iter()
+            .filter(|cluster| cluster.line == line)
+            .min_by(|a, b| {
+                let distance = |cluster: &GlyphCluster| {
+                    (cluster.left as f64 - x)
+                        .max(x - cluster.right as f64)
+                        .max(0.0f64)
+                };
+                distance(a).total_cmp(&distance(b))
+            })
+    }
+}
+
+/// Fonts used to lay out the paragraph.
+///
+/// The layout only needs the font metrics and the shaped glyphs,
+/// so it doesn't depend on the device the text is drawn with.
+pub(crate) trait LayoutFonts {
+    /// Returns the vertical metrics of the font (`None` if the font is not registered).
+    fn get_metrics(
+        &self,
+        style: &ParagraphStyle<Paint>,
+    ) -> Result<Option<FontMetrics>, &'static str>;
+
+    /// Returns the shaped glyphs of the text of the same direction (in the logical order).
+    fn shape(
+        &self,
+        style: &ParagraphStyle<Paint>,
+        text: &str,
+        direction: TextDirection,
+    ) -> Result<Vec<ShapedGlyph>, &'static str>;
+}
+
+impl<D: Device> LayoutFonts for Fonts<D> {
+    fn get_metrics(
+        &self,
+        style: &ParagraphStyle<Paint>,
+    ) -> Result<Option<FontMetrics>, &'static str> {
+        let mut data = self.data.lock().unwrap();
+        let Some(font) = data.fonts.get_mut(&style.family) else {
+            return Ok(None);
+        };
+
+        let params = FontParams {
+            size: style.size as u8,
+        };
+        font.get_metrics(params).map(Some)
+    }
+
+    fn shape(
+        &self,
+        style: &ParagraphStyle<Paint>,
+        text: &str,
+        direction: TextDirection,
+    ) -> Result<Vec<ShapedGlyph>, &'static str> {
+        let mut data = self.data.lock().unwrap();
+        let Some(font) = data.fonts.get_mut(&style.family) else {
+            return Ok(Vec::new());
+        };
+
+        let params = FontParams {
+            size: style.size as u8,
+        };
+        font.shape(params, text, direction)
+    }
+}
+
+/// Returns the vertical metrics of the style.
+fn measure(
+    fonts: &impl LayoutFonts,
+    style: &ParagraphStyle<Paint>,
+) -> Result<VerticalMetrics, &'static str> {
+    let Some(font_metrics) = fonts.get_metrics(style)? else {
+        // the text with an unknown font is not drawn
+        return Ok(VerticalMetrics::default());
+    };
+
+    let (ascent, descent) = match style.height_factor {
+        Some(height_factor) => {
+            let font_height = (font_metrics.ascent + font_metrics.descent).max(1.0e-6f32);
+            let scale = style.size * height_factor / font_height;
+            (font_metrics.ascent * scale, font_metrics.descent * scale)
+        }
+        None => (font_metrics.ascent, font_metrics.descent),
+    };
+
+    Ok(VerticalMetrics {
+        unscaled_ascent: font_metrics.ascent,
+        ascent,
+        descent,
+    })
+}
+
+/// Returns the embedding levels of the line characters with the whitespace
+/// before the separators and at the end of the line reset to the paragraph level
+/// (rule L1 of UAX #9).
+///
+/// Only the levels of the line characters are computed, not the levels of the whole text.
+fn get_line_levels(bidi: &BidiInfo, chars: &[CharInfo]) -> Vec<Level> {
+    let Some(first) = chars.first() else {
+        return Vec::new();
+    };
+    let Some(paragraph) = bidi
+        .paragraphs
+        .iter()
+        .find(|paragraph| paragraph.range.contains(&first.byte))
+    else {
+        return Vec::new();
+    };
+
+    let mut levels = chars
+        .iter()
+        .map(|ch| bidi.levels[ch.byte])
+        .collect::<Vec<_>>();
+    let mut reset_from = Some(0usize);
+    let mut previous_level = paragraph.level;
+    for (index, ch) in chars.iter().enumerate() {
+        match bidi.original_classes[ch.byte] {
+            // the segment and paragraph separators with the whitespace before them
+            BidiClass::B | BidiClass::S => {
+                levels[reset_from.unwrap_or(index)..=index].fill(paragraph.level);
+                reset_from = None;
+            }
+            BidiClass::WS | BidiClass::FSI | BidiClass::LRI | BidiClass::RLI | BidiClass::PDI => {
+                reset_from.get_or_insert(index);
+            }
+            // the explicit formatting characters retained in the text
+            BidiClass::RLE
+            | BidiClass::LRE
+            | BidiClass::RLO
+            | BidiClass::LRO
+            | BidiClass::PDF
+            | BidiClass::BN => {
+                reset_from.get_or_insert(index);
+                levels[index] = previous_level;
+            }
+            _ => reset_from = None,
+        }
+        previous_level = levels[index];
+    }
+    if let Some(reset_from) = reset_from {
+        levels[reset_from..].fill(paragraph.level);
+    }
+    levels
+}
+
+/// Returns the glyphs stored in the logical order from left to right.
+fn in_visual_order(
+    glyphs: &[ShapedGlyph],
+    direction: TextDirection,
+) -> impl Iterator<Item = &ShapedGlyph> {
+    let len = glyphs.len();
+    (0..len).map(move |index| match direction {
+        TextDirection::LTR => &glyphs[index],
+        TextDirection::RTL => &glyphs[len - 1 - index],
+    })
+}
+
+/// Groups the glyphs into the grapheme clusters (UAX #29).
+fn collect_clusters(text: &str, runs: &[TextRun]) -> Vec<GlyphCluster> {
+    let mut boundaries = vec![0usize];
+    for grapheme in text.graphemes(true) {
+        boundaries.push(boundaries.last().unwrap() + grapheme.encode_utf16().count());
+    }
+
+    let mut clusters: Vec<GlyphCluster> = Vec::new();
+    for run in runs {
+        for glyph in &run.glyphs {
+            let (begin_utf16, end_utf16) = if run.is_ellipsis {
+                (glyph.begin_utf16, glyph.end_utf16)
+            } else {
+                // the shaper clusters may span several graphemes (like ligatures)
+                let begin = boundaries.partition_point(|boundary| *boundary <= glyph.begin_utf16);
+                let end = boundaries.partition_point(|boundary| *boundary < glyph.end_utf16);
+                (boundaries[begin - 1], boundaries[end.max(begin)])
+            };
+            let (left, right) = (glyph.pen_x, glyph.pen_x + glyph.advance);
+
+            // the glyphs of the cluster are placed next to each other
+            match clusters.last_mut() {
+                Some(cluster)
+                    if begin_utf16 < cluster.end_utf16.max(cluster.begin_utf16 + 1)
+                        && cluster.begin_utf16 < end_utf16.max(begin_utf16 + 1)
+                        && cluster.line == glyph.line
+                        && cluster.is_ellipsis == run.is_ellipsis =>
+                {
+                    cluster.begin_utf16 = cluster.begin_utf16.min(begin_utf16);
+                    cluster.end_utf16 = cluster.end_utf16.max(end_utf16);
+                    cluster.left = cluster.left.min(left);
+                    cluster.right = cluster.right.max(right);
+                }
+                _ => clusters.push(GlyphCluster {
+                    begin_utf16,
+                    end_utf16,
+                    line: glyph.line,
+                    left,
+                    right,
+                    is_ellipsis: run.is_ellipsis,
+                    direction: glyph.direction,
+                }),
+            }
+        }
+    }
+
+    // the right-to-left text is placed in the reversed order
+    clusters.sort_by_key(|cluster| cluster.begin_utf16);
+    clusters
+}
+
+fn get_color(style: &ParagraphStyle<Paint>) -> Color {
+    style
+        .foreground
+        .as_ref()
+        .map_or([0.0f32, 0.0f32, 0.0f32, 1.0f32], |paint| paint.color)
+}
+
+fn is_line_break(ch: char) -> bool {
+    matches!(
+        ch,
+        '\n' | '\u{0B}' | '\u{0C}' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use drawing_api::{ParagraphStyle, TextAlignment, TextDirection};
+
+    use crate::generic::texture_font::{FontMetrics, ShapedGlyph};
+
+    use unicode_bidi::{BidiInfo, Level};
+
+    use super::{
+        get_line_levels, CharInfo, LayoutFonts, Paint, ParagraphContent, ParagraphLayout, TextChunk,
+    };
+
+    /// Every character is a glyph `10` pixels wide, the ascent is `8` and the descent is `2`.
+    struct TestFonts;
+
+    impl LayoutFonts for TestFonts {
+        fn get_metrics(
+            &self,
+            _style: &ParagraphStyle<Paint>,
+        ) -> Result<Option<FontMetrics>, &'static str> {
+            Ok(Some(FontMetrics {
+                ascent: 8.0f32,
+                descent: 2.0f32,
+            }))
+        }
+
+        fn shape(
+            &self,
+            _style: &ParagraphStyle<Paint>,
+            text: &str,
+            _direction: TextDirection,
+        ) -> Result<Vec<ShapedGlyph>, &'static str> {
+            Ok(text
+                .char_indices()
+                .map(|(cluster, ch)| ShapedGlyph {
+                    glyph_id: ch as u32,
+                    cluster,
+                    x_advance: 10.0f32,
+                    x_offset: 0.0f32,
+                    y_offset: 0.0f32,
+                })
+                .collect())
+        }
+    }
+
+    fn lay_out(style: ParagraphStyle<Paint>, text: &str, max_width: f32) -> ParagraphLayout {
+        let content = ParagraphContent {
+            style: style.clone(),
+            chunks: vec![TextChunk {
+                style,
+                text: text.to_string(),
+            }],
+        };
+        ParagraphLayout::new(&TestFonts, &content, max_width).unwrap()
+    }
+
+    /// Returns the UTF-16 ranges of the lines (excluding the new line characters).
+    fn line_ranges(layout: &ParagraphLayout) -> Vec<(usize, usize)> {
+        layout
+            .lines
+            .iter()
+            .map(|line| (line.start_utf16, line.end_utf16))
+            .collect()
+    }
+
+    /// Returns the glyphs of the text (not the ellipsis) with their positions in the visual order.
+    fn visual_text(layout: &ParagraphLayout, line: usize) -> Vec<(char, f32)> {
+        let mut glyphs = layout
+            .runs
+            .iter()
+            .filter(|run| !run.is_ellipsis)
+            .flat_map(|run| run.glyphs.iter())
+            .filter(|glyph| glyph.line == line)
+            .map(|glyph| (char::from_u32(glyph.glyph_id).unwrap(), glyph.pen_x))
+            .collect::<Vec<_>>();
+        glyphs.sort_by(|a, b| a.1.total_cmp(&b.1));
+        glyphs
+    }
+
+    #[test]
+    fn lines_are_wrapped_between_words() {
+        let layout = lay_out(ParagraphStyle::default(), "aaa bbb ccc", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 8), (8, 11)]);
+        assert_eq!(layout.lines[0].end_excluding_whitespace_utf16, 7);
+        assert_eq!(layout.lines[0].width, 70.0f64);
+        assert_eq!(layout.lines[1].width, 30.0f64);
+        assert!(!layout.lines[0].hardbreak);
+        assert_eq!(layout.lines[0].baseline, 8.0f64);
+        assert_eq!(layout.lines[1].baseline, 18.0f64);
+        assert_eq!(layout.min_intrinsic_width, 30.0f32);
+        assert_eq!(layout.max_intrinsic_width, 110.0f32);
+
+        let second_line = visual_text(&layout, 1);
+        assert_eq!(second_line, [('c', 0.0f32), ('c', 10.0f32), ('c', 20.0f32)]);
+    }
+
+    #[test]
+    fn unbounded_width_breaks_only_on_new_lines() {
+        let layout = lay_out(ParagraphStyle::default(), "aaa bbb\nccc", f32::INFINITY);
+
+        assert_eq!(line_ranges(&layout), [(0, 7), (8, 11)]);
+        assert!(layout.lines[0].hardbreak);
+        assert_eq!(layout.lines[0].end_including_newline_utf16, 8);
+        assert_eq!(layout.max_intrinsic_width, 70.0f32);
+    }
+
+    #[test]
+    fn word_longer_than_the_line_is_broken_between_characters() {
+        let layout = lay_out(ParagraphStyle::default(), "abcdefgh", 35.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 3), (3, 6), (6, 8)]);
+        assert_eq!(layout.min_intrinsic_width, 80.0f32);
+    }
+
+    #[test]
+    fn lines_are_aligned_in_the_layout_width() {
+        let style = ParagraphStyle {
+            text_alignment: TextAlignment::Center,
+            ..Default::default()
+        };
+        let layout = lay_out(style, "aaa bbb ccc", 75.0f32);
+
+        assert_eq!(layout.lines[0].left, 2.5f64);
+        assert_eq!(layout.lines[1].left, 22.5f64);
+        assert_eq!(visual_text(&layout, 1)[0], ('c', 22.5f32));
+
+        let style = ParagraphStyle {
+            text_alignment: TextAlignment::Justify,
+            ..Default::default()
+        };
+        let layout = lay_out(style, "a b c dd", 75.0f32);
+
+        // the trailing space is not stretched and the last line is not justified
+        assert_eq!(line_ranges(&layout), [(0, 6), (6, 8)]);
+        assert_eq!(
+            visual_text(&layout, 0),
+            [
+                ('a', 0.0f32),
+                (' ', 10.0f32),
+                ('b', 32.5f32),
+                (' ', 42.5f32),
+                ('c', 65.0f32),
+                (' ', 75.0f32),
+            ]
+        );
+        assert_eq!(visual_text(&layout, 1)[0], ('d', 0.0f32));
+    }
+
+    #[test]
+    fn ellipsis_replaces_the_end_of_the_last_line() {
+        let style = ParagraphStyle {
+            max_lines: Some(1),
+            ellipsis: Some("…".to_string()),
+            ..Default::default()
+        };
+        let layout = lay_out(style, "aaa bbb ccc", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 6)]);
+        assert_eq!(layout.lines[0].width, 70.0f64);
+        assert_eq!(
+            visual_text(&layout, 0).last().copied(),
+            Some(('b', 50.0f32))
+        );
+
+        let ellipsis = layout.runs.last().unwrap();
+        assert!(ellipsis.is_ellipsis);
+        assert_eq!(ellipsis.glyphs.len(), 1);
+        assert_eq!(ellipsis.glyphs[0].pen_x, 60.0f32);
+        assert_eq!(
+            (ellipsis.glyphs[0].begin_utf16, ellipsis.glyphs[0].end_utf16),
+            (6, 6)
+        );
+    }
+
+    #[test]
+    fn ellipsis_without_line_limit_ends_the_first_wrapped_line() {
+        let style = ParagraphStyle {
+            ellipsis: Some("…".to_string()),
+            ..Default::default()
+        };
+        let layout = lay_out(style, "aa\nbbb ccc ddd", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 2), (3, 9)]);
+        assert!(layout.runs.last().unwrap().is_ellipsis);
+    }
+
+    /// Checks that every logical position maps to a visual position which maps back to it.
+    fn assert_hit_test_round_trips(layout: &ParagraphLayout) {
+        let text_len_utf16 = layout.text.encode_utf16().count();
+        let last_utf16 = layout.lines.last().unwrap().end_utf16;
+        for index in 0..text_len_utf16 {
+            let Some(cluster) = layout.get_cluster_at_code_unit_index_utf16(index) else {
+                // only the new line characters and the text hidden by the ellipsis have no glyphs
+                let ch = layout.text.chars().nth(index).unwrap();
+                assert!(ch == '\n' || index >= last_utf16, "no cluster at {index}");
+                continue;
+            };
+            assert!((cluster.begin_utf16..cluster.end_utf16).contains(&index));
+
+            let line = &layout.lines[cluster.line];
+            let x = (cluster.left + cluster.right) as f64 / 2.0f64;
+            let y = line.baseline - line.ascent / 2.0f64;
+            let hit = layout.get_cluster_at_coordinates(x, y).unwrap();
+            assert_eq!(
+                (hit.begin_utf16, hit.end_utf16),
+                (cluster.begin_utf16, cluster.end_utf16)
+            );
+        }
+    }
+
+    #[test]
+    fn hit_test_maps_positions_back_to_the_text() {
+        let layout = lay_out(ParagraphStyle::default(), "ab cd\nefg hij", 55.0f32);
+        assert_eq!(line_ranges(&layout), [(0, 5), (6, 10), (10, 13)]);
+        assert_hit_test_round_trips(&layout);
+
+        let cluster = layout.get_cluster_at_code_unit_index_utf16(7).unwrap();
+        assert_eq!(
+            (cluster.line, cluster.left, cluster.right),
+            (1, 10.0f32, 20.0f32)
+        );
+
+        // the points outside of the text are mapped to the closest cluster of the line
+        let hit = layout.get_cluster_at_coordinates(-5.0f64, 15.0f64).unwrap();
+        assert_eq!(hit.begin_utf16, 6);
+        let hit = layout
+            .get_cluster_at_coordinates(500.0f64, 500.0f64)
+            .unwrap();
+        assert_eq!(hit.begin_utf16, 12);
+    }
+
+    #[test]
+    fn hit_test_skips_the_ellipsis() {
+        let style = ParagraphStyle {
+            max_lines: Some(1),
+            ellipsis: Some("…".to_string()),
+            ..Default::default()
+        };
+        let layout = lay_out(style, "aaa bbb ccc", 75.0f32);
+        assert_hit_test_round_trips(&layout);
+        assert!(layout.get_cluster_at_code_unit_index_utf16(6).is_none());
+
+        // the ellipsis is still reported under the point
+        let hit = layout.get_cluster_at_coordinates(65.0f64, 5.0f64).unwrap();
+        assert!(hit.is_ellipsis);
+        assert_eq!((hit.begin_utf16, hit.end_utf16), (6, 6));
+    }
+
+    fn rtl_style() -> ParagraphStyle<Paint> {
+        ParagraphStyle {
+            text_direction: TextDirection::RTL,
+            ..Default::default()
+        }
+    }
+
+    fn visual_chars(layout: &ParagraphLayout, line: usize) -> String {
+        visual_text(layout, line).iter().map(|(ch, _)| ch).collect()
+    }
+
+    #[test]
+    fn mixed_text_is_placed_in_the_visual_order() {
+        let layout = lay_out(ParagraphStyle::default(), "abc אבג def", f32::INFINITY);
+        assert_eq!(visual_chars(&layout, 0), "abc גבא def");
+
+        let cluster = layout.get_cluster_at_code_unit_index_utf16(4).unwrap();
+        assert_eq!(cluster.direction, TextDirection::RTL);
+        assert_eq!((cluster.left, cluster.right), (60.0f32, 70.0f32));
+        let cluster = layout.get_cluster_at_code_unit_index_utf16(0).unwrap();
+        assert_eq!(cluster.direction, TextDirection::LTR);
+        assert_hit_test_round_trips(&layout);
+
+        // the left-to-right text is embedded in the right-to-left paragraph
+        let layout = lay_out(rtl_style(), "אבג abc", f32::INFINITY);
+        assert_eq!(visual_chars(&layout, 0), "abc גבא");
+        assert_eq!(
+            layout.get_cluster_at_code_unit_index_utf16(0).unwrap().left,
+            60.0f32
+        );
+        assert_hit_test_round_trips(&layout);
+    }
+
+    #[test]
+    fn wrapped_lines_are_reordered_separately() {
+        let layout = lay_out(rtl_style(), "אבג abc def דהו", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 8), (8, 15)]);
+        assert_eq!(visual_chars(&layout, 0), " abc גבא");
+        assert_eq!(visual_chars(&layout, 1), "והד def");
+
+        // the trailing whitespace goes beyond the left edge of the right-to-left line
+        assert_eq!(visual_text(&layout, 0)[0], (' ', -5.0f32));
+        assert_eq!(visual_text(&layout, 0)[1], ('a', 5.0f32));
+        assert_hit_test_round_trips(&layout);
+    }
+
+    #[test]
+    fn ellipsis_is_placed_at_the_start_of_the_right_to_left_line() {
+        let style = ParagraphStyle {
+            max_lines: Some(1),
+            ellipsis: Some("…".to_string()),
+            ..rtl_style()
+        };
+        let layout = lay_out(style, "אבג דהו זחט", 75.0f32);
+
+        assert_eq!(line_ranges(&layout), [(0, 6)]);
+        assert_eq!(layout.lines[0].width, 70.0f64);
+        assert_eq!(layout.lines[0].left, 5.0f64);
+        assert_eq!(visual_chars(&layout, 0), "הד גבא");
+        assert_eq!(visual_text(&layout, 0)[0], ('ה', 15.0f32));
+
+        let ellipsis = layout.runs.last().unwrap();
+        assert!(ellipsis.is_ellipsis);
+        assert_eq!(ellipsis.glyphs[0].pen_x, 5.0f32);
+        assert_eq!(ellipsis.glyphs[0].direction, TextDirection::RTL);
+        assert_hit_test_round_trips(&layout);
+    }
+
+    #[test]
+    fn line_levels_match_the_whole_text_levels() {
+        for (text, level) in [
+            ("abc אבג  def  ", Level::ltr()),
+            ("אבג\tabc def \u{2067}x\u{2069} ", Level::rtl()),
+            ("  abc\u{202B}אב\u{202C} גד  ", Level::rtl()),
+        ] {
+            let bidi = BidiInfo::new(text, Some(level));
+            let chars = text
+                .char_indices()
+                .map(|(byte, ch)| CharInfo {
+                    ch,
+                    chunk: 0,
+                    byte,
+                    direction: TextDirection::LTR,
+                    advance: 0.0f32,
+                    glyphs: 0..0,
+                    begin_utf16: 0,
+                    end_utf16: 0,
+                })
+                .collect::<Vec<_>>();
+
+            let paragraph = &bidi.paragraphs[0];
+            for (start, end) in [(0, chars.len()), (2, 9), (4, chars.len() - 1)] {
+                let bytes = chars[start].byte..chars.get(end).map_or(text.len(), |ch| ch.byte);
+                let expected = bidi.reordered_levels_per_char(paragraph, bytes);
+                assert_eq!(
+                    get_line_levels(&bidi, &chars[start..end]),
+                    expected[start..end],
+                    "{text:?} {start}..{end}"
+                );
+            }
+        }
+    }
+}
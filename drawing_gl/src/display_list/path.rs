@@ -1,14 +1,23 @@
-use drawing_api::FillType;
+use drawing_api::{FillType, PixelPoint, PixelRect};
 
 use super::PathElement;
 
 pub struct Path {
     pub(crate) path: Vec<PathElement>,
     pub(crate) fill_type: FillType,
+
+    /// Set when the path consists of a single rounded rectangle with equal
+    /// circular corners, so it can be drawn with the analytic fast paths.
+    pub(crate) rounded_rect: Option<(PixelRect, f32)>,
 }
 
 impl drawing_api::Path for Path {
     fn get_bounds(&self) -> drawing_api::PixelRect {
-        todo!()
+        let points = self.path.iter().flat_map(|element| match element {
+            PathElement::MoveTo(p) | PathElement::LineTo(p) => vec![*p],
+            PathElement::BezierTo(c1, c2, p) => vec![*c1, *c2, *p],
+            PathElement::ClosePath | PathElement::Solidity(_) => Vec::new(),
+        });
+        PixelRect::from_points(points.collect::<Vec<PixelPoint>>())
     }
 }
@@ -1,10 +1,13 @@
 use drawing_api::{FillType, PixelPoint};
 
+use crate::generic::renderer::{arc_path, oval_path, rect_rounded_radii_path};
+
 use super::PathElement;
 
 pub struct PathBuilder {
     path: Vec<PathElement>,
     fill_type: FillType,
+    rounded_rect: Option<(drawing_api::PixelRect, f32)>,
 }
 
 impl Default for PathBuilder {
@@ -12,6 +15,7 @@ impl Default for PathBuilder {
         Self {
             path: Vec::new(),
             fill_type: FillType::NonZero,
+            rounded_rect: None,
         }
     }
 }
@@ -25,12 +29,14 @@ impl drawing_api::PathBuilder for PathBuilder {
 
     fn move_to(&mut self, location: impl Into<drawing_api::PixelPoint>) {
         let location = location.into();
+        self.rounded_rect = None;
         self.path
             .push(PathElement::MoveTo(PixelPoint::new(location.x, location.y)));
     }
 
     fn line_to(&mut self, location: impl Into<drawing_api::PixelPoint>) {
         let location = location.into();
+        self.rounded_rect = None;
         self.path
             .push(PathElement::LineTo(PixelPoint::new(location.x, location.y)));
     }
@@ -44,6 +50,7 @@ impl drawing_api::PathBuilder for PathBuilder {
         let control_point_1 = control_point_1.into();
         let control_point_2 = control_point_2.into();
         let end_point = end_point.into();
+        self.rounded_rect = None;
         self.path.push(PathElement::BezierTo(
             PixelPoint::new(control_point_1.x, control_point_1.y),
             PixelPoint::new(control_point_2.x, control_point_2.y),
@@ -56,11 +63,31 @@ impl drawing_api::PathBuilder for PathBuilder {
         rect: impl Into<drawing_api::PixelRect>,
         rounding_radii: &drawing_api::RoundingRadii,
     ) {
-        todo!()
+        let rect = rect.into();
+
+        // remember a lone rounded rectangle with equal circular corners
+        let radius = rounding_radii.top_left.width;
+        let is_uniform = [
+            rounding_radii.top_left,
+            rounding_radii.top_right,
+            rounding_radii.bottom_left,
+            rounding_radii.bottom_right,
+        ]
+        .iter()
+        .all(|r| r.width == radius && r.height == radius);
+        self.rounded_rect = if self.path.is_empty() && is_uniform {
+            Some((rect, radius))
+        } else {
+            None
+        };
+
+        self.path
+            .append(&mut rect_rounded_radii_path(rect, rounding_radii));
     }
 
     fn add_oval(&mut self, oval_bounds: impl Into<drawing_api::PixelRect>) {
-        todo!()
+        self.rounded_rect = None;
+        self.path.append(&mut oval_path(oval_bounds));
     }
 
     fn add_arc(
@@ -69,7 +96,12 @@ impl drawing_api::PathBuilder for PathBuilder {
         start_angle_degrees: f32,
         end_angle_degrees: f32,
     ) {
-        todo!()
+        self.rounded_rect = None;
+        self.path.append(&mut arc_path(
+            oval_bounds,
+            start_angle_degrees,
+            end_angle_degrees,
+        ));
     }
 
     fn close(&mut self) {
@@ -80,6 +112,7 @@ impl drawing_api::PathBuilder for PathBuilder {
         super::Path {
             path: self.path,
             fill_type: self.fill_type,
+            rounded_rect: self.rounded_rect,
         }
     }
 
@@ -87,6 +120,7 @@ impl drawing_api::PathBuilder for PathBuilder {
         super::Path {
             path: self.path.clone(),
             fill_type: self.fill_type,
+            rounded_rect: self.rounded_rect,
         }
     }
 }
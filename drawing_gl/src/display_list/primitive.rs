@@ -1,4 +1,7 @@
-use drawing_api::{PixelPoint, PixelRect};
+use drawing_api::{
+    BlendMode, BlurStyle, ClipOperation, ColorFilter, PixelPoint, PixelRect, TextureSampling,
+    TileMode,
+};
 
 use crate::{generic::device::Color, units::PixelTransform};
 
@@ -36,6 +39,17 @@ pub enum Primitive<Texture: drawing_api::Texture, Fonts: drawing_api::Fonts> {
         text: String,
     },
 
+    /// Glyphs placed by the paragraph layout.
+    Glyphs {
+        fonts: Fonts,
+        family_name: String,
+        size: f32,
+        color: Color,
+        position: PixelPoint,
+        clipping_rect: Option<PixelRect>,
+        glyphs: Vec<Glyph>,
+    },
+
     Stroke {
         path: Vec<PathElement>,
         thickness: f32,
@@ -61,6 +75,7 @@ pub enum Primitive<Texture: drawing_api::Texture, Fonts: drawing_api::Fonts> {
 
     ClipPath {
         path: Vec<PathElement>,
+        operation: ClipOperation,
         primitives: Vec<Primitive<Texture, Fonts>>,
     },
 
@@ -70,9 +85,36 @@ pub enum Primitive<Texture: drawing_api::Texture, Fonts: drawing_api::Fonts> {
     },
 
     Composite {
-        color: Color,
+        bounds: PixelRect,
+        alpha: f32,
+        backdrop_filter: Option<ImageFilter>,
+        primitives: Vec<Primitive<Texture, Fonts>>,
+    },
+
+    Filter {
+        filter: ImageFilter,
+        primitives: Vec<Primitive<Texture, Fonts>>,
+    },
+
+    ColorFilter {
+        filter: ColorFilter,
         primitives: Vec<Primitive<Texture, Fonts>>,
     },
+
+    Blend {
+        blend_mode: BlendMode,
+        primitives: Vec<Primitive<Texture, Fonts>>,
+    },
+}
+
+/// Glyph placed on the baseline.
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    /// Font specific glyph index.
+    pub glyph_id: u32,
+
+    /// Position of the glyph origin relative to the primitive position.
+    pub position: PixelPoint,
 }
 
 #[derive(Clone, Debug)]
@@ -121,6 +163,77 @@ pub enum Brush<Texture: drawing_api::Texture> {
         texture: Texture,
         transform: PixelTransform,
         alpha: f32,
+        horizontal_tile_mode: TileMode,
+        vertical_tile_mode: TileMode,
+        sampling: TextureSampling,
+    },
+
+    Gradient {
+        shape: GradientShape,
+        colors: Vec<Color>,
+        stops: Vec<f32>,
+        tile_mode: TileMode,
+        transform: PixelTransform,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum ImageFilter {
+    Blur {
+        x_sigma: f32,
+        y_sigma: f32,
+        tile_mode: TileMode,
+    },
+
+    Dilate {
+        x_radius: f32,
+        y_radius: f32,
+    },
+
+    Erode {
+        x_radius: f32,
+        y_radius: f32,
+    },
+
+    Matrix {
+        transform: PixelTransform,
+        filtering: bool,
+    },
+
+    Compose {
+        outer: Box<ImageFilter>,
+        inner: Box<ImageFilter>,
+    },
+
+    MaskBlur {
+        sigma: f32,
+        style: BlurStyle,
+    },
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum GradientShape {
+    Linear {
+        start_point: PixelPoint,
+        end_point: PixelPoint,
+    },
+
+    Radial {
+        center_point: PixelPoint,
+        radius: f32,
+    },
+
+    Conical {
+        start_center: PixelPoint,
+        start_radius: f32,
+        end_center: PixelPoint,
+        end_radius: f32,
+    },
+
+    Sweep {
+        center_point: PixelPoint,
+        start_angle: f32,
+        end_angle: f32,
     },
 }
 
@@ -140,6 +140,50 @@ impl<T: Texture, F: Fonts> Clipping<T> for Vec<Primitive<T, F>> {
                     }
                 }
 
+                Primitive::Glyphs {
+                    fonts,
+                    family_name,
+                    size,
+                    color,
+                    position,
+                    clipping_rect: rect,
+                    glyphs,
+                } => {
+                    let clipped = if let Some(rect) = rect {
+                        clip_rect(
+                            rect.origin.x,
+                            rect.origin.y,
+                            rect.size.width,
+                            rect.size.height,
+                            clipping_rect.origin.x,
+                            clipping_rect.origin.y,
+                            clipping_rect.size.width,
+                            clipping_rect.size.height,
+                        )
+                    } else {
+                        Some((
+                            clipping_rect.origin.x,
+                            clipping_rect.origin.y,
+                            clipping_rect.size.width,
+                            clipping_rect.size.height,
+                        ))
+                    };
+                    if let Some(clipped) = clipped {
+                        res.push(Primitive::Glyphs {
+                            fonts: fonts.clone(),
+                            family_name,
+                            size,
+                            color,
+                            position,
+                            clipping_rect: Some(PixelRect::new(
+                                PixelPoint::new(clipped.0, clipped.1),
+                                PixelSize::new(clipped.2, clipped.3),
+                            )),
+                            glyphs,
+                        });
+                    }
+                }
+
                 Primitive::Stroke {
                     path,
                     thickness,
@@ -204,9 +248,15 @@ impl<T: Texture, F: Fonts> Clipping<T> for Vec<Primitive<T, F>> {
                     res.push(Primitive::ClipRect { rect, primitives })
                 }
 
-                Primitive::ClipPath { path, primitives } => {
-                    res.push(Primitive::ClipPath { path, primitives })
-                }
+                Primitive::ClipPath {
+                    path,
+                    operation,
+                    primitives,
+                } => res.push(Primitive::ClipPath {
+                    path,
+                    operation,
+                    primitives,
+                }),
 
                 Primitive::Transform {
                     transform,
@@ -216,11 +266,45 @@ impl<T: Texture, F: Fonts> Clipping<T> for Vec<Primitive<T, F>> {
                     primitives,
                 }),
 
-                Primitive::Composite { color, primitives } => {
+                Primitive::Filter { filter, primitives } => {
+                    res.push(Primitive::Filter { filter, primitives })
+                }
+
+                Primitive::ColorFilter { filter, primitives } => {
+                    let clipped_primitives = primitives.clip(clipping_rect);
+                    if !clipped_primitives.is_empty() {
+                        res.push(Primitive::ColorFilter {
+                            filter,
+                            primitives: clipped_primitives,
+                        })
+                    }
+                }
+
+                Primitive::Blend {
+                    blend_mode,
+                    primitives,
+                } => {
+                    let clipped_primitives = primitives.clip(clipping_rect);
+                    if !clipped_primitives.is_empty() {
+                        res.push(Primitive::Blend {
+                            blend_mode,
+                            primitives: clipped_primitives,
+                        })
+                    }
+                }
+
+                Primitive::Composite {
+                    bounds,
+                    alpha,
+                    backdrop_filter,
+                    primitives,
+                } => {
                     let clipped_primitives = primitives.clip(clipping_rect);
                     if !clipped_primitives.is_empty() {
                         res.push(Primitive::Composite {
-                            color,
+                            bounds,
+                            alpha,
+                            backdrop_filter,
                             primitives: clipped_primitives,
                         })
                     }
@@ -2,6 +2,7 @@ use crate::generic::clipping::Scissor;
 use crate::generic::device::colored_vertex::ColoredVertex;
 use crate::generic::device::textured_vertex::TexturedVertex;
 use crate::generic::device::textured_y8_vertex::TexturedY8Vertex;
+use crate::generic::device::FilterPass;
 use crate::generic::device::Paint;
 use crate::generic::device::RenderTarget;
 use crate::generic::path::{Bounds, Path};
@@ -10,6 +11,10 @@ use crate::units::PixelToDeviceTransform;
 use crate::units::PixelToUvTransform;
 use crate::PathElement;
 use core::option::Option;
+use drawing_api::BlendMode;
+use drawing_api::BlurStyle;
+use drawing_api::ClipOperation;
+use drawing_api::ColorFilter;
 use drawing_api::ColorFormat;
 use drawing_api::Texture;
 use drawing_api::{PixelPoint, PixelRect};
@@ -210,8 +215,67 @@ pub trait Device {
         transform: PixelToDeviceTransform,
     );
 
+    // filters
+
+    /// Draws the texture over the whole target (of the same size)
+    /// applying the filter in the horizontal or vertical direction.
+    fn filter_pass(
+        &mut self,
+        target: &Self::RenderTarget,
+        texture: &Self::Texture,
+        pass: FilterPass,
+        horizontal: bool,
+    );
+
+    /// Draws the blurred texture over the whole target (of the same size)
+    /// combined with the original texture according to the blur style.
+    fn blur_mask_composite(
+        &mut self,
+        target: &Self::RenderTarget,
+        blurred: &Self::Texture,
+        original: &Self::Texture,
+        style: BlurStyle,
+    );
+
+    // blending
+
+    /// Returns a copy of the target's content, used as the destination
+    /// color of the advanced blend modes.
+    fn copy_render_target(
+        &mut self,
+        target: &Self::RenderTarget,
+    ) -> Result<Self::Texture, &'static str>;
+
+    /// Replaces the target's content (of the same size as the textures)
+    /// with the source blended over the destination copy.
+    fn blend_composite(
+        &mut self,
+        target: &Self::RenderTarget,
+        source: &Self::Texture,
+        destination: &Self::Texture,
+        blend_mode: BlendMode,
+    );
+
+    // clipping
+
+    /// Limits drawing on the target to the area inside (intersect)
+    /// or outside (difference) of the paths, within the current clip area.
+    fn push_clip_paths(
+        &mut self,
+        target: &Self::RenderTarget,
+        paths: &[Path],
+        operation: ClipOperation,
+        transform: PixelToDeviceTransform,
+    );
+
+    /// Restores the clip area from before the last `push_clip_paths()` call.
+    fn pop_clip_paths(&mut self, target: &Self::RenderTarget);
+
     // state
 
+    /// Sets the color filter applied to the colors of all following draws.
+    fn set_color_filter(&mut self, color_filter: Option<&ColorFilter>);
+
     fn save_state(&mut self) {}
 
     fn restore_state(&mut self) {}
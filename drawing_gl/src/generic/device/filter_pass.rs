@@ -0,0 +1,9 @@
+use drawing_api::TileMode;
+
+/// One dimensional image filter applied in a single render pass.
+#[derive(Debug, Copy, Clone)]
+pub enum FilterPass {
+    Blur { sigma: f32, tile_mode: TileMode },
+    Dilate { radius: f32 },
+    Erode { radius: f32 },
+}
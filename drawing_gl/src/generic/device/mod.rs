@@ -7,6 +7,9 @@ pub use colored_vertex::*;
 mod device;
 pub use device::*;
 
+mod filter_pass;
+pub use filter_pass::*;
+
 mod paint;
 pub use paint::*;
 
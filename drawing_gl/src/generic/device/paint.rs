@@ -3,11 +3,22 @@
 // which was translated from https://github.com/memononen/nanovg (zlib license)
 
 use crate::units::PixelTransform;
-use crate::Brush;
-use drawing_api::PixelRect;
+use crate::{Brush, GradientShape};
+use drawing_api::{PixelRect, TextureSampling, TileMode};
 
 use super::Color;
 
+/// Number of texels of the gradient color ramp.
+pub const GRADIENT_RAMP_SIZE: usize = 256;
+
+#[derive(Debug, Copy, Clone)]
+pub struct GradientPaint {
+    pub shape: GradientShape,
+    pub tile_mode: TileMode,
+    /// premultiplied RGBA colors sampled along the gradient
+    pub ramp: [[u8; 4]; GRADIENT_RAMP_SIZE],
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Paint<Texture1: drawing_api::Texture> {
     pub xform: PixelTransform,
@@ -17,6 +28,10 @@ pub struct Paint<Texture1: drawing_api::Texture> {
     pub inner_color: Color,
     pub outer_color: Color,
     pub image: Option<Texture1>,
+    /// horizontal and vertical tile modes of the image
+    pub tile_modes: [TileMode; 2],
+    pub filtering: bool,
+    pub gradient: Option<GradientPaint>,
 }
 
 impl<Texture1: drawing_api::Texture> Paint<Texture1> {
@@ -31,6 +46,9 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                     inner_color: *color,
                     outer_color: *color,
                     image: None,
+                    tile_modes: [TileMode::Clamp, TileMode::Clamp],
+                    filtering: true,
+                    gradient: None,
                 },
                 None,
             ),
@@ -71,6 +89,9 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                         inner_color: *inner_color,
                         outer_color: *outer_color,
                         image: None,
+                        tile_modes: [TileMode::Clamp, TileMode::Clamp],
+                        filtering: true,
+                        gradient: None,
                     },
                     None,
                 )
@@ -101,6 +122,9 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                         inner_color: *inner_color,
                         outer_color: *outer_color,
                         image: None,
+                        tile_modes: [TileMode::Clamp, TileMode::Clamp],
+                        filtering: true,
+                        gradient: None,
                     },
                     None,
                 )
@@ -130,6 +154,9 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                         inner_color: *inner_color,
                         outer_color: *outer_color,
                         image: None,
+                        tile_modes: [TileMode::Clamp, TileMode::Clamp],
+                        filtering: true,
+                        gradient: None,
                     },
                     None,
                 )
@@ -139,6 +166,9 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                 texture,
                 transform,
                 alpha,
+                horizontal_tile_mode,
+                vertical_tile_mode,
+                sampling,
             } => {
                 let extent_size = {
                     let descriptor = texture.get_descriptor();
@@ -153,12 +183,100 @@ impl<Texture1: drawing_api::Texture> Paint<Texture1> {
                         inner_color: [1.0, 1.0, 1.0, *alpha],
                         outer_color: [1.0, 1.0, 1.0, *alpha],
                         image: Some(texture.clone()),
+                        tile_modes: [*horizontal_tile_mode, *vertical_tile_mode],
+                        filtering: *sampling == TextureSampling::Linear,
+                        gradient: None,
                     },
                     Some(texture.clone()),
                 )
             }
+
+            Brush::Gradient {
+                shape,
+                colors,
+                stops,
+                tile_mode,
+                transform,
+            } => (
+                Paint {
+                    xform: *transform,
+                    extent: [0.0, 0.0],
+                    radius: 0.0,
+                    feather: 1.0,
+                    inner_color: [1.0, 1.0, 1.0, 1.0],
+                    outer_color: [1.0, 1.0, 1.0, 1.0],
+                    image: None,
+                    tile_modes: [TileMode::Clamp, TileMode::Clamp],
+                    filtering: true,
+                    gradient: Some(GradientPaint {
+                        shape: *shape,
+                        tile_mode: *tile_mode,
+                        ramp: gradient_ramp(colors, stops),
+                    }),
+                },
+                None,
+            ),
+        }
+    }
+}
+
+/// Samples the color stops into a premultiplied color ramp.
+///
+/// When the number of stops doesn't match the number of colors,
+/// the colors are distributed evenly.
+fn gradient_ramp(colors: &[Color], stops: &[f32]) -> [[u8; 4]; GRADIENT_RAMP_SIZE] {
+    let mut ramp = [[0u8; 4]; GRADIENT_RAMP_SIZE];
+    if colors.is_empty() {
+        return ramp;
+    }
+
+    let stop = |i: usize| {
+        if stops.len() == colors.len() {
+            stops[i]
+        } else if colors.len() > 1 {
+            i as f32 / (colors.len() - 1) as f32
+        } else {
+            0.0
+        }
+    };
+
+    let mut index = 0;
+    for (i, texel) in ramp.iter_mut().enumerate() {
+        let t = i as f32 / (GRADIENT_RAMP_SIZE - 1) as f32;
+        while index + 1 < colors.len() && stop(index + 1) < t {
+            index += 1;
         }
+
+        let color = if t <= stop(0) {
+            colors[0]
+        } else if index + 1 >= colors.len() {
+            colors[colors.len() - 1]
+        } else {
+            let (t0, t1) = (stop(index), stop(index + 1));
+            let f = if t1 > t0 {
+                ((t - t0) / (t1 - t0)).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            let (c0, c1) = (colors[index], colors[index + 1]);
+            [
+                c0[0] + (c1[0] - c0[0]) * f,
+                c0[1] + (c1[1] - c0[1]) * f,
+                c0[2] + (c1[2] - c0[2]) * f,
+                c0[3] + (c1[3] - c0[3]) * f,
+            ]
+        };
+
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        *texel = [
+            to_u8(color[0] * color[3]),
+            to_u8(color[1] * color[3]),
+            to_u8(color[2] * color[3]),
+            to_u8(color[3]),
+        ];
     }
+
+    ramp
 }
 
 /*impl drawing_api::Paint for Paint<GlTexture> {
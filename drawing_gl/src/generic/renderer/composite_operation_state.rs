@@ -1,6 +1,7 @@
 use crate::BasicCompositeOperation;
 use crate::BlendFactor;
 use crate::CompositeOperation;
+use drawing_api::BlendMode;
 
 #[derive(Debug, Copy, Clone)]
 pub struct CompositeOperationState {
@@ -69,3 +70,48 @@ impl From<CompositeOperation> for CompositeOperationState {
         }
     }
 }
+
+/// Returns the composite operation realizing the blend mode with blend factors.
+///
+/// Returns `None` for the advanced blend modes, which need the destination
+/// color in the shader.
+pub fn blend_mode_to_composite_operation(blend_mode: BlendMode) -> Option<CompositeOperation> {
+    match blend_mode {
+        BlendMode::Clear => Some(CompositeOperation::BlendFunc {
+            src: BlendFactor::Zero,
+            dst: BlendFactor::Zero,
+        }),
+        BlendMode::Source => Some(CompositeOperation::Basic(BasicCompositeOperation::Copy)),
+        BlendMode::Destination => Some(CompositeOperation::BlendFunc {
+            src: BlendFactor::Zero,
+            dst: BlendFactor::One,
+        }),
+        BlendMode::SourceOver => Some(CompositeOperation::Basic(BasicCompositeOperation::SrcOver)),
+        BlendMode::DestinationOver => {
+            Some(CompositeOperation::Basic(BasicCompositeOperation::DstOver))
+        }
+        BlendMode::SourceIn => Some(CompositeOperation::Basic(BasicCompositeOperation::SrcIn)),
+        BlendMode::DestinationIn => Some(CompositeOperation::Basic(BasicCompositeOperation::DstIn)),
+        BlendMode::SourceOut => Some(CompositeOperation::Basic(BasicCompositeOperation::SrcOut)),
+        BlendMode::DestinationOut => {
+            Some(CompositeOperation::Basic(BasicCompositeOperation::DstOut))
+        }
+        BlendMode::SourceATop => Some(CompositeOperation::Basic(BasicCompositeOperation::Atop)),
+        BlendMode::DestinationATop => {
+            Some(CompositeOperation::Basic(BasicCompositeOperation::DstAtop))
+        }
+        BlendMode::Xor => Some(CompositeOperation::Basic(BasicCompositeOperation::Xor)),
+        BlendMode::Plus => Some(CompositeOperation::Basic(BasicCompositeOperation::Lighter)),
+        // src * dst
+        BlendMode::Modulate => Some(CompositeOperation::BlendFunc {
+            src: BlendFactor::Zero,
+            dst: BlendFactor::SrcColor,
+        }),
+        // src + dst - src * dst
+        BlendMode::Screen => Some(CompositeOperation::BlendFunc {
+            src: BlendFactor::One,
+            dst: BlendFactor::OneMinusSrcColor,
+        }),
+        _ => None,
+    }
+}
@@ -1,3 +1,5 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
 use crate::PathElement;
 use drawing_api::*;
 
@@ -121,6 +123,74 @@ pub fn rect_rounded_varying_path<R: Into<PixelRect>>(
     }
 }
 
+/// Creates a rounded rectangle path with separate horizontal and vertical radius for every corner.
+///
+/// When the radii of the neighbouring corners do not fit on the side of the rectangle,
+/// all the radii are scaled down proportionally.
+pub fn rect_rounded_radii_path<R: Into<PixelRect>>(
+    rect: R,
+    radii: &RoundingRadii,
+) -> Vec<PathElement> {
+    let rect = rect.into();
+    let (left, top, right, bottom) = (rect.min_x(), rect.min_y(), rect.max_x(), rect.max_y());
+    let (width, height) = (right - left, bottom - top);
+
+    let clamp = |size: PixelSize| PixelSize::new(size.width.max(0.0), size.height.max(0.0));
+    let lt = clamp(radii.top_left);
+    let rt = clamp(radii.top_right);
+    let rb = clamp(radii.bottom_right);
+    let lb = clamp(radii.bottom_left);
+
+    let mut scale = 1.0f32;
+    for (length, r1, r2) in [
+        (width, lt.width, rt.width),
+        (width, lb.width, rb.width),
+        (height, lt.height, lb.height),
+        (height, rt.height, rb.height),
+    ] {
+        if r1 + r2 > length {
+            scale = scale.min(length / (r1 + r2));
+        }
+    }
+    let (lt, rt, rb, lb) = (lt * scale, rt * scale, rb * scale, lb * scale);
+
+    if [lt, rt, rb, lb]
+        .iter()
+        .all(|r| r.width < 0.1 || r.height < 0.1)
+    {
+        return rect_path(rect);
+    }
+
+    vec![
+        PathElement::MoveTo(PixelPoint::new(left, top + lt.height)),
+        PathElement::LineTo(PixelPoint::new(left, bottom - lb.height)),
+        PathElement::BezierTo(
+            PixelPoint::new(left, bottom - lb.height * (1.0 - KAPPA90)),
+            PixelPoint::new(left + lb.width * (1.0 - KAPPA90), bottom),
+            PixelPoint::new(left + lb.width, bottom),
+        ),
+        PathElement::LineTo(PixelPoint::new(right - rb.width, bottom)),
+        PathElement::BezierTo(
+            PixelPoint::new(right - rb.width * (1.0 - KAPPA90), bottom),
+            PixelPoint::new(right, bottom - rb.height * (1.0 - KAPPA90)),
+            PixelPoint::new(right, bottom - rb.height),
+        ),
+        PathElement::LineTo(PixelPoint::new(right, top + rt.height)),
+        PathElement::BezierTo(
+            PixelPoint::new(right, top + rt.height * (1.0 - KAPPA90)),
+            PixelPoint::new(right - rt.width * (1.0 - KAPPA90), top),
+            PixelPoint::new(right - rt.width, top),
+        ),
+        PathElement::LineTo(PixelPoint::new(left + lt.width, top)),
+        PathElement::BezierTo(
+            PixelPoint::new(left + lt.width * (1.0 - KAPPA90), top),
+            PixelPoint::new(left, top + lt.height * (1.0 - KAPPA90)),
+            PixelPoint::new(left, top + lt.height),
+        ),
+        PathElement::ClosePath,
+    ]
+}
+
 pub fn rect_rounded_varying_path_half<R: Into<PixelRect>>(
     rect: R,
     lt: f32,
@@ -252,6 +322,127 @@ pub fn ellipse_path<P: Into<PixelPoint>>(
     ]
 }
 
+/// Creates an ellipse path inscribed in the given rectangle.
+pub fn oval_path<R: Into<PixelRect>>(oval_bounds: R) -> Vec<PathElement> {
+    let oval_bounds = oval_bounds.into();
+    ellipse_path(
+        oval_bounds.center(),
+        oval_bounds.size.width * 0.5f32,
+        oval_bounds.size.height * 0.5f32,
+    )
+}
+
+/// Creates an open path of the elliptical arc inscribed in the given rectangle.
+///
+/// # Arguments
+///
+/// * `oval_bounds` - bounds of the ellipse
+/// * `start_angle_degrees` - angle of the starting point, measured clockwise from the positive x-axis
+/// * `end_angle_degrees` - angle of the ending point, the arc is drawn clockwise when it is greater than the start angle
+pub fn arc_path<R: Into<PixelRect>>(
+    oval_bounds: R,
+    start_angle_degrees: f32,
+    end_angle_degrees: f32,
+) -> Vec<PathElement> {
+    let oval_bounds = oval_bounds.into();
+    let center = oval_bounds.center();
+    let radius_x = oval_bounds.size.width * 0.5f32;
+    let radius_y = oval_bounds.size.height * 0.5f32;
+
+    let start_angle = start_angle_degrees.to_radians();
+    let sweep_angle = (end_angle_degrees - start_angle_degrees)
+        .to_radians()
+        .clamp(-2.0f32 * PI, 2.0f32 * PI);
+
+    // every bezier segment spans at most 90 degrees
+    let segments = ((sweep_angle.abs() / FRAC_PI_2).ceil() as usize).max(1);
+    let delta = sweep_angle / segments as f32;
+    let kappa = 4.0f32 / 3.0f32 * (delta * 0.25f32).tan();
+
+    let mut res = Vec::with_capacity(segments + 1);
+    let (sin0, cos0) = start_angle.sin_cos();
+    res.push(PathElement::MoveTo(PixelPoint::new(
+        center.x + radius_x * cos0,
+        center.y + radius_y * sin0,
+    )));
+
+    for i in 0..segments {
+        let (sin0, cos0) = (start_angle + delta * i as f32).sin_cos();
+        let (sin1, cos1) = (start_angle + delta * (i + 1) as f32).sin_cos();
+        res.push(PathElement::BezierTo(
+            PixelPoint::new(
+                center.x + radius_x * (cos0 - kappa * sin0),
+                center.y + radius_y * (sin0 + kappa * cos0),
+            ),
+            PixelPoint::new(
+                center.x + radius_x * (cos1 + kappa * sin1),
+                center.y + radius_y * (sin1 - kappa * cos1),
+            ),
+            PixelPoint::new(center.x + radius_x * cos1, center.y + radius_y * sin1),
+        ));
+    }
+
+    res
+}
+
+/// Reverses the direction of all the sub-paths.
+///
+/// Useful to create holes in shapes filled with the non-zero winding rule.
+pub fn reverse_path(path: &[PathElement]) -> Vec<PathElement> {
+    let mut res = Vec::with_capacity(path.len());
+    let mut start = 0;
+    for i in 1..=path.len() {
+        if i == path.len() || matches!(path[i], PathElement::MoveTo(_)) {
+            reverse_sub_path(&path[start..i], &mut res);
+            start = i;
+        }
+    }
+    res
+}
+
+fn reverse_sub_path(path: &[PathElement], res: &mut Vec<PathElement>) {
+    let mut current: Option<PixelPoint> = None;
+    let mut segments = Vec::with_capacity(path.len());
+    let mut solidity = None;
+    let mut closed = false;
+
+    for path_element in path {
+        match path_element {
+            PathElement::MoveTo(point) => current = Some(*point),
+            PathElement::LineTo(point) => {
+                if let Some(from) = current {
+                    segments.push((from, path_element.clone()));
+                }
+                current = Some(*point);
+            }
+            PathElement::BezierTo(_, _, point) => {
+                if let Some(from) = current {
+                    segments.push((from, path_element.clone()));
+                }
+                current = Some(*point);
+            }
+            PathElement::ClosePath => closed = true,
+            PathElement::Solidity(s) => solidity = Some(*s),
+        }
+    }
+
+    if let Some(last_point) = current {
+        res.push(PathElement::MoveTo(last_point));
+        if let Some(solidity) = solidity {
+            res.push(PathElement::Solidity(solidity));
+        }
+        for (from, segment) in segments.into_iter().rev() {
+            match segment {
+                PathElement::BezierTo(c1, c2, _) => res.push(PathElement::BezierTo(c2, c1, from)),
+                _ => res.push(PathElement::LineTo(from)),
+            }
+        }
+        if closed {
+            res.push(PathElement::ClosePath);
+        }
+    }
+}
+
 /// Creates a path that represents pixel aligned rectangle path to be used with stroke.
 ///
 /// # Arguments
@@ -1,11 +1,14 @@
-use crate::generic::device::{Device, RenderTarget};
+use crate::generic::device::{Device, FilterPass, RenderTarget};
+use crate::generic::renderer::{
+    blend_mode_to_composite_operation, rect_path, CompositeOperationState,
+};
 use crate::generic::texture_font::Font;
 use crate::units::{PixelToUvTransform, PixelTransform};
 use crate::Fonts;
 use crate::{
-    display_list::StrokeStyle,
+    display_list::{ImageFilter, StrokeStyle},
     generic::{clipping::Scissor, path::FlattenedPath, texture_font::FontParams},
-    BasicCompositeOperation, CompositeOperation, LineJoin, PathElement, Primitive,
+    BasicCompositeOperation, Brush, CompositeOperation, LineJoin, PathElement, Primitive,
 };
 use drawing_api::*;
 
@@ -38,6 +41,8 @@ impl Renderer {
             antialiasing,
             PixelTransform::identity(),
             Scissor::empty(),
+            None,
+            CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
         )
     }
 
@@ -49,10 +54,14 @@ impl Renderer {
         antialiasing: bool,
         pixel_transform: PixelTransform,
         scissor: Scissor,
+        color_filter: Option<&ColorFilter>,
+        composite_operation: CompositeOperationState,
     ) -> Result<(), &'static str> {
         let pixel_to_device_transform = pixel_transform.then(&render_target.get_device_transform());
 
         for primitive in primitives {
+            device.set_color_filter(color_filter);
+
             match primitive {
                 Primitive::Clear { color } => {
                     device.clear(render_target, color);
@@ -119,6 +128,29 @@ impl Renderer {
                     }
                 }
 
+                Primitive::Glyphs {
+                    fonts,
+                    family_name,
+                    color,
+                    position,
+                    clipping_rect,
+                    size,
+                    glyphs,
+                } => {
+                    if let Some(font) = fonts.data.lock().unwrap().fonts.get_mut(family_name) {
+                        font.draw_glyphs(
+                            device,
+                            render_target,
+                            color,
+                            glyphs,
+                            *position,
+                            *clipping_rect,
+                            FontParams { size: *size as u8 },
+                            pixel_to_device_transform,
+                        )?;
+                    }
+                }
+
                 Primitive::Stroke {
                     path,
                     thickness,
@@ -141,13 +173,13 @@ impl Renderer {
                         render_target,
                         &paint,
                         texture.as_ref(),
-                        true,
+                        paint.filtering,
                         &flattened_path.paths,
                         stroke_width,
                         1.0f32 / aspect_ratio,
                         antialiasing,
                         scissor,
-                        CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                        composite_operation,
                         pixel_to_device_transform,
                     );
                 }
@@ -175,13 +207,13 @@ impl Renderer {
                         render_target,
                         &paint,
                         texture.as_ref(),
-                        true,
+                        paint.filtering,
                         &flattened_path.paths,
                         stroke_width,
                         1.0f32 / aspect_ratio,
                         antialiasing,
                         scissor,
-                        CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                        composite_operation,
                         pixel_to_device_transform,
                     );
                 }
@@ -196,13 +228,13 @@ impl Renderer {
                         render_target,
                         &paint,
                         texture.as_ref(),
-                        true,
+                        paint.filtering,
                         &flattened_path.paths,
                         flattened_path.bounds,
                         1.0f32 / aspect_ratio,
                         antialiasing,
                         scissor,
-                        CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                        composite_operation,
                         pixel_to_device_transform,
                     );
                 }
@@ -215,16 +247,37 @@ impl Renderer {
                         antialiasing,
                         pixel_transform,
                         scissor.intersect_with_rect(*rect, &pixel_transform),
+                        color_filter,
+                        composite_operation,
                     )?;
                 }
 
-                Primitive::ClipPath { .. } => {
-                    /*device.save_state();
-
-                    device.set_clip_path(path);
-                    self.draw(device, render_target, primitives, resources, antialiasing)?;
+                Primitive::ClipPath {
+                    path,
+                    operation,
+                    primitives,
+                } => {
+                    let aspect_ratio = render_target.get_aspect_ratio();
+                    let flattened_path = Self::get_fill_path(path, aspect_ratio, false);
 
-                    device.restore_state();*/
+                    device.push_clip_paths(
+                        render_target,
+                        &flattened_path.paths,
+                        *operation,
+                        pixel_to_device_transform,
+                    );
+                    let result = self.draw_internal(
+                        device,
+                        render_target,
+                        primitives,
+                        antialiasing,
+                        pixel_transform,
+                        scissor,
+                        color_filter,
+                        composite_operation,
+                    );
+                    device.pop_clip_paths(render_target);
+                    result?;
                 }
 
                 Primitive::Transform {
@@ -238,42 +291,412 @@ impl Renderer {
                         antialiasing,
                         transform.then(&pixel_transform),
                         scissor.apply_transform(transform),
+                        color_filter,
+                        composite_operation,
+                    )?;
+                }
+
+                Primitive::Composite {
+                    bounds,
+                    alpha,
+                    backdrop_filter,
+                    primitives,
+                } => {
+                    // the layer covers its bounds (in the device pixels) within the target
+                    let size = render_target.get_size();
+                    let layer_rect = pixel_transform
+                        .outer_transformed_rect(bounds)
+                        .intersection(&PixelRect::new(
+                            PixelPoint::new(0.0f32, 0.0f32),
+                            PixelSize::new(size.0 as f32, size.1 as f32),
+                        ))
+                        .map(|rect| rect.round_out());
+                    let layer_rect = match layer_rect {
+                        Some(rect) if !rect.is_empty() => rect,
+                        _ => continue,
+                    };
+
+                    let (texture, texture_view) = device.create_render_target(
+                        layer_rect.size.width as u16,
+                        layer_rect.size.height as u16,
+                    )?;
+                    device.clear(&texture_view, &[0.0f32, 0.0f32, 0.0f32, 0.0f32]);
+                    let to_layer =
+                        PixelTransform::translation(-layer_rect.origin.x, -layer_rect.origin.y);
+
+                    // the layer starts with the filtered content below it
+                    if let Some(filter) = backdrop_filter {
+                        device.set_color_filter(None);
+                        let backdrop = device.copy_render_target(render_target)?;
+                        let backdrop = Self::apply_image_filter(
+                            device,
+                            backdrop,
+                            size,
+                            filter,
+                            &pixel_transform,
+                        )?;
+                        Self::draw_texture_over_target(
+                            device,
+                            &texture_view,
+                            &backdrop,
+                            to_layer,
+                            false,
+                            Scissor::empty(),
+                            CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                        );
+                    }
+
+                    self.draw_internal(
+                        device,
+                        &texture_view,
+                        primitives,
+                        antialiasing,
+                        pixel_transform.then(&to_layer),
+                        Scissor::new(*bounds),
+                        None,
+                        CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                    )?;
+
+                    // draw the layer's bounds with the layer texture in the local coordinates
+                    let composite = [Primitive::Fill {
+                        path: rect_path(*bounds),
+                        brush: Brush::ImagePattern {
+                            texture: texture.clone(),
+                            transform: PixelTransform::translation(
+                                layer_rect.origin.x,
+                                layer_rect.origin.y,
+                            )
+                            .then(
+                                &pixel_transform
+                                    .inverse()
+                                    .unwrap_or_else(PixelTransform::identity),
+                            ),
+                            alpha: *alpha,
+                            horizontal_tile_mode: TileMode::Decal,
+                            vertical_tile_mode: TileMode::Decal,
+                            sampling: TextureSampling::Linear,
+                        },
+                    }];
+                    self.draw_internal(
+                        device,
+                        render_target,
+                        &composite,
+                        antialiasing,
+                        pixel_transform,
+                        scissor,
+                        color_filter,
+                        composite_operation,
                     )?;
                 }
 
-                Primitive::Composite { color, primitives } => {
+                Primitive::Filter { filter, primitives } => {
                     let size = render_target.get_size();
-                    let (texture2, texture2_view) =
+                    let (texture, texture_view) =
                         device.create_render_target(size.0 as u16, size.1 as u16)?;
+                    device.clear(&texture_view, &[0.0f32, 0.0f32, 0.0f32, 0.0f32]);
 
-                    device.clear(&texture2_view, &[0.0f32, 0.0f32, 0.0f32, 0.0f32]);
+                    self.draw_internal(
+                        device,
+                        &texture_view,
+                        primitives,
+                        antialiasing,
+                        pixel_transform,
+                        Scissor::empty(),
+                        color_filter,
+                        CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                    )?;
 
-                    self.draw(&texture2_view, primitives, antialiasing)?;
+                    device.set_color_filter(None);
+                    let texture =
+                        Self::apply_image_filter(device, texture, size, filter, &pixel_transform)?;
 
-                    let pixel_to_uv_transform = Self::get_uv_transform(texture2.get_descriptor());
-                    device.rect_textured(
+                    Self::draw_texture_over_target(
+                        device,
                         render_target,
-                        &texture2,
+                        &texture,
+                        PixelTransform::identity(),
                         false,
-                        color,
-                        PixelRect::new(
-                            PixelPoint::new(0.0f32, 0.0f32),
-                            PixelSize::new(size.0 as f32, size.1 as f32),
-                        ),
-                        PixelRect::new(
-                            PixelPoint::new(0.0f32, 0.0f32),
-                            PixelSize::new(size.0 as f32, size.1 as f32),
-                        ),
-                        pixel_to_device_transform,
-                        pixel_to_uv_transform,
+                        scissor,
+                        composite_operation,
                     );
                 }
+
+                Primitive::ColorFilter { filter, primitives } => {
+                    self.draw_internal(
+                        device,
+                        render_target,
+                        primitives,
+                        antialiasing,
+                        pixel_transform,
+                        scissor,
+                        Some(filter),
+                        composite_operation,
+                    )?;
+                }
+
+                Primitive::Blend {
+                    blend_mode,
+                    primitives,
+                } => {
+                    if let Some(operation) = blend_mode_to_composite_operation(*blend_mode) {
+                        self.draw_internal(
+                            device,
+                            render_target,
+                            primitives,
+                            antialiasing,
+                            pixel_transform,
+                            scissor,
+                            color_filter,
+                            operation.into(),
+                        )?;
+                    } else {
+                        // advanced blend modes: draw the content offscreen
+                        // and blend it with a copy of the destination
+                        let size = render_target.get_size();
+                        let (texture, texture_view) =
+                            device.create_render_target(size.0, size.1)?;
+                        device.clear(&texture_view, &[0.0f32, 0.0f32, 0.0f32, 0.0f32]);
+
+                        self.draw_internal(
+                            device,
+                            &texture_view,
+                            primitives,
+                            antialiasing,
+                            pixel_transform,
+                            scissor,
+                            color_filter,
+                            CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                        )?;
+
+                        let destination = device.copy_render_target(render_target)?;
+                        device.blend_composite(render_target, &texture, &destination, *blend_mode);
+                    }
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Applies the filter to the texture with the content of the whole render target.
+    ///
+    /// The filter parameters are given in the coordinate space of the content,
+    /// so they are scaled with the pixel transformation.
+    fn apply_image_filter<D: Device>(
+        device: &mut D,
+        texture: D::Texture,
+        size: (u16, u16),
+        filter: &ImageFilter,
+        pixel_transform: &PixelTransform,
+    ) -> Result<D::Texture, &'static str> {
+        let scale_x = (pixel_transform.m11 * pixel_transform.m11
+            + pixel_transform.m12 * pixel_transform.m12)
+            .sqrt();
+        let scale_y = (pixel_transform.m21 * pixel_transform.m21
+            + pixel_transform.m22 * pixel_transform.m22)
+            .sqrt();
+
+        match filter {
+            ImageFilter::Blur {
+                x_sigma,
+                y_sigma,
+                tile_mode,
+            } => {
+                let texture = Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Blur {
+                        sigma: x_sigma * scale_x,
+                        tile_mode: *tile_mode,
+                    },
+                    true,
+                )?;
+                Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Blur {
+                        sigma: y_sigma * scale_y,
+                        tile_mode: *tile_mode,
+                    },
+                    false,
+                )
+            }
+
+            ImageFilter::Dilate { x_radius, y_radius } => {
+                let texture = Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Dilate {
+                        radius: x_radius * scale_x,
+                    },
+                    true,
+                )?;
+                Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Dilate {
+                        radius: y_radius * scale_y,
+                    },
+                    false,
+                )
+            }
+
+            ImageFilter::Erode { x_radius, y_radius } => {
+                let texture = Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Erode {
+                        radius: x_radius * scale_x,
+                    },
+                    true,
+                )?;
+                Self::filter_pass(
+                    device,
+                    texture,
+                    size,
+                    FilterPass::Erode {
+                        radius: y_radius * scale_y,
+                    },
+                    false,
+                )
+            }
+
+            ImageFilter::Matrix {
+                transform,
+                filtering,
+            } => {
+                // the matrix is applied in the content's coordinate space
+                let transform = pixel_transform
+                    .inverse()
+                    .unwrap_or_else(PixelTransform::identity)
+                    .then(transform)
+                    .then(pixel_transform);
+
+                let (result, result_view) = device.create_render_target(size.0, size.1)?;
+                device.clear(&result_view, &[0.0f32, 0.0f32, 0.0f32, 0.0f32]);
+                Self::draw_texture_over_target(
+                    device,
+                    &result_view,
+                    &texture,
+                    transform,
+                    *filtering,
+                    Scissor::empty(),
+                    CompositeOperation::Basic(BasicCompositeOperation::SrcOver).into(),
+                );
+                Ok(result)
+            }
+
+            ImageFilter::MaskBlur { sigma, style } => {
+                let blurred = Self::filter_pass(
+                    device,
+                    texture.clone(),
+                    size,
+                    FilterPass::Blur {
+                        sigma: sigma * scale_x,
+                        tile_mode: TileMode::Decal,
+                    },
+                    true,
+                )?;
+                let blurred = Self::filter_pass(
+                    device,
+                    blurred,
+                    size,
+                    FilterPass::Blur {
+                        sigma: sigma * scale_y,
+                        tile_mode: TileMode::Decal,
+                    },
+                    false,
+                )?;
+
+                if *style == BlurStyle::Normal {
+                    return Ok(blurred);
+                }
+
+                let (result, result_view) = device.create_render_target(size.0, size.1)?;
+                device.blur_mask_composite(&result_view, &blurred, &texture, *style);
+                Ok(result)
+            }
+
+            ImageFilter::Compose { outer, inner } => {
+                let texture =
+                    Self::apply_image_filter(device, texture, size, inner, pixel_transform)?;
+                Self::apply_image_filter(device, texture, size, outer, pixel_transform)
+            }
+        }
+    }
+
+    fn filter_pass<D: Device>(
+        device: &mut D,
+        texture: D::Texture,
+        size: (u16, u16),
+        pass: FilterPass,
+        horizontal: bool,
+    ) -> Result<D::Texture, &'static str> {
+        let is_identity = match pass {
+            FilterPass::Blur { sigma, .. } => sigma <= 0.0f32,
+            FilterPass::Dilate { radius } | FilterPass::Erode { radius } => radius < 0.5f32,
+        };
+        if is_identity {
+            return Ok(texture);
+        }
+
+        let (result, result_view) = device.create_render_target(size.0, size.1)?;
+        device.filter_pass(&result_view, &texture, pass, horizontal);
+        Ok(result)
+    }
+
+    /// Draws the texture of the render target's size over the whole render target.
+    fn draw_texture_over_target<D: Device>(
+        device: &mut D,
+        render_target: &D::RenderTarget,
+        texture: &D::Texture,
+        transform: PixelTransform,
+        filtering: bool,
+        scissor: Scissor,
+        composite_operation: CompositeOperationState,
+    ) {
+        let size = render_target.get_size();
+        let path = rect_path(PixelRect::new(
+            PixelPoint::new(0.0f32, 0.0f32),
+            PixelSize::new(size.0 as f32, size.1 as f32),
+        ));
+        let brush = Brush::ImagePattern {
+            texture: texture.clone(),
+            transform,
+            alpha: 1.0f32,
+            horizontal_tile_mode: TileMode::Decal,
+            vertical_tile_mode: TileMode::Decal,
+            sampling: if filtering {
+                TextureSampling::Linear
+            } else {
+                TextureSampling::NearestNeighbor
+            },
+        };
+
+        let aspect_ratio = render_target.get_aspect_ratio();
+        let flattened_path = Self::get_fill_path(&path, aspect_ratio, false);
+        let (paint, texture) = crate::generic::device::Paint::from_brush(&brush);
+
+        device.fill(
+            render_target,
+            &paint,
+            texture.as_ref(),
+            paint.filtering,
+            &flattened_path.paths,
+            flattened_path.bounds,
+            1.0f32 / aspect_ratio,
+            false,
+            scissor,
+            composite_operation,
+            render_target.get_device_transform(),
+        );
+    }
+
     fn get_stroke_path(
         path: &[PathElement],
         stroke_width: f32,
@@ -1,4 +1,8 @@
-use crate::{generic::device::Device, units::PixelToDeviceTransform};
+use crate::{
+    display_list::Glyph,
+    generic::{device::Device, texture_font::ShapedGlyph},
+    units::PixelToDeviceTransform,
+};
 use drawing_api::*;
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone)]
@@ -6,6 +10,16 @@ pub struct FontParams {
     pub size: u8,
 }
 
+/// Vertical metrics of the font (in pixels).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top of the line.
+    pub ascent: f32,
+
+    /// Distance from the baseline to the bottom of the line.
+    pub descent: f32,
+}
+
 pub trait Font<D: Device> {
     /// Safe to call from any thread for any device type (even for OpenGL).
     /// To achieve it the device specific resources creation (like texture)
@@ -32,6 +46,24 @@ pub trait Font<D: Device> {
         text: &str,
     ) -> Result<(Vec<i16>, u16), &'static str>;
 
+    /// Safe to call from any thread for any device type (even for OpenGL).
+    /// To achieve it the device specific resources creation (like texture)
+    /// is delayed to the first draw() call.
+    fn get_metrics(&mut self, params: FontParams) -> Result<FontMetrics, &'static str>;
+
+    /// Converts the text of the same direction into the glyphs (in the logical order)
+    /// with their advances and offsets.
+    ///
+    /// Safe to call from any thread for any device type (even for OpenGL).
+    /// To achieve it the device specific resources creation (like texture)
+    /// is delayed to the first draw() call.
+    fn shape(
+        &mut self,
+        params: FontParams,
+        text: &str,
+        direction: TextDirection,
+    ) -> Result<Vec<ShapedGlyph>, &'static str>;
+
     // Not safe to call from any thread for some device types (like OpenGL).
     fn draw(
         &mut self,
@@ -44,4 +76,18 @@ pub trait Font<D: Device> {
         font_params: FontParams,
         transform: PixelToDeviceTransform,
     ) -> Result<(), &'static str>;
+
+    /// Draws the glyphs placed on the baseline relative to `pos`.
+    // Not safe to call from any thread for some device types (like OpenGL).
+    fn draw_glyphs(
+        &mut self,
+        device: &mut D,
+        target: &D::RenderTarget,
+        color: &crate::generic::device::Color,
+        glyphs: &[Glyph],
+        pos: PixelPoint,
+        clipping_rect: Option<PixelRect>,
+        font_params: FontParams,
+        transform: PixelToDeviceTransform,
+    ) -> Result<(), &'static str>;
 }
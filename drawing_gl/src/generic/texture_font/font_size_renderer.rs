@@ -25,10 +25,11 @@
 
 #![deny(missing_docs)]
 
+use crate::display_list::Glyph;
 use crate::generic::clipping::clip_image;
 use crate::generic::device::Device;
 use crate::generic::device::TexturedY8Vertex;
-use crate::generic::texture_font::bitmap_font::BitmapFont;
+use crate::generic::texture_font::bitmap_font::{BitmapFont, BitmapGlyph};
 use crate::units::PixelToDeviceTransform;
 use drawing_api::ColorFormat;
 
@@ -45,13 +46,13 @@ use drawing_api::ColorFormat;
 /// ```
 pub struct FontSizeRenderer<D: Device> {
     bitmap_font: BitmapFont,
-    texture: Option<D::Texture>,
+    texture: Option<(D::Texture, u32)>,
     vertex_data: Vec<TexturedY8Vertex>,
 }
 
 impl<D: Device> FontSizeRenderer<D> {
     pub fn new(font_data: &[u8], font_size: u8) -> Result<Self, &'static str> {
-        let bitmap_font = BitmapFont::from_bytes(font_data, font_size, None)?;
+        let bitmap_font = BitmapFont::from_bytes(font_data, font_size)?;
 
         Ok(FontSizeRenderer {
             bitmap_font,
@@ -88,59 +89,39 @@ impl<D: Device> FontSizeRenderer<D> {
                     // in the font too.
                     None => continue,
                 };
-                let x_offset = x + ch_info.x_offset as f32;
-                let y_offset = y + ch_info.y_offset as f32;
-                let tex = ch_info.tex;
-
-                if let Some(clipping_rect) = clipping_rect {
-                    if let Some(clipped) = clip_image(
-                        x_offset,
-                        y_offset,
-                        ch_info.width as f32,
-                        ch_info.height as f32,
-                        clipping_rect[0],
-                        clipping_rect[1],
-                        clipping_rect[2],
-                        clipping_rect[3],
-                        &[
-                            tex[0],
-                            tex[1],
-                            tex[0] + ch_info.tex_width,
-                            tex[1] + ch_info.tex_height,
-                        ],
-                    ) {
-                        Self::add_image(
-                            &mut self.vertex_data,
-                            clipped.0,
-                            clipped.1,
-                            clipped.2,
-                            clipped.3,
-                            clipped.4,
-                            color,
-                        );
-                    }
-                } else {
-                    Self::add_image(
-                        &mut self.vertex_data,
-                        x_offset,
-                        y_offset,
-                        ch_info.width as f32,
-                        ch_info.height as f32,
-                        [
-                            tex[0],
-                            tex[1],
-                            tex[0] + ch_info.tex_width,
-                            tex[1] + ch_info.tex_height,
-                        ],
-                        color,
-                    );
-                }
+                Self::add_char(&mut self.vertex_data, ch_info, x, y, clipping_rect, color);
 
                 x += ch_info.x_advance as f32;
             }
         }
     }
 
+    /// Add glyphs placed on the baseline relative to `pos` (in pixel coordinates).
+    pub fn add_glyphs(
+        &mut self,
+        glyphs: &[Glyph],
+        pos: [f32; 2],
+        clipping_rect: Option<[f32; 4]>,
+        color: [f32; 4],
+    ) {
+        // the glyph bitmaps are placed relative to the top of the em box
+        let font_size = self.bitmap_font.get_font_size() as f32;
+        for glyph in glyphs {
+            let ch_info = match self.bitmap_font.find_glyph(glyph.glyph_id) {
+                Some(info) => info,
+                None => continue,
+            };
+            Self::add_char(
+                &mut self.vertex_data,
+                ch_info,
+                (pos[0] + glyph.position.x).round(),
+                (pos[1] + glyph.position.y - font_size).round(),
+                clipping_rect,
+                color,
+            );
+        }
+    }
+
     /// Draw using provided projection matrix.
     ///
     /// # Examples
@@ -156,17 +137,36 @@ impl<D: Device> FontSizeRenderer<D> {
         target: &D::RenderTarget,
         transform: PixelToDeviceTransform,
     ) -> Result<(), &'static str> {
-        if self.texture.is_none() {
-            self.texture = Some(device.create_texture(
+        if self.vertex_data.is_empty() {
+            return Ok(());
+        }
+
+        // the glyphs are rasterized on demand, the texture is recreated when new glyphs are added
+        let version = self.bitmap_font.get_version();
+        if self
+            .texture
+            .as_ref()
+            .is_none_or(|(_, texture_version)| *texture_version != version)
+        {
+            let texture = device.create_texture(
                 self.bitmap_font.get_image(),
                 self.bitmap_font.get_width(),
                 self.bitmap_font.get_height(),
                 ColorFormat::Y8,
-            )?);
+            )?;
+            self.texture = Some((texture, version));
         }
+
+        // texture coordinates are collected in pixels
+        let width = self.bitmap_font.get_width() as f32;
+        let height = self.bitmap_font.get_height() as f32;
+        for vertex in &mut self.vertex_data {
+            vertex.tex_coords = [vertex.tex_coords[0] / width, vertex.tex_coords[1] / height];
+        }
+
         device.triangles_textured_y8(
             target,
-            self.texture.as_ref().unwrap(),
+            &self.texture.as_ref().unwrap().0,
             false,
             &self.vertex_data,
             transform,
@@ -179,6 +179,65 @@ impl<D: Device> FontSizeRenderer<D> {
         &self.bitmap_font
     }
 
+    pub fn get_bitmap_font_mut(&mut self) -> &mut BitmapFont {
+        &mut self.bitmap_font
+    }
+
+    fn add_char(
+        vertex_data: &mut Vec<TexturedY8Vertex>,
+        ch_info: &BitmapGlyph,
+        x: f32,
+        y: f32,
+        clipping_rect: Option<[f32; 4]>,
+        color: [f32; 4],
+    ) {
+        let x_offset = x + ch_info.x_offset as f32;
+        let y_offset = y + ch_info.y_offset as f32;
+        if ch_info.width == 0 || ch_info.height == 0 {
+            return;
+        }
+        let uv = [
+            ch_info.tex_x as f32,
+            ch_info.tex_y as f32,
+            (ch_info.tex_x + ch_info.width) as f32,
+            (ch_info.tex_y + ch_info.height) as f32,
+        ];
+
+        if let Some(clipping_rect) = clipping_rect {
+            if let Some(clipped) = clip_image(
+                x_offset,
+                y_offset,
+                ch_info.width as f32,
+                ch_info.height as f32,
+                clipping_rect[0],
+                clipping_rect[1],
+                clipping_rect[2],
+                clipping_rect[3],
+                &uv,
+            ) {
+                Self::add_image(
+                    vertex_data,
+                    clipped.0,
+                    clipped.1,
+                    clipped.2,
+                    clipped.3,
+                    clipped.4,
+                    color,
+                );
+            }
+        } else {
+            Self::add_image(
+                vertex_data,
+                x_offset,
+                y_offset,
+                ch_info.width as f32,
+                ch_info.height as f32,
+                uv,
+                color,
+            );
+        }
+    }
+
     fn add_image(
         vertex_data: &mut Vec<TexturedY8Vertex>,
         x1: f32,
@@ -5,5 +5,8 @@ pub use font::*;
 
 mod font_size_renderer;
 
+mod text_shaper;
+pub use text_shaper::ShapedGlyph;
+
 mod texture_font;
 pub use self::texture_font::TextureFont;
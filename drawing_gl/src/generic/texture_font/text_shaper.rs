@@ -0,0 +1,136 @@
+//! Converts the text into the positioned glyphs using HarfBuzz algorithms
+//! (complex scripts, ligatures, kerning, mark positioning).
+
+use drawing_api::TextDirection;
+use rustybuzz::ttf_parser::Tag;
+use unicode_script::{Script, UnicodeScript};
+
+/// Glyph produced by the shaper (values in pixels).
+#[derive(Copy, Clone, Debug)]
+pub struct ShapedGlyph {
+    pub glyph_id: u32,
+
+    /// Byte offset of the first character of the cluster the glyph belongs to.
+    pub cluster: usize,
+
+    pub x_advance: f32,
+    pub x_offset: f32,
+    pub y_offset: f32,
+}
+
+/// Font data together with the face parsed for shaping,
+/// so the font tables are not parsed again for every shaped run.
+pub struct ShapingFace {
+    // borrows the heap buffer of `data`, it is declared first so it is dropped first
+    face: rustybuzz::Face<'static>,
+    data: Vec<u8>,
+}
+
+impl ShapingFace {
+    pub fn new(data: Vec<u8>) -> Result<Self, &'static str> {
+        // SAFETY: the buffer of `data` is never modified nor reallocated
+        // and it is dropped after `face` (moving the `Vec` doesn't move the buffer)
+        let font_data: &'static [u8] =
+            unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+        let face =
+            rustybuzz::Face::from_slice(font_data, 0).ok_or("face: cannot parse font data")?;
+        Ok(Self { face, data })
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Shapes the text of the same direction with the font.
+///
+/// The text is split into the runs of the same script, every run is shaped separately.
+/// The glyphs are returned in the logical order (the right-to-left text is reversed).
+///
+/// There is no font fallback: the characters missing in the font
+/// are shaped to the `.notdef` glyph (glyph 0) of the font.
+pub fn shape_text(
+    font: &ShapingFace,
+    font_size: f32,
+    text: &str,
+    direction: TextDirection,
+) -> Result<Vec<ShapedGlyph>, &'static str> {
+    let face = &font.face;
+    let scale = font_size / face.units_per_em().max(1) as f32;
+
+    // tabs are drawn as 4 spaces
+    let space_glyph = face.glyph_index(' ').map_or(0, |id| id.0 as u32);
+    let space_advance = face
+        .glyph_hor_advance(rustybuzz::ttf_parser::GlyphId(space_glyph as u16))
+        .unwrap_or(0) as f32
+        * scale;
+
+    let mut glyphs = Vec::with_capacity(text.len());
+    for (start, end, script) in split_scripts(text) {
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(&text[start..end]);
+        if let Some(script) = rustybuzz::Script::from_iso15924_tag(Tag::from_bytes_lossy(
+            script.short_name().as_bytes(),
+        )) {
+            buffer.set_script(script);
+        }
+        buffer.set_direction(match direction {
+            TextDirection::LTR => rustybuzz::Direction::LeftToRight,
+            TextDirection::RTL => rustybuzz::Direction::RightToLeft,
+        });
+        buffer.guess_segment_properties();
+
+        let output = rustybuzz::shape(face, &[], buffer);
+        let run_start = glyphs.len();
+        for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+            let cluster = start + info.cluster as usize;
+            if text[cluster..].starts_with('\t') {
+                glyphs.push(ShapedGlyph {
+                    glyph_id: space_glyph,
+                    cluster,
+                    x_advance: space_advance * 4.0f32,
+                    x_offset: 0.0f32,
+                    y_offset: 0.0f32,
+                });
+            } else {
+                glyphs.push(ShapedGlyph {
+                    glyph_id: info.glyph_id,
+                    cluster,
+                    x_advance: position.x_advance as f32 * scale,
+                    x_offset: position.x_offset as f32 * scale,
+                    y_offset: position.y_offset as f32 * scale,
+                });
+            }
+        }
+        if direction == TextDirection::RTL {
+            glyphs[run_start..].reverse();
+        }
+    }
+
+    Ok(glyphs)
+}
+
+/// Splits the text into the byte ranges of the same script.
+///
+/// The common and inherited characters (spaces, punctuation, combining marks)
+/// belong to the surrounding script.
+fn split_scripts(text: &str) -> Vec<(usize, usize, Script)> {
+    let mut runs: Vec<(usize, usize, Script)> = Vec::new();
+    for (index, ch) in text.char_indices() {
+        let script = ch.script();
+        let end = index + ch.len_utf8();
+        match runs.last_mut() {
+            Some(run)
+                if script == run.2 || matches!(script, Script::Common | Script::Inherited) =>
+            {
+                run.1 = end;
+            }
+            Some(run) if matches!(run.2, Script::Common | Script::Inherited) => {
+                run.1 = end;
+                run.2 = script;
+            }
+            _ => runs.push((index, end, script)),
+        }
+    }
+    runs
+}
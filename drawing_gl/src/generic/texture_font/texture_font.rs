@@ -1,4 +1,5 @@
 use font_size_renderer::FontSizeRenderer;
+use text_shaper::{shape_text, ShapingFace};
 
 use crate::generic::device::*;
 use crate::generic::texture_font::*;
@@ -8,7 +9,7 @@ use drawing_api::*;
 use std::collections::HashMap;
 
 pub struct TextureFont<D: Device> {
-    bytes: Vec<u8>,
+    face: ShapingFace,
     font_renderers: HashMap<u8, FontSizeRenderer<D>>,
 }
 
@@ -26,14 +27,14 @@ impl<D: Device> TextureFont<D> {
     }
 
     fn create_font_renderer(&self, size: u8) -> Result<FontSizeRenderer<D>, &'static str> {
-        Ok(FontSizeRenderer::new(&self.bytes, size)?)
+        Ok(FontSizeRenderer::new(self.face.get_data(), size)?)
     }
 }
 
 impl<D: Device> Font<D> for TextureFont<D> {
     fn create(bytes: Vec<u8>) -> Result<Self, &'static str> {
         Ok(TextureFont {
-            bytes,
+            face: ShapingFace::new(bytes)?,
             font_renderers: HashMap::new(),
         })
     }
@@ -60,13 +61,53 @@ impl<D: Device> Font<D> for TextureFont<D> {
         Ok(())
     }
 
+    fn draw_glyphs(
+        &mut self,
+        device: &mut D,
+        target: &D::RenderTarget,
+        color: &crate::generic::device::Color,
+        glyphs: &[crate::display_list::Glyph],
+        pos: PixelPoint,
+        clipping_rect: Option<PixelRect>,
+        font_params: FontParams,
+        transform: PixelToDeviceTransform,
+    ) -> Result<(), &'static str> {
+        let renderer = self.get_or_create_font_renderer(font_params.size)?;
+        renderer.add_glyphs(
+            glyphs,
+            [pos.x, pos.y],
+            clipping_rect.map(|r| [r.origin.x, r.origin.y, r.size.width, r.size.height]),
+            *color,
+        );
+        renderer.draw_at(device, target, transform)?;
+        Ok(())
+    }
+
+    fn get_metrics(&mut self, params: FontParams) -> Result<FontMetrics, &'static str> {
+        let renderer = self.get_or_create_font_renderer(params.size)?;
+        let bitmap_font = renderer.get_bitmap_font();
+        Ok(FontMetrics {
+            ascent: bitmap_font.get_ascent(),
+            descent: bitmap_font.get_descent(),
+        })
+    }
+
+    fn shape(
+        &mut self,
+        params: FontParams,
+        text: &str,
+        direction: TextDirection,
+    ) -> Result<Vec<ShapedGlyph>, &'static str> {
+        shape_text(&self.face, params.size as f32, text, direction)
+    }
+
     fn get_dimensions(
         &mut self,
         params: FontParams,
         text: &str,
     ) -> Result<(u16, u16), &'static str> {
         let renderer = self.get_or_create_font_renderer(params.size)?;
-        let dims = renderer.get_bitmap_font().measure(text);
+        let dims = renderer.get_bitmap_font_mut().measure(text);
         Ok((dims.0 as u16, dims.1 as u16))
     }
 
@@ -76,7 +117,7 @@ impl<D: Device> Font<D> for TextureFont<D> {
         text: &str,
     ) -> Result<(Vec<i16>, u16), &'static str> {
         let renderer = self.get_or_create_font_renderer(params.size)?;
-        let dims = renderer.get_bitmap_font().measure_each_char(text);
+        let dims = renderer.get_bitmap_font_mut().measure_each_char(text);
         Ok((dims.0, dims.1 as u16))
     }
 }
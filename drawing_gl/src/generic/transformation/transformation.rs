@@ -55,6 +55,11 @@ impl<T: Texture, F: Fonts> Transformation<T> for Vec<Primitive<T, F>> {
                     position,
                     clipping_rect,
                     ..
+                }
+                | Primitive::Glyphs {
+                    position,
+                    clipping_rect,
+                    ..
                 } => {
                     <Point2D<f32, drawing_api::PixelUnit> as Transformation<T>>::translate(
                         position, offset,
@@ -85,7 +90,9 @@ impl<T: Texture, F: Fonts> Transformation<T> for Vec<Primitive<T, F>> {
                     primitives.translate(offset);
                 }
 
-                Primitive::ClipPath { path, primitives } => {
+                Primitive::ClipPath {
+                    path, primitives, ..
+                } => {
                     <Vec<PathElement> as Transformation<T>>::translate(path, offset);
                     primitives.translate(offset);
                 }
@@ -94,7 +101,20 @@ impl<T: Texture, F: Fonts> Transformation<T> for Vec<Primitive<T, F>> {
                     *transform = transform.then_translate(Vector2D::new(offset.x, offset.y));
                 }
 
-                Primitive::Composite { primitives, .. } => primitives.translate(offset),
+                Primitive::Composite {
+                    bounds, primitives, ..
+                } => {
+                    <Rect<f32, drawing_api::PixelUnit> as Transformation<T>>::translate(
+                        bounds, offset,
+                    );
+                    primitives.translate(offset);
+                }
+
+                Primitive::Filter { primitives, .. } => primitives.translate(offset),
+
+                Primitive::ColorFilter { primitives, .. } => primitives.translate(offset),
+
+                Primitive::Blend { primitives, .. } => primitives.translate(offset),
             }
         }
     }
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use gl::types::{GLint, GLuint};
+
+// The highest bit of the stencil buffer holds the clip mask (set = inside).
+// The remaining bits are used for path filling & stroking.
+pub(crate) const STENCIL_CLIP_BIT: GLuint = 0x80;
+pub(crate) const STENCIL_FILL_BITS: GLuint = 0x7f;
+
+/// Returns the stencil reference value and mask which limit drawing to the clip area.
+pub(crate) fn clip_stencil_func(clip_active: bool) -> (GLint, GLuint) {
+    if clip_active {
+        (STENCIL_CLIP_BIT as GLint, STENCIL_CLIP_BIT)
+    } else {
+        (0, 0)
+    }
+}
+
+/// Clip masks pushed on the render targets (identified by their framebuffers).
+///
+/// The clip area of a render target is kept in the stencil buffer,
+/// after popping a clip mask it is rebuilt from the remaining ones.
+pub(crate) struct ClipStacks<T> {
+    stacks: HashMap<GLuint, Vec<T>>,
+}
+
+impl<T> ClipStacks<T> {
+    pub fn new() -> Self {
+        Self {
+            stacks: HashMap::new(),
+        }
+    }
+
+    /// Returns whether the render target has an active clip mask.
+    pub fn is_active(&self, framebuffer_id: GLuint) -> bool {
+        self.stacks.contains_key(&framebuffer_id)
+    }
+
+    pub fn push(&mut self, framebuffer_id: GLuint, clip_mask: T) {
+        self.stacks
+            .entry(framebuffer_id)
+            .or_default()
+            .push(clip_mask);
+    }
+
+    /// Pops the last clip mask of the render target.
+    ///
+    /// Returns the remaining clip masks to rebuild the clip area from
+    /// or `None` if the render target is not clipped anymore.
+    pub fn pop(&mut self, framebuffer_id: GLuint) -> Option<&[T]> {
+        let clip_stack = self.stacks.get_mut(&framebuffer_id)?;
+        clip_stack.pop();
+        if clip_stack.is_empty() {
+            self.stacks.remove(&framebuffer_id);
+            return None;
+        }
+        self.stacks.get(&framebuffer_id).map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clip_stencil_func, ClipStacks, STENCIL_CLIP_BIT, STENCIL_FILL_BITS};
+
+    #[test]
+    fn stencil_bits_do_not_overlap() {
+        assert_eq!(STENCIL_CLIP_BIT & STENCIL_FILL_BITS, 0);
+        assert_eq!(STENCIL_CLIP_BIT | STENCIL_FILL_BITS, 0xff);
+    }
+
+    #[test]
+    fn stencil_test_is_limited_to_the_clip_bit() {
+        // without the clip mask every pixel passes
+        assert_eq!(clip_stencil_func(false), (0, 0));
+
+        let (reference, mask) = clip_stencil_func(true);
+        assert_eq!(reference as u32 & STENCIL_FILL_BITS, 0);
+        assert_eq!(mask & STENCIL_FILL_BITS, 0);
+        assert_eq!(reference as u32 & mask, STENCIL_CLIP_BIT);
+    }
+
+    #[test]
+    fn clip_masks_are_kept_per_render_target() {
+        let mut clip_stacks = ClipStacks::new();
+        clip_stacks.push(1, "a");
+        clip_stacks.push(1, "b");
+        clip_stacks.push(2, "c");
+        assert!(clip_stacks.is_active(1));
+        assert!(clip_stacks.is_active(2));
+        assert!(!clip_stacks.is_active(3));
+
+        assert_eq!(clip_stacks.pop(1), Some(&["a"][..]));
+        assert!(clip_stacks.is_active(1));
+        assert_eq!(clip_stacks.pop(1), None);
+        assert!(!clip_stacks.is_active(1));
+        assert!(clip_stacks.is_active(2));
+
+        // popping without a clip mask is ignored
+        assert_eq!(clip_stacks.pop(1), None);
+        assert_eq!(clip_stacks.pop(3), None);
+    }
+}
@@ -1,21 +1,26 @@
 use drawing_api::{
-    euclid::Vector2D, Capabilities, ColorFormat, ContextGl, DrawingContext, PixelPoint, Texture,
-    TextureDescriptor,
+    euclid::Vector2D, Capabilities, ClipOperation, ColorFormat, ContextGl, DrawingContext,
+    PixelPoint, Texture, TextureDescriptor,
 };
 use gl::types::*;
 use std::{borrow::Cow, cell::RefCell, os::raw::c_void, rc::Rc, sync::Arc};
 
 use crate::{
+    clip_stencil_func,
     generic::{
         clipping::Scissor,
-        device::{ColoredVertex, Device, Paint, TexturedVertex},
+        device::{ColoredVertex, Device, FilterPass, Paint, TexturedVertex, GRADIENT_RAMP_SIZE},
+        renderer::CompositeOperationState,
     },
+    get_size_class,
     pipelines::{
-        ColoredPipeline, FragUniforms, ShaderType, TexturedPipeline, TexturedY8Pipeline,
-        UniversalPipeline,
+        ColoredPipeline, FilterPipeline, FilterType, FragUniforms, ShaderType, TexturedPipeline,
+        TexturedY8Pipeline, UniversalPipeline,
     },
     units::{PixelToDeviceTransform, PixelTransform},
-    GlSurface, GlTexture, GlTextureData,
+    BlendFactor, ClipStacks, GlSurface, GlTexture, GlTextureData, GradientShape,
+    PooledRenderTarget, RenderTargetPool, RenderTargetPoolStats, STENCIL_CLIP_BIT,
+    STENCIL_FILL_BITS,
 };
 
 pub struct GlContextData {
@@ -30,6 +35,31 @@ pub struct GlContextData {
 
     universal_pipeline: UniversalPipeline,
     universal_pipeline_buffers: (GLuint, GLuint),
+
+    filter_pipeline: FilterPipeline,
+    filter_pipeline_buffers: (GLuint, GLuint),
+
+    // color ramp of the currently drawn gradient
+    gradient_ramp_texture: GLuint,
+
+    // active clip masks for every framebuffer
+    clip_stacks: ClipStacks<ClipMask>,
+
+    // whether the current render target has an active clip mask
+    clip_active: bool,
+
+    // color filter applied to the drawn primitives
+    color_filter: Option<drawing_api::ColorFilter>,
+
+    // offscreen render targets kept for reuse
+    render_target_pool: RenderTargetPool<PooledRenderTarget>,
+}
+
+/// A single clip path pushed on the clip stack of a render target.
+struct ClipMask {
+    fill_vertices: Vec<Vec<TexturedVertex>>,
+    operation: ClipOperation,
+    transform: [[f32; 4]; 4],
 }
 
 #[derive(Clone)]
@@ -51,6 +81,11 @@ impl Drop for GlContextData {
 
             gl::DeleteVertexArrays(1, &self.universal_pipeline_buffers.1);
             gl::DeleteBuffers(1, &self.universal_pipeline_buffers.0);
+
+            gl::DeleteVertexArrays(1, &self.filter_pipeline_buffers.1);
+            gl::DeleteBuffers(1, &self.filter_pipeline_buffers.0);
+
+            gl::DeleteTextures(1, &self.gradient_ramp_texture);
         }
     }
 }
@@ -63,6 +98,7 @@ impl GlContext {
         height: u16,
         format: drawing_api::ColorFormat,
         flipped_y: bool,
+        premultiplied: bool,
     ) -> Result<GlTexture, &'static str> {
         let mut texture_id: GLuint = 0;
         unsafe {
@@ -86,6 +122,7 @@ impl GlContext {
                 gl_format,
                 gl_type,
                 flipped_y,
+                premultiplied,
             }),
         };
 
@@ -109,10 +146,243 @@ impl GlContext {
         Ok(texture)
     }
 
+    /// Creates a framebuffer with a color texture and a depth & stencil attachment.
+    fn create_framebuffer(
+        &self,
+        width: u16,
+        height: u16,
+        color_format: ColorFormat,
+    ) -> Result<(GlTexture, GLuint, GLuint), &'static str> {
+        let mut framebuffer_id: GLuint = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer_id);
+        }
+        // the renderer outputs premultiplied colors
+        let texture = self.create_texture(None, width, height, color_format, true, true)?;
+        unsafe {
+            gl::FramebufferTexture(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, texture.data.id, 0);
+            let draw_buffers = gl::COLOR_ATTACHMENT0;
+            gl::DrawBuffers(1, &draw_buffers);
+        }
+        // stencil buffer is needed for path filling and clipping
+        let mut depth_stencil_renderbuffer_id: GLuint = 0;
+        unsafe {
+            gl::GenRenderbuffers(1, &mut depth_stencil_renderbuffer_id);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth_stencil_renderbuffer_id);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH24_STENCIL8,
+                width as GLsizei,
+                height as GLsizei,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth_stencil_renderbuffer_id,
+            );
+        }
+
+        Ok((texture, framebuffer_id, depth_stencil_renderbuffer_id))
+    }
+
     pub fn set_render_target(&mut self, target: &GlSurface) {
+        let mut context_data = self.data.borrow_mut();
+        context_data.clip_active = context_data.clip_stacks.is_active(target.framebuffer_id);
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, target.framebuffer_id);
-            gl::Viewport(0, 0, target.width as GLint, target.height as GLint);
+            gl::Viewport(
+                0,
+                target.viewport_y as GLint,
+                target.width as GLint,
+                target.height as GLint,
+            );
+
+            // draw only inside the clip area
+            if context_data.clip_active {
+                gl::Enable(gl::STENCIL_TEST);
+                gl::StencilMask(0x00);
+                gl::StencilFunc(gl::EQUAL, STENCIL_CLIP_BIT as GLint, STENCIL_CLIP_BIT);
+                gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
+            } else {
+                gl::Disable(gl::STENCIL_TEST);
+                gl::StencilMask(0xff);
+            }
+        }
+    }
+
+    /// Returns the statistics of the pool of offscreen render targets
+    /// used for layers and filters.
+    pub fn get_render_target_pool_stats(&self) -> RenderTargetPoolStats {
+        self.data.borrow().render_target_pool.get_stats()
+    }
+
+    /// Sets the maximum memory (in bytes) taken by the pooled render targets.
+    pub fn set_render_target_pool_budget(&self, budget: usize) {
+        self.data.borrow_mut().render_target_pool.set_budget(budget);
+    }
+
+    /// Releases the unused pooled render targets until the pool takes
+    /// at most `max_bytes`. Use 0 to release all unused render targets.
+    pub fn trim_render_target_pool(&self, max_bytes: usize) {
+        self.data.borrow_mut().render_target_pool.trim(max_bytes);
+    }
+
+    /// Releases the pooled render targets not used in the last frames.
+    ///
+    /// Should be called once per presented frame (it is called by `Surface::present()`).
+    pub fn end_frame(&self) {
+        self.data.borrow_mut().render_target_pool.end_frame();
+    }
+
+    /// Returns the rectangle (offset and size in texture coordinates) of the render target's
+    /// content in the texture, which can be bigger than the render target (when it is pooled).
+    fn get_content_tex_rect(texture: &GlTexture, target: &GlSurface) -> [f32; 4] {
+        let width = (target.width as f32 / texture.data.width as f32).min(1.0);
+        let height = (target.height as f32 / texture.data.height as f32).min(1.0);
+        // the render target textures are flipped, the content is at the top
+        [0.0, 1.0 - height, width, height]
+    }
+
+    /// Binds the image or the gradient color ramp used by the paint.
+    fn bind_paint_texture(&mut self, paint: &Paint<GlTexture>, filtering: bool) {
+        if let Some(gradient) = &paint.gradient {
+            let context_data = self.data.borrow();
+            unsafe {
+                gl::BindTexture(gl::TEXTURE_2D, context_data.gradient_ramp_texture);
+                gl::TexSubImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    0,
+                    0,
+                    GRADIENT_RAMP_SIZE as GLsizei,
+                    1,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    gradient.ramp.as_ptr() as *const GLvoid,
+                );
+            }
+        } else if let Some(ref texture) = paint.image {
+            unsafe {
+                gl::Enable(gl::TEXTURE_2D);
+                gl::BindTexture(gl::TEXTURE_2D, texture.get_gl_handle() as GLuint);
+                if filtering {
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+                } else {
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+                }
+                gl::TexParameteri(
+                    gl::TEXTURE_2D,
+                    gl::TEXTURE_WRAP_S,
+                    convert_wrap_mode(paint.tile_modes[0]),
+                );
+                gl::TexParameteri(
+                    gl::TEXTURE_2D,
+                    gl::TEXTURE_WRAP_T,
+                    convert_wrap_mode(paint.tile_modes[1]),
+                );
+            }
+        }
+    }
+
+    /// Intersects the clip area of the currently bound render target with the clip mask.
+    fn apply_clip_mask(universal_pipeline: &mut UniversalPipeline, clip_mask: &ClipMask) {
+        universal_pipeline.apply();
+        universal_pipeline.set_transform(&clip_mask.transform);
+        universal_pipeline.apply_frag_uniforms(&FragUniforms {
+            stroke_thr: -1.0,
+            type_: ShaderType::Simple as i32,
+            ..FragUniforms::default()
+        });
+
+        unsafe {
+            gl::Enable(gl::STENCIL_TEST);
+            gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
+
+            // Draw the clip path's winding inside the current clip area
+            gl::StencilMask(STENCIL_FILL_BITS);
+            gl::StencilFunc(gl::EQUAL, STENCIL_CLIP_BIT as GLint, STENCIL_CLIP_BIT);
+            gl::StencilOpSeparate(gl::FRONT, gl::KEEP, gl::KEEP, gl::INCR_WRAP);
+            gl::StencilOpSeparate(gl::BACK, gl::KEEP, gl::KEEP, gl::DECR_WRAP);
+            gl::Disable(gl::CULL_FACE);
+            for fill_vertices in &clip_mask.fill_vertices {
+                if !fill_vertices.is_empty() {
+                    universal_pipeline.draw(fill_vertices, gl::TRIANGLE_FAN);
+                }
+            }
+
+            // Remove the clip bit from pixels outside (intersect) or inside (difference) the path
+            gl::StencilMask(STENCIL_CLIP_BIT);
+            match clip_mask.operation {
+                ClipOperation::Intersect => {
+                    gl::StencilFunc(gl::EQUAL, STENCIL_CLIP_BIT as GLint, 0xff);
+                }
+                ClipOperation::Difference => {
+                    gl::StencilFunc(gl::NOTEQUAL, 0x00, STENCIL_FILL_BITS);
+                }
+            }
+            gl::StencilOp(gl::KEEP, gl::KEEP, gl::ZERO);
+            universal_pipeline.set_transform(&[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]);
+            universal_pipeline.draw(
+                &[
+                    TexturedVertex::new([1.0, 1.0], [0.5, 1.0], [1.0, 1.0, 1.0, 1.0]),
+                    TexturedVertex::new([1.0, -1.0], [0.5, 1.0], [1.0, 1.0, 1.0, 1.0]),
+                    TexturedVertex::new([-1.0, 1.0], [0.5, 1.0], [1.0, 1.0, 1.0, 1.0]),
+                    TexturedVertex::new([-1.0, -1.0], [0.5, 1.0], [1.0, 1.0, 1.0, 1.0]),
+                ],
+                gl::TRIANGLE_STRIP,
+            );
+
+            // Clear the winding
+            gl::StencilMask(STENCIL_FILL_BITS);
+            gl::ClearStencil(0);
+            gl::Clear(gl::STENCIL_BUFFER_BIT);
+
+            gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+            gl::Disable(gl::STENCIL_TEST);
+        }
+    }
+
+    /// Sets the blend factors for the premultiplied colors of the universal pipeline.
+    fn set_composite_operation(state: &CompositeOperationState) {
+        unsafe {
+            gl::BlendFuncSeparate(
+                convert_blend_factor(state.src_rgb),
+                convert_blend_factor(state.dst_rgb),
+                convert_blend_factor(state.src_alpha),
+                convert_blend_factor(state.dst_alpha),
+            );
+        }
+    }
+
+    /// Restores the source over blending of the unpremultiplied colors
+    /// used by the other pipelines (the result is premultiplied).
+    fn reset_composite_operation() {
+        unsafe {
+            gl::BlendFuncSeparate(
+                gl::SRC_ALPHA,
+                gl::ONE_MINUS_SRC_ALPHA,
+                gl::ONE,
+                gl::ONE_MINUS_SRC_ALPHA,
+            );
+        }
+    }
+
+    /// Marks the whole currently bound render target as inside the clip area.
+    fn reset_clip_stencil() {
+        unsafe {
+            gl::StencilMask(0xff);
+            gl::ClearStencil(STENCIL_CLIP_BIT as GLint);
+            gl::Clear(gl::STENCIL_BUFFER_BIT);
+            gl::ClearStencil(0);
         }
     }
 
@@ -136,6 +406,10 @@ impl GlContext {
 
         let mut context_data = self.data.borrow_mut();
         context_data.colored_pipeline.apply();
+        let color_filter = context_data.color_filter.clone();
+        context_data
+            .colored_pipeline
+            .set_color_filter(color_filter.as_ref());
         context_data.colored_pipeline.set_transform(&transform);
         context_data.colored_pipeline.draw_lines(&[v1, v2, v3]);
     }
@@ -153,6 +427,7 @@ impl GlContext {
             paint_mat: Default::default(),
             inner_color: premul_color(paint.inner_color),
             outer_color: premul_color(paint.outer_color),
+            gradient_params: Default::default(),
             scissor_ext: Default::default(),
             scissor_scale: Default::default(),
             extent: Default::default(),
@@ -162,6 +437,10 @@ impl GlContext {
             stroke_thr,
             tex_type: 0,
             type_: 0,
+            gradient_type: 0,
+            tile_mode: 0,
+            tile_mode_y: 0,
+            _padding: 0,
             //_padding: [0u8; 16],
         };
 
@@ -194,7 +473,83 @@ impl GlContext {
 
         let invxform;
 
-        if let Some(texture) = texture {
+        if let Some(gradient) = &paint.gradient {
+            frag.type_ = ShaderType::FillGradientRamp as i32;
+            (frag.gradient_type, frag.gradient_params) = match gradient.shape {
+                GradientShape::Linear {
+                    start_point,
+                    end_point,
+                } => (
+                    0,
+                    [
+                        start_point.x,
+                        start_point.y,
+                        end_point.x,
+                        end_point.y,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                    ],
+                ),
+                GradientShape::Radial {
+                    center_point,
+                    radius,
+                } => (
+                    1,
+                    [
+                        center_point.x,
+                        center_point.y,
+                        0.0,
+                        0.0,
+                        radius,
+                        0.0,
+                        0.0,
+                        0.0,
+                    ],
+                ),
+                GradientShape::Conical {
+                    start_center,
+                    start_radius,
+                    end_center,
+                    end_radius,
+                } => (
+                    2,
+                    [
+                        start_center.x,
+                        start_center.y,
+                        end_center.x,
+                        end_center.y,
+                        start_radius,
+                        end_radius,
+                        0.0,
+                        0.0,
+                    ],
+                ),
+                GradientShape::Sweep {
+                    center_point,
+                    start_angle,
+                    end_angle,
+                } => (
+                    3,
+                    [
+                        center_point.x,
+                        center_point.y,
+                        0.0,
+                        0.0,
+                        start_angle,
+                        end_angle,
+                        0.0,
+                        0.0,
+                    ],
+                ),
+            };
+            frag.tile_mode = convert_tile_mode(gradient.tile_mode);
+            invxform = paint
+                .xform
+                .inverse()
+                .unwrap_or_else(PixelTransform::identity);
+        } else if let Some(texture) = texture {
             frag.type_ = ShaderType::FillImage as i32;
 
             if texture.data.flipped_y {
@@ -211,6 +566,9 @@ impl GlContext {
                     .unwrap_or_else(PixelTransform::identity);
             };
 
+            frag.tile_mode = convert_tile_mode(paint.tile_modes[0]);
+            frag.tile_mode_y = convert_tile_mode(paint.tile_modes[1]);
+
             match texture.data.gl_format {
                 gl::BGRA => frag.tex_type = 0,
                 gl::RED => frag.tex_type = 1,
@@ -243,7 +601,7 @@ impl Device for GlContext {
         height: u16,
         format: ColorFormat,
     ) -> Result<Self::Texture, &'static str> {
-        self.create_texture(Some(contents), width, height, format, false)
+        self.create_texture(Some(contents), width, height, format, false, false)
     }
 
     fn create_render_target(
@@ -251,26 +609,61 @@ impl Device for GlContext {
         width: u16,
         height: u16,
     ) -> Result<(Self::Texture, Self::RenderTarget), &'static str> {
-        let mut framebuffer_id: GLuint = 0;
+        let pooled = self
+            .data
+            .borrow_mut()
+            .render_target_pool
+            .acquire(width, height)
+            .map(|render_target| {
+                (
+                    render_target.texture.clone(),
+                    render_target.framebuffer_id,
+                    render_target.depth_stencil_renderbuffer_id,
+                )
+            });
+        let (texture, framebuffer_id, depth_stencil_renderbuffer_id) = match pooled {
+            Some(render_target) => render_target,
+            None => {
+                let (class_width, class_height) = get_size_class(width, height);
+                let (texture, framebuffer_id, depth_stencil_renderbuffer_id) =
+                    self.create_framebuffer(class_width, class_height, ColorFormat::RGBA)?;
+
+                // the pool owns the framebuffer and releases it when the texture is not used anymore
+                self.data
+                    .borrow_mut()
+                    .render_target_pool
+                    .insert(PooledRenderTarget::new(
+                        texture.clone(),
+                        framebuffer_id,
+                        depth_stencil_renderbuffer_id,
+                    ));
+
+                (texture, framebuffer_id, depth_stencil_renderbuffer_id)
+            }
+        };
+
+        // The content is drawn in the top left corner of the (flipped) texture
+        // and the rest stays transparent, so the texture can be sampled
+        // in pixels like a texture of the requested size.
         unsafe {
-            gl::GenFramebuffers(1, &mut framebuffer_id);
             gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer_id);
+            gl::StencilMask(0xff);
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
         }
-        let texture = self.create_texture(None, width, height, ColorFormat::RGBA, true)?;
-        unsafe {
-            gl::FramebufferTexture(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, texture.data.id, 0);
-            let draw_buffers = gl::COLOR_ATTACHMENT0;
-            gl::DrawBuffers(1, &draw_buffers);
-        }
+
+        let viewport_y = texture.data.height - height;
         Ok((
             texture,
             GlSurface {
                 context: self.clone(),
                 framebuffer_id,
+                depth_stencil_renderbuffer_id,
                 width,
                 height,
+                viewport_y,
                 color_format: ColorFormat::RGBA,
-                is_owner: true,
+                is_owner: false,
             },
         ))
     }
@@ -299,6 +692,10 @@ impl Device for GlContext {
 
         let mut context_data = self.data.borrow_mut();
         context_data.colored_pipeline.apply();
+        let color_filter = context_data.color_filter.clone();
+        context_data
+            .colored_pipeline
+            .set_color_filter(color_filter.as_ref());
         context_data.colored_pipeline.set_transform(&transform);
         context_data.colored_pipeline.draw(vertices);
     }
@@ -333,6 +730,10 @@ impl Device for GlContext {
 
         let mut context_data = self.data.borrow_mut();
         context_data.textured_pipeline.apply();
+        let color_filter = context_data.color_filter.clone();
+        context_data
+            .textured_pipeline
+            .set_color_filter(color_filter.as_ref());
         context_data.textured_pipeline.set_transform(&transform);
         context_data
             .textured_pipeline
@@ -370,6 +771,10 @@ impl Device for GlContext {
 
         let mut context_data = self.data.borrow_mut();
         context_data.textured_y8_pipeline.apply();
+        let color_filter = context_data.color_filter.clone();
+        context_data
+            .textured_y8_pipeline
+            .set_color_filter(color_filter.as_ref());
         context_data.textured_y8_pipeline.set_transform(&transform);
         context_data
             .textured_y8_pipeline
@@ -410,6 +815,8 @@ impl Device for GlContext {
         transform: PixelToDeviceTransform,
     ) {
         self.set_render_target(target);
+        self.bind_paint_texture(paint, filtering);
+        Self::set_composite_operation(&composite_operation_state);
         let transform = [
             [transform.m11, transform.m12, 0.0, 0.0],
             [transform.m21, transform.m22, 0.0, 0.0],
@@ -419,14 +826,20 @@ impl Device for GlContext {
 
         let mut context_data = self.data.borrow_mut();
         context_data.universal_pipeline.apply();
+        let color_filter = context_data.color_filter.clone();
+        context_data
+            .universal_pipeline
+            .set_color_filter(color_filter.as_ref());
         context_data.universal_pipeline.set_transform(&transform);
 
+        let (clip_ref, clip_mask) = clip_stencil_func(context_data.clip_active);
+
         unsafe {
             gl::Enable(gl::STENCIL_TEST);
-            gl::StencilMask(0xff);
+            gl::StencilMask(STENCIL_FILL_BITS);
 
             // Fill the stroke base without overlap
-            gl::StencilFunc(gl::EQUAL, 0x0, 0xff);
+            gl::StencilFunc(gl::EQUAL, clip_ref, STENCIL_FILL_BITS | clip_mask);
             gl::StencilOp(gl::KEEP, gl::KEEP, gl::INCR);
             if antialiasing {
                 context_data
@@ -472,7 +885,7 @@ impl Device for GlContext {
                         fringe_width,
                         -1.0,
                     ));
-                gl::StencilFunc(gl::EQUAL, 0x0, 0xff);
+                gl::StencilFunc(gl::EQUAL, clip_ref, STENCIL_FILL_BITS | clip_mask);
                 gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
                 for path in paths {
                     let stroke_vertices = path.get_stroke();
@@ -486,7 +899,7 @@ impl Device for GlContext {
 
             // Clear stencil buffer.
             gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
-            gl::StencilFunc(gl::ALWAYS, 0x0, 0xff);
+            gl::StencilFunc(gl::ALWAYS, 0x0, STENCIL_FILL_BITS);
             gl::StencilOp(gl::ZERO, gl::ZERO, gl::ZERO);
             for path in paths {
                 let stroke_vertices = path.get_stroke();
@@ -500,6 +913,8 @@ impl Device for GlContext {
 
             gl::Disable(gl::STENCIL_TEST);
         }
+
+        Self::reset_composite_operation();
     }
 
     fn fill(
@@ -517,20 +932,8 @@ impl Device for GlContext {
         transform: PixelToDeviceTransform,
     ) {
         self.set_render_target(target);
-
-        if let Some(ref texture) = paint.image {
-            unsafe {
-                gl::Enable(gl::TEXTURE_2D);
-                gl::BindTexture(gl::TEXTURE_2D, texture.get_gl_handle() as GLuint);
-                if filtering {
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
-                } else {
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
-                    gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
-                }
-            }
-        }
+        self.bind_paint_texture(paint, filtering);
+        Self::set_composite_operation(&composite_operation_state);
 
         if paths.len() == 1 && paths[0].convex {
             // convex fill
@@ -546,6 +949,10 @@ impl Device for GlContext {
 
             let mut context_data = self.data.borrow_mut();
             context_data.universal_pipeline.apply();
+            let color_filter = context_data.color_filter.clone();
+            context_data
+                .universal_pipeline
+                .set_color_filter(color_filter.as_ref());
             context_data.universal_pipeline.set_transform(&transform);
 
             context_data
@@ -582,13 +989,19 @@ impl Device for GlContext {
 
             let mut context_data = self.data.borrow_mut();
             context_data.universal_pipeline.apply();
+            let color_filter = context_data.color_filter.clone();
+            context_data
+                .universal_pipeline
+                .set_color_filter(color_filter.as_ref());
             context_data.universal_pipeline.set_transform(&transform);
 
+            let (clip_ref, clip_mask) = clip_stencil_func(context_data.clip_active);
+
             unsafe {
-                // Draw shapes on stencil buffer
+                // Draw shapes on stencil buffer (inside the clip area)
                 gl::Enable(gl::STENCIL_TEST);
-                gl::StencilMask(0xff);
-                gl::StencilFunc(gl::ALWAYS, 0, 0xff);
+                gl::StencilMask(STENCIL_FILL_BITS);
+                gl::StencilFunc(gl::EQUAL, clip_ref, clip_mask);
                 gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
 
                 context_data
@@ -621,7 +1034,7 @@ impl Device for GlContext {
 
                 // Draw anti-aliased pixels
                 if antialiasing {
-                    gl::StencilFunc(gl::EQUAL, 0x00, 0xff);
+                    gl::StencilFunc(gl::EQUAL, clip_ref, STENCIL_FILL_BITS | clip_mask);
                     gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
                     for path in paths {
                         let stroke_vertices = path.get_stroke();
@@ -659,7 +1072,7 @@ impl Device for GlContext {
                     ),
                 ];
 
-                gl::StencilFunc(gl::NOTEQUAL, 0x00, 0xff);
+                gl::StencilFunc(gl::NOTEQUAL, 0x00, STENCIL_FILL_BITS);
                 gl::StencilOp(gl::ZERO, gl::ZERO, gl::ZERO);
                 context_data
                     .universal_pipeline
@@ -668,6 +1081,254 @@ impl Device for GlContext {
                 gl::Disable(gl::STENCIL_TEST);
             }
         }
+
+        Self::reset_composite_operation();
+    }
+
+    fn filter_pass(
+        &mut self,
+        target: &Self::RenderTarget,
+        texture: &Self::Texture,
+        pass: FilterPass,
+        horizontal: bool,
+    ) {
+        // limits the number of texture samples per pixel
+        const MAX_RADIUS: f32 = 128.0;
+
+        self.set_render_target(target);
+
+        let texel_step = if horizontal {
+            [1.0 / target.width as f32, 0.0]
+        } else {
+            [0.0, 1.0 / target.height as f32]
+        };
+        let (filter_type, sigma, radius, tile_mode) = match pass {
+            FilterPass::Blur { sigma, tile_mode } => (
+                FilterType::Blur,
+                sigma,
+                (sigma * 3.0).ceil().min(MAX_RADIUS),
+                convert_tile_mode(tile_mode),
+            ),
+            FilterPass::Dilate { radius } => (
+                FilterType::Dilate,
+                0.0,
+                radius.round().min(MAX_RADIUS),
+                convert_tile_mode(drawing_api::TileMode::Clamp),
+            ),
+            FilterPass::Erode { radius } => (
+                FilterType::Erode,
+                0.0,
+                radius.round().min(MAX_RADIUS),
+                convert_tile_mode(drawing_api::TileMode::Clamp),
+            ),
+        };
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, texture.data.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+
+            // the result replaces the target's content
+            gl::Disable(gl::BLEND);
+        }
+
+        let mut context_data = self.data.borrow_mut();
+        context_data.filter_pipeline.apply();
+        context_data.filter_pipeline.set_filter(
+            filter_type,
+            texel_step,
+            sigma,
+            radius as i32,
+            tile_mode,
+        );
+        context_data
+            .filter_pipeline
+            .set_tex_rect(Self::get_content_tex_rect(texture, target));
+        context_data.filter_pipeline.draw(&[
+            TexturedVertex::new([-1.0, -1.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, -1.0], [1.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([-1.0, 1.0], [0.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, 1.0], [1.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+        ]);
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+        }
+    }
+
+    fn blur_mask_composite(
+        &mut self,
+        target: &Self::RenderTarget,
+        blurred: &Self::Texture,
+        original: &Self::Texture,
+        style: drawing_api::BlurStyle,
+    ) {
+        self.set_render_target(target);
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, original.data.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, blurred.data.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+
+            // the result replaces the target's content
+            gl::Disable(gl::BLEND);
+        }
+
+        let mut context_data = self.data.borrow_mut();
+        context_data.filter_pipeline.apply();
+        context_data
+            .filter_pipeline
+            .set_blur_mask_composite(match style {
+                drawing_api::BlurStyle::Normal => 0,
+                drawing_api::BlurStyle::Solid => 1,
+                drawing_api::BlurStyle::Outer => 2,
+                drawing_api::BlurStyle::Inner => 3,
+            });
+        context_data
+            .filter_pipeline
+            .set_tex_rect(Self::get_content_tex_rect(blurred, target));
+        context_data.filter_pipeline.draw(&[
+            TexturedVertex::new([-1.0, -1.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, -1.0], [1.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([-1.0, 1.0], [0.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, 1.0], [1.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+        ]);
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+        }
+    }
+
+    fn copy_render_target(
+        &mut self,
+        target: &Self::RenderTarget,
+    ) -> Result<Self::Texture, &'static str> {
+        let (texture, copy) = self.create_render_target(target.width, target.height)?;
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, target.framebuffer_id);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, copy.framebuffer_id);
+            gl::BlitFramebuffer(
+                0,
+                target.viewport_y as GLint,
+                target.width as GLint,
+                target.viewport_y as GLint + target.height as GLint,
+                0,
+                copy.viewport_y as GLint,
+                target.width as GLint,
+                copy.viewport_y as GLint + copy.height as GLint,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+        }
+        Ok(texture)
+    }
+
+    fn blend_composite(
+        &mut self,
+        target: &Self::RenderTarget,
+        source: &Self::Texture,
+        destination: &Self::Texture,
+        blend_mode: drawing_api::BlendMode,
+    ) {
+        self.set_render_target(target);
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, destination.data.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, source.data.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+
+            // the result replaces the target's content (within the clip area)
+            gl::Disable(gl::BLEND);
+        }
+
+        let mut context_data = self.data.borrow_mut();
+        context_data.filter_pipeline.apply();
+        context_data.filter_pipeline.set_blend(blend_mode);
+        context_data
+            .filter_pipeline
+            .set_tex_rect(Self::get_content_tex_rect(source, target));
+        context_data.filter_pipeline.draw(&[
+            TexturedVertex::new([-1.0, -1.0], [0.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, -1.0], [1.0, 0.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([-1.0, 1.0], [0.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+            TexturedVertex::new([1.0, 1.0], [1.0, 1.0], [1.0, 1.0, 1.0, 1.0]),
+        ]);
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+        }
+    }
+
+    fn push_clip_paths(
+        &mut self,
+        target: &Self::RenderTarget,
+        paths: &[crate::generic::path::Path],
+        operation: ClipOperation,
+        transform: PixelToDeviceTransform,
+    ) {
+        self.set_render_target(target);
+
+        let clip_mask = ClipMask {
+            fill_vertices: paths.iter().map(|path| path.get_fill().to_vec()).collect(),
+            operation,
+            transform: [
+                [transform.m11, transform.m12, 0.0, 0.0],
+                [transform.m21, transform.m22, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [transform.m31, transform.m32, 0.0, 1.0],
+            ],
+        };
+
+        let mut context_data = self.data.borrow_mut();
+        if !context_data.clip_active {
+            Self::reset_clip_stencil();
+        }
+        Self::apply_clip_mask(&mut context_data.universal_pipeline, &clip_mask);
+        context_data
+            .clip_stacks
+            .push(target.framebuffer_id, clip_mask);
+    }
+
+    fn pop_clip_paths(&mut self, target: &Self::RenderTarget) {
+        self.set_render_target(target);
+
+        let mut context_data = self.data.borrow_mut();
+        let GlContextData {
+            clip_stacks,
+            universal_pipeline,
+            ..
+        } = &mut *context_data;
+        // rebuild the clip area from the remaining clip masks
+        if let Some(clip_stack) = clip_stacks.pop(target.framebuffer_id) {
+            Self::reset_clip_stencil();
+            for clip_mask in clip_stack {
+                Self::apply_clip_mask(universal_pipeline, clip_mask);
+            }
+        }
+    }
+
+    fn set_color_filter(&mut self, color_filter: Option<&drawing_api::ColorFilter>) {
+        self.data.borrow_mut().color_filter = color_filter.cloned();
     }
 }
 
@@ -690,14 +1351,14 @@ impl DrawingContext for GlContext {
             transformations: true,
             layers: true,
             rect_clipping: true,
-            path_clipping: false,
+            path_clipping: true,
             color_filters: true,
-            image_filters: false,
-            mask_filters: false,
+            image_filters: true,
+            mask_filters: true,
             textures: true,
-            text_metrics: false,
+            text_metrics: true,
             text_decorations: false,
-            shadows: false,
+            shadows: true,
             fragment_color_sources: false,
             fragment_image_filters: false,
         };
@@ -721,6 +1382,7 @@ impl DrawingContext for GlContext {
                 }
             }
             drawing_api::GraphicsApi::Vulkan { major: _, minor: _ } => None,
+            _ => None,
         }
     }
 
@@ -740,9 +1402,38 @@ impl DrawingContext for GlContext {
             descriptor.height as u16,
             descriptor.color_format,
             false,
+            false,
         )
     }
 
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        if width == 0 || height == 0 {
+            return Err("surface size cannot be zero");
+        }
+        let width = u16::try_from(width).map_err(|_| "surface width is too big")?;
+        let height = u16::try_from(height).map_err(|_| "surface height is too big")?;
+        let (texture, framebuffer_id, depth_stencil_renderbuffer_id) =
+            self.create_framebuffer(width, height, color_format)?;
+        Ok((
+            GlSurface {
+                context: self.clone(),
+                framebuffer_id,
+                depth_stencil_renderbuffer_id,
+                width,
+                height,
+                viewport_y: 0,
+                color_format,
+                is_owner: true,
+            },
+            texture,
+        ))
+    }
+
     unsafe fn new_color_source_from_fragment_program(
         &self,
         frag_program: &Self::FragmentProgram,
@@ -778,9 +1469,9 @@ impl ContextGl for GlContext {
 
         unsafe {
             gl::Enable(gl::BLEND);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
             gl::Disable(gl::CULL_FACE);
         }
+        Self::reset_composite_operation();
 
         let mut colored_pipeline = ColoredPipeline::new();
         let colored_pipeline_buffers = colored_pipeline.create_vbo_and_vao();
@@ -798,6 +1489,39 @@ impl ContextGl for GlContext {
         let universal_pipeline_buffers = universal_pipeline.create_vbo_and_vao();
         universal_pipeline.set_buffers(universal_pipeline_buffers);
 
+        let mut filter_pipeline = FilterPipeline::new();
+        let filter_pipeline_buffers = filter_pipeline.create_vbo_and_vao();
+        filter_pipeline.set_buffers(filter_pipeline_buffers);
+
+        let mut gradient_ramp_texture: GLuint = 0;
+        unsafe {
+            gl::GenTextures(1, &mut gradient_ramp_texture);
+            gl::BindTexture(gl::TEXTURE_2D, gradient_ramp_texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as GLint,
+                GRADIENT_RAMP_SIZE as GLsizei,
+                1,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                gl::CLAMP_TO_EDGE as GLint,
+            );
+        }
+
         Ok(Self {
             data: Rc::new(RefCell::new(GlContextData {
                 colored_pipeline,
@@ -808,6 +1532,13 @@ impl ContextGl for GlContext {
                 textured_y8_pipeline_buffers,
                 universal_pipeline,
                 universal_pipeline_buffers,
+                filter_pipeline,
+                filter_pipeline_buffers,
+                gradient_ramp_texture,
+                clip_stacks: ClipStacks::new(),
+                clip_active: false,
+                color_filter: None,
+                render_target_pool: RenderTargetPool::new(),
             })),
         })
     }
@@ -822,8 +1553,10 @@ impl ContextGl for GlContext {
         Ok(GlSurface {
             context: self.clone(),
             framebuffer_id,
+            depth_stencil_renderbuffer_id: 0,
             width: width as u16,
             height: height as u16,
+            viewport_y: 0,
             color_format,
             is_owner: false,
         })
@@ -853,6 +1586,42 @@ fn premul_color(color: crate::generic::device::Color) -> crate::generic::device:
     ]
 }
 
+fn convert_blend_factor(factor: BlendFactor) -> GLenum {
+    match factor {
+        BlendFactor::Zero => gl::ZERO,
+        BlendFactor::One => gl::ONE,
+        BlendFactor::SrcColor => gl::SRC_COLOR,
+        BlendFactor::OneMinusSrcColor => gl::ONE_MINUS_SRC_COLOR,
+        BlendFactor::DstColor => gl::DST_COLOR,
+        BlendFactor::OneMinusDstColor => gl::ONE_MINUS_DST_COLOR,
+        BlendFactor::SrcAlpha => gl::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => gl::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::DstAlpha => gl::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => gl::ONE_MINUS_DST_ALPHA,
+        BlendFactor::SrcAlphaSaturate => gl::SRC_ALPHA_SATURATE,
+    }
+}
+
+/// Returns the texture wrap mode of the tile mode.
+///
+/// The decal mode is emulated in the shader (the texture is clamped).
+fn convert_wrap_mode(tile_mode: drawing_api::TileMode) -> GLint {
+    match tile_mode {
+        drawing_api::TileMode::Clamp | drawing_api::TileMode::Decal => gl::CLAMP_TO_EDGE as GLint,
+        drawing_api::TileMode::Repeat => gl::REPEAT as GLint,
+        drawing_api::TileMode::Mirror => gl::MIRRORED_REPEAT as GLint,
+    }
+}
+
+fn convert_tile_mode(tile_mode: drawing_api::TileMode) -> i32 {
+    match tile_mode {
+        drawing_api::TileMode::Clamp => 0,
+        drawing_api::TileMode::Repeat => 1,
+        drawing_api::TileMode::Mirror => 2,
+        drawing_api::TileMode::Decal => 3,
+    }
+}
+
 #[inline]
 fn xform_to_3x4(xform: PixelTransform) -> [f32; 12] {
     let mut m = [0f32; 12];
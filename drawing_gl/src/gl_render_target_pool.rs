@@ -0,0 +1,334 @@
+use std::{collections::HashMap, sync::Arc};
+
+use gl::types::GLuint;
+
+use crate::GlTexture;
+
+// default memory budget of the pooled render targets
+const DEFAULT_BUDGET: usize = 128 * 1024 * 1024;
+
+// number of frames after which an unused render target is released
+const MAX_UNUSED_FRAMES: u64 = 3;
+
+// color (RGBA8) and depth & stencil (D24S8) bytes per pixel
+const BYTES_PER_PIXEL: usize = 8;
+
+// render target sizes are rounded up to the multiple of this value
+const SIZE_STEP: u32 = 64;
+
+/// Statistics of the offscreen render target pool.
+#[derive(Debug, Copy, Clone)]
+pub struct RenderTargetPoolStats {
+    /// Number of render targets in the pool (used and free).
+    pub count: usize,
+
+    /// Number of render targets which are not used at the moment.
+    pub free_count: usize,
+
+    /// GPU memory taken by the render targets in bytes (estimation).
+    pub bytes: usize,
+
+    /// Memory budget in bytes, free render targets above it are released.
+    pub budget: usize,
+}
+
+/// Render target kept in the pool.
+///
+/// The pool logic doesn't depend on OpenGL, so it can be tested without a context.
+pub(crate) trait PoolItem {
+    /// Returns whether the render target is not used outside of the pool.
+    fn is_free(&self) -> bool;
+
+    /// Returns the size of the render target in pixels.
+    fn get_size(&self) -> (u16, u16);
+
+    fn bytes(&self) -> usize {
+        let (width, height) = self.get_size();
+        width as usize * height as usize * BYTES_PER_PIXEL
+    }
+}
+
+/// Offscreen render target (texture, framebuffer and stencil attachment) kept for reuse.
+///
+/// The render target is used as long as its texture is referenced
+/// outside of the pool.
+pub(crate) struct PooledRenderTarget {
+    pub texture: GlTexture,
+    pub framebuffer_id: GLuint,
+    pub depth_stencil_renderbuffer_id: GLuint,
+}
+
+impl PooledRenderTarget {
+    pub fn new(
+        texture: GlTexture,
+        framebuffer_id: GLuint,
+        depth_stencil_renderbuffer_id: GLuint,
+    ) -> Self {
+        Self {
+            texture,
+            framebuffer_id,
+            depth_stencil_renderbuffer_id,
+        }
+    }
+}
+
+impl PoolItem for PooledRenderTarget {
+    fn is_free(&self) -> bool {
+        Arc::strong_count(&self.texture.data) == 1
+    }
+
+    fn get_size(&self) -> (u16, u16) {
+        (self.texture.data.width, self.texture.data.height)
+    }
+}
+
+impl Drop for PooledRenderTarget {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer_id);
+            gl::DeleteRenderbuffers(1, &self.depth_stencil_renderbuffer_id);
+        }
+    }
+}
+
+/// Returns the size of the render target allocated for the requested size.
+///
+/// The sizes are rounded up, so the render targets can be reused
+/// when the requested size changes slightly (e.g. during animations).
+pub(crate) fn get_size_class(width: u16, height: u16) -> (u16, u16) {
+    let round_up = |size: u16| {
+        (size.max(1) as u32)
+            .div_ceil(SIZE_STEP)
+            .saturating_mul(SIZE_STEP)
+            .min(u16::MAX as u32) as u16
+    };
+    (round_up(width), round_up(height))
+}
+
+struct PoolEntry<T> {
+    item: T,
+    id: u64,
+    last_used_frame: u64,
+}
+
+/// Render targets grouped by their size class.
+pub(crate) struct RenderTargetPool<T: PoolItem> {
+    buckets: HashMap<(u16, u16), Vec<PoolEntry<T>>>,
+    budget: usize,
+    frame: u64,
+    next_id: u64,
+}
+
+impl<T: PoolItem> RenderTargetPool<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: HashMap::new(),
+            budget: DEFAULT_BUDGET,
+            frame: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Returns a free render target of the size class that fits the given size.
+    pub fn acquire(&mut self, width: u16, height: u16) -> Option<&T> {
+        let frame = self.frame;
+        self.buckets
+            .get_mut(&get_size_class(width, height))?
+            .iter_mut()
+            .find(|entry| entry.item.is_free())
+            .map(|entry| {
+                entry.last_used_frame = frame;
+                &entry.item
+            })
+    }
+
+    /// Adds a newly created render target, which is in use.
+    pub fn insert(&mut self, item: T) {
+        let entry = PoolEntry {
+            id: self.next_id,
+            last_used_frame: self.frame,
+            item,
+        };
+        self.next_id += 1;
+        self.buckets
+            .entry(entry.item.get_size())
+            .or_default()
+            .push(entry);
+        self.trim(self.budget);
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim(budget);
+    }
+
+    /// Releases the render targets not used for a few frames
+    /// and the ones above the budget.
+    ///
+    /// Should be called once per presented frame.
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        self.release(|entry| frame - entry.last_used_frame > MAX_UNUSED_FRAMES);
+        self.trim(self.budget);
+    }
+
+    /// Releases the free render targets, the least recently used first,
+    /// until the pool takes at most `max_bytes`.
+    pub fn trim(&mut self, max_bytes: usize) {
+        let mut bytes = self.get_stats().bytes;
+        if bytes <= max_bytes {
+            return;
+        }
+
+        let mut candidates = self
+            .buckets
+            .values()
+            .flatten()
+            .filter(|entry| entry.item.is_free())
+            .map(|entry| (entry.last_used_frame, entry.id, entry.item.bytes()))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        let mut released = Vec::new();
+        for (_, id, item_bytes) in candidates {
+            if bytes <= max_bytes {
+                break;
+            }
+            bytes -= item_bytes;
+            released.push(id);
+        }
+
+        self.release(|entry| released.contains(&entry.id));
+    }
+
+    pub fn get_stats(&self) -> RenderTargetPoolStats {
+        let mut stats = RenderTargetPoolStats {
+            count: 0,
+            free_count: 0,
+            bytes: 0,
+            budget: self.budget,
+        };
+        for entry in self.buckets.values().flatten() {
+            stats.count += 1;
+            stats.bytes += entry.item.bytes();
+            if entry.item.is_free() {
+                stats.free_count += 1;
+            }
+        }
+        stats
+    }
+
+    /// Drops the free render targets matching the predicate.
+    fn release(&mut self, predicate: impl Fn(&PoolEntry<T>) -> bool) {
+        for bucket in self.buckets.values_mut() {
+            bucket.retain(|entry| !(entry.item.is_free() && predicate(entry)));
+        }
+        self.buckets.retain(|_, bucket| !bucket.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{get_size_class, PoolItem, RenderTargetPool, BYTES_PER_PIXEL};
+
+    /// Render target which is used as long as its handle is cloned.
+    struct TestRenderTarget {
+        size: (u16, u16),
+        handle: Rc<()>,
+    }
+
+    impl PoolItem for TestRenderTarget {
+        fn is_free(&self) -> bool {
+            Rc::strong_count(&self.handle) == 1
+        }
+
+        fn get_size(&self) -> (u16, u16) {
+            self.size
+        }
+    }
+
+    /// Creates a render target of the size class like the GL context does.
+    fn create(pool: &mut RenderTargetPool<TestRenderTarget>, width: u16, height: u16) -> Rc<()> {
+        let handle = Rc::new(());
+        pool.insert(TestRenderTarget {
+            size: get_size_class(width, height),
+            handle: handle.clone(),
+        });
+        handle
+    }
+
+    #[test]
+    fn size_class_is_rounded_up() {
+        assert_eq!(get_size_class(0, 1), (64, 64));
+        assert_eq!(get_size_class(64, 65), (64, 128));
+        assert_eq!(get_size_class(71, 53), (128, 64));
+        assert_eq!(
+            get_size_class(u16::MAX, u16::MAX - 10),
+            (u16::MAX, u16::MAX)
+        );
+    }
+
+    #[test]
+    fn free_render_target_of_the_size_class_is_reused() {
+        let mut pool = RenderTargetPool::new();
+        let handle = create(&mut pool, 50, 50);
+
+        // still used
+        assert!(pool.acquire(52, 51).is_none());
+
+        drop(handle);
+        assert!(pool.acquire(52, 51).is_some());
+        assert!(pool.acquire(65, 51).is_none());
+        assert_eq!(pool.get_stats().count, 1);
+    }
+
+    #[test]
+    fn unused_render_targets_are_released_after_frames() {
+        let mut pool = RenderTargetPool::new();
+        let used = create(&mut pool, 50, 50);
+        drop(create(&mut pool, 100, 100));
+
+        for _ in 0..3 {
+            pool.end_frame();
+        }
+        assert_eq!(pool.get_stats().count, 2);
+
+        // the used render target is kept
+        pool.end_frame();
+        let stats = pool.get_stats();
+        assert_eq!((stats.count, stats.free_count), (1, 0));
+
+        drop(used);
+        pool.end_frame();
+        assert_eq!(pool.get_stats().count, 0);
+    }
+
+    #[test]
+    fn least_recently_used_render_targets_are_released_above_budget() {
+        let mut pool = RenderTargetPool::new();
+        let bytes = 64 * 64 * BYTES_PER_PIXEL;
+        pool.set_budget(4 * bytes);
+
+        drop(create(&mut pool, 64, 128));
+        pool.end_frame();
+        drop(create(&mut pool, 128, 64));
+        pool.end_frame();
+        assert_eq!(pool.get_stats().bytes, 4 * bytes);
+
+        // the oldest one is released to fit the new one
+        let used = create(&mut pool, 1, 1);
+        let stats = pool.get_stats();
+        assert_eq!((stats.count, stats.bytes), (2, 3 * bytes));
+        assert!(pool.acquire(64, 128).is_none());
+        assert!(pool.acquire(128, 64).is_some());
+
+        // the used render targets are kept even above the budget
+        pool.set_budget(0);
+        let stats = pool.get_stats();
+        assert_eq!((stats.count, stats.free_count), (1, 0));
+        drop(used);
+    }
+}
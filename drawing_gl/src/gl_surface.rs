@@ -2,15 +2,21 @@ use crate::generic::device::RenderTarget;
 use crate::generic::renderer::Renderer;
 use crate::units::PixelToDeviceTransform;
 use crate::GlContext;
-use drawing_api::{euclid::Vector2D, ColorFormat};
-use gl::types::GLuint;
+use drawing_api::{check_pixel_rect, euclid::Vector2D, ColorFormat, PixelData, PixelIntRect};
+use gl::types::*;
 
 pub struct GlSurface {
     pub(crate) context: GlContext,
 
     pub(crate) framebuffer_id: GLuint,
+    pub(crate) depth_stencil_renderbuffer_id: GLuint,
     pub(crate) width: u16,
     pub(crate) height: u16,
+
+    // distance between the bottom of the content and the bottom of the framebuffer
+    // (pooled render targets can be bigger than the requested size)
+    pub(crate) viewport_y: u16,
+
     pub(crate) color_format: ColorFormat,
     pub(crate) is_owner: bool,
 }
@@ -20,6 +26,9 @@ impl Drop for GlSurface {
         if self.is_owner {
             unsafe {
                 gl::DeleteFramebuffers(1, &self.framebuffer_id);
+                if self.depth_stencil_renderbuffer_id != 0 {
+                    gl::DeleteRenderbuffers(1, &self.depth_stencil_renderbuffer_id);
+                }
             }
         }
     }
@@ -61,7 +70,45 @@ impl drawing_api::Surface for GlSurface {
         Ok(())
     }
 
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        check_pixel_rect(rect, self.width as u32, self.height as u32)?;
+
+        let gl_format = match self.color_format {
+            ColorFormat::RGBA => gl::RGBA,
+            ColorFormat::Y8 => gl::RED,
+        };
+        let row_stride = rect.size.width as usize * self.color_format.bytes_per_pixel();
+        let mut data = vec![0u8; row_stride * rect.size.height as usize];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer_id);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            // OpenGL origin is in the bottom left corner
+            gl::ReadPixels(
+                rect.min_x() as GLint,
+                (self.viewport_y as u32 + self.height as u32 - rect.max_y()) as GLint,
+                rect.size.width as GLsizei,
+                rect.size.height as GLsizei,
+                gl_format,
+                gl::UNSIGNED_BYTE,
+                data.as_mut_ptr() as *mut GLvoid,
+            );
+        }
+
+        // the renderer outputs premultiplied colors
+        let mut pixels = PixelData {
+            data,
+            width: rect.size.width,
+            height: rect.size.height,
+            row_stride: row_stride as u32,
+            color_format: self.color_format,
+            premultiplied: true,
+        };
+        pixels.flip_vertically();
+        Ok(pixels)
+    }
+
     fn present(self) -> Result<(), &'static str> {
+        self.context.end_frame();
         Ok(())
     }
 }
@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
 use drawing_api::ColorFormat;
+use drawing_api::PixelData;
+use drawing_api::PixelIntRect;
 use drawing_api::Texture;
 use drawing_api::TextureDescriptor;
 use gl::types::*;
@@ -14,6 +16,10 @@ pub(crate) struct GlTextureData {
     pub gl_format: GLuint,
     pub gl_type: GLuint,
     pub flipped_y: bool,
+
+    // whether the color channels are multiplied by alpha (true for the render targets,
+    // the uploaded contents are read back as they were uploaded)
+    pub premultiplied: bool,
 }
 
 impl Drop for GlTextureData {
@@ -46,6 +52,7 @@ impl GlTexture {
                 gl_format,
                 gl_type,
                 flipped_y: false,
+                premultiplied: false,
             }),
         }
     }
@@ -94,4 +101,35 @@ impl Texture for GlTexture {
     fn get_gl_handle(&self) -> usize {
         self.data.id as usize
     }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        let descriptor = self.get_descriptor();
+        let row_stride = descriptor.width as usize * descriptor.color_format.bytes_per_pixel();
+        let mut data = vec![0u8; row_stride * descriptor.height as usize];
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.data.id);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::GetTexImage(
+                gl::TEXTURE_2D,
+                0,
+                self.data.gl_format,
+                self.data.gl_type,
+                data.as_mut_ptr() as *mut GLvoid,
+            );
+        }
+
+        // render targets are stored from bottom to top
+        let mut pixels = PixelData {
+            data,
+            width: descriptor.width,
+            height: descriptor.height,
+            row_stride: row_stride as u32,
+            color_format: descriptor.color_format,
+            premultiplied: self.data.premultiplied,
+        };
+        if self.data.flipped_y {
+            pixels.flip_vertically();
+        }
+        pixels.crop(rect)
+    }
 }
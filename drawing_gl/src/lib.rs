@@ -1,11 +1,17 @@
 mod display_list;
 
+mod gl_clip_stack;
+pub(crate) use gl_clip_stack::*;
+
 mod gl_context;
 pub use gl_context::*;
 
 mod gl_fragment_program;
 pub use gl_fragment_program::*;
 
+mod gl_render_target_pool;
+pub use gl_render_target_pool::*;
+
 mod gl_surface;
 pub use gl_surface::*;
 
@@ -22,5 +28,5 @@ mod utils;
 
 pub use display_list::{
     BasicCompositeOperation, BlendFactor, Brush, CompositeOperation, DisplayListBuilder, Fonts,
-    LineCap, LineJoin, Paint, PathBuilder, PathElement, Primitive, Solidity,
+    Glyph, GradientShape, LineCap, LineJoin, Paint, PathBuilder, PathElement, Primitive, Solidity,
 };
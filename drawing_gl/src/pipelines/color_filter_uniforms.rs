@@ -0,0 +1,110 @@
+use drawing_api::{BlendMode, ColorFilter};
+use gl::types::*;
+use std::ffi::CString;
+
+/// Resolves the `#include` directives of the shared shader snippets.
+pub fn preprocess_shader(source: &str) -> String {
+    source
+        .replace(
+            "#include \"color_filter.glsl\"",
+            include_str!("shaders/color_filter.glsl"),
+        )
+        .replace(
+            "#include \"blend.glsl\"",
+            include_str!("shaders/blend.glsl"),
+        )
+}
+
+/// Locations of the color filter uniforms declared in `color_filter.glsl`.
+pub struct ColorFilterUniforms {
+    type_location: GLint,
+    blend_mode_location: GLint,
+    color_location: GLint,
+    matrix_location: GLint,
+    offset_location: GLint,
+}
+
+impl ColorFilterUniforms {
+    pub fn new(program_id: GLuint) -> Self {
+        let location = |name: &str| unsafe {
+            let str = CString::new(name).unwrap();
+            gl::GetUniformLocation(program_id, str.as_ptr())
+        };
+
+        ColorFilterUniforms {
+            type_location: location("colorFilterType"),
+            blend_mode_location: location("colorFilterBlendMode"),
+            color_location: location("colorFilterColor"),
+            matrix_location: location("colorFilterMatrix"),
+            offset_location: location("colorFilterOffset"),
+        }
+    }
+
+    /// Sets the uniforms, the program has to be in use.
+    pub fn set(&self, color_filter: Option<&ColorFilter>) {
+        unsafe {
+            match color_filter {
+                None => gl::Uniform1i(self.type_location, 0),
+
+                Some(ColorFilter::Matrix(matrix)) => {
+                    // 5x4 row-major matrix, the last column is the offset
+                    let m = &matrix.m;
+                    let matrix = [
+                        m[0], m[1], m[2], m[3], m[5], m[6], m[7], m[8], m[10], m[11], m[12], m[13],
+                        m[15], m[16], m[17], m[18],
+                    ];
+                    gl::Uniform1i(self.type_location, 1);
+                    gl::UniformMatrix4fv(self.matrix_location, 1, gl::TRUE, matrix.as_ptr());
+                    gl::Uniform4f(self.offset_location, m[4], m[9], m[14], m[19]);
+                }
+
+                Some(ColorFilter::Blend(color, blend_mode)) => {
+                    gl::Uniform1i(self.type_location, 2);
+                    gl::Uniform1i(self.blend_mode_location, convert_blend_mode(*blend_mode));
+                    gl::Uniform4f(
+                        self.color_location,
+                        color.red,
+                        color.green,
+                        color.blue,
+                        color.alpha,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns the index of the blend mode used by `blendColors()` in `blend.glsl`.
+pub fn convert_blend_mode(blend_mode: BlendMode) -> GLint {
+    match blend_mode {
+        BlendMode::Clear => 0,
+        BlendMode::Source => 1,
+        BlendMode::Destination => 2,
+        BlendMode::SourceOver => 3,
+        BlendMode::DestinationOver => 4,
+        BlendMode::SourceIn => 5,
+        BlendMode::DestinationIn => 6,
+        BlendMode::SourceOut => 7,
+        BlendMode::DestinationOut => 8,
+        BlendMode::SourceATop => 9,
+        BlendMode::DestinationATop => 10,
+        BlendMode::Xor => 11,
+        BlendMode::Plus => 12,
+        BlendMode::Modulate => 13,
+        BlendMode::Screen => 14,
+        BlendMode::Overlay => 15,
+        BlendMode::Darken => 16,
+        BlendMode::Lighten => 17,
+        BlendMode::ColorDodge => 18,
+        BlendMode::ColorBurn => 19,
+        BlendMode::HardLight => 20,
+        BlendMode::SoftLight => 21,
+        BlendMode::Difference => 22,
+        BlendMode::Exclusion => 23,
+        BlendMode::Multiply => 24,
+        BlendMode::Hue => 25,
+        BlendMode::Saturation => 26,
+        BlendMode::Color => 27,
+        BlendMode::Luminosity => 28,
+    }
+}
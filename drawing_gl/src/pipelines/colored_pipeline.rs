@@ -1,3 +1,4 @@
+use super::{preprocess_shader, ColorFilterUniforms};
 use crate::utils::*;
 
 use crate::generic::device::ColoredVertex;
@@ -9,12 +10,15 @@ pub struct ColoredPipeline {
     vbo: GLuint,
     vao: GLuint,
     transform_location: GLint,
+    color_filter_uniforms: ColorFilterUniforms,
 }
 
 impl ColoredPipeline {
     pub fn new() -> Self {
         let vertex_shader = Shader::from_vert_str(include_str!("shaders/colored.glslv")).unwrap();
-        let pixel_shader = Shader::from_frag_str(include_str!("shaders/colored.glslf")).unwrap();
+        let pixel_shader =
+            Shader::from_frag_str(&preprocess_shader(include_str!("shaders/colored.glslf")))
+                .unwrap();
         let program = Program::from_shaders(&[vertex_shader, pixel_shader]).unwrap();
 
         let transform_location = unsafe {
@@ -22,11 +26,14 @@ impl ColoredPipeline {
             gl::GetUniformLocation(program.id(), str.as_ptr())
         };
 
+        let color_filter_uniforms = ColorFilterUniforms::new(program.id());
+
         ColoredPipeline {
             program,
             vbo: 0,
             vao: 0,
             transform_location,
+            color_filter_uniforms,
         }
     }
 
@@ -46,6 +53,10 @@ impl ColoredPipeline {
         }
     }
 
+    pub fn set_color_filter(&mut self, color_filter: Option<&drawing_api::ColorFilter>) {
+        self.color_filter_uniforms.set(color_filter);
+    }
+
     pub fn draw(&mut self, array: &[ColoredVertex]) {
         self.apply_array(array);
         unsafe {
@@ -0,0 +1,203 @@
+use super::{convert_blend_mode, preprocess_shader};
+use crate::utils::*;
+
+use crate::generic::device::TexturedVertex;
+use gl::types::*;
+use std::ffi::CString;
+
+/// Pipeline for one dimensional image filter passes (blur, dilate, erode)
+/// and full target compositing passes.
+///
+/// Vertex positions are given in normalized device coordinates.
+pub struct FilterPipeline {
+    program: Program,
+    vbo: GLuint,
+    vao: GLuint,
+    filter_type_location: GLint,
+    texel_step_location: GLint,
+    sigma_location: GLint,
+    radius_location: GLint,
+    tile_mode_location: GLint,
+    blur_style_location: GLint,
+    mask_sampler_location: GLint,
+    blend_mode_location: GLint,
+    tex_rect_location: GLint,
+}
+
+pub enum FilterType {
+    Blur,
+    Dilate,
+    Erode,
+    BlurMaskComposite,
+    Blend,
+}
+
+impl FilterPipeline {
+    pub fn new() -> Self {
+        let vertex_shader = Shader::from_vert_str(include_str!("shaders/filter.glslv")).unwrap();
+        let pixel_shader =
+            Shader::from_frag_str(&preprocess_shader(include_str!("shaders/filter.glslf")))
+                .unwrap();
+        let program = Program::from_shaders(&[vertex_shader, pixel_shader]).unwrap();
+
+        let get_location = |name: &str| unsafe {
+            let str = CString::new(name).unwrap();
+            gl::GetUniformLocation(program.id(), str.as_ptr())
+        };
+        let filter_type_location = get_location("filter_type");
+        let texel_step_location = get_location("texel_step");
+        let sigma_location = get_location("sigma");
+        let radius_location = get_location("radius");
+        let tile_mode_location = get_location("tile_mode");
+        let blur_style_location = get_location("blur_style");
+        let mask_sampler_location = get_location("mask_sampler");
+        let blend_mode_location = get_location("blend_mode");
+        let tex_rect_location = get_location("tex_rect");
+
+        FilterPipeline {
+            program,
+            vbo: 0,
+            vao: 0,
+            filter_type_location,
+            texel_step_location,
+            sigma_location,
+            radius_location,
+            tile_mode_location,
+            blur_style_location,
+            mask_sampler_location,
+            blend_mode_location,
+            tex_rect_location,
+        }
+    }
+
+    pub fn set_buffers(&mut self, buffers_vbo_vba: (GLuint, GLuint)) {
+        self.vbo = buffers_vbo_vba.0;
+        self.vao = buffers_vbo_vba.1;
+    }
+
+    pub fn apply(&mut self) {
+        self.program.set_used();
+    }
+
+    pub fn set_filter(
+        &mut self,
+        filter_type: FilterType,
+        texel_step: [f32; 2],
+        sigma: f32,
+        radius: i32,
+        tile_mode: i32,
+    ) {
+        unsafe {
+            gl::Uniform1i(self.filter_type_location, filter_type as GLint);
+            gl::Uniform2f(self.texel_step_location, texel_step[0], texel_step[1]);
+            gl::Uniform1f(self.sigma_location, sigma);
+            gl::Uniform1i(self.radius_location, radius);
+            gl::Uniform1i(self.tile_mode_location, tile_mode);
+        }
+    }
+
+    /// Sets the rectangle (offset and size in texture coordinates) of the content
+    /// in the textures. Both textures of the compositing passes have the same size.
+    pub fn set_tex_rect(&mut self, tex_rect: [f32; 4]) {
+        unsafe {
+            gl::Uniform4f(
+                self.tex_rect_location,
+                tex_rect[0],
+                tex_rect[1],
+                tex_rect[2],
+                tex_rect[3],
+            );
+        }
+    }
+
+    /// The original shape texture has to be bound to the texture unit 1.
+    pub fn set_blur_mask_composite(&mut self, blur_style: i32) {
+        unsafe {
+            gl::Uniform1i(
+                self.filter_type_location,
+                FilterType::BlurMaskComposite as GLint,
+            );
+            gl::Uniform1i(self.blur_style_location, blur_style);
+            gl::Uniform1i(self.mask_sampler_location, 1);
+        }
+    }
+
+    /// The destination texture has to be bound to the texture unit 1.
+    pub fn set_blend(&mut self, blend_mode: drawing_api::BlendMode) {
+        unsafe {
+            gl::Uniform1i(self.filter_type_location, FilterType::Blend as GLint);
+            gl::Uniform1i(self.blend_mode_location, convert_blend_mode(blend_mode));
+            gl::Uniform1i(self.mask_sampler_location, 1);
+        }
+    }
+
+    pub fn draw(&mut self, array: &[TexturedVertex]) {
+        self.apply_array(array);
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, array.len() as GLint);
+        }
+    }
+
+    fn apply_array(&mut self, array: &[TexturedVertex]) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,                                                    // target
+                (array.len() * std::mem::size_of::<TexturedVertex>()) as GLsizeiptr, // size of data in bytes
+                array.as_ptr() as *const GLvoid, // pointer to data
+                gl::STREAM_DRAW,                 // usage
+            );
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    pub fn create_vbo_and_vao(&self) -> (GLuint, GLuint) {
+        let mut vbo: GLuint = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut vbo);
+        }
+
+        let mut vao: GLuint = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+        }
+
+        FilterPipeline::specify_layout(self.program.id(), vbo, vao);
+
+        (vbo, vao)
+    }
+
+    fn specify_layout(program_id: GLuint, vbo: GLuint, vao: GLuint) {
+        unsafe {
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            let str = CString::new("in_position").unwrap();
+            let pos_attr = gl::GetAttribLocation(program_id, str.as_ptr());
+            gl::EnableVertexAttribArray(pos_attr as GLuint);
+            gl::VertexAttribPointer(
+                pos_attr as GLuint,
+                2,
+                gl::FLOAT,
+                gl::FALSE as GLboolean,
+                (8 * std::mem::size_of::<f32>()) as GLint, // stride (byte offset between consecutive attributes)
+                std::ptr::null(),
+            ); // offset of the first component
+
+            let str = CString::new("in_tex_coords").unwrap();
+            let pos_attr = gl::GetAttribLocation(program_id, str.as_ptr());
+            gl::EnableVertexAttribArray(pos_attr as GLuint);
+            gl::VertexAttribPointer(
+                pos_attr as GLuint,
+                2,
+                gl::FLOAT,
+                gl::FALSE as GLboolean,
+                (8 * std::mem::size_of::<f32>()) as GLint, // stride (byte offset between consecutive attributes)
+                (2 * std::mem::size_of::<f32>()) as *const GLvoid,
+            ); // offset of the first component
+
+            gl::BindVertexArray(0);
+        }
+    }
+}
@@ -1,6 +1,12 @@
+mod color_filter_uniforms;
+pub use self::color_filter_uniforms::*;
+
 mod colored_pipeline;
 pub use self::colored_pipeline::*;
 
+mod filter_pipeline;
+pub use self::filter_pipeline::*;
+
 mod textured_pipeline;
 pub use self::textured_pipeline::*;
 
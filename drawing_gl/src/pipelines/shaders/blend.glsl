@@ -0,0 +1,110 @@
+// blend modes in the order of drawing_api::BlendMode, on premultiplied colors
+// (the advanced modes according to the W3C compositing specification)
+
+float blendColorDodge(float cb, float cs) {
+    if (cb <= 0.0) return 0.0;
+    if (cs >= 1.0) return 1.0;
+    return min(1.0, cb / (1.0 - cs));
+}
+
+float blendColorBurn(float cb, float cs) {
+    if (cb >= 1.0) return 1.0;
+    if (cs <= 0.0) return 0.0;
+    return 1.0 - min(1.0, (1.0 - cb) / cs);
+}
+
+float blendSoftLight(float cb, float cs) {
+    if (cs <= 0.5) return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    float d = cb <= 0.25 ? ((16.0 * cb - 12.0) * cb + 4.0) * cb : sqrt(cb);
+    return cb + (2.0 * cs - 1.0) * (d - cb);
+}
+
+vec3 blendHardLight(vec3 cb, vec3 cs) {
+    vec3 multiply = cb * 2.0 * cs;
+    vec3 screen = 1.0 - (1.0 - cb) * (2.0 - 2.0 * cs);
+    return mix(multiply, screen, step(0.5, cs));
+}
+
+float blendLum(vec3 c) {
+    return dot(c, vec3(0.3, 0.59, 0.11));
+}
+
+vec3 blendClipColor(vec3 c) {
+    float l = blendLum(c);
+    float n = min(min(c.r, c.g), c.b);
+    float x = max(max(c.r, c.g), c.b);
+    if (n < 0.0) c = l + (c - l) * l / max(l - n, 1e-6);
+    if (x > 1.0) c = l + (c - l) * (1.0 - l) / max(x - l, 1e-6);
+    return c;
+}
+
+vec3 blendSetLum(vec3 c, float l) {
+    return blendClipColor(c + (l - blendLum(c)));
+}
+
+float blendSat(vec3 c) {
+    return max(max(c.r, c.g), c.b) - min(min(c.r, c.g), c.b);
+}
+
+vec3 blendSetSat(vec3 c, float s) {
+    float n = min(min(c.r, c.g), c.b);
+    float x = max(max(c.r, c.g), c.b);
+    return x > n ? (c - n) * s / (x - n) : vec3(0.0);
+}
+
+vec4 blendColors(vec4 src, vec4 dst, int mode) {
+    // Porter-Duff modes
+    if (mode == 0) return vec4(0.0);
+    if (mode == 1) return src;
+    if (mode == 2) return dst;
+    if (mode == 3) return src + dst * (1.0 - src.a);
+    if (mode == 4) return dst + src * (1.0 - dst.a);
+    if (mode == 5) return src * dst.a;
+    if (mode == 6) return dst * src.a;
+    if (mode == 7) return src * (1.0 - dst.a);
+    if (mode == 8) return dst * (1.0 - src.a);
+    if (mode == 9) return src * dst.a + dst * (1.0 - src.a);
+    if (mode == 10) return dst * src.a + src * (1.0 - dst.a);
+    if (mode == 11) return src * (1.0 - dst.a) + dst * (1.0 - src.a);
+    if (mode == 12) return min(src + dst, 1.0);
+    if (mode == 13) return src * dst;
+    if (mode == 14) return src + dst - src * dst;
+
+    // advanced modes, the blend function works on unpremultiplied colors
+    vec3 cs = src.a > 0.0 ? src.rgb / src.a : vec3(0.0);
+    vec3 cb = dst.a > 0.0 ? dst.rgb / dst.a : vec3(0.0);
+    vec3 b;
+    if (mode == 15) {
+        b = blendHardLight(cs, cb);
+    } else if (mode == 16) {
+        b = min(cs, cb);
+    } else if (mode == 17) {
+        b = max(cs, cb);
+    } else if (mode == 18) {
+        b = vec3(blendColorDodge(cb.r, cs.r), blendColorDodge(cb.g, cs.g), blendColorDodge(cb.b, cs.b));
+    } else if (mode == 19) {
+        b = vec3(blendColorBurn(cb.r, cs.r), blendColorBurn(cb.g, cs.g), blendColorBurn(cb.b, cs.b));
+    } else if (mode == 20) {
+        b = blendHardLight(cb, cs);
+    } else if (mode == 21) {
+        b = vec3(blendSoftLight(cb.r, cs.r), blendSoftLight(cb.g, cs.g), blendSoftLight(cb.b, cs.b));
+    } else if (mode == 22) {
+        b = abs(cb - cs);
+    } else if (mode == 23) {
+        b = cb + cs - 2.0 * cb * cs;
+    } else if (mode == 24) {
+        b = cb * cs;
+    } else if (mode == 25) {
+        b = blendSetLum(blendSetSat(cs, blendSat(cb)), blendLum(cb));
+    } else if (mode == 26) {
+        b = blendSetLum(blendSetSat(cb, blendSat(cs)), blendLum(cb));
+    } else if (mode == 27) {
+        b = blendSetLum(cs, blendLum(cb));
+    } else {
+        b = blendSetLum(cb, blendLum(cs));
+    }
+
+    return vec4(
+        (1.0 - dst.a) * src.rgb + (1.0 - src.a) * dst.rgb + src.a * dst.a * clamp(b, 0.0, 1.0),
+        src.a + dst.a - src.a * dst.a);
+}
@@ -0,0 +1,28 @@
+#include "blend.glsl"
+
+// color filter of the drawn primitive
+// (0 - none, 1 - 5x4 color matrix, 2 - blend with the constant color)
+uniform int colorFilterType;
+uniform int colorFilterBlendMode;
+uniform vec4 colorFilterColor;
+uniform mat4 colorFilterMatrix;
+uniform vec4 colorFilterOffset;
+
+// works on unpremultiplied colors
+vec4 applyColorFilter(vec4 color) {
+    if (colorFilterType == 1) {
+        return clamp(colorFilterMatrix * color + colorFilterOffset, 0.0, 1.0);
+    } else if (colorFilterType == 2) {
+        vec4 src = vec4(colorFilterColor.rgb * colorFilterColor.a, colorFilterColor.a);
+        vec4 dst = vec4(color.rgb * color.a, color.a);
+        vec4 result = blendColors(src, dst, colorFilterBlendMode);
+        return result.a > 0.0 ? vec4(result.rgb / result.a, result.a) : vec4(0.0);
+    }
+    return color;
+}
+
+vec4 applyColorFilterPremultiplied(vec4 color) {
+    if (colorFilterType == 0) return color;
+    vec4 result = applyColorFilter(color.a > 0.0 ? vec4(color.rgb / color.a, color.a) : vec4(0.0));
+    return vec4(result.rgb * result.a, result.a);
+}
@@ -1,3 +1,4 @@
+use super::{preprocess_shader, ColorFilterUniforms};
 use crate::utils::*;
 
 use crate::generic::device::TexturedVertex;
@@ -10,12 +11,15 @@ pub struct TexturedPipeline {
     vao: GLuint,
     transform_location: GLint,
     flipped_y_location: GLint,
+    color_filter_uniforms: ColorFilterUniforms,
 }
 
 impl TexturedPipeline {
     pub fn new() -> Self {
         let vertex_shader = Shader::from_vert_str(include_str!("shaders/textured.glslv")).unwrap();
-        let pixel_shader = Shader::from_frag_str(include_str!("shaders/textured.glslf")).unwrap();
+        let pixel_shader =
+            Shader::from_frag_str(&preprocess_shader(include_str!("shaders/textured.glslf")))
+                .unwrap();
         let program = Program::from_shaders(&[vertex_shader, pixel_shader]).unwrap();
 
         let transform_location = unsafe {
@@ -27,12 +31,15 @@ impl TexturedPipeline {
             gl::GetUniformLocation(program.id(), str.as_ptr())
         };
 
+        let color_filter_uniforms = ColorFilterUniforms::new(program.id());
+
         TexturedPipeline {
             program,
             vbo: 0,
             vao: 0,
             transform_location,
             flipped_y_location,
+            color_filter_uniforms,
         }
     }
 
@@ -52,6 +59,10 @@ impl TexturedPipeline {
         }
     }
 
+    pub fn set_color_filter(&mut self, color_filter: Option<&drawing_api::ColorFilter>) {
+        self.color_filter_uniforms.set(color_filter);
+    }
+
     pub fn set_flipped_y(&mut self, flipped_y: bool) {
         unsafe {
             gl::Uniform1i(self.flipped_y_location, if flipped_y { 1 } else { 0 });
@@ -1,3 +1,4 @@
+use super::{preprocess_shader, ColorFilterUniforms};
 use crate::utils::*;
 
 use crate::generic::device::TexturedY8Vertex;
@@ -10,14 +11,17 @@ pub struct TexturedY8Pipeline {
     vao: GLuint,
     transform_location: GLint,
     flipped_y_location: GLint,
+    color_filter_uniforms: ColorFilterUniforms,
 }
 
 impl TexturedY8Pipeline {
     pub fn new() -> Self {
         let vertex_shader =
             Shader::from_vert_str(include_str!("shaders/textured_y8.glslv")).unwrap();
-        let pixel_shader =
-            Shader::from_frag_str(include_str!("shaders/textured_y8.glslf")).unwrap();
+        let pixel_shader = Shader::from_frag_str(&preprocess_shader(include_str!(
+            "shaders/textured_y8.glslf"
+        )))
+        .unwrap();
         let program = Program::from_shaders(&[vertex_shader, pixel_shader]).unwrap();
 
         let transform_location = unsafe {
@@ -29,12 +33,15 @@ impl TexturedY8Pipeline {
             gl::GetUniformLocation(program.id(), str.as_ptr())
         };
 
+        let color_filter_uniforms = ColorFilterUniforms::new(program.id());
+
         TexturedY8Pipeline {
             program,
             vbo: 0,
             vao: 0,
             transform_location,
             flipped_y_location,
+            color_filter_uniforms,
         }
     }
 
@@ -54,6 +61,10 @@ impl TexturedY8Pipeline {
         }
     }
 
+    pub fn set_color_filter(&mut self, color_filter: Option<&drawing_api::ColorFilter>) {
+        self.color_filter_uniforms.set(color_filter);
+    }
+
     pub fn set_flipped_y(&mut self, flipped_y: bool) {
         unsafe {
             gl::Uniform1i(self.flipped_y_location, if flipped_y { 1 } else { 0 });
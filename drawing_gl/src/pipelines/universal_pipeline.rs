@@ -1,3 +1,4 @@
+use super::{preprocess_shader, ColorFilterUniforms};
 use crate::{generic::device::Color, utils::*};
 use std::os::raw::c_void;
 
@@ -12,6 +13,7 @@ pub struct FragUniforms {
     pub paint_mat: [f32; 12],
     pub inner_color: Color,
     pub outer_color: Color,
+    pub gradient_params: [f32; 8],
     pub scissor_ext: [f32; 2],
     pub scissor_scale: [f32; 2],
     pub extent: [f32; 2],
@@ -21,6 +23,10 @@ pub struct FragUniforms {
     pub stroke_thr: f32,
     pub tex_type: i32,
     pub type_: i32,
+    pub gradient_type: i32,
+    pub tile_mode: i32,
+    pub tile_mode_y: i32,
+    pub _padding: i32,
     // warning! always add padding to multiply of 32 bytes (std140 layout rules)
     //pub _padding: [u8; 16],
 }
@@ -30,6 +36,7 @@ pub enum ShaderType {
     FillImage,
     Simple,
     //Image,
+    FillGradientRamp = 4,
 }
 
 pub struct UniversalPipeline {
@@ -38,12 +45,15 @@ pub struct UniversalPipeline {
     vao: GLuint,
     transform_location: GLint,
     frag_uniform_buf: GLuint,
+    color_filter_uniforms: ColorFilterUniforms,
 }
 
 impl UniversalPipeline {
     pub fn new() -> Self {
         let vertex_shader = Shader::from_vert_str(include_str!("shaders/universal.glslv")).unwrap();
-        let pixel_shader = Shader::from_frag_str(include_str!("shaders/universal.glslf")).unwrap();
+        let pixel_shader =
+            Shader::from_frag_str(&preprocess_shader(include_str!("shaders/universal.glslf")))
+                .unwrap();
         let program = Program::from_shaders(&[vertex_shader, pixel_shader]).unwrap();
 
         let transform_location = unsafe {
@@ -65,12 +75,15 @@ impl UniversalPipeline {
             frag_uniform_buf
         };
 
+        let color_filter_uniforms = ColorFilterUniforms::new(program.id());
+
         UniversalPipeline {
             program,
             vbo: 0,
             vao: 0,
             transform_location,
             frag_uniform_buf,
+            color_filter_uniforms,
         }
     }
 
@@ -90,6 +103,10 @@ impl UniversalPipeline {
         }
     }
 
+    pub fn set_color_filter(&mut self, color_filter: Option<&drawing_api::ColorFilter>) {
+        self.color_filter_uniforms.set(color_filter);
+    }
+
     pub fn apply_frag_uniforms(&self, uniforms: &FragUniforms) {
         unsafe {
             gl::BindBuffer(gl::UNIFORM_BUFFER, self.frag_uniform_buf);
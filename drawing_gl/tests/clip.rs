@@ -0,0 +1,74 @@
+mod common;
+
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, PathBuilder as _};
+use drawing_gl::{DisplayListBuilder, PathBuilder};
+
+const SIZE: u32 = 64;
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn clip_path_intersect_draws_inside() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    let mut path_builder = PathBuilder::default();
+    path_builder.add_rect(rect(16.0, 16.0, 32.0, 32.0));
+    let path = path_builder.build();
+
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_path(&path, ClipOperation::Intersect);
+    dlb.draw_rect(rect(0.0, 0.0, SIZE as f32, SIZE as f32), "#F00");
+    let pixels = common::render(&mut surface, SIZE, SIZE, &dlb.build().unwrap());
+
+    assert!(common::is_red(pixels.get_pixel(32, 32)));
+    assert!(common::is_empty(pixels.get_pixel(4, 4)));
+    assert!(common::is_empty(pixels.get_pixel(60, 60)));
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn clip_oval_intersect_draws_inside() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_oval(rect(8.0, 8.0, 48.0, 48.0), ClipOperation::Intersect);
+    dlb.draw_rect(rect(0.0, 0.0, SIZE as f32, SIZE as f32), "#F00");
+    let pixels = common::render(&mut surface, SIZE, SIZE, &dlb.build().unwrap());
+
+    assert!(common::is_red(pixels.get_pixel(32, 32)));
+    assert!(common::is_empty(pixels.get_pixel(10, 10)));
+    assert!(common::is_empty(pixels.get_pixel(2, 32)));
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn clip_rounded_rect_intersect_draws_inside() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_rounded_rect(rect(8.0, 8.0, 48.0, 48.0), 8.0, ClipOperation::Intersect);
+    dlb.draw_rect(rect(0.0, 0.0, SIZE as f32, SIZE as f32), "#F00");
+    let pixels = common::render(&mut surface, SIZE, SIZE, &dlb.build().unwrap());
+
+    assert!(common::is_red(pixels.get_pixel(32, 32)));
+    assert!(common::is_empty(pixels.get_pixel(4, 32)));
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn clip_rect_difference_draws_outside_hole() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_rect(rect(16.0, 16.0, 32.0, 32.0), ClipOperation::Difference);
+    dlb.draw_rect(rect(0.0, 0.0, SIZE as f32, SIZE as f32), "#F00");
+    let pixels = common::render(&mut surface, SIZE, SIZE, &dlb.build().unwrap());
+
+    assert!(common::is_empty(pixels.get_pixel(32, 32)));
+    assert!(common::is_red(pixels.get_pixel(4, 4)));
+    assert!(common::is_red(pixels.get_pixel(60, 32)));
+}
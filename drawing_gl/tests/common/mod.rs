@@ -0,0 +1,87 @@
+//! Headless OpenGL context used by the readback tests.
+
+// every test uses only some of the helpers
+#![allow(dead_code)]
+
+use std::ffi::{c_void, CString};
+
+use drawing_api::euclid;
+use drawing_api::prelude::*;
+use drawing_gl::{GlContext, GlSurface, GlTexture};
+use glutin::api::egl::{context::PossiblyCurrentContext, device::Device, display::Display};
+use glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glutin::context::{ContextApi, ContextAttributesBuilder, Version};
+use glutin::prelude::*;
+
+pub struct Headless {
+    // dropped before the EGL context
+    pub context: GlContext,
+    _egl_context: PossiblyCurrentContext,
+    _display: Display,
+}
+
+impl Headless {
+    /// Creates a surfaceless EGL context, returns `None` when no EGL device is available.
+    ///
+    /// The tests using it are ignored by default, run them with `cargo test -- --ignored`
+    /// on a machine with an EGL device (a software one like Mesa's llvmpipe is enough).
+    pub fn new() -> Option<Self> {
+        let device = Device::query_devices().ok()?.next()?;
+        let display = unsafe { Display::with_device(&device, None) }.ok()?;
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .with_stencil_size(8)
+            .build();
+        let config = unsafe { display.find_configs(template) }.ok()?.next()?;
+        let attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+            .build(None);
+        let egl_context = unsafe { display.create_context(&config, &attributes) }
+            .ok()?
+            .make_current_surfaceless()
+            .ok()?;
+
+        let context = unsafe {
+            GlContext::new_gl(|s| {
+                display.get_proc_address(&CString::new(s).unwrap()) as *mut c_void
+            })
+        }
+        .ok()?;
+
+        Some(Self {
+            context,
+            _egl_context: egl_context,
+            _display: display,
+        })
+    }
+
+    pub fn create_surface(&self, width: u32, height: u32) -> (GlSurface, GlTexture) {
+        self.context
+            .create_offscreen_surface(width, height, ColorFormat::RGBA)
+            .unwrap()
+    }
+}
+
+/// Draws the display list and returns the RGBA pixels of the whole surface.
+pub fn render(
+    surface: &mut GlSurface,
+    width: u32,
+    height: u32,
+    display_list: &<GlContext as DrawingContext>::DisplayList,
+) -> PixelData {
+    surface.draw(display_list).unwrap();
+    surface
+        .read_pixels(PixelIntRect::new(
+            euclid::point2(0, 0),
+            euclid::size2(width, height),
+        ))
+        .unwrap()
+}
+
+pub fn is_red(pixel: &[u8]) -> bool {
+    pixel[0] > 200 && pixel[1] < 50 && pixel[2] < 50 && pixel[3] > 200
+}
+
+pub fn is_empty(pixel: &[u8]) -> bool {
+    pixel[3] < 50
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::borrow::Cow;
+
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, Paint as _};
+use drawing_gl::{DisplayListBuilder, GlContext};
+
+type Paint = <GlContext as DrawingContext>::Paint;
+
+const SIZE: u32 = 64;
+
+/// Draws a 4x1 texture (two red and two transparent texels) scaled 4 times
+/// and tiled horizontally, returns the pixels of the first row.
+fn draw_tiled(headless: &common::Headless, tile_mode: TileMode) -> Vec<[u8; 4]> {
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+    let mut texels = [0u8; 16];
+    texels[0..8].copy_from_slice(&[255, 0, 0, 255, 255, 0, 0, 255]);
+    let image = unsafe {
+        headless.context.create_texture(
+            Cow::Owned(texels.to_vec()),
+            TextureDescriptor {
+                width: 4,
+                height: 1,
+                color_format: ColorFormat::RGBA,
+                mip_count: 1,
+            },
+        )
+    }
+    .unwrap();
+
+    let mut paint = Paint::default();
+    paint.set_color("#FFF");
+    paint.set_color_source(ColorSource::Image {
+        image,
+        horizontal_tile_mode: tile_mode,
+        vertical_tile_mode: TileMode::Clamp,
+        sampling: TextureSampling::NearestNeighbor,
+        transformation: Some(Matrix::scale(4.0, 4.0, 1.0)),
+    });
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(0.0, 0.0, SIZE as f32, 8.0), &paint);
+    let pixels = common::render(&mut surface, SIZE, SIZE, &dlb.build().unwrap());
+
+    (0..SIZE)
+        .map(|x| pixels.get_pixel(x, 2).try_into().unwrap())
+        .collect()
+}
+
+/// Returns whether the pixels in the middle of the 4 pixel wide texel columns are red.
+fn red_columns(pixels: &[[u8; 4]]) -> Vec<bool> {
+    pixels
+        .iter()
+        .skip(2)
+        .step_by(4)
+        .take(8)
+        .map(|pixel| common::is_red(pixel))
+        .collect()
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn repeat_tiles_the_image() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let pixels = draw_tiled(&headless, TileMode::Repeat);
+    assert_eq!(
+        red_columns(&pixels),
+        [true, true, false, false, true, true, false, false]
+    );
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn mirror_tiles_the_mirrored_image() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let pixels = draw_tiled(&headless, TileMode::Mirror);
+    assert_eq!(
+        red_columns(&pixels),
+        [true, true, false, false, false, false, true, true]
+    );
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn decal_draws_the_image_once() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let pixels = draw_tiled(&headless, TileMode::Decal);
+    assert_eq!(
+        red_columns(&pixels),
+        [true, true, false, false, false, false, false, false]
+    );
+}
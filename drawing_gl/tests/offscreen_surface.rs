@@ -0,0 +1,47 @@
+mod common;
+
+use drawing_api::prelude::*;
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn offscreen_surface_size_is_validated() {
+    let headless = common::Headless::new().expect("no EGL device");
+
+    for (width, height) in [(0, 16), (16, 0), (u16::MAX as u32 + 1, 16), (16, 100_000)] {
+        assert!(headless
+            .context
+            .create_offscreen_surface(width, height, ColorFormat::RGBA)
+            .is_err());
+    }
+    assert!(headless
+        .context
+        .create_offscreen_surface(16, 16, ColorFormat::RGBA)
+        .is_ok());
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn read_pixels_rejects_overflowing_rectangles() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (surface, texture) = headless.create_surface(16, 16);
+
+    let rect = PixelIntRect::new(
+        drawing_api::euclid::point2(u32::MAX, 0),
+        drawing_api::euclid::size2(2, 2),
+    );
+    assert!(surface.read_pixels(rect).is_err());
+    assert!(texture.read_pixels(rect).is_err());
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn offscreen_texture_is_premultiplied() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (_surface, texture) = headless.create_surface(16, 16);
+
+    let rect = PixelIntRect::new(
+        drawing_api::euclid::point2(0, 0),
+        drawing_api::euclid::size2(16, 16),
+    );
+    assert!(texture.read_pixels(rect).unwrap().premultiplied);
+}
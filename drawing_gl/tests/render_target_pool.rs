@@ -0,0 +1,95 @@
+mod common;
+
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, Paint as _};
+use drawing_gl::{DisplayListBuilder, GlContext};
+
+type Paint = <GlContext as DrawingContext>::Paint;
+
+const SIZE: u32 = 100;
+
+fn layer_display_list(layer_bounds: PixelRect) -> <GlContext as DrawingContext>::DisplayList {
+    let paint = Paint::default();
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.save_layer(layer_bounds, Some((&paint).into()), None);
+    dlb.draw_rect(rect(30.0, 30.0, 20.0, 20.0), "#F00");
+    dlb.restore();
+    dlb.build().unwrap()
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn layer_is_drawn_at_its_position() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    // the layer's size is not a multiple of the pool's size step
+    let display_list = layer_display_list(rect(5.0, 7.0, 71.0, 53.0));
+    let pixels = common::render(&mut surface, SIZE, SIZE, &display_list);
+
+    assert!(common::is_red(pixels.get_pixel(31, 31)));
+    assert!(common::is_red(pixels.get_pixel(48, 48)));
+    assert!(common::is_empty(pixels.get_pixel(28, 40)));
+    assert!(common::is_empty(pixels.get_pixel(40, 52)));
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn filtered_content_is_drawn_at_its_position() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    let mut paint = Paint::default();
+    paint.set_color("#F00");
+    paint.set_image_filter(ImageFilter::Blur {
+        x_sigma: 1.0,
+        y_sigma: 1.0,
+        tile_mode: TileMode::Decal,
+    });
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(30.0, 30.0, 20.0, 20.0), &paint);
+    let display_list = dlb.build().unwrap();
+    let pixels = common::render(&mut surface, SIZE, SIZE, &display_list);
+
+    assert!(common::is_red(pixels.get_pixel(40, 40)));
+    assert!(common::is_empty(pixels.get_pixel(20, 40)));
+    assert!(common::is_empty(pixels.get_pixel(40, 60)));
+    assert!(common::is_empty(pixels.get_pixel(90, 90)));
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn render_targets_are_reused_for_similar_sizes() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    surface
+        .draw(&layer_display_list(rect(10.0, 10.0, 50.0, 50.0)))
+        .unwrap();
+    let count = headless.context.get_render_target_pool_stats().count;
+    assert!(count > 0);
+
+    surface
+        .draw(&layer_display_list(rect(10.0, 10.0, 52.0, 51.0)))
+        .unwrap();
+    assert_eq!(headless.context.get_render_target_pool_stats().count, count);
+}
+
+#[test]
+#[ignore = "requires an EGL device"]
+fn render_targets_are_released_after_unused_frames() {
+    let headless = common::Headless::new().expect("no EGL device");
+    let (mut surface, _texture) = headless.create_surface(SIZE, SIZE);
+
+    // drawing doesn't end the frame
+    let display_list = layer_display_list(rect(10.0, 10.0, 50.0, 50.0));
+    for _ in 0..10 {
+        surface.draw(&display_list).unwrap();
+    }
+    assert!(headless.context.get_render_target_pool_stats().count > 0);
+
+    for _ in 0..10 {
+        headless.context.end_frame();
+    }
+    assert_eq!(headless.context.get_render_target_pool_stats().count, 0);
+}
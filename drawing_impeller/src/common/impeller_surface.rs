@@ -1,5 +1,9 @@
 pub struct ImpellerSurface {
     pub(crate) surface: impellers::Surface,
+
+    // framebuffer created for the offscreen surface,
+    // released after the Impeller surface
+    pub(crate) framebuffer: Option<crate::GlFramebuffer>,
 }
 
 impl drawing_api::Surface for ImpellerSurface {
@@ -9,6 +13,24 @@ impl drawing_api::Surface for ImpellerSurface {
         self.surface.draw_display_list(&display_list.display_list)
     }
 
+    fn read_pixels(
+        &self,
+        rect: drawing_api::PixelIntRect,
+    ) -> Result<drawing_api::PixelData, &'static str> {
+        // Impeller cannot read back its surfaces,
+        // only the framebuffers of the offscreen surfaces are known
+        let framebuffer = self
+            .framebuffer
+            .as_ref()
+            .ok_or("impeller: reading pixels is supported only for offscreen surfaces")?;
+        crate::read_framebuffer_pixels(
+            framebuffer.framebuffer_id,
+            framebuffer.width,
+            framebuffer.height,
+            rect,
+        )
+    }
+
     fn present(self) -> Result<(), &'static str> {
         self.surface.present()
     }
@@ -2,6 +2,11 @@
 pub struct ImpellerTexture {
     pub(crate) texture: impellers::Texture,
     pub(crate) descriptor: drawing_api::TextureDescriptor,
+
+    // the textures of the offscreen surfaces are drawn by Impeller
+    // from bottom to top with premultiplied colors
+    pub(crate) flipped_y: bool,
+    pub(crate) premultiplied: bool,
 }
 
 impl drawing_api::Texture for ImpellerTexture {
@@ -12,4 +17,23 @@ impl drawing_api::Texture for ImpellerTexture {
     fn get_gl_handle(&self) -> usize {
         self.texture.get_opengl_handle() as usize
     }
+
+    fn read_pixels(
+        &self,
+        rect: drawing_api::PixelIntRect,
+    ) -> Result<drawing_api::PixelData, &'static str> {
+        // there is no OpenGL handle with other backends
+        let texture_id = self.texture.get_opengl_handle();
+        if texture_id == 0 {
+            return Err("impeller: reading pixels is supported only for OpenGL textures");
+        }
+        crate::read_texture_pixels(
+            texture_id as u32,
+            self.descriptor.width,
+            self.descriptor.height,
+            self.flipped_y,
+            self.premultiplied,
+            rect,
+        )
+    }
 }
@@ -0,0 +1,69 @@
+use gl::types::{GLsizei, GLuint};
+
+/// OpenGL framebuffer object (with stencil attachment) drawing to a texture.
+///
+/// Impeller can only wrap existing framebuffers, so offscreen surfaces
+/// need to create and own them.
+pub(crate) struct GlFramebuffer {
+    pub framebuffer_id: GLuint,
+    depth_stencil_renderbuffer_id: GLuint,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl GlFramebuffer {
+    pub fn new(texture_id: GLuint, width: u32, height: u32) -> Result<Self, &'static str> {
+        let mut framebuffer_id: GLuint = 0;
+        let mut depth_stencil_renderbuffer_id: GLuint = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer_id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer_id);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                texture_id,
+                0,
+            );
+
+            // stencil buffer is needed by Impeller for path filling and clipping
+            gl::GenRenderbuffers(1, &mut depth_stencil_renderbuffer_id);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, depth_stencil_renderbuffer_id);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH24_STENCIL8,
+                width as GLsizei,
+                height as GLsizei,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                depth_stencil_renderbuffer_id,
+            );
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            let framebuffer = Self {
+                framebuffer_id,
+                depth_stencil_renderbuffer_id,
+                width,
+                height,
+            };
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err("impeller: offscreen framebuffer is not complete");
+            }
+            Ok(framebuffer)
+        }
+    }
+}
+
+impl Drop for GlFramebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer_id);
+            gl::DeleteRenderbuffers(1, &self.depth_stencil_renderbuffer_id);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use drawing_api::{check_pixel_rect, ColorFormat, PixelData, PixelIntRect};
+use gl::types::*;
+
+/// Reads back RGBA pixels of the framebuffer drawn by Impeller.
+pub(crate) fn read_framebuffer_pixels(
+    framebuffer_id: GLuint,
+    framebuffer_width: u32,
+    framebuffer_height: u32,
+    rect: PixelIntRect,
+) -> Result<PixelData, &'static str> {
+    check_pixel_rect(rect, framebuffer_width, framebuffer_height)?;
+
+    // Impeller outputs premultiplied colors
+    read_pixels(framebuffer_id, framebuffer_height, true, true, rect)
+}
+
+/// Reads back pixels of an RGBA OpenGL texture used by Impeller.
+///
+/// The texture is attached to a temporary framebuffer, as `glGetTexImage()`
+/// is not available in OpenGL ES.
+pub(crate) fn read_texture_pixels(
+    texture_id: GLuint,
+    width: u32,
+    height: u32,
+    flipped_y: bool,
+    premultiplied: bool,
+    rect: PixelIntRect,
+) -> Result<PixelData, &'static str> {
+    check_pixel_rect(rect, width, height)?;
+
+    let mut framebuffer_id: GLuint = 0;
+    let status = unsafe {
+        gl::GenFramebuffers(1, &mut framebuffer_id);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer_id);
+        gl::FramebufferTexture2D(
+            gl::READ_FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            texture_id,
+            0,
+        );
+        gl::CheckFramebufferStatus(gl::READ_FRAMEBUFFER)
+    };
+
+    let result = if status == gl::FRAMEBUFFER_COMPLETE {
+        read_pixels(framebuffer_id, height, flipped_y, premultiplied, rect)
+    } else {
+        Err("impeller: cannot attach the texture to a framebuffer")
+    };
+
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        gl::DeleteFramebuffers(1, &framebuffer_id);
+    }
+    result
+}
+
+/// Reads the rectangle of the framebuffer.
+///
+/// The rows of flipped framebuffers (like the ones Impeller draws to)
+/// are stored from bottom to top.
+fn read_pixels(
+    framebuffer_id: GLuint,
+    framebuffer_height: u32,
+    flipped_y: bool,
+    premultiplied: bool,
+    rect: PixelIntRect,
+) -> Result<PixelData, &'static str> {
+    let y = if flipped_y {
+        framebuffer_height - rect.max_y()
+    } else {
+        rect.min_y()
+    };
+
+    let row_stride = rect.size.width as usize * 4;
+    let mut data = vec![0u8; row_stride * rect.size.height as usize];
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer_id);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            rect.min_x() as GLint,
+            y as GLint,
+            rect.size.width as GLsizei,
+            rect.size.height as GLsizei,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            data.as_mut_ptr() as *mut GLvoid,
+        );
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+    }
+
+    let mut pixels = PixelData {
+        data,
+        width: rect.size.width,
+        height: rect.size.height,
+        row_stride: row_stride as u32,
+        color_format: ColorFormat::RGBA,
+        premultiplied,
+    };
+    if flipped_y {
+        pixels.flip_vertically();
+    }
+    Ok(pixels)
+}
@@ -1,8 +1,8 @@
 use std::{borrow::Cow, cell::RefCell, os::raw::c_void, rc::Rc};
 
-use drawing_api::{Capabilities, ColorSource, GraphicsApi, TextureDescriptor};
+use drawing_api::{Capabilities, ColorSource, DrawingContext, GraphicsApi, TextureDescriptor};
 
-use crate::{ImpellerSurface, ImpellerTexture};
+use crate::{GlFramebuffer, ImpellerSurface, ImpellerTexture};
 
 #[derive(Clone)]
 pub struct ImpellerContextGl {
@@ -74,6 +74,7 @@ impl drawing_api::DrawingContext for ImpellerContextGl {
                     None
                 }
             }
+            _ => None,
         }
     }
 
@@ -101,9 +102,64 @@ impl drawing_api::DrawingContext for ImpellerContextGl {
         Ok(ImpellerTexture {
             texture,
             descriptor,
+            flipped_y: false,
+            premultiplied: false,
         })
     }
 
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        color_format: drawing_api::ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        if width == 0 || height == 0 {
+            return Err("surface size cannot be zero");
+        }
+        // the same limit as the GL backend has
+        u16::try_from(width).map_err(|_| "surface width is too big")?;
+        u16::try_from(height).map_err(|_| "surface height is too big")?;
+        if color_format != drawing_api::ColorFormat::RGBA {
+            return Err("color format not supported!");
+        }
+
+        let contents = vec![0u8; width as usize * height as usize * 4];
+        let mut texture = unsafe {
+            self.create_texture(
+                Cow::Owned(contents),
+                TextureDescriptor {
+                    width,
+                    height,
+                    color_format,
+                    mip_count: 1,
+                },
+            )?
+        };
+        texture.flipped_y = true;
+        texture.premultiplied = true;
+
+        let framebuffer =
+            GlFramebuffer::new(texture.texture.get_opengl_handle() as u32, width, height)?;
+        let surface = unsafe {
+            self.context
+                .borrow_mut()
+                .wrap_fbo(
+                    framebuffer.framebuffer_id as u64,
+                    impellers::PixelFormat::RGBA8888,
+                    impellers::ISize::new(width as i64, height as i64),
+                )
+                .ok_or("impeller: cannot wrap offscreen framebuffer")?
+        };
+
+        Ok((
+            ImpellerSurface {
+                surface,
+                framebuffer: Some(framebuffer),
+            },
+            texture,
+        ))
+    }
+
     unsafe fn new_color_source_from_fragment_program(
         &self,
         frag_program: &Self::FragmentProgram,
@@ -154,10 +210,13 @@ impl drawing_api::DrawingContext for ImpellerContextGl {
 }
 
 impl drawing_api::ContextGl for ImpellerContextGl {
-    unsafe fn new_gl<F>(loadfn: F) -> Result<Self, &'static str>
+    unsafe fn new_gl<F>(mut loadfn: F) -> Result<Self, &'static str>
     where
         F: FnMut(&str) -> *mut c_void,
     {
+        // OpenGL functions are needed to create framebuffers for offscreen surfaces
+        gl::load_with(|s| loadfn(s) as *const c_void);
+
         unsafe {
             let context = Rc::new(RefCell::new(impellers::Context::new_opengl_es(loadfn)?));
             Ok(Self { context })
@@ -185,7 +244,10 @@ impl drawing_api::ContextGl for ImpellerContextGl {
                     impellers::ISize::new(width as i64, height as i64),
                 )
                 .ok_or("ddd")?;
-            Ok(ImpellerSurface { surface })
+            Ok(ImpellerSurface {
+                surface,
+                framebuffer: None,
+            })
         }
     }
 
@@ -214,6 +276,8 @@ impl drawing_api::ContextGl for ImpellerContextGl {
         Ok(ImpellerTexture {
             texture,
             descriptor,
+            flipped_y: false,
+            premultiplied: false,
         })
     }
 }
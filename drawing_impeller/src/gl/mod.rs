@@ -1,2 +1,8 @@
+mod gl_framebuffer;
+pub(crate) use gl_framebuffer::*;
+
+mod gl_read_pixels;
+pub(crate) use gl_read_pixels::*;
+
 mod impeller_context_gl;
 pub use impeller_context_gl::*;
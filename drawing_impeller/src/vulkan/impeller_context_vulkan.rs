@@ -83,6 +83,7 @@ impl DrawingContext for ImpellerContextVulkan {
                     None
                 }
             }
+            _ => None,
         }
     }
 
@@ -110,9 +111,27 @@ impl DrawingContext for ImpellerContextVulkan {
         Ok(ImpellerTexture {
             texture,
             descriptor,
+            flipped_y: false,
+            premultiplied: false,
         })
     }
 
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        _color_format: drawing_api::ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        if width == 0 || height == 0 {
+            return Err("surface size cannot be zero");
+        }
+        u16::try_from(width).map_err(|_| "surface width is too big")?;
+        u16::try_from(height).map_err(|_| "surface height is too big")?;
+
+        // Impeller can create Vulkan surfaces only from a swapchain
+        Err("impeller: offscreen surfaces are not supported with Vulkan")
+    }
+
     unsafe fn new_color_source_from_fragment_program(
         &self,
         frag_program: &Self::FragmentProgram,
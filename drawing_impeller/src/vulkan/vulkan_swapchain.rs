@@ -13,6 +13,7 @@ impl drawing_api::VulkanSwapchain for VulkanSwapchain {
                 .vk_swapchain
                 .acquire_next_surface_new()
                 .ok_or("impeller: cannot acquire next vulkan surface")?,
+            framebuffer: None,
         })
     }
 }
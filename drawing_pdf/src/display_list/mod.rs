@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use drawing_api::command_list::{self, FontMetrics};
+
+use crate::{PdfContext, PdfTexture};
+
+pub(crate) use command_list::rect_path;
+pub use command_list::{
+    ColorSourceFragment, Fonts, GlyphInfo, ImageFilterFragment, LineMetrics, Path, PathBuilder,
+    PathElement,
+};
+
+pub type DisplayList = command_list::DisplayList<PdfContext>;
+pub type DisplayListBuilder = command_list::DisplayListBuilder<PdfContext>;
+pub type Paint = command_list::Paint<PdfContext>;
+pub type Paragraph = command_list::Paragraph<PdfContext>;
+pub type ParagraphBuilder = command_list::ParagraphBuilder<PdfContext>;
+pub(crate) type Command = command_list::Command<PdfContext>;
+
+impl command_list::Backend for PdfContext {
+    type Texture = PdfTexture;
+    type Fonts = Fonts;
+
+    fn get_advances(
+        fonts: &Fonts,
+        family_name: &str,
+        size: f32,
+        text: &str,
+    ) -> Result<(FontMetrics, HashMap<char, f32>), &'static str> {
+        Ok(fonts.get_advances(family_name, size, text))
+    }
+}
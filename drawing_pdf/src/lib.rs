@@ -0,0 +1,20 @@
+mod display_list;
+
+mod pdf_context;
+pub use pdf_context::*;
+
+mod pdf_fragment_program;
+pub use pdf_fragment_program::*;
+
+mod pdf_surface;
+pub use pdf_surface::*;
+
+mod pdf_texture;
+pub use pdf_texture::*;
+
+mod writer;
+
+pub use display_list::{
+    ColorSourceFragment, DisplayList, DisplayListBuilder, Fonts, GlyphInfo, ImageFilterFragment,
+    LineMetrics, Paint, Paragraph, ParagraphBuilder, Path, PathBuilder, PathElement,
+};
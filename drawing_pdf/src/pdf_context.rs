@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{
+    Capabilities, ColorFormat, ColorSource, DrawingContext, GraphicsApi, ImageFilter,
+    TextureDescriptor,
+};
+
+use crate::writer::{Canvas, PdfDocument};
+use crate::{PdfSurface, PdfTexture, TextureSource};
+
+/// Backend exporting the display lists as pages of a PDF document.
+///
+/// The clones of the context share the document.
+#[derive(Clone)]
+pub struct PdfContext {
+    document: Arc<Mutex<PdfDocument>>,
+}
+
+impl PdfContext {
+    /// Creates an empty document.
+    pub fn new() -> Self {
+        Self {
+            document: Arc::new(Mutex::new(PdfDocument::new())),
+        }
+    }
+
+    /// Adds a page to the document, the size is in points (1/72 inch).
+    pub fn create_surface(&self, width: u32, height: u32) -> Result<PdfSurface, &'static str> {
+        let canvas = Arc::new(Mutex::new(Canvas::new(width, height)));
+        self.document.lock().unwrap().add_page(canvas.clone());
+        Ok(PdfSurface {
+            document: self.document.clone(),
+            canvas,
+        })
+    }
+
+    /// Returns the PDF document with all the pages drawn so far.
+    pub fn get_pdf(&self) -> Vec<u8> {
+        self.document.lock().unwrap().to_pdf()
+    }
+}
+
+impl Default for PdfContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrawingContext for PdfContext {
+    type ColorSourceFragment = crate::display_list::ColorSourceFragment;
+    type DisplayList = crate::display_list::DisplayList;
+    type DisplayListBuilder = crate::display_list::DisplayListBuilder;
+    type Fonts = crate::display_list::Fonts;
+    type FragmentProgram = crate::PdfFragmentProgram;
+    type ImageFilterFragment = crate::display_list::ImageFilterFragment;
+    type Paint = crate::display_list::Paint;
+    type ParagraphBuilder = crate::display_list::ParagraphBuilder;
+    type PathBuilder = crate::display_list::PathBuilder;
+    type Surface = PdfSurface;
+    type Texture = PdfTexture;
+
+    fn get_api_capabilities(api: GraphicsApi) -> Option<Capabilities> {
+        match api {
+            GraphicsApi::Pdf => Some(Capabilities {
+                api: GraphicsApi::Pdf,
+                transformations: true,
+                layers: true,
+                rect_clipping: true,
+                path_clipping: true,
+                color_filters: false,
+                image_filters: false,
+                mask_filters: false,
+                textures: true,
+                text_metrics: true,
+                text_decorations: false,
+                shadows: false,
+                fragment_color_sources: false,
+                fragment_image_filters: false,
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_capabilities(&self) -> Capabilities {
+        PdfContext::get_api_capabilities(GraphicsApi::Pdf).unwrap()
+    }
+
+    unsafe fn create_texture(
+        &self,
+        contents: Cow<'static, [u8]>,
+        descriptor: TextureDescriptor,
+    ) -> Result<Self::Texture, &'static str> {
+        let len = descriptor.width as usize
+            * descriptor.height as usize
+            * descriptor.color_format.bytes_per_pixel();
+        if contents.len() < len {
+            return Err("not enough texture data");
+        }
+
+        Ok(PdfTexture::new(TextureSource::Pixels {
+            width: descriptor.width,
+            height: descriptor.height,
+            color_format: descriptor.color_format,
+            data: contents[..len].to_vec(),
+        }))
+    }
+
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        _color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        // offscreen surfaces are not pages, they are drawn as form XObjects
+        let canvas = Arc::new(Mutex::new(Canvas::new(width, height)));
+        let surface = PdfSurface {
+            document: self.document.clone(),
+            canvas: canvas.clone(),
+        };
+        let texture = PdfTexture::new(TextureSource::Surface {
+            document_id: self.document.lock().unwrap().id,
+            canvas,
+        });
+        Ok((surface, texture))
+    }
+
+    unsafe fn new_color_source_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ColorSource<Self::Texture, Self::ColorSourceFragment> {
+        // fragment programs are not supported, the paint color is used instead
+        ColorSource::Fragment {
+            color_source: crate::display_list::ColorSourceFragment {},
+        }
+    }
+
+    unsafe fn new_image_filter_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ImageFilter<Self::ImageFilterFragment> {
+        // fragment programs are not supported, the filter is ignored
+        ImageFilter::Fragment {
+            image_filter: crate::display_list::ImageFilterFragment {},
+        }
+    }
+}
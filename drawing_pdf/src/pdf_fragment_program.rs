@@ -0,0 +1,8 @@
+#[derive(Clone)]
+pub struct PdfFragmentProgram {}
+
+impl drawing_api::FragmentProgram for PdfFragmentProgram {
+    unsafe fn new(_program: std::borrow::Cow<'static, [u8]>) -> Result<Self, &'static str> {
+        Err("fragment programs are not supported by the PDF backend")
+    }
+}
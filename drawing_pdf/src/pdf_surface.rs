@@ -0,0 +1,52 @@
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{PixelData, PixelIntRect};
+
+use crate::writer::{Canvas, PdfDocument, PdfWriter, Transform};
+
+/// Page of the PDF document (or an offscreen surface used as a texture).
+pub struct PdfSurface {
+    pub(crate) document: Arc<Mutex<PdfDocument>>,
+    pub(crate) canvas: Arc<Mutex<Canvas>>,
+}
+
+impl drawing_api::Surface for PdfSurface {
+    type DisplayList = crate::display_list::DisplayList;
+
+    fn draw(&mut self, display_list: &Self::DisplayList) -> Result<(), &'static str> {
+        // the display list can use the texture of this surface,
+        // so the canvas cannot stay locked while writing
+        let mut canvas = self.canvas.lock().unwrap().clone();
+        {
+            let mut document = self.document.lock().unwrap();
+            let mut writer = PdfWriter::new(
+                &mut document,
+                canvas.width,
+                canvas.height,
+                std::mem::take(&mut canvas.stream),
+            );
+
+            // PDF coordinates start at the bottom
+            let base = Transform::new(
+                1.0f32,
+                0.0f32,
+                0.0f32,
+                -1.0f32,
+                0.0f32,
+                canvas.height as f32,
+            );
+            writer.write_display_list(display_list, &base);
+            canvas.stream = writer.finish();
+        }
+        *self.canvas.lock().unwrap() = canvas;
+        Ok(())
+    }
+
+    fn read_pixels(&self, _rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        Err("reading pixels is not supported by the PDF backend")
+    }
+
+    fn present(self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
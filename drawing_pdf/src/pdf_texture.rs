@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{ColorFormat, PixelData, PixelIntRect, TextureDescriptor};
+
+use crate::writer::Canvas;
+
+static NEXT_TEXTURE_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) enum TextureSource {
+    /// Straight alpha pixels embedded as image XObject.
+    Pixels {
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+        data: Vec<u8>,
+    },
+
+    /// Offscreen surface embedded as form XObject.
+    Surface {
+        /// Document the objects used by the surface belong to.
+        document_id: usize,
+        canvas: Arc<Mutex<Canvas>>,
+    },
+}
+
+/// Image embedded in the PDF document.
+#[derive(Clone)]
+pub struct PdfTexture {
+    /// Identifier used to embed the pixels in the document only once.
+    pub(crate) id: usize,
+    pub(crate) source: Arc<TextureSource>,
+}
+
+impl PdfTexture {
+    pub(crate) fn new(source: TextureSource) -> Self {
+        Self {
+            id: NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn get_size(&self) -> (u32, u32) {
+        match self.source.as_ref() {
+            TextureSource::Pixels { width, height, .. } => (*width, *height),
+            TextureSource::Surface { canvas, .. } => {
+                let canvas = canvas.lock().unwrap();
+                (canvas.width, canvas.height)
+            }
+        }
+    }
+}
+
+impl drawing_api::Texture for PdfTexture {
+    fn get_descriptor(&self) -> TextureDescriptor {
+        let (width, height) = self.get_size();
+        let color_format = match self.source.as_ref() {
+            TextureSource::Pixels { color_format, .. } => *color_format,
+            TextureSource::Surface { .. } => ColorFormat::RGBA,
+        };
+        TextureDescriptor {
+            width,
+            height,
+            color_format,
+            mip_count: 1,
+        }
+    }
+
+    fn get_gl_handle(&self) -> usize {
+        0
+    }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        match self.source.as_ref() {
+            TextureSource::Pixels {
+                width,
+                height,
+                color_format,
+                data,
+            } => PixelData {
+                data: data.clone(),
+                width: *width,
+                height: *height,
+                row_stride: *width * color_format.bytes_per_pixel() as u32,
+                color_format: *color_format,
+                premultiplied: false,
+            }
+            .crop(rect),
+            TextureSource::Surface { .. } => {
+                Err("reading pixels is not supported by the PDF backend")
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+use drawing_api::command_list::{read_i16, read_u16, read_u32, FontFace};
+
+// tables needed to embed the TrueType font in PDF
+const SUBSET_TABLES: [&[u8; 4]; 9] = [
+    b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep",
+];
+
+// composite glyph flags
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Creates a TrueType font with the outlines of the given glyphs only.
+///
+/// The glyph indices are not changed (the other glyphs are empty),
+/// so the text can use the original indices. Returns `None`
+/// when the font doesn't have TrueType outlines.
+pub(crate) fn subset_font(face: &FontFace, glyphs: &BTreeSet<u16>) -> Option<Vec<u8>> {
+    let head = face.get_table(b"head")?;
+    let maxp = face.get_table(b"maxp")?;
+    let loca = face.get_table(b"loca")?;
+    let glyf = face.get_table(b"glyf")?;
+
+    let long_offsets = read_i16(head, 50)? != 0;
+    let glyph_count = read_u16(maxp, 4)? as usize;
+    let get_glyph = |glyph: u16| -> Option<&[u8]> {
+        let index = glyph as usize;
+        if index >= glyph_count {
+            return None;
+        }
+        let (start, end) = if long_offsets {
+            (
+                read_u32(loca, index * 4)? as usize,
+                read_u32(loca, index * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(loca, index * 2)? as usize * 2,
+                read_u16(loca, index * 2 + 2)? as usize * 2,
+            )
+        };
+        glyf.get(start..end.max(start))
+    };
+
+    // the composite glyphs need their components
+    // (the glyph 0 is the missing glyph and is always needed)
+    let mut used = BTreeSet::new();
+    let mut pending = vec![0u16];
+    pending.extend(glyphs.iter().copied());
+    while let Some(glyph) = pending.pop() {
+        if !used.insert(glyph) {
+            continue;
+        }
+        if let Some(data) = get_glyph(glyph) {
+            pending.extend(get_components(data));
+        }
+    }
+
+    // rebuild the glyph data with the long offsets
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((glyph_count + 1) * 4);
+    for glyph in 0..glyph_count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        let data = get_glyph(glyph as u16).filter(|_| used.contains(&(glyph as u16)));
+        if let Some(data) = data {
+            new_glyf.extend_from_slice(data);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0u8);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    new_head.get_mut(8..12)?.fill(0u8);
+    new_head
+        .get_mut(50..52)?
+        .copy_from_slice(&1u16.to_be_bytes());
+
+    let tables = SUBSET_TABLES
+        .iter()
+        .filter_map(|tag| {
+            let data = match *tag {
+                b"glyf" => new_glyf.clone(),
+                b"loca" => new_loca.clone(),
+                b"head" => new_head.clone(),
+                _ => face.get_table(tag)?.to_vec(),
+            };
+            Some((**tag, data))
+        })
+        .collect::<Vec<_>>();
+
+    let mut font = write_font_file(&tables);
+
+    // the whole font checksum is stored in the head table
+    let head_offset = read_u32(&font, 12 + 16 * table_index(&tables, b"head")? + 8)? as usize;
+    let adjustment = 0xB1B0AFBAu32.wrapping_sub(get_checksum(&font));
+    font.get_mut(head_offset + 8..head_offset + 12)?
+        .copy_from_slice(&adjustment.to_be_bytes());
+    Some(font)
+}
+
+/// Returns the glyphs used by the composite glyph.
+fn get_components(data: &[u8]) -> Vec<u16> {
+    let mut components = Vec::new();
+    if read_i16(data, 0).unwrap_or(0) >= 0 {
+        return components;
+    }
+
+    let mut offset = 10;
+    while let (Some(flags), Some(glyph)) = (read_u16(data, offset), read_u16(data, offset + 2)) {
+        components.push(glyph);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            offset += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            offset += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            offset += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+fn table_index(tables: &[([u8; 4], Vec<u8>)], tag: &[u8; 4]) -> Option<usize> {
+    tables.iter().position(|(t, _)| t == tag)
+}
+
+/// Writes the font file, the tables have to be sorted by the tag.
+fn write_font_file(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x00010000u32.to_be_bytes());
+    font.extend_from_slice(&num_tables.to_be_bytes());
+    font.extend_from_slice(&search_range.to_be_bytes());
+    font.extend_from_slice(&entry_selector.to_be_bytes());
+    font.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&get_checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0u8);
+    }
+    font
+}
+
+fn get_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
@@ -0,0 +1,17 @@
+mod color;
+pub(crate) use color::*;
+
+mod font_subset;
+pub(crate) use font_subset::*;
+
+mod pdf_document;
+pub(crate) use pdf_document::*;
+
+mod pdf_writer;
+pub(crate) use pdf_writer::*;
+
+/// 2D affine transformation from the local to the default (page) coordinates.
+pub(crate) type Transform = drawing_api::euclid::default::Transform2D<f32>;
+
+/// Point in the default (page) coordinates.
+pub(crate) type Point = drawing_api::euclid::default::Point2D<f32>;
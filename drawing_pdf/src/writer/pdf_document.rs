@@ -0,0 +1,389 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use drawing_api::command_list::Font;
+
+use super::{format_number, subset_font};
+
+static NEXT_DOCUMENT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Resources used by a content stream.
+///
+/// The resource names are derived from the object numbers.
+#[derive(Clone, Default)]
+pub(crate) struct Resources {
+    pub fonts: BTreeSet<usize>,
+    pub x_objects: BTreeSet<usize>,
+    pub ext_g_states: BTreeSet<usize>,
+    pub patterns: BTreeSet<usize>,
+}
+
+impl Resources {
+    pub fn to_dictionary(&self) -> String {
+        let mut dictionary = String::from("<<");
+        for (key, prefix, ids) in [
+            ("Font", "F", &self.fonts),
+            ("XObject", "X", &self.x_objects),
+            ("ExtGState", "GS", &self.ext_g_states),
+            ("Pattern", "P", &self.patterns),
+        ] {
+            if !ids.is_empty() {
+                dictionary.push_str(&format!(" /{} <<", key));
+                for id in ids {
+                    dictionary.push_str(&format!(" /{}{} {} 0 R", prefix, id, id));
+                }
+                dictionary.push_str(" >>");
+            }
+        }
+        dictionary.push_str(" >>");
+        dictionary
+    }
+}
+
+/// Page description operators with the resources they use.
+#[derive(Clone, Default)]
+pub(crate) struct ContentStream {
+    pub content: String,
+    pub resources: Resources,
+}
+
+/// Drawing area of a page or an offscreen surface.
+#[derive(Clone)]
+pub(crate) struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub stream: ContentStream,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            stream: ContentStream::default(),
+        }
+    }
+}
+
+/// Font embedded in the document, the glyphs used by the text are collected
+/// while drawing and the font file is subsetted when the document is written.
+pub(crate) struct FontResource {
+    pub id: usize,
+    pub font: Arc<Font>,
+    pub family: String,
+
+    /// Glyph indices with the characters they represent.
+    pub glyphs: BTreeMap<u16, char>,
+}
+
+/// PDF document being drawn.
+///
+/// The objects shared by the pages (images, graphics states etc.) are written
+/// immediately, the pages and the fonts are written by `to_pdf()`.
+pub(crate) struct PdfDocument {
+    /// Identifier used to check if the texture belongs to the document.
+    pub id: usize,
+
+    /// Bodies of the objects, the object number is the index + 1.
+    objects: Vec<Vec<u8>>,
+
+    pages: Vec<Arc<Mutex<Canvas>>>,
+    fonts: BTreeMap<String, FontResource>,
+    standard_font: Option<usize>,
+    images: HashMap<(usize, bool), usize>,
+    ext_g_states: HashMap<String, usize>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_DOCUMENT_ID.fetch_add(1, Ordering::Relaxed),
+            objects: Vec::new(),
+            pages: Vec::new(),
+            fonts: BTreeMap::new(),
+            standard_font: None,
+            images: HashMap::new(),
+            ext_g_states: HashMap::new(),
+        }
+    }
+
+    pub fn add_page(&mut self, canvas: Arc<Mutex<Canvas>>) {
+        self.pages.push(canvas);
+    }
+
+    /// Adds the object, returns its number.
+    pub fn add_object(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        self.objects.push(body.into());
+        self.objects.len()
+    }
+
+    /// Adds the stream object, `dictionary` contains the entries other than the length.
+    pub fn add_stream(&mut self, dictionary: &str, data: &[u8]) -> usize {
+        let body = get_stream(dictionary, data);
+        self.add_object(body)
+    }
+
+    /// Returns the embedded font, the font object is written later.
+    pub fn get_font(&mut self, family: &str, font: &Arc<Font>) -> &mut FontResource {
+        if !self.fonts.contains_key(family) {
+            // reserve the object number
+            let id = self.add_object(Vec::new());
+            self.fonts.insert(
+                family.to_string(),
+                FontResource {
+                    id,
+                    font: font.clone(),
+                    family: family.to_string(),
+                    glyphs: BTreeMap::new(),
+                },
+            );
+        }
+        self.fonts.get_mut(family).unwrap()
+    }
+
+    /// Returns the font used when no font is registered.
+    pub fn get_standard_font(&mut self) -> usize {
+        match self.standard_font {
+            Some(id) => id,
+            None => {
+                let id = self.add_object(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+                );
+                self.standard_font = Some(id);
+                id
+            }
+        }
+    }
+
+    /// Returns the graphics state object with the given dictionary.
+    pub fn get_ext_g_state(&mut self, dictionary: String) -> usize {
+        if let Some(id) = self.ext_g_states.get(&dictionary) {
+            return *id;
+        }
+        let id = self.add_object(dictionary.clone());
+        self.ext_g_states.insert(dictionary, id);
+        id
+    }
+
+    /// Returns the image object of the texture (with the given interpolation).
+    pub fn get_image(
+        &mut self,
+        texture_id: usize,
+        interpolate: bool,
+        create: impl FnOnce(&mut PdfDocument) -> usize,
+    ) -> usize {
+        if let Some(id) = self.images.get(&(texture_id, interpolate)) {
+            return *id;
+        }
+        let id = create(self);
+        self.images.insert((texture_id, interpolate), id);
+        id
+    }
+
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut objects = self.objects.clone();
+        let add_object = |objects: &mut Vec<Vec<u8>>, body: Vec<u8>| {
+            objects.push(body);
+            objects.len()
+        };
+
+        for font in self.fonts.values() {
+            let body = write_font(font, &mut |body| add_object(&mut objects, body));
+            objects[font.id - 1] = body;
+        }
+
+        let pages_id = add_object(&mut objects, Vec::new());
+        let mut page_ids = Vec::new();
+        for page in &self.pages {
+            let page = page.lock().unwrap();
+            let content_id =
+                add_object(&mut objects, get_stream("", page.stream.content.as_bytes()));
+            let page_id = add_object(
+                &mut objects,
+                format!(
+                    "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources {} /Contents {} 0 R \
+                     /Group << /S /Transparency /CS /DeviceRGB >> >>",
+                    pages_id,
+                    page.width,
+                    page.height,
+                    page.stream.resources.to_dictionary(),
+                    content_id
+                )
+                .into_bytes(),
+            );
+            page_ids.push(page_id);
+        }
+        objects[pages_id - 1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            page_ids.len()
+        )
+        .into_bytes();
+        let catalog_id = add_object(
+            &mut objects,
+            format!("<< /Type /Catalog /Pages {} 0 R >>", pages_id).into_bytes(),
+        );
+
+        // the binary comment marks the file as binary for the transfer programs
+        let mut pdf = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                catalog_id,
+                xref_offset
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+}
+
+/// Writes the objects of the font, returns the body of the Type 0 font object.
+fn write_font(font: &FontResource, add_object: &mut dyn FnMut(Vec<u8>) -> usize) -> Vec<u8> {
+    let face = &font.font.face;
+    let glyphs = font.glyphs.keys().copied().collect::<BTreeSet<_>>();
+
+    // the subset tag is derived from the used glyphs
+    let hash = glyphs.iter().fold(0xcbf29ce484222325u64, |hash, glyph| {
+        (hash ^ *glyph as u64).wrapping_mul(0x100000001b3)
+    });
+    let tag = (0..6)
+        .map(|i| (b'A' + (hash >> (i * 5) & 0x1F) as u8 % 26) as char)
+        .collect::<String>();
+    let name = face
+        .get_postscript_name()
+        .unwrap_or_else(|| font.family.clone())
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric() || *ch == '-' || *ch == '_')
+        .collect::<String>();
+    let base_font = format!("{}+{}", tag, if name.is_empty() { "Font" } else { &name });
+
+    // CFF outlines cannot be subsetted, the whole font is embedded
+    let (font_file, cid_font_type) = match subset_font(face, &glyphs) {
+        Some(data) => (
+            get_stream(&format!("/Length1 {}", data.len()), &data),
+            "/CIDFontType2 /CIDToGIDMap /Identity",
+        ),
+        None => (
+            get_stream("/Subtype /OpenType", &font.font.data),
+            "/CIDFontType0",
+        ),
+    };
+    let is_true_type = cid_font_type.starts_with("/CIDFontType2");
+    let font_file_id = add_object(font_file);
+
+    let [x_min, y_min, x_max, y_max] = face.get_bounding_box().map(to_glyph_units);
+    let descriptor_id = add_object(
+        format!(
+            "<< /Type /FontDescriptor /FontName /{} /Flags 4 /FontBBox [{} {} {} {}] /ItalicAngle 0 \
+             /Ascent {} /Descent {} /CapHeight {} /StemV 80 /{} {} 0 R >>",
+            base_font,
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+            to_glyph_units(face.get_ascent()),
+            to_glyph_units(-face.get_descent()),
+            to_glyph_units(face.get_ascent()),
+            if is_true_type { "FontFile2" } else { "FontFile3" },
+            font_file_id
+        )
+        .into_bytes(),
+    );
+
+    let widths = glyphs
+        .iter()
+        .map(|glyph| {
+            format!(
+                "{} [{}]",
+                glyph,
+                to_glyph_units(face.get_glyph_advance(*glyph))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let cid_font_id = add_object(
+        format!(
+            "<< /Type /Font /Subtype {} /BaseFont /{} \
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+             /FontDescriptor {} 0 R /DW 0 /W [{}] >>",
+            cid_font_type, base_font, descriptor_id, widths
+        )
+        .into_bytes(),
+    );
+
+    let to_unicode_id = add_object(get_stream("", get_to_unicode(&font.glyphs).as_bytes()));
+
+    format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H \
+         /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+        base_font, cid_font_id, to_unicode_id
+    )
+    .into_bytes()
+}
+
+/// Returns the character map used to extract the text.
+fn get_to_unicode(glyphs: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+
+    // at most 100 entries per section
+    let glyphs = glyphs.iter().collect::<Vec<_>>();
+    for chunk in glyphs.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (glyph, ch) in chunk {
+            let mut utf16 = [0u16; 2];
+            let text = ch
+                .encode_utf16(&mut utf16)
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect::<String>();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", glyph, text));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// Converts the ems to the glyph space units (1/1000 of the text size).
+fn to_glyph_units(value: f32) -> String {
+    format_number(value * 1000.0f32)
+}
+
+fn get_stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = if dictionary.is_empty() {
+        format!("<< /Length {} >>\nstream\n", data.len()).into_bytes()
+    } else {
+        format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes()
+    };
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
@@ -0,0 +1,1282 @@
+use drawing_api::{
+    BlendMode, ClipOperation, Color, ColorFormat, ColorSource, DrawStyle, FillType, ImageFilter,
+    PixelPoint, PixelRect, PixelSize, StrokeCap, StrokeJoin, TextureSampling, TileMode,
+};
+
+use crate::display_list::{rect_path, Command};
+use crate::{DisplayList, Paint, Paragraph, PathElement, PdfTexture, TextureSource};
+
+use super::{filter_color, ContentStream, PdfDocument, Point, Transform};
+
+// maximum number of the gradient repetitions
+const MAX_GRADIENT_REPEAT: i32 = 64;
+
+enum SaveEntry<'a> {
+    Save,
+    Layer {
+        /// Bounds in the default coordinates.
+        bounds: PixelRect,
+        transform: Transform,
+        paint: &'a Option<Paint>,
+    },
+}
+
+/// Converts the display list commands to the content stream operators.
+///
+/// The operators use the absolute transformations (set inside of `q` / `Q` pairs),
+/// so the layers can be drawn as form XObjects in the default coordinates.
+pub(crate) struct PdfWriter<'a> {
+    document: &'a mut PdfDocument,
+    width: u32,
+    height: u32,
+
+    /// The last stream is the one being written (the others are waiting for the layers).
+    streams: Vec<ContentStream>,
+}
+
+impl<'a> PdfWriter<'a> {
+    pub fn new(
+        document: &'a mut PdfDocument,
+        width: u32,
+        height: u32,
+        stream: ContentStream,
+    ) -> Self {
+        Self {
+            document,
+            width,
+            height,
+            streams: vec![stream],
+        }
+    }
+
+    pub fn finish(mut self) -> ContentStream {
+        self.streams.pop().unwrap_or_default()
+    }
+
+    /// Writes the display list, the command transformations are followed by the `base` one.
+    pub fn write_display_list(&mut self, display_list: &DisplayList, base: &Transform) {
+        let mut save_stack = Vec::new();
+
+        for command in display_list.commands.iter() {
+            match command {
+                Command::Save => {
+                    self.push("q\n");
+                    save_stack.push(SaveEntry::Save);
+                }
+
+                // backdrop filters cannot be expressed in PDF, so they are ignored
+                Command::SaveLayer {
+                    bounds,
+                    transform,
+                    paint,
+                    backdrop_filter: _,
+                } => {
+                    let transform = transform.then(base);
+                    self.streams.push(ContentStream::default());
+                    self.push(&format!(
+                        "{}W n\n",
+                        get_path_data(&rect_path(*bounds), Some(&transform))
+                    ));
+                    save_stack.push(SaveEntry::Layer {
+                        bounds: transform_rect(&transform, bounds),
+                        transform,
+                        paint,
+                    });
+                }
+
+                Command::Restore => {
+                    if let Some(entry) = save_stack.pop() {
+                        self.restore(entry);
+                    }
+                }
+
+                Command::Clip {
+                    path,
+                    transform,
+                    operation,
+                } => {
+                    let transform = transform.then(base);
+                    let path_data = get_path_data(&path.path, Some(&transform));
+                    match operation {
+                        ClipOperation::Intersect => self.push(&format!(
+                            "{}{} n\n",
+                            path_data,
+                            get_clip_operator(path.fill_type)
+                        )),
+
+                        // the hole is cut out of the whole canvas with the even-odd rule,
+                        // which is exact for the paths without overlapping parts
+                        ClipOperation::Difference => self.push(&format!(
+                            "0 0 {} {} re {}W* n\n",
+                            self.width, self.height, path_data
+                        )),
+                    }
+                }
+
+                Command::DrawPaint { paint, transform } => {
+                    self.write_paint(paint, &transform.then(base));
+                }
+
+                Command::DrawPath {
+                    path,
+                    transform,
+                    paint,
+                } => {
+                    let bounds = drawing_api::Path::get_bounds(path);
+                    self.write_shape(
+                        &get_path_data(&path.path, None),
+                        path.fill_type,
+                        paint,
+                        &transform.then(base),
+                        bounds,
+                    );
+                }
+
+                Command::DrawTexture {
+                    texture,
+                    src_rect,
+                    dst_rect,
+                    sampling,
+                    transform,
+                    paint,
+                } => {
+                    self.write_texture(
+                        texture,
+                        *src_rect,
+                        *dst_rect,
+                        *sampling,
+                        &transform.then(base),
+                        paint.as_ref(),
+                    );
+                }
+
+                Command::DrawParagraph {
+                    paragraph,
+                    location,
+                    transform,
+                } => {
+                    let transform = Transform::translation(location.x, location.y)
+                        .then(transform)
+                        .then(base);
+                    self.write_paragraph(paragraph, &transform);
+                }
+
+                Command::DrawDisplayList {
+                    display_list,
+                    transform,
+                    opacity,
+                } => {
+                    let transform = transform.then(base);
+                    if *opacity < 1.0f32 {
+                        // the opacity is applied to the whole group
+                        self.streams.push(ContentStream::default());
+                        self.write_display_list(display_list, &transform);
+                        let stream = self.streams.pop().unwrap_or_default();
+                        let bounds = PixelRect::new(
+                            PixelPoint::zero(),
+                            PixelSize::new(self.width as f32, self.height as f32),
+                        );
+                        let form_id = self.add_group(&stream, bounds);
+                        let graphics_state = self.get_graphics_state(
+                            opacity.max(0.0f32),
+                            BlendMode::SourceOver,
+                            None,
+                        );
+                        self.push(&format!("q\n{}/X{} Do\nQ\n", graphics_state, form_id));
+                        self.streams
+                            .last_mut()
+                            .unwrap()
+                            .resources
+                            .x_objects
+                            .insert(form_id);
+                    } else {
+                        self.push("q\n");
+                        self.write_display_list(display_list, &transform);
+                        self.push("Q\n");
+                    }
+                }
+            }
+        }
+
+        while let Some(entry) = save_stack.pop() {
+            self.restore(entry);
+        }
+    }
+
+    fn push(&mut self, operators: &str) {
+        self.streams.last_mut().unwrap().content.push_str(operators);
+    }
+
+    fn restore(&mut self, entry: SaveEntry<'_>) {
+        match entry {
+            SaveEntry::Save => self.push("Q\n"),
+
+            SaveEntry::Layer {
+                bounds,
+                transform,
+                paint,
+            } => {
+                let stream = self.streams.pop().unwrap_or_default();
+                let form_id = self.add_group(&stream, bounds);
+
+                let mut operators = String::from("q\n");
+                if let Some(paint) = paint {
+                    // only the opacity, the blend mode and the matrix filter can be expressed
+                    operators.push_str(&self.get_graphics_state(
+                        paint.color[3].clamp(0.0f32, 1.0f32),
+                        paint.blend_mode,
+                        None,
+                    ));
+                    if let Some(ImageFilter::Matrix { matrix, .. }) = &paint.image_filter {
+                        // the matrix is applied in the local coordinates of the layer
+                        let layer_transform = transform
+                            .inverse()
+                            .unwrap_or_else(Transform::identity)
+                            .then(&matrix.to_2d())
+                            .then(&transform);
+                        operators.push_str(&format!("{} cm\n", format_matrix(&layer_transform)));
+                    }
+                }
+                operators.push_str(&format!("/X{} Do\nQ\n", form_id));
+                self.push(&operators);
+                self.streams
+                    .last_mut()
+                    .unwrap()
+                    .resources
+                    .x_objects
+                    .insert(form_id);
+            }
+        }
+    }
+
+    /// Adds the transparency group form with the content of the stream.
+    fn add_group(&mut self, stream: &ContentStream, bounds: PixelRect) -> usize {
+        self.document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Form /BBox [{} {} {} {}] /Group << /S /Transparency >> /Resources {}",
+                format_number(bounds.min_x()),
+                format_number(bounds.min_y()),
+                format_number(bounds.max_x()),
+                format_number(bounds.max_y()),
+                stream.resources.to_dictionary()
+            ),
+            stream.content.as_bytes(),
+        )
+    }
+
+    /// Fills the whole canvas (limited by the clip) with the paint.
+    fn write_paint(&mut self, paint: &Paint, transform: &Transform) {
+        let Some(inverse) = transform.inverse() else {
+            return;
+        };
+        let canvas_rect = PixelRect::new(
+            PixelPoint::zero(),
+            PixelSize::new(self.width as f32, self.height as f32),
+        );
+        let rect = transform_rect(&inverse, &canvas_rect);
+        let paint = Paint {
+            draw_style: DrawStyle::Fill,
+            ..paint.clone()
+        };
+        self.write_shape(
+            &get_path_data(&rect_path(rect), None),
+            FillType::NonZero,
+            &paint,
+            transform,
+            rect,
+        );
+    }
+
+    /// Paints the path (in the local coordinates).
+    fn write_shape(
+        &mut self,
+        path_data: &str,
+        fill_type: FillType,
+        paint: &Paint,
+        transform: &Transform,
+        bounds: PixelRect,
+    ) {
+        // the matrix image filter transforms the shape in the local coordinates,
+        // the other filters cannot be expressed in PDF
+        let transform = match &paint.image_filter {
+            Some(ImageFilter::Matrix { matrix, .. }) => matrix.to_2d().then(transform),
+            _ => *transform,
+        };
+
+        let (graphics_state, color) = self.get_paint_operators(paint, &transform, bounds);
+        let operator = match (paint.draw_style, fill_type) {
+            (DrawStyle::Fill, FillType::NonZero) => "f",
+            (DrawStyle::Fill, FillType::Odd) => "f*",
+            (DrawStyle::Stroke, _) => "S",
+            (DrawStyle::StrokeAndFill, FillType::NonZero) => "B",
+            (DrawStyle::StrokeAndFill, FillType::Odd) => "B*",
+        };
+
+        self.push(&format!(
+            "q\n{}{} cm\n{}{}{}\nQ\n",
+            graphics_state,
+            format_matrix(&transform),
+            color,
+            path_data,
+            operator
+        ));
+    }
+
+    fn write_texture(
+        &mut self,
+        texture: &PdfTexture,
+        src_rect: PixelRect,
+        dst_rect: PixelRect,
+        sampling: TextureSampling,
+        transform: &Transform,
+        paint: Option<&Paint>,
+    ) {
+        if src_rect.is_empty() {
+            return;
+        }
+        let Some((x_object_id, placement)) = self.get_x_object(texture, sampling, paint) else {
+            return;
+        };
+
+        // maps the source rectangle to the destination one
+        let scale_x = dst_rect.size.width / src_rect.size.width;
+        let scale_y = dst_rect.size.height / src_rect.size.height;
+        let image_transform = placement
+            .then_translate(-src_rect.origin.to_vector().cast_unit())
+            .then_scale(scale_x, scale_y)
+            .then_translate(dst_rect.origin.to_vector().cast_unit())
+            .then(transform);
+
+        let graphics_state = match paint {
+            Some(paint) => self.get_graphics_state(
+                paint.color[3].clamp(0.0f32, 1.0f32),
+                paint.blend_mode,
+                None,
+            ),
+            None => String::new(),
+        };
+        self.push(&format!(
+            "q\n{}{}W n\n{} cm\n/X{} Do\nQ\n",
+            graphics_state,
+            get_path_data(&rect_path(dst_rect), Some(transform)),
+            format_matrix(&image_transform),
+            x_object_id
+        ));
+        self.streams
+            .last_mut()
+            .unwrap()
+            .resources
+            .x_objects
+            .insert(x_object_id);
+    }
+
+    fn write_paragraph(&mut self, paragraph: &Paragraph, transform: &Transform) {
+        let data = &paragraph.data;
+
+        // backgrounds go below the whole text
+        for (run, glyphs) in paragraph.get_runs() {
+            if let Some(background) = &run.background {
+                let path = glyphs
+                    .iter()
+                    .flat_map(|glyph| rect_path(paragraph.get_glyph_bounds(glyph)))
+                    .collect::<Vec<_>>();
+                if path.is_empty() {
+                    continue;
+                }
+                let bounds = get_glyphs_bounds(paragraph, glyphs.iter());
+                let background = Paint {
+                    draw_style: DrawStyle::Fill,
+                    ..background.clone()
+                };
+                self.write_shape(
+                    &get_path_data(&path, None),
+                    FillType::NonZero,
+                    &background,
+                    transform,
+                    bounds,
+                );
+            }
+        }
+
+        for (run, run_glyphs) in paragraph.get_runs() {
+            let Some(foreground) = &run.foreground else {
+                continue;
+            };
+            if run_glyphs.is_empty() {
+                continue;
+            }
+
+            let bounds = get_glyphs_bounds(paragraph, run_glyphs.iter());
+            let (graphics_state, color) = self.get_paint_operators(foreground, transform, bounds);
+            let render_mode = match foreground.draw_style {
+                DrawStyle::Fill => "",
+                DrawStyle::Stroke => "1 Tr\n",
+                DrawStyle::StrokeAndFill => "2 Tr\n",
+            };
+
+            let font = data.fonts.get_font(&run.family);
+            let font_id = match &font {
+                Some((family, font)) => self.document.get_font(family, font).id,
+                None => self.document.get_standard_font(),
+            };
+            self.streams
+                .last_mut()
+                .unwrap()
+                .resources
+                .fonts
+                .insert(font_id);
+
+            let mut text = format!(
+                "q\n{}{} cm\n{}BT\n{}/F{} {} Tf\n",
+                graphics_state,
+                format_matrix(transform),
+                color,
+                render_mode,
+                font_id,
+                format_number(run.size)
+            );
+
+            for line in 0..data.layout.lines.len() {
+                let glyphs = run_glyphs
+                    .iter()
+                    .filter(|glyph| glyph.line == line)
+                    .collect::<Vec<_>>();
+                let Some(first) = glyphs.first() else {
+                    continue;
+                };
+
+                match &font {
+                    Some((family, font)) => {
+                        // the glyphs are shown with the glyph indices, the differences
+                        // between the layout and the font advances are adjusted
+                        let resource = self.document.get_font(family, font);
+                        text.push_str(&format!(
+                            "1 0 0 -1 {} {} Tm\n[",
+                            format_number(first.position.x),
+                            format_number(first.position.y)
+                        ));
+                        for (i, glyph) in glyphs.iter().enumerate() {
+                            let glyph_index = font.face.get_glyph_index(glyph.ch);
+                            resource.glyphs.entry(glyph_index).or_insert(glyph.ch);
+                            text.push_str(&format!("<{:04X}>", glyph_index));
+
+                            if let Some(next) = glyphs.get(i + 1) {
+                                let advance = font.face.get_glyph_advance(glyph_index) * run.size;
+                                let offset = next.position.x - glyph.position.x - advance;
+                                let adjustment = -offset * 1000.0f32 / run.size;
+                                if adjustment.abs() >= 0.01f32 {
+                                    text.push_str(&format_number(adjustment));
+                                }
+                            }
+                        }
+                        text.push_str("] TJ\n");
+                    }
+
+                    None => {
+                        // the standard font metrics are not known, every character is positioned
+                        for glyph in glyphs {
+                            text.push_str(&format!(
+                                "1 0 0 -1 {} {} Tm ({}) Tj\n",
+                                format_number(glyph.position.x),
+                                format_number(glyph.position.y),
+                                escape_string(&encode_win_ansi(glyph.ch))
+                            ));
+                        }
+                    }
+                }
+            }
+
+            text.push_str("ET\nQ\n");
+            self.push(&text);
+        }
+    }
+
+    /// Returns the graphics state operator and the color operators for the paint.
+    ///
+    /// The graphics state has to be set before the transformation (the soft masks
+    /// are in the default coordinates), `bounds` are in the local coordinates.
+    fn get_paint_operators(
+        &mut self,
+        paint: &Paint,
+        transform: &Transform,
+        bounds: PixelRect,
+    ) -> (String, String) {
+        let fill = paint.draw_style != DrawStyle::Stroke;
+        let stroke = paint.draw_style != DrawStyle::Fill;
+        let mut color = String::new();
+        let mut alpha = paint.color[3].clamp(0.0f32, 1.0f32);
+        let mut soft_mask = None;
+
+        let stroke_outset = if stroke {
+            paint.stroke_width.max(1.0f32) * paint.stroke_miter.max(1.0f32)
+        } else {
+            0.0f32
+        };
+        let bounds = bounds.inflate(stroke_outset, stroke_outset);
+
+        let solid_color = |color: [f32; 4]| match &paint.color_filter {
+            Some(filter) => filter_color(color, filter),
+            None => color,
+        };
+        let mut set_solid_color = |rgba: [f32; 4], alpha: &mut f32| {
+            let rgb = format!(
+                "{} {} {}",
+                format_number(rgba[0].clamp(0.0f32, 1.0f32)),
+                format_number(rgba[1].clamp(0.0f32, 1.0f32)),
+                format_number(rgba[2].clamp(0.0f32, 1.0f32))
+            );
+            if fill {
+                color.push_str(&format!("{} rg\n", rgb));
+            }
+            if stroke {
+                color.push_str(&format!("{} RG\n", rgb));
+            }
+            *alpha = rgba[3].clamp(0.0f32, 1.0f32);
+        };
+
+        let pattern = match &paint.color_source {
+            None | Some(ColorSource::Fragment { .. }) => {
+                // fragment programs are not supported, the paint color is used instead
+                set_solid_color(solid_color(paint.color), &mut alpha);
+                None
+            }
+
+            Some(ColorSource::SweepGradient { colors, .. }) => {
+                // there is no sweep gradient in PDF, use the first color
+                let first = colors.first().map_or(paint.color, |c| {
+                    [c.red, c.green, c.blue, c.alpha * paint.color[3]]
+                });
+                set_solid_color(solid_color(first), &mut alpha);
+                None
+            }
+
+            Some(ColorSource::Image {
+                image,
+                sampling,
+                transformation,
+                ..
+            }) => {
+                // the tiling patterns are always repeated
+                let pattern_transform = transformation
+                    .map_or(Transform::identity(), |matrix| matrix.to_2d())
+                    .then(transform);
+                self.add_image_pattern(image, *sampling, paint, &pattern_transform)
+            }
+
+            Some(gradient) => {
+                let colors = get_gradient_colors(gradient)
+                    .iter()
+                    .map(|c| solid_color([c.red, c.green, c.blue, c.alpha]))
+                    .collect::<Vec<_>>();
+                match self.add_gradient(gradient, &colors, transform, bounds) {
+                    Some((pattern_id, mask_id)) => {
+                        soft_mask = mask_id;
+                        Some(pattern_id)
+                    }
+                    None => {
+                        // degenerated gradient, use the first color
+                        let first = colors
+                            .first()
+                            .map_or(paint.color, |c| [c[0], c[1], c[2], c[3] * paint.color[3]]);
+                        set_solid_color(first, &mut alpha);
+                        None
+                    }
+                }
+            }
+        };
+
+        if let Some(pattern_id) = pattern {
+            if fill {
+                color.push_str(&format!("/Pattern cs /P{} scn\n", pattern_id));
+            }
+            if stroke {
+                color.push_str(&format!("/Pattern CS /P{} SCN\n", pattern_id));
+            }
+            self.streams
+                .last_mut()
+                .unwrap()
+                .resources
+                .patterns
+                .insert(pattern_id);
+        }
+
+        if stroke {
+            // zero width is the thinnest line that can be rendered
+            color.push_str(&format!(
+                "{} w\n",
+                format_number(paint.stroke_width.max(0.0f32))
+            ));
+            match paint.stroke_cap {
+                StrokeCap::Butt => (),
+                StrokeCap::Round => color.push_str("1 J\n"),
+                StrokeCap::Square => color.push_str("2 J\n"),
+            }
+            match paint.stroke_join {
+                StrokeJoin::Miter => color.push_str(&format!(
+                    "{} M\n",
+                    format_number(paint.stroke_miter.max(1.0f32))
+                )),
+                StrokeJoin::Round => color.push_str("1 j\n"),
+                StrokeJoin::Bevel => color.push_str("2 j\n"),
+            }
+        }
+
+        // blurring is not supported by PDF, the mask filters are ignored
+        let graphics_state = self.get_graphics_state(alpha, paint.blend_mode, soft_mask);
+        (graphics_state, color)
+    }
+
+    /// Returns the operator setting the graphics state (or an empty string for the default state).
+    fn get_graphics_state(
+        &mut self,
+        alpha: f32,
+        blend_mode: BlendMode,
+        soft_mask: Option<usize>,
+    ) -> String {
+        let mut dictionary = String::from("<< /Type /ExtGState");
+        if alpha < 1.0f32 {
+            dictionary.push_str(&format!(
+                " /ca {0} /CA {0}",
+                format_number(alpha.max(0.0f32))
+            ));
+        }
+        if let Some(name) = get_blend_mode_name(blend_mode) {
+            dictionary.push_str(&format!(" /BM /{}", name));
+        }
+        if let Some(mask_id) = soft_mask {
+            dictionary.push_str(&format!(
+                " /SMask << /Type /Mask /S /Luminosity /G {} 0 R >>",
+                mask_id
+            ));
+        }
+        if dictionary.len() == "<< /Type /ExtGState".len() {
+            return String::new();
+        }
+        dictionary.push_str(" >>");
+
+        let id = self.document.get_ext_g_state(dictionary);
+        self.streams
+            .last_mut()
+            .unwrap()
+            .resources
+            .ext_g_states
+            .insert(id);
+        format!("/GS{} gs\n", id)
+    }
+
+    /// Adds the shading pattern for the gradient, returns the pattern
+    /// and the soft mask form (for the transparent colors).
+    fn add_gradient(
+        &mut self,
+        gradient: &ColorSource<PdfTexture, crate::ColorSourceFragment>,
+        colors: &[[f32; 4]],
+        transform: &Transform,
+        bounds: PixelRect,
+    ) -> Option<(usize, Option<usize>)> {
+        let (stops, tile_mode, transformation) = match gradient {
+            ColorSource::LinearGradient {
+                stops,
+                tile_mode,
+                transformation,
+                ..
+            }
+            | ColorSource::RadialGradient {
+                stops,
+                tile_mode,
+                transformation,
+                ..
+            }
+            | ColorSource::ConicalGradient {
+                stops,
+                tile_mode,
+                transformation,
+                ..
+            } => (stops, *tile_mode, transformation),
+            _ => return None,
+        };
+        if colors.is_empty() {
+            return None;
+        }
+
+        let gradient_transform = transformation.map_or(Transform::identity(), |m| m.to_2d());
+        let pattern_transform = gradient_transform.then(transform);
+
+        // the corners of the painted area in the gradient coordinates
+        let inverse = gradient_transform.inverse()?;
+        let corners = [
+            Point::new(bounds.min_x(), bounds.min_y()),
+            Point::new(bounds.max_x(), bounds.min_y()),
+            Point::new(bounds.max_x(), bounds.max_y()),
+            Point::new(bounds.min_x(), bounds.max_y()),
+        ]
+        .map(|point| inverse.transform_point(point));
+
+        let repeat = matches!(tile_mode, TileMode::Repeat | TileMode::Mirror);
+        let (shading_type, coords, repetitions) = match gradient {
+            ColorSource::LinearGradient { start, end, .. } => {
+                let direction = Point::new(end.x - start.x, end.y - start.y).to_vector();
+                let length_squared = direction.square_length();
+                let (t0, t1) = if repeat && length_squared > 0.0f32 {
+                    let t = corners.map(|corner| {
+                        (corner - Point::new(start.x, start.y)).dot(direction) / length_squared
+                    });
+                    get_repetitions(
+                        t.iter().copied().fold(f32::MAX, f32::min),
+                        t.iter().copied().fold(f32::MIN, f32::max),
+                    )
+                } else {
+                    (0, 1)
+                };
+                let p0 = Point::new(start.x, start.y) + direction * t0 as f32;
+                let p1 = Point::new(start.x, start.y) + direction * t1 as f32;
+                (2, vec![p0.x, p0.y, p1.x, p1.y], (t0, t1))
+            }
+
+            ColorSource::RadialGradient { center, radius, .. } => {
+                let t1 = if repeat && *radius > 0.0f32 {
+                    let distance = corners
+                        .iter()
+                        .map(|corner| (*corner - Point::new(center.x, center.y)).length())
+                        .fold(0.0f32, f32::max);
+                    get_repetitions(0.0f32, distance / radius).1
+                } else {
+                    1
+                };
+                (
+                    3,
+                    vec![
+                        center.x,
+                        center.y,
+                        0.0f32,
+                        center.x,
+                        center.y,
+                        radius * t1 as f32,
+                    ],
+                    (0, t1),
+                )
+            }
+
+            // the conical gradients are not repeated
+            ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                ..
+            } => (
+                3,
+                vec![
+                    start_center.x,
+                    start_center.y,
+                    *start_radius,
+                    end_center.x,
+                    end_center.y,
+                    *end_radius,
+                ],
+                (0, 1),
+            ),
+
+            _ => return None,
+        };
+
+        let offsets = get_stop_offsets(stops, colors.len());
+        let mirror = tile_mode == TileMode::Mirror;
+        let extend = if tile_mode == TileMode::Decal {
+            "false false"
+        } else {
+            "true true"
+        };
+        let coords = coords
+            .iter()
+            .map(|value| format_number(*value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let matrix = format_matrix(&pattern_transform);
+
+        let color_function = get_repeated_function(
+            &get_function(
+                &offsets,
+                &colors
+                    .iter()
+                    .map(|c| [c[0], c[1], c[2]])
+                    .collect::<Vec<_>>(),
+            ),
+            repetitions,
+            mirror,
+        );
+        let pattern_id = self.document.add_object(format!(
+            "<< /Type /Pattern /PatternType 2 /Matrix [{}] /Shading << /ShadingType {} /ColorSpace /DeviceRGB \
+             /Coords [{}] /Function {} /Extend [{}] >> >>",
+            matrix, shading_type, coords, color_function, extend
+        ));
+
+        // the transparency of the colors is in the luminosity of the soft mask
+        if colors.iter().all(|color| color[3] >= 1.0f32) {
+            return Some((pattern_id, None));
+        }
+        let alpha_function = get_repeated_function(
+            &get_function(
+                &offsets,
+                &colors
+                    .iter()
+                    .map(|c| [c[3].clamp(0.0f32, 1.0f32)])
+                    .collect::<Vec<_>>(),
+            ),
+            repetitions,
+            mirror,
+        );
+        let mask_pattern_id = self.document.add_object(format!(
+            "<< /Type /Pattern /PatternType 2 /Matrix [{}] /Shading << /ShadingType {} /ColorSpace /DeviceGray \
+             /Coords [{}] /Function {} /Extend [{}] >> >>",
+            matrix, shading_type, coords, alpha_function, extend
+        ));
+        let mask_id = self.document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Form /BBox [0 0 {0} {1}] \
+                 /Group << /S /Transparency /CS /DeviceGray >> /Resources << /Pattern << /P{2} {2} 0 R >> >>",
+                self.width, self.height, mask_pattern_id
+            ),
+            format!(
+                "/Pattern cs /P{} scn\n0 0 {} {} re f\n",
+                mask_pattern_id, self.width, self.height
+            )
+            .as_bytes(),
+        );
+        Some((pattern_id, Some(mask_id)))
+    }
+
+    /// Adds the tiling pattern with the image.
+    fn add_image_pattern(
+        &mut self,
+        image: &PdfTexture,
+        sampling: TextureSampling,
+        paint: &Paint,
+        transform: &Transform,
+    ) -> Option<usize> {
+        let (width, height) = image.get_size();
+        let (x_object_id, placement) = self.get_x_object(image, sampling, Some(paint))?;
+        Some(
+            self.document.add_stream(
+                &format!(
+                    "/Type /Pattern /PatternType 1 /PaintType 1 /TilingType 1 /BBox [0 0 {0} {1}] \
+                 /XStep {0} /YStep {1} /Matrix [{2}] /Resources << /XObject << /X{3} {3} 0 R >> >>",
+                    width,
+                    height,
+                    format_matrix(transform),
+                    x_object_id
+                ),
+                format!(
+                    "q {} cm /X{} Do Q\n",
+                    format_matrix(&placement),
+                    x_object_id
+                )
+                .as_bytes(),
+            ),
+        )
+    }
+
+    /// Returns the XObject with the texture content and the transformation
+    /// from the XObject to the texture pixel coordinates.
+    fn get_x_object(
+        &mut self,
+        texture: &PdfTexture,
+        sampling: TextureSampling,
+        paint: Option<&Paint>,
+    ) -> Option<(usize, Transform)> {
+        let interpolate = sampling != TextureSampling::NearestNeighbor;
+        match texture.source.as_ref() {
+            TextureSource::Pixels {
+                width,
+                height,
+                color_format,
+                data,
+            } => {
+                // images are drawn in the unit square with the first row at the top
+                let placement = Transform::new(
+                    *width as f32,
+                    0.0f32,
+                    0.0f32,
+                    -(*height as f32),
+                    0.0f32,
+                    *height as f32,
+                );
+                let color_filter = paint.and_then(|paint| paint.color_filter.as_ref());
+                let id = match color_filter {
+                    // the filtered images are not shared
+                    Some(filter) => {
+                        let data = filter_pixels(data, *color_format, filter);
+                        add_image(
+                            self.document,
+                            *width,
+                            *height,
+                            ColorFormat::RGBA,
+                            &data,
+                            interpolate,
+                        )
+                    }
+                    None => self
+                        .document
+                        .get_image(texture.id, interpolate, |document| {
+                            add_image(document, *width, *height, *color_format, data, interpolate)
+                        }),
+                };
+                Some((id, placement))
+            }
+
+            TextureSource::Surface {
+                document_id,
+                canvas,
+            } => {
+                // the objects used by the surface belong to its document
+                if *document_id != self.document.id {
+                    return None;
+                }
+                let canvas = canvas.lock().unwrap().clone();
+                let id = self.document.add_stream(
+                    &format!(
+                        "/Type /XObject /Subtype /Form /BBox [0 0 {} {}] /Group << /S /Transparency >> /Resources {}",
+                        canvas.width,
+                        canvas.height,
+                        canvas.stream.resources.to_dictionary()
+                    ),
+                    canvas.stream.content.as_bytes(),
+                );
+                let placement = Transform::new(
+                    1.0f32,
+                    0.0f32,
+                    0.0f32,
+                    -1.0f32,
+                    0.0f32,
+                    canvas.height as f32,
+                );
+                Some((id, placement))
+            }
+        }
+    }
+}
+
+/// Adds the image XObject (with the soft mask for the transparent pixels).
+fn add_image(
+    document: &mut PdfDocument,
+    width: u32,
+    height: u32,
+    color_format: ColorFormat,
+    data: &[u8],
+    interpolate: bool,
+) -> usize {
+    let interpolate = if interpolate { "true" } else { "false" };
+    match color_format {
+        ColorFormat::RGBA => {
+            let rgb = data
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect::<Vec<_>>();
+            let alpha = data
+                .chunks_exact(4)
+                .map(|pixel| pixel[3])
+                .collect::<Vec<_>>();
+            let soft_mask = if alpha.iter().all(|a| *a == 255) {
+                String::new()
+            } else {
+                let mask_id = document.add_stream(
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray \
+                         /BitsPerComponent 8 /Interpolate {}",
+                        width, height, interpolate
+                    ),
+                    &alpha,
+                );
+                format!(" /SMask {} 0 R", mask_id)
+            };
+            document.add_stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                     /BitsPerComponent 8 /Interpolate {}{}",
+                    width, height, interpolate, soft_mask
+                ),
+                &rgb,
+            )
+        }
+
+        ColorFormat::Y8 => document.add_stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray \
+                 /BitsPerComponent 8 /Interpolate {}",
+                width, height, interpolate
+            ),
+            data,
+        ),
+    }
+}
+
+/// Applies the color filter to the pixels, returns RGBA pixels.
+fn filter_pixels(
+    data: &[u8],
+    color_format: ColorFormat,
+    filter: &drawing_api::ColorFilter,
+) -> Vec<u8> {
+    let to_color = |pixel: &[u8]| -> [f32; 4] {
+        match color_format {
+            ColorFormat::RGBA => std::array::from_fn(|i| pixel[i] as f32 / 255.0f32),
+            ColorFormat::Y8 => {
+                let value = pixel[0] as f32 / 255.0f32;
+                [value, value, value, 1.0f32]
+            }
+        }
+    };
+    data.chunks_exact(color_format.bytes_per_pixel())
+        .flat_map(|pixel| {
+            filter_color(to_color(pixel), filter)
+                .map(|value| (value.clamp(0.0f32, 1.0f32) * 255.0f32).round() as u8)
+        })
+        .collect()
+}
+
+fn get_gradient_colors(gradient: &ColorSource<PdfTexture, crate::ColorSourceFragment>) -> &[Color] {
+    match gradient {
+        ColorSource::LinearGradient { colors, .. }
+        | ColorSource::RadialGradient { colors, .. }
+        | ColorSource::ConicalGradient { colors, .. }
+        | ColorSource::SweepGradient { colors, .. } => colors,
+        _ => &[],
+    }
+}
+
+/// Returns the offsets of the colors (in 0..1 range, not decreasing).
+fn get_stop_offsets(stops: &[f32], color_count: usize) -> Vec<f32> {
+    let mut last = 0.0f32;
+    (0..color_count)
+        .map(|i| {
+            // when the number of stops doesn't match, the colors are distributed evenly
+            let offset = if stops.len() == color_count {
+                stops[i]
+            } else if color_count > 1 {
+                i as f32 / (color_count - 1) as f32
+            } else {
+                0.0f32
+            };
+            last = offset.clamp(last, 1.0f32);
+            last
+        })
+        .collect()
+}
+
+/// Returns the function interpolating the values between the stops (in 0..1 domain).
+fn get_function<const N: usize>(offsets: &[f32], values: &[[f32; N]]) -> String {
+    let format_values = |value: &[f32; N]| {
+        value
+            .iter()
+            .map(|v| format_number(*v))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let exponential = |c0: &[f32; N], c1: &[f32; N]| {
+        format!(
+            "<< /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >>",
+            format_values(c0),
+            format_values(c1)
+        )
+    };
+
+    // the first and the last colors extend to the ends
+    let mut stops = offsets
+        .iter()
+        .copied()
+        .zip(values.iter())
+        .collect::<Vec<_>>();
+    if stops.first().is_some_and(|(offset, _)| *offset > 0.0f32) {
+        stops.insert(0, (0.0f32, stops[0].1));
+    }
+    if stops.last().is_some_and(|(offset, _)| *offset < 1.0f32) {
+        stops.push((1.0f32, stops[stops.len() - 1].1));
+    }
+    while stops.len() > 2 && stops[1].0 <= 0.0f32 {
+        stops.remove(0);
+    }
+    while stops.len() > 2 && stops[stops.len() - 2].0 >= 1.0f32 {
+        stops.pop();
+    }
+
+    match stops.len() {
+        0 => exponential(&[0.0f32; N], &[0.0f32; N]),
+        1 => exponential(stops[0].1, stops[0].1),
+        2 => exponential(stops[0].1, stops[1].1),
+        _ => format!(
+            "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+            stops
+                .windows(2)
+                .map(|pair| exponential(pair[0].1, pair[1].1))
+                .collect::<Vec<_>>()
+                .join(" "),
+            stops[1..stops.len() - 1]
+                .iter()
+                .map(|(offset, _)| format_number(*offset))
+                .collect::<Vec<_>>()
+                .join(" "),
+            vec!["0 1"; stops.len() - 1].join(" ")
+        ),
+    }
+}
+
+/// Returns the function repeating the given one for the gradient parameters
+/// in `repetitions` range (every repetition is reversed for the mirror mode).
+fn get_repeated_function(function: &str, repetitions: (i32, i32), mirror: bool) -> String {
+    let (t0, t1) = repetitions;
+    let count = (t1 - t0).max(1);
+    if count == 1 && t0 == 0 {
+        return function.to_string();
+    }
+    format!(
+        "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+        vec![function; count as usize].join(" "),
+        (1..count)
+            .map(|i| format_number(i as f32 / count as f32))
+            .collect::<Vec<_>>()
+            .join(" "),
+        (t0..t0 + count)
+            .map(|t| if mirror && t.rem_euclid(2) == 1 {
+                "1 0"
+            } else {
+                "0 1"
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+}
+
+/// Returns the range of the whole repetitions covering the gradient parameters.
+fn get_repetitions(min: f32, max: f32) -> (i32, i32) {
+    let t0 = min.floor().max(-(MAX_GRADIENT_REPEAT as f32)) as i32;
+    let t1 = (max.ceil() as i32).clamp(t0 + 1, t0 + MAX_GRADIENT_REPEAT);
+    (t0, t1)
+}
+
+fn get_glyphs_bounds<'a>(
+    paragraph: &Paragraph,
+    glyphs: impl Iterator<Item = &'a drawing_api::command_list::PositionedGlyph>,
+) -> PixelRect {
+    glyphs
+        .map(|glyph| paragraph.get_glyph_bounds(glyph))
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or_else(PixelRect::zero)
+}
+
+fn get_blend_mode_name(blend_mode: BlendMode) -> Option<&'static str> {
+    // Porter-Duff modes other than source-over cannot be expressed
+    match blend_mode {
+        BlendMode::Screen => Some("Screen"),
+        BlendMode::Overlay => Some("Overlay"),
+        BlendMode::Darken => Some("Darken"),
+        BlendMode::Lighten => Some("Lighten"),
+        BlendMode::ColorDodge => Some("ColorDodge"),
+        BlendMode::ColorBurn => Some("ColorBurn"),
+        BlendMode::HardLight => Some("HardLight"),
+        BlendMode::SoftLight => Some("SoftLight"),
+        BlendMode::Difference => Some("Difference"),
+        BlendMode::Exclusion => Some("Exclusion"),
+        BlendMode::Multiply | BlendMode::Modulate => Some("Multiply"),
+        BlendMode::Hue => Some("Hue"),
+        BlendMode::Saturation => Some("Saturation"),
+        BlendMode::Color => Some("Color"),
+        BlendMode::Luminosity => Some("Luminosity"),
+        _ => None,
+    }
+}
+
+fn get_clip_operator(fill_type: FillType) -> &'static str {
+    match fill_type {
+        FillType::NonZero => "W",
+        FillType::Odd => "W*",
+    }
+}
+
+fn format_matrix(transform: &Transform) -> String {
+    format!(
+        "{} {} {} {} {} {}",
+        format_number(transform.m11),
+        format_number(transform.m12),
+        format_number(transform.m21),
+        format_number(transform.m22),
+        format_number(transform.m31),
+        format_number(transform.m32)
+    )
+}
+
+/// Returns the bounding box of the transformed rectangle.
+fn transform_rect(transform: &Transform, rect: &PixelRect) -> PixelRect {
+    let points = [
+        Point::new(rect.min_x(), rect.min_y()),
+        Point::new(rect.max_x(), rect.min_y()),
+        Point::new(rect.max_x(), rect.max_y()),
+        Point::new(rect.min_x(), rect.max_y()),
+    ]
+    .map(|point| {
+        let point = transform.transform_point(point);
+        PixelPoint::new(point.x, point.y)
+    });
+    PixelRect::from_points(points)
+}
+
+/// Returns the path construction operators, the points are transformed
+/// by the `transform` if given.
+fn get_path_data(path: &[PathElement], transform: Option<&Transform>) -> String {
+    let point = |p: &PixelPoint| {
+        let p = match transform {
+            Some(transform) => transform.transform_point(Point::new(p.x, p.y)),
+            None => Point::new(p.x, p.y),
+        };
+        format!("{} {}", format_number(p.x), format_number(p.y))
+    };
+
+    let mut data = String::new();
+    for element in path {
+        match element {
+            PathElement::MoveTo(p) => data.push_str(&format!("{} m\n", point(p))),
+            PathElement::LineTo(p) => data.push_str(&format!("{} l\n", point(p))),
+            PathElement::BezierTo(c1, c2, p) => {
+                data.push_str(&format!("{} {} {} c\n", point(c1), point(c2), point(p)))
+            }
+            PathElement::ClosePath => data.push_str("h\n"),
+        }
+    }
+    data
+}
+
+/// Returns the character in the standard Latin encoding (unknown characters become `?`).
+fn encode_win_ansi(ch: char) -> Vec<u8> {
+    let code = ch as u32;
+    if (0x20..0x7F).contains(&code) || (0xA0..=0xFF).contains(&code) {
+        vec![code as u8]
+    } else {
+        vec![b'?']
+    }
+}
+
+fn escape_string(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len());
+    for byte in data {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                result.push('\\');
+                result.push(*byte as char);
+            }
+            0x20..=0x7E => result.push(*byte as char),
+            _ => result.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    result
+}
+
+/// Formats the number with at most 3 decimal places.
+pub(crate) fn format_number(value: f32) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,196 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use drawing_api::command_list::FontFace;
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, Fonts as _, ParagraphBuilder as _};
+use drawing_pdf::{DisplayListBuilder, Fonts, ParagraphBuilder, PdfContext};
+
+const FONT: &[u8] = include_bytes!("../../drawing_examples/assets/OpenSans-Regular.ttf");
+
+/// Returns the position of `needle` in `data` starting the search at `from`.
+fn find(data: &[u8], needle: &str, from: usize) -> Option<usize> {
+    data[from..]
+        .windows(needle.len())
+        .position(|window| window == needle.as_bytes())
+        .map(|position| from + position)
+}
+
+/// Returns the ASCII text from `start` to the end of the line.
+fn line_at(data: &[u8], start: usize) -> &str {
+    let end = find(data, "\n", start).unwrap();
+    std::str::from_utf8(&data[start..end]).unwrap()
+}
+
+/// Returns the offsets of the objects listed in the cross-reference table.
+fn read_xref(pdf: &[u8]) -> Vec<usize> {
+    let startxref = find(pdf, "startxref\n", 0).unwrap();
+    let xref_offset = line_at(pdf, startxref + "startxref\n".len())
+        .parse::<usize>()
+        .unwrap();
+    assert_eq!(line_at(pdf, xref_offset), "xref");
+
+    let mut position = xref_offset + "xref\n".len();
+    let subsection = line_at(pdf, position);
+    let count = subsection
+        .strip_prefix("0 ")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+    position += subsection.len() + 1;
+
+    // every entry is exactly 20 bytes long
+    assert_eq!(&pdf[position..position + 20], b"0000000000 65535 f \n");
+    (1..count)
+        .map(|i| {
+            let entry = &pdf[position + i * 20..position + (i + 1) * 20];
+            let entry = std::str::from_utf8(entry).unwrap();
+            assert!(entry.ends_with(" 00000 n \n"));
+            entry[..10].parse::<usize>().unwrap()
+        })
+        .collect()
+}
+
+/// Returns the body of the object (between `obj` and `endobj`).
+fn object(pdf: &[u8], offsets: &[usize], id: usize) -> String {
+    let start = offsets[id - 1];
+    let header = format!("{} 0 obj\n", id);
+    assert_eq!(line_at(pdf, start), header.trim_end());
+    let end = find(pdf, "\nendobj\n", start).unwrap();
+    String::from_utf8_lossy(&pdf[start + header.len()..end]).into_owned()
+}
+
+/// Returns the object id referenced as `<key> <id> 0 R` in the dictionary.
+fn reference(dictionary: &str, key: &str) -> usize {
+    let start = dictionary.find(&format!("{} ", key)).unwrap() + key.len() + 1;
+    dictionary[start..]
+        .split(' ')
+        .next()
+        .unwrap()
+        .parse::<usize>()
+        .unwrap()
+}
+
+fn draw_page(context: &PdfContext, display_list: &<PdfContext as DrawingContext>::DisplayList) {
+    let mut surface = context.create_surface(64, 32).unwrap();
+    surface.draw(display_list).unwrap();
+}
+
+#[test]
+fn xref_points_to_the_objects() {
+    let context = PdfContext::new();
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(1.0, 2.0, 10.0, 20.0), "#F00");
+    draw_page(&context, &dlb.build().unwrap());
+    let pdf = context.get_pdf();
+
+    assert!(pdf.starts_with(b"%PDF-1.7\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+
+    let offsets = read_xref(&pdf);
+    for id in 1..=offsets.len() {
+        object(&pdf, &offsets, id);
+    }
+
+    let trailer = find(&pdf, "trailer\n", 0).unwrap();
+    let trailer = line_at(&pdf, trailer + "trailer\n".len());
+    assert!(trailer.contains(&format!("/Size {} ", offsets.len() + 1)));
+    let catalog = object(&pdf, &offsets, reference(trailer, "/Root"));
+    assert!(catalog.starts_with("<< /Type /Catalog "));
+}
+
+#[test]
+fn pages_are_listed_in_the_page_tree() {
+    let context = PdfContext::new();
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(1.0, 2.0, 10.0, 20.0), "#F00");
+    let display_list = dlb.build().unwrap();
+    draw_page(&context, &display_list);
+    draw_page(&context, &display_list);
+    let pdf = context.get_pdf();
+
+    let offsets = read_xref(&pdf);
+    let trailer = find(&pdf, "trailer\n", 0).unwrap();
+    let catalog = object(
+        &pdf,
+        &offsets,
+        reference(line_at(&pdf, trailer + "trailer\n".len()), "/Root"),
+    );
+    let pages_id = reference(&catalog, "/Pages");
+    let pages = object(&pdf, &offsets, pages_id);
+    assert!(pages.starts_with("<< /Type /Pages "));
+    assert!(pages.contains("/Count 2"));
+
+    let kids = &pages[pages.find("/Kids [").unwrap() + "/Kids [".len()..];
+    let kids = &kids[..kids.find(']').unwrap()];
+    let kids = kids
+        .split(" 0 R")
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<usize>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(kids.len(), 2);
+    for kid in kids {
+        let page = object(&pdf, &offsets, kid);
+        assert!(page.starts_with("<< /Type /Page "));
+        assert!(page.contains("/MediaBox [0 0 64 32]"));
+        assert_eq!(reference(&page, "/Parent"), pages_id);
+
+        let contents = object(&pdf, &offsets, reference(&page, "/Contents"));
+        assert!(contents.contains("\nstream\n"));
+        assert!(contents.ends_with("\nendstream"));
+    }
+}
+
+#[test]
+fn glyph_widths_match_the_font() {
+    let mut fonts = Fonts::default();
+    fonts
+        .register_font(Cow::Borrowed(FONT), Some("F1"))
+        .unwrap();
+
+    let mut pb = ParagraphBuilder::new(&fonts).unwrap();
+    pb.push_style(("F1", 10.0, "#000"));
+    pb.add_text("AVi");
+    let paragraph = pb.build(f32::INFINITY).unwrap();
+
+    let context = PdfContext::new();
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_paragraph((0.0, 10.0), &paragraph);
+    draw_page(&context, &dlb.build().unwrap());
+    let pdf = context.get_pdf();
+
+    let offsets = read_xref(&pdf);
+    let cid_font = (1..=offsets.len())
+        .map(|id| object(&pdf, &offsets, id))
+        .find(|body| body.contains("/Subtype /CIDFontType"))
+        .unwrap();
+    let widths = &cid_font[cid_font.find("/W [").unwrap() + "/W [".len()..];
+    let widths = &widths[..widths.find("] >>").unwrap()];
+
+    // the widths are listed as `glyph [width]` in the glyph order
+    let widths = widths
+        .split(']')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (glyph, width) = entry.split_once(" [").unwrap();
+            (glyph.parse::<u16>().unwrap(), width.parse::<f32>().unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    let face = FontFace::parse(Arc::new(FONT.to_vec())).unwrap();
+    let mut expected = "AVi"
+        .chars()
+        .map(|ch| face.get_glyph_index(ch))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(
+        widths.iter().map(|(glyph, _)| *glyph).collect::<Vec<_>>(),
+        expected
+    );
+    for (glyph, width) in widths {
+        assert_ne!(glyph, 0);
+        assert!((width - face.get_glyph_advance(glyph) * 1000.0f32).abs() < 0.01f32);
+    }
+}
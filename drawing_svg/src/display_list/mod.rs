@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use drawing_api::command_list::{self, FontMetrics};
+
+use crate::{SvgContext, SvgTexture};
+
+pub(crate) use command_list::rect_path;
+pub use command_list::{
+    ColorSourceFragment, Fonts, GlyphInfo, ImageFilterFragment, LineMetrics, Path, PathBuilder,
+    PathElement,
+};
+
+pub type DisplayList = command_list::DisplayList<SvgContext>;
+pub type DisplayListBuilder = command_list::DisplayListBuilder<SvgContext>;
+pub type Paint = command_list::Paint<SvgContext>;
+pub type Paragraph = command_list::Paragraph<SvgContext>;
+pub type ParagraphBuilder = command_list::ParagraphBuilder<SvgContext>;
+pub(crate) type Command = command_list::Command<SvgContext>;
+
+impl command_list::Backend for SvgContext {
+    type Texture = SvgTexture;
+    type Fonts = Fonts;
+
+    fn get_advances(
+        fonts: &Fonts,
+        family_name: &str,
+        size: f32,
+        text: &str,
+    ) -> Result<(FontMetrics, HashMap<char, f32>), &'static str> {
+        Ok(fonts.get_advances(family_name, size, text))
+    }
+}
@@ -0,0 +1,20 @@
+mod display_list;
+
+mod svg_context;
+pub use svg_context::*;
+
+mod svg_fragment_program;
+pub use svg_fragment_program::*;
+
+mod svg_surface;
+pub use svg_surface::*;
+
+mod svg_texture;
+pub use svg_texture::*;
+
+mod writer;
+
+pub use display_list::{
+    ColorSourceFragment, DisplayList, DisplayListBuilder, Fonts, GlyphInfo, ImageFilterFragment,
+    LineMetrics, Paint, Paragraph, ParagraphBuilder, Path, PathBuilder, PathElement,
+};
@@ -0,0 +1,122 @@
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{
+    Capabilities, ColorFormat, ColorSource, DrawingContext, GraphicsApi, ImageFilter,
+    TextureDescriptor,
+};
+
+use crate::writer::SvgDocument;
+use crate::{SvgSurface, SvgTexture, TextureSource};
+
+/// Backend exporting the display lists as SVG documents.
+#[derive(Clone, Default)]
+pub struct SvgContext {}
+
+impl SvgContext {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Creates an empty document.
+    pub fn create_surface(&self, width: u32, height: u32) -> Result<SvgSurface, &'static str> {
+        Ok(SvgSurface {
+            document: Arc::new(Mutex::new(SvgDocument::new(width, height))),
+        })
+    }
+}
+
+impl DrawingContext for SvgContext {
+    type ColorSourceFragment = crate::display_list::ColorSourceFragment;
+    type DisplayList = crate::display_list::DisplayList;
+    type DisplayListBuilder = crate::display_list::DisplayListBuilder;
+    type Fonts = crate::display_list::Fonts;
+    type FragmentProgram = crate::SvgFragmentProgram;
+    type ImageFilterFragment = crate::display_list::ImageFilterFragment;
+    type Paint = crate::display_list::Paint;
+    type ParagraphBuilder = crate::display_list::ParagraphBuilder;
+    type PathBuilder = crate::display_list::PathBuilder;
+    type Surface = SvgSurface;
+    type Texture = SvgTexture;
+
+    fn get_api_capabilities(api: GraphicsApi) -> Option<Capabilities> {
+        match api {
+            GraphicsApi::Svg => Some(Capabilities {
+                api: GraphicsApi::Svg,
+                transformations: true,
+                layers: true,
+                rect_clipping: true,
+                path_clipping: true,
+                color_filters: true,
+                image_filters: true,
+                mask_filters: true,
+                textures: true,
+                text_metrics: true,
+                text_decorations: false,
+                shadows: true,
+                fragment_color_sources: false,
+                fragment_image_filters: false,
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_capabilities(&self) -> Capabilities {
+        SvgContext::get_api_capabilities(GraphicsApi::Svg).unwrap()
+    }
+
+    unsafe fn create_texture(
+        &self,
+        contents: Cow<'static, [u8]>,
+        descriptor: TextureDescriptor,
+    ) -> Result<Self::Texture, &'static str> {
+        let len = descriptor.width as usize
+            * descriptor.height as usize
+            * descriptor.color_format.bytes_per_pixel();
+        if contents.len() < len {
+            return Err("not enough texture data");
+        }
+
+        Ok(SvgTexture::new(TextureSource::Pixels {
+            width: descriptor.width,
+            height: descriptor.height,
+            color_format: descriptor.color_format,
+            data: contents[..len].to_vec(),
+        }))
+    }
+
+    fn create_offscreen_surface(
+        &self,
+        width: u32,
+        height: u32,
+        _color_format: ColorFormat,
+    ) -> Result<(Self::Surface, Self::Texture), &'static str> {
+        let surface = self.create_surface(width, height)?;
+        let texture = SvgTexture::new(TextureSource::Surface(surface.document.clone()));
+        Ok((surface, texture))
+    }
+
+    unsafe fn new_color_source_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ColorSource<Self::Texture, Self::ColorSourceFragment> {
+        // fragment programs are not supported, the paint color is used instead
+        ColorSource::Fragment {
+            color_source: crate::display_list::ColorSourceFragment {},
+        }
+    }
+
+    unsafe fn new_image_filter_from_fragment_program(
+        &self,
+        _frag_program: &Self::FragmentProgram,
+        _samplers: &[Self::Texture],
+        _uniform_data: &[u8],
+    ) -> ImageFilter<Self::ImageFilterFragment> {
+        // fragment programs are not supported, the filter is ignored
+        ImageFilter::Fragment {
+            image_filter: crate::display_list::ImageFilterFragment {},
+        }
+    }
+}
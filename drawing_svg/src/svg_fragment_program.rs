@@ -0,0 +1,8 @@
+#[derive(Clone)]
+pub struct SvgFragmentProgram {}
+
+impl drawing_api::FragmentProgram for SvgFragmentProgram {
+    unsafe fn new(_program: std::borrow::Cow<'static, [u8]>) -> Result<Self, &'static str> {
+        Err("fragment programs are not supported by the SVG backend")
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{PixelData, PixelIntRect};
+
+use crate::writer::{SvgDocument, SvgWriter, Transform};
+
+/// SVG document the display lists are drawn into.
+pub struct SvgSurface {
+    pub(crate) document: Arc<Mutex<SvgDocument>>,
+}
+
+impl SvgSurface {
+    /// Returns the SVG document with everything drawn so far.
+    pub fn get_svg(&self) -> String {
+        self.document.lock().unwrap().to_svg()
+    }
+}
+
+impl drawing_api::Surface for SvgSurface {
+    type DisplayList = crate::display_list::DisplayList;
+
+    fn draw(&mut self, display_list: &Self::DisplayList) -> Result<(), &'static str> {
+        // the display list can use the texture of this surface,
+        // so the document cannot stay locked while writing
+        let mut document = self.document.lock().unwrap().clone();
+        SvgWriter::new(&mut document).write_display_list(display_list, &Transform::identity());
+        *self.document.lock().unwrap() = document;
+        Ok(())
+    }
+
+    fn read_pixels(&self, _rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        Err("reading pixels is not supported by the SVG backend")
+    }
+
+    fn present(self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
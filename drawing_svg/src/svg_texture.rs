@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use drawing_api::{ColorFormat, PixelData, PixelIntRect, TextureDescriptor};
+
+use crate::writer::SvgDocument;
+
+static NEXT_TEXTURE_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) enum TextureSource {
+    /// Straight alpha pixels embedded as PNG image.
+    Pixels {
+        width: u32,
+        height: u32,
+        color_format: ColorFormat,
+        data: Vec<u8>,
+    },
+
+    /// Offscreen surface embedded as nested SVG image.
+    Surface(Arc<Mutex<SvgDocument>>),
+}
+
+/// Image embedded in the SVG document.
+#[derive(Clone)]
+pub struct SvgTexture {
+    /// Identifier used to embed the pixels in the document only once.
+    pub(crate) id: usize,
+    pub(crate) source: Arc<TextureSource>,
+}
+
+impl SvgTexture {
+    pub(crate) fn new(source: TextureSource) -> Self {
+        Self {
+            id: NEXT_TEXTURE_ID.fetch_add(1, Ordering::Relaxed),
+            source: Arc::new(source),
+        }
+    }
+
+    pub(crate) fn get_size(&self) -> (u32, u32) {
+        match self.source.as_ref() {
+            TextureSource::Pixels { width, height, .. } => (*width, *height),
+            TextureSource::Surface(document) => {
+                let document = document.lock().unwrap();
+                (document.width, document.height)
+            }
+        }
+    }
+}
+
+impl drawing_api::Texture for SvgTexture {
+    fn get_descriptor(&self) -> TextureDescriptor {
+        let (width, height) = self.get_size();
+        let color_format = match self.source.as_ref() {
+            TextureSource::Pixels { color_format, .. } => *color_format,
+            TextureSource::Surface(_) => ColorFormat::RGBA,
+        };
+        TextureDescriptor {
+            width,
+            height,
+            color_format,
+            mip_count: 1,
+        }
+    }
+
+    fn get_gl_handle(&self) -> usize {
+        0
+    }
+
+    fn read_pixels(&self, rect: PixelIntRect) -> Result<PixelData, &'static str> {
+        match self.source.as_ref() {
+            TextureSource::Pixels {
+                width,
+                height,
+                color_format,
+                data,
+            } => PixelData {
+                data: data.clone(),
+                width: *width,
+                height: *height,
+                row_stride: *width * color_format.bytes_per_pixel() as u32,
+                color_format: *color_format,
+                premultiplied: false,
+            }
+            .crop(rect),
+            TextureSource::Surface(_) => Err("reading pixels is not supported by the SVG backend"),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use drawing_api::ColorFormat;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// maximum size of the uncompressed deflate block
+const MAX_STORED_BLOCK: usize = 65535;
+
+/// Encodes the data with the standard base64 alphabet (with padding).
+pub(crate) fn encode_base64(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(BASE64_ALPHABET[(value >> (18 - i * 6)) as usize & 0x3F] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+/// Encodes the image as PNG (RGBA with straight alpha or grayscale).
+///
+/// The image data is stored without compression, which keeps the encoder small.
+pub(crate) fn encode_png(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    color_format: ColorFormat,
+) -> Vec<u8> {
+    let (color_type, bytes_per_pixel) = match color_format {
+        ColorFormat::RGBA => (6u8, 4usize),
+        ColorFormat::Y8 => (0u8, 1usize),
+    };
+    let row_length = width as usize * bytes_per_pixel;
+
+    // every row starts with the filter type (none)
+    let mut raw = Vec::with_capacity((row_length + 1) * height as usize);
+    for row in data.chunks(row_length.max(1)).take(height as usize) {
+        raw.push(0u8);
+        raw.extend_from_slice(row);
+    }
+
+    let mut zlib = vec![0x78u8, 0x01u8];
+    let blocks = raw.chunks(MAX_STORED_BLOCK).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        zlib.extend_from_slice(&[1u8, 0u8, 0u8, 0xFFu8, 0xFFu8]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let len = block.len() as u16;
+        zlib.push(if i + 1 == blocks.len() { 1u8 } else { 0u8 });
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8u8, color_type, 0u8, 0u8, 0u8]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_png_chunk(&mut png, b"IHDR", &header);
+    write_png_chunk(&mut png, b"IDAT", &zlib);
+    write_png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320u32 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
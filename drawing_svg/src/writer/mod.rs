@@ -0,0 +1,14 @@
+mod encoding;
+pub(crate) use encoding::*;
+
+mod svg_document;
+pub(crate) use svg_document::*;
+
+mod svg_writer;
+pub(crate) use svg_writer::*;
+
+/// 2D affine transformation from the local to the document coordinates.
+pub(crate) type Transform = drawing_api::euclid::default::Transform2D<f32>;
+
+/// Point in the document coordinates.
+pub(crate) type Point = drawing_api::euclid::default::Point2D<f32>;
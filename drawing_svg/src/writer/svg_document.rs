@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use super::encode_base64;
+
+/// SVG document being drawn.
+#[derive(Clone)]
+pub(crate) struct SvgDocument {
+    pub width: u32,
+    pub height: u32,
+
+    /// Definitions shared by all the drawings (embedded images).
+    pub defs: String,
+
+    pub body: String,
+
+    /// Fonts used by the text, embedded with `@font-face` rules.
+    pub fonts: BTreeMap<String, Arc<Vec<u8>>>,
+
+    /// Textures already embedded in `defs`.
+    pub images: HashSet<usize>,
+
+    next_id: usize,
+}
+
+impl SvgDocument {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            defs: String::new(),
+            body: String::new(),
+            fonts: BTreeMap::new(),
+            images: HashSet::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Returns an identifier unique in the document.
+    pub fn get_next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::with_capacity(self.defs.len() + self.body.len() + 512);
+        svg.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+             version=\"1.1\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            self.width, self.height
+        ));
+
+        if !self.fonts.is_empty() || !self.defs.is_empty() {
+            svg.push_str("<defs>\n");
+            if !self.fonts.is_empty() {
+                svg.push_str("<style type=\"text/css\"><![CDATA[\n");
+                for (family, data) in &self.fonts {
+                    let (mime_type, format) = if data.starts_with(b"OTTO") {
+                        ("font/otf", "opentype")
+                    } else {
+                        ("font/ttf", "truetype")
+                    };
+                    svg.push_str(&format!(
+                        "@font-face {{ font-family: \"{}\"; src: url(\"data:{};base64,{}\") format(\"{}\"); }}\n",
+                        family.replace('"', "\\\""),
+                        mime_type,
+                        encode_base64(data),
+                        format
+                    ));
+                }
+                svg.push_str("]]></style>\n");
+            }
+            svg.push_str(&self.defs);
+            svg.push_str("</defs>\n");
+        }
+
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
@@ -0,0 +1,1122 @@
+use drawing_api::{
+    BlendMode, BlurStyle, ClipOperation, ColorFilter, ColorSource, DrawStyle, FillType,
+    ImageFilter, MaskFilter, PixelPoint, PixelRect, PixelSize, StrokeCap, StrokeJoin,
+    TextureSampling, TileMode,
+};
+
+use crate::display_list::{rect_path, Command};
+use crate::{
+    DisplayList, ImageFilterFragment, Paint, Paragraph, PathElement, SvgTexture, TextureSource,
+};
+
+use super::{encode_base64, encode_png, Point, SvgDocument, Transform};
+
+/// Converts the display list commands to SVG elements.
+///
+/// All the elements have absolute transformations, so the groups
+/// are used only for the clipping and the layers.
+pub(crate) struct SvgWriter<'a> {
+    document: &'a mut SvgDocument,
+}
+
+impl<'a> SvgWriter<'a> {
+    pub fn new(document: &'a mut SvgDocument) -> Self {
+        Self { document }
+    }
+
+    /// Writes the display list, the command transformations are followed by the `base` one.
+    pub fn write_display_list(&mut self, display_list: &DisplayList, base: &Transform) {
+        // number of groups opened on the current save level
+        let mut open_groups = 0usize;
+        let mut save_stack = Vec::new();
+
+        for command in display_list.commands.iter() {
+            match command {
+                Command::Save => save_stack.push(std::mem::take(&mut open_groups)),
+
+                // backdrop filters cannot be expressed in SVG 1.1 (the `BackgroundImage`
+                // filter input is not supported by the viewers), so they are ignored
+                Command::SaveLayer {
+                    bounds,
+                    transform,
+                    paint,
+                    backdrop_filter: _,
+                } => {
+                    save_stack.push(std::mem::take(&mut open_groups));
+                    open_groups = self.write_layer_start(*bounds, &transform.then(base), paint);
+                }
+
+                Command::Restore => {
+                    self.close_groups(open_groups);
+                    open_groups = save_stack.pop().unwrap_or(0);
+                }
+
+                Command::Clip {
+                    path,
+                    transform,
+                    operation,
+                } => {
+                    self.write_clip(
+                        &path.path,
+                        path.fill_type,
+                        &transform.then(base),
+                        *operation,
+                    );
+                    open_groups += 1;
+                }
+
+                Command::DrawPaint { paint, transform } => {
+                    self.write_paint(paint, &transform.then(base));
+                }
+
+                Command::DrawPath {
+                    path,
+                    transform,
+                    paint,
+                } => {
+                    let bounds = drawing_api::Path::get_bounds(path);
+                    let element = format!(
+                        "<path d=\"{}\"{}",
+                        get_path_data(&path.path),
+                        get_fill_rule_attribute("fill-rule", path.fill_type)
+                    );
+                    self.write_shape(element, paint, &transform.then(base), bounds);
+                }
+
+                Command::DrawTexture {
+                    texture,
+                    src_rect,
+                    dst_rect,
+                    sampling,
+                    transform,
+                    paint,
+                } => {
+                    self.write_texture(
+                        texture,
+                        *src_rect,
+                        *dst_rect,
+                        *sampling,
+                        &transform.then(base),
+                        paint.as_ref(),
+                    );
+                }
+
+                Command::DrawParagraph {
+                    paragraph,
+                    location,
+                    transform,
+                } => {
+                    let transform = Transform::translation(location.x, location.y)
+                        .then(transform)
+                        .then(base);
+                    self.write_paragraph(paragraph, &transform);
+                }
+
+                Command::DrawDisplayList {
+                    display_list,
+                    transform,
+                    opacity,
+                } => {
+                    if *opacity < 1.0f32 {
+                        self.document.body.push_str(&format!(
+                            "<g opacity=\"{}\">\n",
+                            format_number(opacity.max(0.0f32))
+                        ));
+                    } else {
+                        self.document.body.push_str("<g>\n");
+                    }
+                    self.write_display_list(display_list, &transform.then(base));
+                    self.close_groups(1);
+                }
+            }
+        }
+
+        self.close_groups(open_groups + save_stack.iter().sum::<usize>());
+    }
+
+    fn close_groups(&mut self, count: usize) {
+        for _ in 0..count {
+            self.document.body.push_str("</g>\n");
+        }
+    }
+
+    /// Opens the groups of the layer, returns the number of the groups.
+    fn write_layer_start(
+        &mut self,
+        bounds: PixelRect,
+        transform: &Transform,
+        paint: &Option<Paint>,
+    ) -> usize {
+        let clip_id = self.document.get_next_id("clip");
+        self.document.body.push_str(&format!(
+            "<defs><clipPath id=\"{}\" clipPathUnits=\"userSpaceOnUse\"><path d=\"{}\"{}/></clipPath></defs>\n<g clip-path=\"url(#{})\">\n",
+            clip_id,
+            get_path_data(&rect_path(bounds)),
+            get_transform_attribute(transform),
+            clip_id
+        ));
+        let Some(paint) = paint else {
+            return 1;
+        };
+
+        // the layer group is in the document coordinates
+        let scale = get_scale(transform);
+        let spread = paint
+            .image_filter
+            .as_ref()
+            .map_or(0.0f32, get_filter_spread)
+            * scale.0.max(scale.1);
+        let device_bounds = transform_rect(transform, &bounds).inflate(spread, spread);
+
+        let mut attributes = String::new();
+        if paint.color[3] < 1.0f32 {
+            attributes.push_str(&format!(
+                " opacity=\"{}\"",
+                format_number(paint.color[3].max(0.0f32))
+            ));
+        }
+        if let Some(filter_id) = self.write_filter(
+            None,
+            paint.color_filter.as_ref(),
+            paint.image_filter.as_ref(),
+            false,
+            device_bounds,
+            scale,
+        ) {
+            attributes.push_str(&format!(" filter=\"url(#{})\"", filter_id));
+        }
+        attributes.push_str(&get_blend_mode_attribute(paint.blend_mode));
+        self.document.body.push_str(&format!("<g{}>\n", attributes));
+
+        if let Some(ImageFilter::Matrix { matrix, .. }) = &paint.image_filter {
+            // the matrix is applied in the local coordinates of the layer
+            let layer_transform = transform
+                .inverse()
+                .unwrap_or_else(Transform::identity)
+                .then(&matrix.to_2d())
+                .then(transform);
+            self.document.body.push_str(&format!(
+                "<g{}>\n",
+                get_transform_attribute(&layer_transform)
+            ));
+            return 3;
+        }
+        2
+    }
+
+    fn write_clip(
+        &mut self,
+        path: &[PathElement],
+        fill_type: FillType,
+        transform: &Transform,
+        operation: ClipOperation,
+    ) {
+        match operation {
+            ClipOperation::Intersect => {
+                let id = self.document.get_next_id("clip");
+                self.document.body.push_str(&format!(
+                    "<defs><clipPath id=\"{}\" clipPathUnits=\"userSpaceOnUse\"><path d=\"{}\"{}{}/></clipPath></defs>\n<g clip-path=\"url(#{})\">\n",
+                    id,
+                    get_path_data(path),
+                    get_fill_rule_attribute("clip-rule", fill_type),
+                    get_transform_attribute(transform),
+                    id
+                ));
+            }
+
+            ClipOperation::Difference => {
+                // the hole is cut with a mask (white is visible, black is hidden)
+                let id = self.document.get_next_id("mask");
+                let (width, height) = (self.document.width, self.document.height);
+                self.document.body.push_str(&format!(
+                    "<defs><mask id=\"{0}\" maskUnits=\"userSpaceOnUse\" x=\"0\" y=\"0\" width=\"{1}\" height=\"{2}\">\
+                     <rect x=\"0\" y=\"0\" width=\"{1}\" height=\"{2}\" fill=\"#ffffff\"/>\
+                     <path d=\"{3}\"{4}{5} fill=\"#000000\"/></mask></defs>\n<g mask=\"url(#{0})\">\n",
+                    id,
+                    width,
+                    height,
+                    get_path_data(path),
+                    get_fill_rule_attribute("fill-rule", fill_type),
+                    get_transform_attribute(transform),
+                ));
+            }
+        }
+    }
+
+    /// Fills the whole document (limited by the clip) with the paint.
+    fn write_paint(&mut self, paint: &Paint, transform: &Transform) {
+        let Some(inverse) = transform.inverse() else {
+            return;
+        };
+        let document_rect = PixelRect::new(
+            PixelPoint::zero(),
+            PixelSize::new(self.document.width as f32, self.document.height as f32),
+        );
+        let rect = transform_rect(&inverse, &document_rect);
+        let paint = Paint {
+            draw_style: DrawStyle::Fill,
+            ..paint.clone()
+        };
+        let element = format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+            format_number(rect.origin.x),
+            format_number(rect.origin.y),
+            format_number(rect.size.width),
+            format_number(rect.size.height)
+        );
+        self.write_shape(element, &paint, transform, rect);
+    }
+
+    /// Writes the element with the paint attributes.
+    ///
+    /// The `element` is the beginning of the tag (without the closing bracket),
+    /// `bounds` are the bounds of the shape in the local coordinates.
+    fn write_shape(
+        &mut self,
+        element: String,
+        paint: &Paint,
+        transform: &Transform,
+        bounds: PixelRect,
+    ) {
+        let attributes = self.get_paint_attributes(paint, bounds);
+
+        // the matrix image filter transforms the shape in the local coordinates
+        let transform = match &paint.image_filter {
+            Some(ImageFilter::Matrix { matrix, .. }) => matrix.to_2d().then(transform),
+            _ => *transform,
+        };
+
+        self.document.body.push_str(&format!(
+            "{}{}{}/>\n",
+            element,
+            get_transform_attribute(&transform),
+            attributes
+        ));
+    }
+
+    fn write_texture(
+        &mut self,
+        texture: &SvgTexture,
+        src_rect: PixelRect,
+        dst_rect: PixelRect,
+        sampling: TextureSampling,
+        transform: &Transform,
+        paint: Option<&Paint>,
+    ) {
+        let image_id = self.get_image_id(texture);
+
+        let mut attributes = get_transform_attribute(transform);
+        if sampling == TextureSampling::NearestNeighbor {
+            attributes.push_str(" image-rendering=\"optimizeSpeed\"");
+        }
+        if let Some(paint) = paint {
+            if paint.color[3] < 1.0f32 {
+                attributes.push_str(&format!(
+                    " opacity=\"{}\"",
+                    format_number(paint.color[3].max(0.0f32))
+                ));
+            }
+            let mask_blur = paint
+                .mask_filter
+                .as_ref()
+                .map(|MaskFilter::Blur { style, sigma }| (*sigma, *style));
+            if let Some(filter_id) = self.write_filter(
+                mask_blur,
+                paint.color_filter.as_ref(),
+                paint.image_filter.as_ref(),
+                true,
+                dst_rect,
+                (1.0f32, 1.0f32),
+            ) {
+                attributes.push_str(&format!(" filter=\"url(#{})\"", filter_id));
+            }
+            attributes.push_str(&get_blend_mode_attribute(paint.blend_mode));
+        }
+
+        // the nested viewport crops and scales the source rectangle
+        self.document.body.push_str(&format!(
+            "<g{}><svg x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\" \
+             preserveAspectRatio=\"none\" overflow=\"hidden\"><use xlink:href=\"#{}\"/></svg></g>\n",
+            attributes,
+            format_number(dst_rect.origin.x),
+            format_number(dst_rect.origin.y),
+            format_number(dst_rect.size.width),
+            format_number(dst_rect.size.height),
+            format_number(src_rect.origin.x),
+            format_number(src_rect.origin.y),
+            format_number(src_rect.size.width),
+            format_number(src_rect.size.height),
+            image_id
+        ));
+    }
+
+    fn write_paragraph(&mut self, paragraph: &Paragraph, transform: &Transform) {
+        let data = &paragraph.data;
+        self.document
+            .body
+            .push_str(&format!("<g{}>\n", get_transform_attribute(transform)));
+
+        // backgrounds go below the whole text
+        for (run, glyphs) in paragraph.get_runs() {
+            if let Some(background) = &run.background {
+                let path = glyphs
+                    .iter()
+                    .flat_map(|glyph| rect_path(paragraph.get_glyph_bounds(glyph)))
+                    .collect::<Vec<_>>();
+                if path.is_empty() {
+                    continue;
+                }
+                let bounds = drawing_api::Path::get_bounds(&crate::Path::new(path.clone()));
+                let element = format!("<path d=\"{}\"", get_path_data(&path));
+                self.write_shape(element, background, &Transform::identity(), bounds);
+            }
+        }
+
+        // the characters are positioned one by one to follow the layout exactly
+        for (run, run_glyphs) in paragraph.get_runs() {
+            let Some(foreground) = &run.foreground else {
+                continue;
+            };
+            let family = match data.fonts.get_font(&run.family) {
+                Some((family, font)) => {
+                    self.document
+                        .fonts
+                        .entry(family.clone())
+                        .or_insert_with(|| font.data.clone());
+                    family
+                }
+                None => run.family.clone(),
+            };
+
+            for line in 0..data.layout.lines.len() {
+                let glyphs = run_glyphs
+                    .iter()
+                    .filter(|glyph| glyph.line == line)
+                    .collect::<Vec<_>>();
+                let Some(first) = glyphs.first() else {
+                    continue;
+                };
+
+                let text = glyphs.iter().map(|glyph| glyph.ch).collect::<String>();
+                let x = glyphs
+                    .iter()
+                    .map(|glyph| format_number(glyph.position.x))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let bounds = glyphs
+                    .iter()
+                    .map(|glyph| paragraph.get_glyph_bounds(glyph))
+                    .fold(PixelRect::zero(), |a, b| a.union(&b));
+                let attributes = self.get_paint_attributes(foreground, bounds);
+
+                self.document.body.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" font-weight=\"{}\"{} xml:space=\"preserve\"{}>{}</text>\n",
+                    x,
+                    format_number(first.position.y),
+                    escape_xml(&family),
+                    format_number(run.size),
+                    get_font_weight(run.weight),
+                    if run.style == drawing_api::FontStyle::Italic {
+                        " font-style=\"italic\""
+                    } else {
+                        ""
+                    },
+                    attributes,
+                    escape_xml(&text)
+                ));
+            }
+        }
+
+        self.close_groups(1);
+    }
+
+    /// Returns the attributes of the paint (writes the needed definitions).
+    fn get_paint_attributes(&mut self, paint: &Paint, bounds: PixelRect) -> String {
+        let server = self.write_paint_server(paint);
+        let opacity = paint.color[3].clamp(0.0f32, 1.0f32);
+        let mut attributes = String::new();
+
+        let fill = paint.draw_style != DrawStyle::Stroke;
+        let stroke = paint.draw_style != DrawStyle::Fill;
+        if fill {
+            attributes.push_str(&format!(" fill=\"{}\"", server));
+            if opacity < 1.0f32 {
+                attributes.push_str(&format!(" fill-opacity=\"{}\"", format_number(opacity)));
+            }
+        } else {
+            attributes.push_str(" fill=\"none\"");
+        }
+
+        if stroke {
+            attributes.push_str(&format!(" stroke=\"{}\"", server));
+            if opacity < 1.0f32 {
+                attributes.push_str(&format!(" stroke-opacity=\"{}\"", format_number(opacity)));
+            }
+            if paint.stroke_width > 0.0f32 {
+                attributes.push_str(&format!(
+                    " stroke-width=\"{}\"",
+                    format_number(paint.stroke_width)
+                ));
+            } else {
+                // hairline
+                attributes.push_str(" stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"");
+            }
+            match paint.stroke_cap {
+                StrokeCap::Butt => (),
+                StrokeCap::Round => attributes.push_str(" stroke-linecap=\"round\""),
+                StrokeCap::Square => attributes.push_str(" stroke-linecap=\"square\""),
+            }
+            match paint.stroke_join {
+                // 4 is the default miter limit
+                StrokeJoin::Miter if paint.stroke_miter == 4.0f32 => (),
+                StrokeJoin::Miter => attributes.push_str(&format!(
+                    " stroke-miterlimit=\"{}\"",
+                    format_number(paint.stroke_miter.max(1.0f32))
+                )),
+                StrokeJoin::Round => attributes.push_str(" stroke-linejoin=\"round\""),
+                StrokeJoin::Bevel => attributes.push_str(" stroke-linejoin=\"bevel\""),
+            }
+        }
+
+        let stroke_outset = if stroke {
+            paint.stroke_width.max(1.0f32) * paint.stroke_miter.max(1.0f32)
+        } else {
+            0.0f32
+        };
+        let mask_blur = paint
+            .mask_filter
+            .as_ref()
+            .map(|MaskFilter::Blur { style, sigma }| (*sigma, *style));
+        let spread = stroke_outset
+            + mask_blur.map_or(0.0f32, |(sigma, _)| 3.0f32 * sigma)
+            + paint
+                .image_filter
+                .as_ref()
+                .map_or(0.0f32, get_filter_spread);
+        if let Some(filter_id) = self.write_filter(
+            mask_blur,
+            paint.color_filter.as_ref(),
+            paint.image_filter.as_ref(),
+            true,
+            bounds.inflate(spread, spread),
+            (1.0f32, 1.0f32),
+        ) {
+            attributes.push_str(&format!(" filter=\"url(#{})\"", filter_id));
+        }
+
+        attributes.push_str(&get_blend_mode_attribute(paint.blend_mode));
+        attributes
+    }
+
+    /// Returns the color or the reference to the gradient or pattern.
+    fn write_paint_server(&mut self, paint: &Paint) -> String {
+        let Some(color_source) = &paint.color_source else {
+            return format_color(&paint.color);
+        };
+
+        let gradient_transform = |transformation: &Option<drawing_api::Matrix>, name: &str| {
+            transformation.map_or(String::new(), |matrix| {
+                format!(" {}=\"{}\"", name, get_matrix(&matrix.to_2d()))
+            })
+        };
+
+        let definition = match color_source {
+            ColorSource::LinearGradient {
+                start,
+                end,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                let id = self.document.get_next_id("gradient");
+                let definition = format!(
+                    "<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}{}>{}</linearGradient>",
+                    id,
+                    format_number(start.x),
+                    format_number(start.y),
+                    format_number(end.x),
+                    format_number(end.y),
+                    get_spread_method_attribute(*tile_mode),
+                    gradient_transform(transformation, "gradientTransform"),
+                    get_gradient_stops(colors, stops)
+                );
+                (id, definition)
+            }
+
+            ColorSource::RadialGradient {
+                center,
+                radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                let id = self.document.get_next_id("gradient");
+                let definition = format!(
+                    "<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\"{}{}>{}</radialGradient>",
+                    id,
+                    format_number(center.x),
+                    format_number(center.y),
+                    format_number(*radius),
+                    get_spread_method_attribute(*tile_mode),
+                    gradient_transform(transformation, "gradientTransform"),
+                    get_gradient_stops(colors, stops)
+                );
+                (id, definition)
+            }
+
+            ColorSource::ConicalGradient {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+                colors,
+                stops,
+                tile_mode,
+                transformation,
+            } => {
+                // SVG 1.1 has only the focal point, the start radius (`fr`) is from SVG 2
+                let id = self.document.get_next_id("gradient");
+                let definition = format!(
+                    "<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\" fx=\"{}\" fy=\"{}\" fr=\"{}\"{}{}>{}</radialGradient>",
+                    id,
+                    format_number(end_center.x),
+                    format_number(end_center.y),
+                    format_number(*end_radius),
+                    format_number(start_center.x),
+                    format_number(start_center.y),
+                    format_number(*start_radius),
+                    get_spread_method_attribute(*tile_mode),
+                    gradient_transform(transformation, "gradientTransform"),
+                    get_gradient_stops(colors, stops)
+                );
+                (id, definition)
+            }
+
+            ColorSource::SweepGradient { colors, .. } => {
+                // there is no sweep gradient in SVG, use the first color
+                return colors.first().map_or(format_color(&paint.color), |color| {
+                    format_color(&[color.red, color.green, color.blue, color.alpha])
+                });
+            }
+
+            ColorSource::Image {
+                image,
+                horizontal_tile_mode: _,
+                vertical_tile_mode: _,
+                sampling,
+                transformation,
+            } => {
+                // patterns are always repeated
+                let image_id = self.get_image_id(image);
+                let (width, height) = image.get_size();
+                let id = self.document.get_next_id("pattern");
+                let definition = format!(
+                    "<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" x=\"0\" y=\"0\" width=\"{}\" height=\"{}\"{}><use xlink:href=\"#{}\"{}/></pattern>",
+                    id,
+                    width,
+                    height,
+                    gradient_transform(transformation, "patternTransform"),
+                    image_id,
+                    if *sampling == TextureSampling::NearestNeighbor {
+                        " image-rendering=\"optimizeSpeed\""
+                    } else {
+                        ""
+                    }
+                );
+                (id, definition)
+            }
+
+            // fragment programs are not supported, fall back to the paint color
+            ColorSource::Fragment { .. } => return format_color(&paint.color),
+        };
+
+        let (id, definition) = definition;
+        self.document
+            .body
+            .push_str(&format!("<defs>{}</defs>\n", definition));
+        format!("url(#{})", id)
+    }
+
+    /// Writes the filter element, returns its identifier.
+    ///
+    /// The `region` is in the user coordinates of the filtered element,
+    /// the filter parameters are multiplied by the `scale`.
+    fn write_filter(
+        &mut self,
+        mask_blur: Option<(f32, BlurStyle)>,
+        color_filter: Option<&ColorFilter>,
+        image_filter: Option<&ImageFilter<ImageFilterFragment>>,
+        is_shape: bool,
+        region: PixelRect,
+        scale: (f32, f32),
+    ) -> Option<String> {
+        let mut primitives = FilterPrimitives::default();
+
+        if let Some((sigma, style)) = mask_blur {
+            let blurred = primitives.add(&format!(
+                "<feGaussianBlur in=\"SourceGraphic\" stdDeviation=\"{} {}\"",
+                format_number(sigma * scale.0),
+                format_number(sigma * scale.1)
+            ));
+            match style {
+                BlurStyle::Normal => (),
+                BlurStyle::Solid => {
+                    primitives.add_merge(&blurred, "SourceGraphic");
+                }
+                BlurStyle::Outer => {
+                    primitives.add(&format!(
+                        "<feComposite in=\"{}\" in2=\"SourceGraphic\" operator=\"out\"",
+                        blurred
+                    ));
+                }
+                BlurStyle::Inner => {
+                    primitives.add(&format!(
+                        "<feComposite in=\"{}\" in2=\"SourceGraphic\" operator=\"in\"",
+                        blurred
+                    ));
+                }
+            }
+        }
+
+        if let Some(color_filter) = color_filter {
+            write_color_filter(&mut primitives, color_filter);
+            if is_shape {
+                // the color filter changes only the pixels of the shape
+                let filtered = primitives.last.clone();
+                primitives.add(&format!(
+                    "<feComposite in=\"{}\" in2=\"SourceAlpha\" operator=\"in\"",
+                    filtered
+                ));
+            }
+        }
+
+        if let Some(image_filter) = image_filter {
+            write_image_filter(&mut primitives, image_filter, scale);
+        }
+
+        if primitives.content.is_empty() {
+            return None;
+        }
+
+        let id = self.document.get_next_id("filter");
+        self.document.body.push_str(&format!(
+            "<defs><filter id=\"{}\" filterUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" color-interpolation-filters=\"sRGB\">{}</filter></defs>\n",
+            id,
+            format_number(region.origin.x),
+            format_number(region.origin.y),
+            format_number(region.size.width),
+            format_number(region.size.height),
+            primitives.content
+        ));
+        Some(id)
+    }
+
+    /// Returns the identifier of the image element with the texture content.
+    fn get_image_id(&mut self, texture: &SvgTexture) -> String {
+        match texture.source.as_ref() {
+            TextureSource::Pixels {
+                width,
+                height,
+                color_format,
+                data,
+            } => {
+                let id = format!("image{}", texture.id);
+                if self.document.images.insert(texture.id) {
+                    let png = encode_png(data, *width, *height, *color_format);
+                    self.document.defs.push_str(&format!(
+                        "<image id=\"{}\" x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" xlink:href=\"data:image/png;base64,{}\"/>\n",
+                        id,
+                        width,
+                        height,
+                        encode_base64(&png)
+                    ));
+                }
+                id
+            }
+
+            TextureSource::Surface(document) => {
+                // the surface content can change, so it is embedded on every use
+                let (width, height, svg) = {
+                    let document = document.lock().unwrap();
+                    (document.width, document.height, document.to_svg())
+                };
+                let id = self.document.get_next_id("image");
+                self.document.body.push_str(&format!(
+                    "<defs><image id=\"{}\" x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" xlink:href=\"data:image/svg+xml;base64,{}\"/></defs>\n",
+                    id,
+                    width,
+                    height,
+                    encode_base64(svg.as_bytes())
+                ));
+                id
+            }
+        }
+    }
+}
+
+/// Chain of the filter primitives, every primitive takes the result of the previous one.
+struct FilterPrimitives {
+    content: String,
+    last: String,
+    count: usize,
+}
+
+impl Default for FilterPrimitives {
+    fn default() -> Self {
+        Self {
+            content: String::new(),
+            last: "SourceGraphic".to_string(),
+            count: 0,
+        }
+    }
+}
+
+impl FilterPrimitives {
+    /// Adds the primitive (the beginning of the tag), returns the name of its result.
+    fn add(&mut self, primitive: &str) -> String {
+        self.count += 1;
+        self.last = format!("r{}", self.count);
+        self.content
+            .push_str(&format!("{} result=\"{}\"/>", primitive, self.last));
+        self.last.clone()
+    }
+
+    /// Draws `top` over `bottom`.
+    fn add_merge(&mut self, bottom: &str, top: &str) -> String {
+        self.count += 1;
+        self.last = format!("r{}", self.count);
+        self.content.push_str(&format!(
+            "<feMerge result=\"{}\"><feMergeNode in=\"{}\"/><feMergeNode in=\"{}\"/></feMerge>",
+            self.last, bottom, top
+        ));
+        self.last.clone()
+    }
+}
+
+fn write_color_filter(primitives: &mut FilterPrimitives, color_filter: &ColorFilter) {
+    let input = primitives.last.clone();
+    match color_filter {
+        ColorFilter::Matrix(matrix) => {
+            // both use unpremultiplied colors with offsets in 0..1 range
+            let values = matrix
+                .m
+                .iter()
+                .map(|value| format_number(*value))
+                .collect::<Vec<_>>()
+                .join(" ");
+            primitives.add(&format!(
+                "<feColorMatrix in=\"{}\" type=\"matrix\" values=\"{}\"",
+                input, values
+            ));
+        }
+
+        ColorFilter::Blend(color, blend_mode) => {
+            let flood = primitives.add(&format!(
+                "<feFlood flood-color=\"{}\" flood-opacity=\"{}\"",
+                format_color(&[color.red, color.green, color.blue, color.alpha]),
+                format_number(color.alpha.clamp(0.0f32, 1.0f32))
+            ));
+
+            // the filter color is the source, the filtered color is the destination
+            let composite = |operator: &str, source: &str, destination: &str| {
+                format!(
+                    "<feComposite in=\"{}\" in2=\"{}\" operator=\"{}\"",
+                    source, destination, operator
+                )
+            };
+            let primitive = match blend_mode {
+                BlendMode::Clear => {
+                    "<feFlood flood-color=\"#000000\" flood-opacity=\"0\"".to_string()
+                }
+                BlendMode::Source => return,
+                BlendMode::Destination => composite("over", &input, &input),
+                BlendMode::SourceOver => composite("over", &flood, &input),
+                BlendMode::DestinationOver => composite("over", &input, &flood),
+                BlendMode::SourceIn => composite("in", &flood, &input),
+                BlendMode::DestinationIn => composite("in", &input, &flood),
+                BlendMode::SourceOut => composite("out", &flood, &input),
+                BlendMode::DestinationOut => composite("out", &input, &flood),
+                BlendMode::SourceATop => composite("atop", &flood, &input),
+                BlendMode::DestinationATop => composite("atop", &input, &flood),
+                BlendMode::Xor => composite("xor", &flood, &input),
+                BlendMode::Plus => format!(
+                    "<feComposite in=\"{}\" in2=\"{}\" operator=\"arithmetic\" k1=\"0\" k2=\"1\" k3=\"1\" k4=\"0\"",
+                    flood, input
+                ),
+                BlendMode::Modulate => format!(
+                    "<feComposite in=\"{}\" in2=\"{}\" operator=\"arithmetic\" k1=\"1\" k2=\"0\" k3=\"0\" k4=\"0\"",
+                    flood, input
+                ),
+                _ => format!(
+                    "<feBlend in=\"{}\" in2=\"{}\" mode=\"{}\"",
+                    flood,
+                    input,
+                    get_css_blend_mode(*blend_mode).unwrap_or("normal")
+                ),
+            };
+            primitives.add(&primitive);
+        }
+    }
+}
+
+fn write_image_filter(
+    primitives: &mut FilterPrimitives,
+    image_filter: &ImageFilter<ImageFilterFragment>,
+    scale: (f32, f32),
+) {
+    let input = primitives.last.clone();
+    match image_filter {
+        ImageFilter::Blur {
+            x_sigma, y_sigma, ..
+        } => {
+            primitives.add(&format!(
+                "<feGaussianBlur in=\"{}\" stdDeviation=\"{} {}\"",
+                input,
+                format_number(x_sigma * scale.0),
+                format_number(y_sigma * scale.1)
+            ));
+        }
+
+        ImageFilter::Dilate { x_radius, y_radius } => {
+            primitives.add(&format!(
+                "<feMorphology in=\"{}\" operator=\"dilate\" radius=\"{} {}\"",
+                input,
+                format_number(x_radius * scale.0),
+                format_number(y_radius * scale.1)
+            ));
+        }
+
+        ImageFilter::Erode { x_radius, y_radius } => {
+            primitives.add(&format!(
+                "<feMorphology in=\"{}\" operator=\"erode\" radius=\"{} {}\"",
+                input,
+                format_number(x_radius * scale.0),
+                format_number(y_radius * scale.1)
+            ));
+        }
+
+        // the matrix is applied with the transformation of the element,
+        // fragment programs are not supported
+        ImageFilter::Matrix { .. } | ImageFilter::Fragment { .. } => (),
+
+        ImageFilter::Compose { outer, inner } => {
+            write_image_filter(primitives, inner, scale);
+            write_image_filter(primitives, outer, scale);
+        }
+    }
+}
+
+/// Returns how far the filter can move the content (in the local coordinates).
+fn get_filter_spread(image_filter: &ImageFilter<ImageFilterFragment>) -> f32 {
+    match image_filter {
+        ImageFilter::Blur {
+            x_sigma, y_sigma, ..
+        } => 3.0f32 * x_sigma.max(*y_sigma),
+        ImageFilter::Dilate { x_radius, y_radius } => x_radius.max(*y_radius),
+        ImageFilter::Erode { .. } | ImageFilter::Matrix { .. } | ImageFilter::Fragment { .. } => {
+            0.0f32
+        }
+        ImageFilter::Compose { outer, inner } => {
+            get_filter_spread(outer) + get_filter_spread(inner)
+        }
+    }
+}
+
+fn get_gradient_stops(colors: &[drawing_api::Color], stops: &[f32]) -> String {
+    colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| {
+            // when the number of stops doesn't match, the colors are distributed evenly
+            let offset = if stops.len() == colors.len() {
+                stops[i]
+            } else if colors.len() > 1 {
+                i as f32 / (colors.len() - 1) as f32
+            } else {
+                0.0f32
+            };
+            format!(
+                "<stop offset=\"{}\" stop-color=\"{}\"{}/>",
+                format_number(offset.clamp(0.0f32, 1.0f32)),
+                format_color(&[color.red, color.green, color.blue, color.alpha]),
+                if color.alpha < 1.0f32 {
+                    format!(
+                        " stop-opacity=\"{}\"",
+                        format_number(color.alpha.max(0.0f32))
+                    )
+                } else {
+                    String::new()
+                }
+            )
+        })
+        .collect()
+}
+
+fn get_spread_method_attribute(tile_mode: TileMode) -> &'static str {
+    match tile_mode {
+        // there is no decal spread method, the closest is to extend the edge colors
+        TileMode::Clamp | TileMode::Decal => "",
+        TileMode::Repeat => " spreadMethod=\"repeat\"",
+        TileMode::Mirror => " spreadMethod=\"reflect\"",
+    }
+}
+
+fn get_blend_mode_attribute(blend_mode: BlendMode) -> String {
+    // Porter-Duff modes other than source-over cannot be expressed
+    get_css_blend_mode(blend_mode).map_or(String::new(), |mode| {
+        format!(" style=\"mix-blend-mode:{}\"", mode)
+    })
+}
+
+fn get_css_blend_mode(blend_mode: BlendMode) -> Option<&'static str> {
+    match blend_mode {
+        BlendMode::Plus => Some("plus-lighter"),
+        BlendMode::Screen => Some("screen"),
+        BlendMode::Overlay => Some("overlay"),
+        BlendMode::Darken => Some("darken"),
+        BlendMode::Lighten => Some("lighten"),
+        BlendMode::ColorDodge => Some("color-dodge"),
+        BlendMode::ColorBurn => Some("color-burn"),
+        BlendMode::HardLight => Some("hard-light"),
+        BlendMode::SoftLight => Some("soft-light"),
+        BlendMode::Difference => Some("difference"),
+        BlendMode::Exclusion => Some("exclusion"),
+        BlendMode::Multiply | BlendMode::Modulate => Some("multiply"),
+        BlendMode::Hue => Some("hue"),
+        BlendMode::Saturation => Some("saturation"),
+        BlendMode::Color => Some("color"),
+        BlendMode::Luminosity => Some("luminosity"),
+        _ => None,
+    }
+}
+
+fn get_font_weight(weight: drawing_api::FontWeight) -> u32 {
+    match weight {
+        drawing_api::FontWeight::Thin => 100,
+        drawing_api::FontWeight::ExtraLight => 200,
+        drawing_api::FontWeight::Light => 300,
+        drawing_api::FontWeight::Regular => 400,
+        drawing_api::FontWeight::Medium => 500,
+        drawing_api::FontWeight::SemiBold => 600,
+        drawing_api::FontWeight::Bold => 700,
+        drawing_api::FontWeight::ExtraBold => 800,
+        drawing_api::FontWeight::Black => 900,
+    }
+}
+
+fn get_fill_rule_attribute(name: &str, fill_type: FillType) -> String {
+    match fill_type {
+        FillType::NonZero => String::new(),
+        FillType::Odd => format!(" {}=\"evenodd\"", name),
+    }
+}
+
+fn get_transform_attribute(transform: &Transform) -> String {
+    if *transform == Transform::identity() {
+        String::new()
+    } else {
+        format!(" transform=\"{}\"", get_matrix(transform))
+    }
+}
+
+fn get_matrix(transform: &Transform) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        format_number(transform.m11),
+        format_number(transform.m12),
+        format_number(transform.m21),
+        format_number(transform.m22),
+        format_number(transform.m31),
+        format_number(transform.m32)
+    )
+}
+
+/// Returns the scale of the transformation along the axes.
+fn get_scale(transform: &Transform) -> (f32, f32) {
+    (
+        (transform.m11 * transform.m11 + transform.m12 * transform.m12).sqrt(),
+        (transform.m21 * transform.m21 + transform.m22 * transform.m22).sqrt(),
+    )
+}
+
+/// Returns the bounding box of the transformed rectangle.
+fn transform_rect(transform: &Transform, rect: &PixelRect) -> PixelRect {
+    let points = [
+        Point::new(rect.min_x(), rect.min_y()),
+        Point::new(rect.max_x(), rect.min_y()),
+        Point::new(rect.max_x(), rect.max_y()),
+        Point::new(rect.min_x(), rect.max_y()),
+    ]
+    .map(|point| {
+        let point = transform.transform_point(point);
+        PixelPoint::new(point.x, point.y)
+    });
+    PixelRect::from_points(points)
+}
+
+fn get_path_data(path: &[PathElement]) -> String {
+    let mut data = String::new();
+    for element in path {
+        match element {
+            PathElement::MoveTo(p) => {
+                data.push_str(&format!("M{} {}", format_number(p.x), format_number(p.y)))
+            }
+            PathElement::LineTo(p) => {
+                data.push_str(&format!("L{} {}", format_number(p.x), format_number(p.y)))
+            }
+            PathElement::BezierTo(c1, c2, p) => data.push_str(&format!(
+                "C{} {} {} {} {} {}",
+                format_number(c1.x),
+                format_number(c1.y),
+                format_number(c2.x),
+                format_number(c2.y),
+                format_number(p.x),
+                format_number(p.y)
+            )),
+            PathElement::ClosePath => data.push('Z'),
+        }
+    }
+    data
+}
+
+/// Formats the straight alpha color as `#rrggbb` (the alpha is set separately).
+fn format_color(color: &[f32; 4]) -> String {
+    let to_u8 = |value: f32| (value.clamp(0.0f32, 1.0f32) * 255.0f32).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        to_u8(color[0]),
+        to_u8(color[1]),
+        to_u8(color[2])
+    )
+}
+
+/// Formats the number with at most 3 decimal places.
+fn format_number(value: f32) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
@@ -0,0 +1,124 @@
+use drawing_api::prelude::*;
+use drawing_api::{DisplayListBuilder as _, Paint as _, PathBuilder as _};
+use drawing_svg::{DisplayListBuilder, Paint, PathBuilder, SvgContext};
+
+/// Draws the display list and returns the lines of the SVG document.
+fn render(display_list: &<SvgContext as DrawingContext>::DisplayList) -> Vec<String> {
+    let mut surface = SvgContext::new().create_surface(64, 32).unwrap();
+    surface.draw(display_list).unwrap();
+    surface.get_svg().lines().map(str::to_owned).collect()
+}
+
+/// Returns the lines between the `<svg>` element and its end.
+fn body(lines: &[String]) -> &[String] {
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("<svg "))
+        .unwrap();
+    let end = lines.iter().position(|line| line == "</svg>").unwrap();
+    &lines[start + 1..end]
+}
+
+fn triangle() -> <PathBuilder as drawing_api::PathBuilder>::Path {
+    let mut path_builder = PathBuilder::default();
+    path_builder.move_to((1.0, 1.0));
+    path_builder.line_to((5.0, 1.0));
+    path_builder.line_to((5.0, 5.0));
+    path_builder.close();
+    path_builder.build()
+}
+
+#[test]
+fn document_has_the_surface_size() {
+    let lines = render(&DisplayListBuilder::new(None).build().unwrap());
+
+    assert_eq!(lines[0], r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    assert!(lines[1].starts_with("<svg "));
+    assert!(lines[1].contains(r#"width="64" height="32" viewBox="0 0 64 32""#));
+    assert!(body(&lines).is_empty());
+}
+
+#[test]
+fn rect_is_written_as_a_filled_path() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_rect(rect(1.0, 2.0, 10.0, 20.0), "#F00");
+    let lines = render(&dlb.build().unwrap());
+
+    assert_eq!(
+        body(&lines),
+        [r##"<path d="M1 2L11 2L11 22L1 22Z" fill="#ff0000"/>"##]
+    );
+}
+
+#[test]
+fn path_is_written_with_its_transform() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.translate(10.0, 5.0);
+    dlb.scale(2.0, 2.0);
+    dlb.draw_path(&triangle(), "#00F");
+    let lines = render(&dlb.build().unwrap());
+
+    assert_eq!(
+        body(&lines),
+        [r##"<path d="M1 1L5 1L5 5Z" transform="matrix(2 0 0 2 10 5)" fill="#0000ff"/>"##]
+    );
+}
+
+#[test]
+fn stroke_is_written_with_its_width() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.draw_path(&triangle(), Paint::stroke_color("#0F0", 2.0));
+    let lines = render(&dlb.build().unwrap());
+
+    let body = body(&lines);
+    assert_eq!(body.len(), 1);
+    assert!(body[0].starts_with(r#"<path d="M1 1L5 1L5 5Z""#));
+    assert!(body[0].contains(r##"stroke="#00ff00""##));
+    assert!(body[0].contains(r#"stroke-width="2""#));
+    assert!(body[0].contains(r#"fill="none""#));
+}
+
+#[test]
+fn clip_groups_the_content_until_restore() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.save();
+    dlb.translate(10.0, 5.0);
+    dlb.scale(2.0, 2.0);
+    dlb.clip_rect(rect(0.0, 0.0, 8.0, 8.0), ClipOperation::Intersect);
+    dlb.draw_path(&triangle(), "#00F");
+    dlb.restore();
+    dlb.draw_rect(rect(1.0, 2.0, 10.0, 20.0), "#F00");
+    let lines = render(&dlb.build().unwrap());
+
+    assert_eq!(
+        body(&lines),
+        [
+            r#"<defs><clipPath id="clip1" clipPathUnits="userSpaceOnUse"><path d="M0 0L8 0L8 8L0 8Z" transform="matrix(2 0 0 2 10 5)"/></clipPath></defs>"#,
+            r#"<g clip-path="url(#clip1)">"#,
+            r##"<path d="M1 1L5 1L5 5Z" transform="matrix(2 0 0 2 10 5)" fill="#0000ff"/>"##,
+            "</g>",
+            r##"<path d="M1 2L11 2L11 22L1 22Z" fill="#ff0000"/>"##,
+        ]
+    );
+}
+
+#[test]
+fn nested_clips_get_unique_ids() {
+    let mut dlb = DisplayListBuilder::new(None);
+    dlb.clip_rect(rect(0.0, 0.0, 8.0, 8.0), ClipOperation::Intersect);
+    dlb.clip_oval(rect(0.0, 0.0, 8.0, 8.0), ClipOperation::Intersect);
+    dlb.draw_rect(rect(1.0, 2.0, 10.0, 20.0), "#F00");
+    let lines = render(&dlb.build().unwrap());
+
+    let body = body(&lines);
+    assert!(body
+        .iter()
+        .any(|line| line.contains(r#"<clipPath id="clip1""#)));
+    assert!(body
+        .iter()
+        .any(|line| line.contains(r#"<clipPath id="clip2""#)));
+    assert_eq!(
+        body.iter().filter(|line| line.starts_with("<g ")).count(),
+        body.iter().filter(|line| *line == "</g>").count()
+    );
+}